@@ -16,6 +16,10 @@ pub struct BenchModel {
     pub name: String,
     pub parallel_safe: bool,
     pub tool_shim: Option<BenchToolShimOpt>,
+    /// Directory of provider fixtures, one per eval and run. Evals record into it, or replay
+    /// from it when `provider` is "replay"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixture_dir: Option<PathBuf>,
 }
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct BenchEval {
@@ -48,12 +52,14 @@ impl Default for BenchRunConfig {
                         use_tool_shim: false,
                         tool_shim_model: None,
                     }),
+                    fixture_dir: None,
                 },
                 BenchModel {
                     provider: "databricks".to_string(),
                     name: "goose-claude-3-5-sonnet".to_string(),
                     parallel_safe: true,
                     tool_shim: None,
+                    fixture_dir: None,
                 },
             ],
             evals: vec![BenchEval {
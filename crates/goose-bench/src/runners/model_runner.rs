@@ -5,6 +5,7 @@ use crate::runners::eval_runner::EvalRunner;
 use crate::utilities::{await_process_exits, parallel_bench_cmd};
 use anyhow::{Context, Result};
 use dotenvy::from_path_iter;
+use goose::providers::replay::FIXTURE_CONFIG_KEY;
 use std::collections::HashMap;
 use std::fs::read_to_string;
 use std::path::PathBuf;
@@ -106,7 +107,8 @@ impl ModelRunner {
                         .to_string()
                         .context("Failed to serialize configuration")?;
 
-                    let handle = parallel_bench_cmd("exec-eval".to_string(), cfg, envs.clone());
+                    let eval_envs = Self::fixture_envs(model, eval_selector, &run_id, &envs);
+                    let handle = parallel_bench_cmd("exec-eval".to_string(), cfg, eval_envs);
                     results_handles.get_mut(suite).unwrap().push(handle);
                 }
             }
@@ -120,7 +122,8 @@ impl ModelRunner {
                     .to_string()
                     .context("Failed to serialize configuration")?;
 
-                let handle = parallel_bench_cmd("exec-eval".to_string(), cfg, envs.clone());
+                let eval_envs = Self::fixture_envs(model, eval_selector, &run_id, &envs);
+                let handle = parallel_bench_cmd("exec-eval".to_string(), cfg, eval_envs);
 
                 // Wait for this process to complete before starting the next one
                 let mut child_procs = vec![handle];
@@ -218,6 +221,25 @@ impl ModelRunner {
         result
     }
 
+    /// Point an eval subprocess at its own provider fixture, if the model has a fixture dir
+    fn fixture_envs(
+        model: &BenchModel,
+        eval: &BenchEval,
+        run_id: &str,
+        envs: &[(String, String)],
+    ) -> Vec<(String, String)> {
+        let mut envs = envs.to_vec();
+        if let Some(dir) = &model.fixture_dir {
+            let name = eval.selector.replace(['/', ':'], "_");
+            let path = dir.join(format!("{}-run-{}.json", name, run_id));
+            envs.push((
+                FIXTURE_CONFIG_KEY.to_string(),
+                path.to_string_lossy().to_string(),
+            ));
+        }
+        envs
+    }
+
     fn toolshim_envs(&self) -> Vec<(String, String)> {
         // read tool-shim preference from config, set respective env vars accordingly
         let mut shim_envs: Vec<(String, String)> = Vec::new();
@@ -2,23 +2,41 @@ use anyhow::Result;
 use clap::{Args, Parser, Subcommand};
 
 use goose::config::{Config, ExtensionConfig};
+use goose::permission::{ApprovalPolicy, OnConfirmation};
+use goose::providers::replay;
+use goose::scheduler::{
+    JobRetryPolicy, JobRunRecord, JobRunStatus, OverlapPolicy, DEFAULT_RETRY_BACKOFF_SECS,
+};
+use goose::scheduler_factory::SchedulerType;
+use goose::scheduler_triggers::JobTrigger;
 
+use crate::commands::audit::{handle_audit_list, handle_audit_verify, AuditListArgs};
 use crate::commands::bench::agent_generator;
 use crate::commands::configure::handle_configure;
 use crate::commands::info::handle_info;
 use crate::commands::mcp::run_server;
-use crate::commands::recipe::{handle_deeplink, handle_list, handle_validate};
+use crate::commands::memory::{
+    handle_memory_edit, handle_memory_list, handle_memory_prune, MemoryEditArgs, MemoryListArgs,
+    MemoryPruneArgs, MemoryScope,
+};
+use crate::commands::recipe::{
+    handle_deeplink, handle_keygen, handle_list, handle_lock, handle_sign, handle_test,
+    handle_validate,
+};
 // Import the new handlers from commands::schedule
 use crate::commands::schedule::{
     handle_schedule_add, handle_schedule_cron_help, handle_schedule_list, handle_schedule_remove,
     handle_schedule_run_now, handle_schedule_services_status, handle_schedule_services_stop,
-    handle_schedule_sessions,
+    handle_schedule_sessions, JobRunSettings,
 };
 use crate::commands::session::{handle_session_list, handle_session_remove};
 use crate::recipes::extract_from_cli::extract_recipe_info_from_cli;
 use crate::recipes::recipe::{explain_recipe, render_recipe_as_yaml};
 use crate::session;
-use crate::session::{build_session, SessionBuilderConfig, SessionSettings};
+use crate::session::{
+    build_session, write_json_line, ExitReason, OutputFormat, RunSummary, SessionBuilderConfig,
+    SessionSettings, StreamEvent,
+};
 use goose_bench::bench_config::BenchRunConfig;
 use goose_bench::runners::bench_runner::BenchRunner;
 use goose_bench::runners::eval_runner::EvalRunner;
@@ -74,6 +92,78 @@ fn parse_key_val(s: &str) -> Result<(String, String), String> {
     }
 }
 
+/// Records the outcome of a run started by the Temporal scheduler, whose service runs jobs
+/// through `goose run --scheduled-job-id`. The scheduler fires after-job triggers from it.
+fn record_scheduled_run(
+    job_id: &str,
+    started_at: chrono::DateTime<chrono::Utc>,
+    summary: &RunSummary,
+) {
+    if !matches!(SchedulerType::from_config(), SchedulerType::Temporal) {
+        return;
+    }
+    let status = if summary.exit_reason == ExitReason::Completed {
+        JobRunStatus::Succeeded
+    } else {
+        JobRunStatus::Failed
+    };
+    let record = JobRunRecord {
+        job_id: job_id.to_string(),
+        status,
+        started_at,
+        duration_ms: summary.duration_ms,
+        attempt: 1,
+        error: summary.error.clone(),
+        session_id: summary.session_id.clone(),
+    };
+    if let Err(e) = goose::temporal_scheduler::record_run(&record) {
+        tracing::error!("Failed to record run of scheduled job '{}': {}", job_id, e);
+    }
+}
+
+fn parse_on_confirmation(s: &str) -> Result<OnConfirmation, String> {
+    match s {
+        "deny" => Ok(OnConfirmation::Deny),
+        "allow" => Ok(OnConfirmation::Allow),
+        "fail" => Ok(OnConfirmation::Fail),
+        _ => Err(format!("expected deny, allow or fail, not '{}'", s)),
+    }
+}
+
+/// How `goose run` answers tool confirmation requests without asking
+#[derive(Args, Debug)]
+struct ApprovalArgs {
+    /// Tools allowed without asking
+    #[arg(
+        long = "allow-tool",
+        value_name = "PATTERN",
+        help = "Allow a tool that needs confirmation (can be specified multiple times)",
+        long_help = "Allow calls to tools matching this pattern without asking, where '*' matches any characters, e.g. 'developer__*'. With an allowlist, other tools that need confirmation are denied unless --on-confirmation says otherwise. Can be specified multiple times.",
+        action = clap::ArgAction::Append
+    )]
+    allow_tools: Vec<String>,
+
+    /// Tools denied without asking
+    #[arg(
+        long = "deny-tool",
+        value_name = "PATTERN",
+        help = "Deny a tool without asking (can be specified multiple times)",
+        long_help = "Deny calls to tools matching this pattern without asking, where '*' matches any characters. Takes precedence over --allow-tool. Can be specified multiple times.",
+        action = clap::ArgAction::Append
+    )]
+    deny_tools: Vec<String>,
+
+    /// How to answer other tool confirmations
+    #[arg(
+        long = "on-confirmation",
+        value_name = "ACTION",
+        value_parser = parse_on_confirmation,
+        help = "Answer tool confirmations not matched by --allow-tool or --deny-tool (deny, allow, fail)",
+        long_help = "How to answer tool confirmation requests that no --allow-tool or --deny-tool pattern matches: 'deny' declines the call and lets the agent carry on, 'allow' runs it, 'fail' stops the run with exit code 1. Defaults to 'deny' when --allow-tool is given and 'allow' otherwise."
+    )]
+    on_confirmation: Option<OnConfirmation>,
+}
+
 #[derive(Subcommand)]
 enum SessionCommand {
     #[command(about = "List all available sessions")]
@@ -123,6 +213,40 @@ enum SessionCommand {
     },
 }
 
+/// Events that start a scheduled job besides its cron expression
+#[derive(Args, Debug)]
+struct ScheduleTriggerArgs {
+    #[arg(
+        long,
+        value_name = "DIR",
+        action = clap::ArgAction::Append,
+        help = "Run when a file under this directory changes (can be repeated)",
+        long_help = "Run the job when a file under this directory is created, modified or removed. The recipe receives trigger_path and trigger_event parameters. Can be specified multiple times."
+    )]
+    watch: Vec<String>,
+    #[arg(
+        long,
+        value_name = "GLOB",
+        requires = "watch",
+        help = "Only watch files matching this glob, relative to the watched directory (e.g., '**/*.md')"
+    )]
+    watch_glob: Option<String>,
+    #[arg(
+        long,
+        value_name = "SECRET",
+        help = "Run when goose-server receives POST /schedule/<id>/webhook with this secret",
+        long_help = "Run the job when goose-server receives POST /schedule/<id>/webhook with the secret in the X-Goose-Webhook-Secret header. Fields of a JSON object body are passed as recipe parameters."
+    )]
+    webhook_secret: Option<String>,
+    #[arg(
+        long,
+        value_name = "ID",
+        action = clap::ArgAction::Append,
+        help = "Run after this job finishes successfully (can be repeated)"
+    )]
+    after_job: Vec<String>,
+}
+
 #[derive(Subcommand, Debug)]
 enum SchedulerCommand {
     #[command(about = "Add a new scheduled job")]
@@ -131,18 +255,70 @@ enum SchedulerCommand {
         id: String,
         #[arg(
             long,
+            required_unless_present_any = ["watch", "webhook_secret", "after_job"],
             help = "Cron expression for the schedule",
-            long_help = "Cron expression for when to run the job. Examples:\n  '0 * * * *'     - Every hour at minute 0\n  '0 */2 * * *'   - Every 2 hours\n  '@hourly'       - Every hour (shorthand)\n  '0 9 * * *'     - Every day at 9:00 AM\n  '0 9 * * 1'     - Every Monday at 9:00 AM\n  '0 0 1 * *'     - First day of every month at midnight"
+            long_help = "Cron expression for when to run the job. Examples:\n  '0 * * * *'     - Every hour at minute 0\n  '0 */2 * * *'   - Every 2 hours\n  '@hourly'       - Every hour (shorthand)\n  '0 9 * * *'     - Every day at 9:00 AM\n  '0 9 * * 1'     - Every Monday at 9:00 AM\n  '0 0 1 * *'     - First day of every month at midnight\nMay be omitted when the job has triggers."
         )]
-        cron: String,
+        cron: Option<String>,
+        #[arg(
+            long,
+            value_name = "TIMEZONE",
+            help = "IANA timezone the cron expression is evaluated in (e.g., 'America/New_York'); defaults to UTC"
+        )]
+        timezone: Option<String>,
         #[arg(
             long,
             help = "Recipe source (path to file, or base64 encoded recipe string)"
         )]
         recipe_source: String,
+        #[arg(
+            long,
+            value_name = "KEY=VALUE",
+            help = "Recipe parameters for each run (e.g., --params topic=weekly --params team=goose)",
+            long_help = "Key-value parameters passed to the recipe every time the job runs. Can be specified multiple times.",
+            action = clap::ArgAction::Append,
+            value_parser = parse_key_val,
+        )]
+        params: Vec<(String, String)>,
+        #[arg(
+            long,
+            value_name = "POLICY",
+            default_value = "skip",
+            help = "What to do when a run is triggered while the previous one is still running (skip, queue, cancel-previous)"
+        )]
+        overlap: OverlapPolicy,
+        #[arg(
+            long,
+            value_name = "SECONDS",
+            help = "Cancel a run that takes longer than this many seconds"
+        )]
+        max_runtime: Option<u64>,
+        #[arg(
+            long,
+            value_name = "COUNT",
+            help = "Retry a failed or timed out run up to this many times"
+        )]
+        retries: Option<u32>,
+        #[arg(
+            long,
+            value_name = "SECONDS",
+            requires = "retries",
+            help = "Delay before the first retry, doubled after each further attempt (default: 60)"
+        )]
+        retry_backoff: Option<u64>,
+        #[command(flatten)]
+        triggers: Box<ScheduleTriggerArgs>,
     },
     #[command(about = "List all scheduled jobs")]
-    List {},
+    List {
+        #[arg(
+            long,
+            value_name = "COUNT",
+            default_value_t = 3,
+            help = "Number of upcoming run times to show for each job"
+        )]
+        next: usize,
+    },
     #[command(about = "Remove a scheduled job by ID")]
     Remove {
         #[arg(long, help = "ID of the job to remove")] // Changed from positional to named --id
@@ -159,7 +335,10 @@ enum SchedulerCommand {
         limit: Option<u32>,
     },
     /// Run a scheduled job immediately
-    #[command(about = "Run a scheduled job immediately")]
+    #[command(
+        about = "Run a scheduled job immediately",
+        long_about = "Run a scheduled job immediately. If the job is already running this fails, unless the job's overlap policy is queue, in which case the run starts once the current one finishes."
+    )]
     RunNow {
         /// ID of the schedule to run
         #[arg(long, help = "ID of the schedule to run")] // Explicitly make it --id
@@ -173,7 +352,24 @@ enum SchedulerCommand {
     ServicesStop {},
     /// Show cron expression examples and help
     #[command(about = "Show cron expression examples and help")]
-    CronHelp {},
+    CronHelp {
+        /// Cron expression to evaluate; without one, expressions are read interactively
+        #[arg(help = "Cron expression to evaluate (interactive when omitted)")]
+        expression: Option<String>,
+        #[arg(
+            long,
+            value_name = "TIMEZONE",
+            help = "IANA timezone to evaluate the expression in; defaults to UTC"
+        )]
+        timezone: Option<String>,
+        #[arg(
+            long,
+            value_name = "COUNT",
+            default_value_t = 5,
+            help = "Number of upcoming run times to show"
+        )]
+        count: usize,
+    },
 }
 
 #[derive(Subcommand)]
@@ -250,6 +446,29 @@ enum RecipeCommand {
         recipe_name: String,
     },
 
+    /// Run a recipe's sidecar test file
+    #[command(
+        about = "Run the test cases for a recipe",
+        long_about = "Run the cases in <recipe>.test.yaml next to the recipe headlessly. Cases set parameters, expected tool calls and assertions on the final output, and can replay recorded provider responses from a fixture."
+    )]
+    Test {
+        /// Recipe name or path to the recipe file to test
+        #[arg(help = "recipe name to get recipe file or full path to the recipe file to test")]
+        recipe_name: String,
+
+        /// Test file to use instead of the sidecar next to the recipe
+        #[arg(long, value_name = "FILE", help = "Path to the test file")]
+        tests: Option<PathBuf>,
+
+        /// Write a JUnit XML report
+        #[arg(long, value_name = "FILE", help = "Write a JUnit XML report to FILE")]
+        junit: Option<PathBuf>,
+
+        /// Write a JSON report
+        #[arg(long, value_name = "FILE", help = "Write a JSON report to FILE")]
+        json: Option<PathBuf>,
+    },
+
     /// List available recipes
     #[command(about = "List available recipes")]
     List {
@@ -270,6 +489,220 @@ enum RecipeCommand {
         )]
         verbose: bool,
     },
+
+    /// Pin recipes from remote sources in goose-recipes.lock
+    #[command(
+        about = "Pin recipes from remote sources to their current version",
+        long_about = "Record the git revision or content hash of recipes from git and HTTP sources in ./goose-recipes.lock. Once the lockfile exists, recipes are loaded at their pinned version and newly used remote recipes are pinned automatically."
+    )]
+    Lock {
+        /// Recipes to pin; with none, every recipe in the lockfile is updated
+        #[arg(help = "recipe names to pin (default: update every pinned recipe)")]
+        recipe_names: Vec<String>,
+    },
+
+    /// Sign a recipe file
+    #[command(
+        about = "Sign a recipe file with an ed25519 key",
+        long_about = "Add an ed25519 signature to a recipe file. Users who list the key in GOOSE_RECIPE_TRUSTED_KEYS can verify that the recipe comes from you and has not been modified."
+    )]
+    Sign {
+        /// Path to the recipe file to sign
+        #[arg(help = "path to the recipe file to sign")]
+        recipe_path: PathBuf,
+
+        /// Signing key to use
+        #[arg(
+            long,
+            value_name = "FILE",
+            help = "Signing key created by `goose recipe keygen` (default: recipe-signing.key in the config directory)"
+        )]
+        key: Option<PathBuf>,
+    },
+
+    /// Generate a key for signing recipes
+    #[command(about = "Generate an ed25519 key for signing recipes")]
+    Keygen {
+        /// Where to write the key
+        #[arg(
+            long,
+            value_name = "FILE",
+            help = "Where to write the key (default: recipe-signing.key in the config directory)"
+        )]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum AuditCommand {
+    /// Verify the hash chain of the tool call audit log
+    #[command(about = "Verify that the audit log has not been tampered with")]
+    Verify {
+        #[arg(
+            long,
+            value_name = "FILE",
+            help = "Path to the audit log (default: configured log)"
+        )]
+        path: Option<PathBuf>,
+    },
+
+    /// List audit log entries
+    #[command(about = "List tool call audit entries")]
+    List {
+        #[arg(
+            long,
+            value_name = "FILE",
+            help = "Path to the audit log (default: configured log)"
+        )]
+        path: Option<PathBuf>,
+
+        #[arg(long, help = "Only show tools whose name contains this text")]
+        tool: Option<String>,
+
+        #[arg(long, help = "Only show entries from this session")]
+        session: Option<String>,
+
+        #[arg(long, help = "Only show this decision (allowed, denied, skipped)")]
+        decision: Option<String>,
+
+        #[arg(
+            long,
+            help = "Only show this decision source (user, smart_approve, permission_store, rule)"
+        )]
+        source: Option<String>,
+
+        #[arg(
+            long,
+            help = "Only show this result status (started, success, error, not_run, cancelled)"
+        )]
+        status: Option<String>,
+
+        #[arg(long, help = "Only show entries at or after this RFC 3339 timestamp")]
+        since: Option<String>,
+
+        #[arg(short, long, help = "Show only the most recent N entries")]
+        limit: Option<usize>,
+
+        #[arg(
+            long = "format",
+            value_name = "FORMAT",
+            help = "Output format (text, json)",
+            default_value = "text"
+        )]
+        format: String,
+    },
+}
+
+/// Which memories a `goose memory` command works on, both scopes unless one is given
+#[derive(Args, Debug)]
+struct MemoryScopeArgs {
+    #[arg(
+        long,
+        conflicts_with = "global",
+        help = "Only the memories of the current project"
+    )]
+    local: bool,
+
+    #[arg(long, help = "Only the memories shared by all projects")]
+    global: bool,
+}
+
+impl MemoryScopeArgs {
+    fn scope(&self) -> MemoryScope {
+        MemoryScope::from_flags(self.local, self.global)
+    }
+}
+
+#[derive(Subcommand)]
+enum MemoryCommand {
+    /// List saved memories
+    #[command(about = "List local and global memories")]
+    List {
+        #[command(flatten)]
+        scope: MemoryScopeArgs,
+
+        #[arg(long, help = "Only show memories of this category")]
+        category: Option<String>,
+
+        #[arg(long, help = "Also show memories whose TTL has passed")]
+        expired: bool,
+
+        #[arg(
+            long = "format",
+            value_name = "FORMAT",
+            help = "Output format (text, json)",
+            default_value = "text"
+        )]
+        format: String,
+    },
+
+    /// Change a saved memory
+    #[command(about = "Edit the content, category, tags or TTL of a memory")]
+    Edit {
+        #[arg(help = "Id of the memory, as shown by goose memory list")]
+        id: String,
+
+        #[command(flatten)]
+        scope: MemoryScopeArgs,
+
+        #[arg(long, help = "New content of the memory")]
+        content: Option<String>,
+
+        #[arg(long, help = "Move the memory to this category")]
+        category: Option<String>,
+
+        #[arg(
+            long,
+            value_delimiter = ',',
+            help = "Replace the tags, separated by commas"
+        )]
+        tags: Option<Vec<String>>,
+
+        #[arg(
+            long,
+            value_name = "DURATION",
+            conflicts_with = "no_ttl",
+            help = "Expire the memory this long from now, like 12h, 30d or 2w"
+        )]
+        ttl: Option<String>,
+
+        #[arg(long, help = "Keep the memory until it is removed")]
+        no_ttl: bool,
+    },
+
+    /// Remove saved memories
+    #[command(
+        about = "Remove expired, unused or selected memories",
+        long_about = "Remove memories whose TTL has passed, memories not used for a while (--unused-for) and memories picked by id (--id). Asks for confirmation unless --yes is given."
+    )]
+    Prune {
+        #[command(flatten)]
+        scope: MemoryScopeArgs,
+
+        #[arg(
+            long,
+            value_name = "DURATION",
+            help = "Also remove memories not saved or used for this long, like 90d"
+        )]
+        unused_for: Option<String>,
+
+        #[arg(long, help = "Only remove memories of this category")]
+        category: Option<String>,
+
+        #[arg(
+            long = "id",
+            value_name = "ID",
+            action = clap::ArgAction::Append,
+            help = "Also remove the memory with this id (can be repeated)"
+        )]
+        ids: Vec<String>,
+
+        #[arg(long, help = "Show what would be removed without removing it")]
+        dry_run: bool,
+
+        #[arg(short, long, help = "Remove without asking for confirmation")]
+        yes: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -563,6 +996,18 @@ enum Command {
         )]
         quiet: bool,
 
+        /// Output format for headless runs
+        #[arg(
+            long = "output-format",
+            value_name = "FORMAT",
+            value_enum,
+            default_value = "text",
+            conflicts_with = "interactive",
+            help = "Output format for the run (text, json, stream-json)",
+            long_help = "Output format for the run. 'json' prints one JSON object with the result, usage and messages when the run ends. 'stream-json' prints every agent event as a JSON line as it happens, followed by a result line. The exit code is 0 on success, 1 on error, 2 when the turn limit was reached and 130 when cancelled."
+        )]
+        output_format: OutputFormat,
+
         /// Scheduled job ID (used internally for scheduled executions)
         #[arg(
             long = "scheduled-job-id",
@@ -578,7 +1023,7 @@ enum Command {
             long = "sub-recipe",
             value_name = "RECIPE",
             help = "Sub-recipe name or file path (can be specified multiple times)",
-            long_help = "Specify sub-recipes to include alongside the main recipe. Can be:\n  - Recipe names from GitHub (if GOOSE_RECIPE_GITHUB_REPO is configured)\n  - Local file paths to YAML files\nCan be specified multiple times to include multiple sub-recipes.",
+            long_help = "Specify sub-recipes to include alongside the main recipe. Can be:\n  - Recipe names from the configured recipe sources (GOOSE_RECIPE_SOURCES)\n  - Local file paths to YAML files\nCan be specified multiple times to include multiple sub-recipes.",
             action = clap::ArgAction::Append
         )]
         additional_sub_recipes: Vec<String>,
@@ -600,6 +1045,35 @@ enum Command {
             long_help = "Override the GOOSE_MODEL environment variable for this run. The model must be supported by the specified provider."
         )]
         model: Option<String>,
+
+        /// Record provider responses to, or replay them from, a fixture file
+        #[arg(
+            long = "fixture",
+            value_name = "FILE",
+            help = "Record provider responses to a fixture, or replay them with --provider replay",
+            long_help = "With --provider replay, serve model responses from this fixture instead of calling a provider. With any other provider, record every response to the fixture so the run can be replayed later."
+        )]
+        fixture: Option<PathBuf>,
+
+        /// Fail on requests missing from the fixture instead of replaying in order
+        #[arg(
+            long = "strict-replay",
+            requires = "fixture",
+            help = "Fail when a request has no recorded response in the fixture"
+        )]
+        strict_replay: bool,
+
+        /// Record over an existing fixture instead of refusing to
+        #[arg(
+            long = "overwrite-fixture",
+            requires = "fixture",
+            help = "Replace an existing fixture when recording to it"
+        )]
+        overwrite_fixture: bool,
+
+        /// Approval policy for tool calls that need confirmation
+        #[command(flatten)]
+        approval: Box<ApprovalArgs>,
     },
 
     /// Recipe utilities for validation and deeplinking
@@ -616,6 +1090,20 @@ enum Command {
         command: SchedulerCommand,
     },
 
+    /// Inspect the tool call audit log
+    #[command(about = "Inspect and verify the tool call audit log")]
+    Audit {
+        #[command(subcommand)]
+        command: AuditCommand,
+    },
+
+    /// Manage the memories of the memory extension
+    #[command(about = "List, edit and prune saved memories")]
+    Memory {
+        #[command(subcommand)]
+        command: MemoryCommand,
+    },
+
     /// Update the Goose CLI version
     #[command(about = "Update the goose CLI version")]
     Update {
@@ -698,6 +1186,8 @@ pub async fn cli() -> Result<()> {
         Some(Command::Session { .. }) => "session",
         Some(Command::Run { .. }) => "run",
         Some(Command::Schedule { .. }) => "schedule",
+        Some(Command::Audit { .. }) => "audit",
+        Some(Command::Memory { .. }) => "memory",
         Some(Command::Update { .. }) => "update",
         Some(Command::Bench { .. }) => "bench",
         Some(Command::Recipe { .. }) => "recipe",
@@ -868,10 +1358,24 @@ pub async fn cli() -> Result<()> {
             render_recipe,
             scheduled_job_id,
             quiet,
+            output_format,
             additional_sub_recipes,
             provider,
             model,
+            fixture,
+            strict_replay,
+            overwrite_fixture,
+            approval,
         }) => {
+            // Set for the whole process so subagents and lead/worker providers share the fixture
+            if let Some(fixture) = fixture {
+                replay::set_fixture(Some(replay::FixtureSettings {
+                    path: fixture,
+                    strict: strict_replay,
+                    overwrite: overwrite_fixture,
+                }));
+            }
+
             let (input_config, recipe_info) = match (instructions, input_text, recipe) {
                 (Some(file), _, _) if file == "-" => {
                     let mut input = String::new();
@@ -959,9 +1463,9 @@ pub async fn cli() -> Result<()> {
                 debug,
                 max_tool_repetitions,
                 max_turns,
-                scheduled_job_id,
+                scheduled_job_id: scheduled_job_id.clone(),
                 interactive, // Use the interactive flag from the Run command
-                quiet,
+                quiet: quiet || output_format != OutputFormat::Text,
                 sub_recipes: recipe_info.as_ref().and_then(|r| r.sub_recipes.clone()),
                 final_output_response: recipe_info
                     .as_ref()
@@ -969,11 +1473,39 @@ pub async fn cli() -> Result<()> {
                 retry_config: recipe_info.as_ref().and_then(|r| r.retry_config.clone()),
             })
             .await;
+            session.set_output_format(output_format);
+
+            // Approval policies answer confirmations when nobody is there to. Flags add to the
+            // recipe's policy and take precedence over it, and a recipe can only refuse tools.
+            if !interactive {
+                let recipe_policy = recipe_info
+                    .as_ref()
+                    .and_then(|r| r.session_settings.as_ref())
+                    .and_then(|s| s.approval.clone())
+                    .unwrap_or_default();
+                let goose_mode = Config::global()
+                    .get_param::<String>("GOOSE_MODE")
+                    .unwrap_or_else(|_| "auto".to_string());
+                let restrictions = recipe_policy.restrictions(&goose_mode);
+                if !recipe_policy.allow_tools.is_empty()
+                    || recipe_policy.on_confirmation == Some(OnConfirmation::Allow)
+                {
+                    eprintln!(
+                        "Ignoring the tools the recipe allows; allow them with --allow-tool or --on-confirmation allow"
+                    );
+                }
+                session.set_approval_policy(restrictions.merge(ApprovalPolicy {
+                    allow_tools: approval.allow_tools,
+                    deny_tools: approval.deny_tools,
+                    on_confirmation: approval.on_confirmation,
+                }));
+            }
 
             if interactive {
                 let _ = session.interactive(input_config.contents).await;
             } else if let Some(contents) = input_config.contents {
                 let session_start = std::time::Instant::now();
+                let started_at = chrono::Utc::now();
                 let session_type = if recipe_info.is_some() {
                     "recipe"
                 } else {
@@ -1022,7 +1554,18 @@ pub async fn cli() -> Result<()> {
                     );
                 }
 
-                result?;
+                let summary = session.run_summary(session_duration);
+                if let Some(job_id) = &scheduled_job_id {
+                    record_scheduled_run(job_id, started_at, &summary);
+                }
+                match output_format {
+                    OutputFormat::Text => result?,
+                    OutputFormat::Json => write_json_line(&summary),
+                    OutputFormat::StreamJson => write_json_line(&StreamEvent::Result(&summary)),
+                }
+                if summary.exit_reason != ExitReason::Completed {
+                    std::process::exit(summary.exit_reason.exit_code());
+                }
             } else {
                 eprintln!("Error: no text provided for prompt in headless mode");
                 std::process::exit(1);
@@ -1035,12 +1578,55 @@ pub async fn cli() -> Result<()> {
                 SchedulerCommand::Add {
                     id,
                     cron,
+                    timezone,
                     recipe_source,
+                    params,
+                    overlap,
+                    max_runtime,
+                    retries,
+                    retry_backoff,
+                    triggers,
                 } => {
-                    handle_schedule_add(id, cron, recipe_source).await?;
+                    let ScheduleTriggerArgs {
+                        watch,
+                        watch_glob,
+                        webhook_secret,
+                        after_job,
+                    } = *triggers;
+                    let retry = retries.map(|max_retries| JobRetryPolicy {
+                        max_retries,
+                        backoff_secs: retry_backoff.unwrap_or(DEFAULT_RETRY_BACKOFF_SECS),
+                    });
+                    let triggers = watch
+                        .into_iter()
+                        .map(|path| JobTrigger::FileWatch {
+                            path,
+                            glob: watch_glob.clone(),
+                        })
+                        .chain(webhook_secret.map(|secret| JobTrigger::Webhook { secret }))
+                        .chain(
+                            after_job
+                                .into_iter()
+                                .map(|job_id| JobTrigger::AfterJob { job_id }),
+                        )
+                        .collect();
+                    handle_schedule_add(
+                        id,
+                        cron,
+                        timezone,
+                        recipe_source,
+                        params,
+                        triggers,
+                        JobRunSettings {
+                            overlap_policy: overlap,
+                            max_runtime_secs: max_runtime,
+                            retry,
+                        },
+                    )
+                    .await?;
                 }
-                SchedulerCommand::List {} => {
-                    handle_schedule_list().await?;
+                SchedulerCommand::List { next } => {
+                    handle_schedule_list(next).await?;
                 }
                 SchedulerCommand::Remove { id } => {
                     handle_schedule_remove(id).await?;
@@ -1059,12 +1645,91 @@ pub async fn cli() -> Result<()> {
                 SchedulerCommand::ServicesStop {} => {
                     handle_schedule_services_stop().await?;
                 }
-                SchedulerCommand::CronHelp {} => {
-                    handle_schedule_cron_help().await?;
+                SchedulerCommand::CronHelp {
+                    expression,
+                    timezone,
+                    count,
+                } => {
+                    handle_schedule_cron_help(expression, timezone, count).await?;
                 }
             }
             return Ok(());
         }
+        Some(Command::Audit { command }) => {
+            match command {
+                AuditCommand::Verify { path } => handle_audit_verify(path)?,
+                AuditCommand::List {
+                    path,
+                    tool,
+                    session,
+                    decision,
+                    source,
+                    status,
+                    since,
+                    limit,
+                    format,
+                } => handle_audit_list(AuditListArgs {
+                    path,
+                    tool,
+                    session,
+                    decision,
+                    source,
+                    status,
+                    since,
+                    limit,
+                    format,
+                })?,
+            }
+            return Ok(());
+        }
+        Some(Command::Memory { command }) => {
+            match command {
+                MemoryCommand::List {
+                    scope,
+                    category,
+                    expired,
+                    format,
+                } => handle_memory_list(MemoryListArgs {
+                    scope: scope.scope(),
+                    category,
+                    expired,
+                    format,
+                })?,
+                MemoryCommand::Edit {
+                    id,
+                    scope,
+                    content,
+                    category,
+                    tags,
+                    ttl,
+                    no_ttl,
+                } => handle_memory_edit(MemoryEditArgs {
+                    id,
+                    scope: scope.scope(),
+                    content,
+                    category,
+                    tags,
+                    ttl,
+                    no_ttl,
+                })?,
+                MemoryCommand::Prune {
+                    scope,
+                    unused_for,
+                    category,
+                    ids,
+                    dry_run,
+                    yes,
+                } => handle_memory_prune(MemoryPruneArgs {
+                    scope: scope.scope(),
+                    unused_for,
+                    category,
+                    ids,
+                    dry_run,
+                    yes,
+                })?,
+            }
+            return Ok(());
+        }
         Some(Command::Update {
             canary,
             reconfigure,
@@ -1101,9 +1766,26 @@ pub async fn cli() -> Result<()> {
                 RecipeCommand::Deeplink { recipe_name } => {
                     handle_deeplink(&recipe_name)?;
                 }
+                RecipeCommand::Test {
+                    recipe_name,
+                    tests,
+                    junit,
+                    json,
+                } => {
+                    handle_test(&recipe_name, tests, junit, json).await?;
+                }
                 RecipeCommand::List { format, verbose } => {
                     handle_list(&format, verbose)?;
                 }
+                RecipeCommand::Lock { recipe_names } => {
+                    handle_lock(&recipe_names)?;
+                }
+                RecipeCommand::Sign { recipe_path, key } => {
+                    handle_sign(&recipe_path, key)?;
+                }
+                RecipeCommand::Keygen { output } => {
+                    handle_keygen(output)?;
+                }
             }
             return Ok(());
         }
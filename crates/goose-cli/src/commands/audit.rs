@@ -0,0 +1,138 @@
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use console::style;
+use goose::agents::tool_audit::{
+    query_audit_log, verify_audit_log, AuditDecision, AuditFilter, ToolAuditEntry, ToolAuditLog,
+};
+use serde::de::DeserializeOwned;
+use std::path::PathBuf;
+
+/// Filters accepted by `goose audit list`, as given on the command line
+pub struct AuditListArgs {
+    pub path: Option<PathBuf>,
+    pub tool: Option<String>,
+    pub session: Option<String>,
+    pub decision: Option<String>,
+    pub source: Option<String>,
+    pub status: Option<String>,
+    pub since: Option<String>,
+    pub limit: Option<usize>,
+    pub format: String,
+}
+
+fn parse_value<T: DeserializeOwned>(field: &str, value: &str) -> Result<T> {
+    serde_json::from_value(serde_json::Value::String(value.to_string()))
+        .map_err(|_| anyhow!("Invalid {} '{}'", field, value))
+}
+
+fn audit_path(path: Option<PathBuf>) -> PathBuf {
+    path.unwrap_or_else(ToolAuditLog::configured_path)
+}
+
+/// Verify the hash chain of the audit log
+pub fn handle_audit_verify(path: Option<PathBuf>) -> Result<()> {
+    let path = audit_path(path);
+    let verification = verify_audit_log(&path)?;
+
+    match verification.broken {
+        None => {
+            println!(
+                "{} {} entries verified in {}",
+                style("✓").green().bold(),
+                verification.verified,
+                path.display()
+            );
+            Ok(())
+        }
+        Some((line, reason)) => {
+            println!(
+                "{} chain broken at line {}: {} ({} entries verified before it)",
+                style("✗").red().bold(),
+                line,
+                reason,
+                verification.verified
+            );
+            Err(anyhow!("Audit log verification failed"))
+        }
+    }
+}
+
+fn print_entry(entry: &ToolAuditEntry) {
+    let record = &entry.record;
+    let decision = match record.decision {
+        AuditDecision::Allowed => style("allowed").green(),
+        AuditDecision::Denied => style("denied").red(),
+        AuditDecision::Skipped => style("skipped").yellow(),
+    };
+    let source = serde_json::to_value(record.decision_source)
+        .ok()
+        .and_then(|v| v.as_str().map(String::from))
+        .unwrap_or_default();
+    let status = serde_json::to_value(record.status)
+        .ok()
+        .and_then(|v| v.as_str().map(String::from))
+        .unwrap_or_default();
+    let duration = record
+        .duration_ms
+        .map(|ms| format!(" {}ms", ms))
+        .unwrap_or_default();
+
+    println!(
+        "{:>6} {} {} {} ({}) {}{}",
+        entry.seq,
+        entry.timestamp.format("%Y-%m-%d %H:%M:%S"),
+        style(&record.tool_name).cyan(),
+        decision,
+        source,
+        status,
+        duration
+    );
+    if let Some(session_id) = &record.session_id {
+        println!("       session: {}", session_id);
+    }
+    println!("       arguments: {}", record.arguments);
+    if let Some(error) = &record.error {
+        println!("       error: {}", style(error).red());
+    }
+}
+
+/// List audit entries matching the given filters
+pub fn handle_audit_list(args: AuditListArgs) -> Result<()> {
+    let since = args
+        .since
+        .map(|since| {
+            DateTime::parse_from_rfc3339(&since)
+                .map(|dt| dt.with_timezone(&Utc))
+                .with_context(|| format!("Invalid --since '{}', expected RFC 3339", since))
+        })
+        .transpose()?;
+
+    let filter = AuditFilter {
+        tool: args.tool,
+        session_id: args.session,
+        decision: args
+            .decision
+            .map(|d| parse_value("decision", &d))
+            .transpose()?,
+        source: args.source.map(|s| parse_value("source", &s)).transpose()?,
+        status: args.status.map(|s| parse_value("status", &s)).transpose()?,
+        since,
+        limit: args.limit,
+    };
+
+    let path = audit_path(args.path);
+    let entries = query_audit_log(&path, &filter)?;
+
+    match args.format.as_str() {
+        "json" => println!("{}", serde_json::to_string_pretty(&entries)?),
+        _ => {
+            if entries.is_empty() {
+                println!("No audit entries found in {}", path.display());
+            }
+            for entry in &entries {
+                print_entry(entry);
+            }
+        }
+    }
+    Ok(())
+}
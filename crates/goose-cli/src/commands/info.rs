@@ -2,6 +2,7 @@ use anyhow::Result;
 use console::style;
 use etcetera::{choose_app_strategy, AppStrategy};
 use goose::config::Config;
+use goose::providers::rate_limit::{configured_rate_limits, rate_limit_snapshots};
 use serde_yaml;
 
 fn print_aligned(label: &str, value: &str, width: usize) {
@@ -40,6 +41,37 @@ pub fn handle_info(verbose: bool) -> Result<()> {
         print_aligned(label, path, basic_padding);
     }
 
+    // Print rate limits, configured ones and any that are live in this process
+    let limits: std::collections::BTreeMap<_, _> = configured_rate_limits().into_iter().collect();
+    let snapshots = rate_limit_snapshots();
+    if !limits.is_empty() || !snapshots.is_empty() {
+        println!("\n{}", style("Rate Limits:").cyan().bold());
+        for (key, limit) in &limits {
+            let describe = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+            println!(
+                "  {:<width$} rpm: {}  tpm: {}  max in flight: {}",
+                key,
+                describe(limit.requests_per_minute.map(|v| v.to_string())),
+                describe(limit.tokens_per_minute.map(|v| v.to_string())),
+                describe(limit.max_in_flight.map(|v| v.to_string())),
+                width = basic_padding
+            );
+        }
+        for snapshot in &snapshots {
+            let blocked = snapshot
+                .blocked_for_ms
+                .map(|ms| format!("  blocked for {}ms", ms))
+                .unwrap_or_default();
+            println!(
+                "  {:<width$} in flight: {}{}",
+                snapshot.key,
+                snapshot.in_flight,
+                blocked,
+                width = basic_padding
+            );
+        }
+    }
+
     // Print verbose info if requested
     if verbose {
         println!("\n{}", style("Goose Configuration:").cyan().bold());
@@ -1,5 +1,9 @@
 use anyhow::{anyhow, Result};
-use goose_mcp::{ComputerControllerRouter, DeveloperRouter, MemoryRouter, TutorialRouter};
+use async_trait::async_trait;
+use goose::providers::base::Provider;
+use goose_mcp::{
+    ComputerControllerRouter, DeveloperRouter, MemoryEmbedder, MemoryRouter, TutorialRouter,
+};
 use mcp_server::router::RouterService;
 use mcp_server::{BoundedService, ByteTransport, Server};
 use tokio::io::{stdin, stdout};
@@ -14,6 +18,25 @@ use nix::unistd::getpgrp;
 #[cfg(unix)]
 use nix::unistd::Pid;
 
+/// Ranks memories with embeddings of the configured provider
+struct ProviderEmbedder(Arc<dyn Provider>);
+
+#[async_trait]
+impl MemoryEmbedder for ProviderEmbedder {
+    async fn embed(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        Ok(self.0.create_embeddings(texts).await?)
+    }
+}
+
+/// The memory server, using the configured provider for embeddings when it supports them
+fn memory_router() -> MemoryRouter {
+    let router = MemoryRouter::new();
+    match goose::providers::create_embedding_provider() {
+        Some(provider) => router.with_embedder(Arc::new(ProviderEmbedder(provider))),
+        None => router,
+    }
+}
+
 pub async fn run_server(name: &str) -> Result<()> {
     crate::logging::setup_logging(Some(&format!("mcp-{name}")), None)?;
 
@@ -28,7 +51,7 @@ pub async fn run_server(name: &str) -> Result<()> {
     let router: Option<Box<dyn BoundedService>> = match name {
         "developer" => Some(Box::new(RouterService(DeveloperRouter::new()))),
         "computercontroller" => Some(Box::new(RouterService(ComputerControllerRouter::new()))),
-        "memory" => Some(Box::new(RouterService(MemoryRouter::new()))),
+        "memory" => Some(Box::new(RouterService(memory_router()))),
         "tutorial" => Some(Box::new(RouterService(TutorialRouter::new()))),
         _ => None,
     };
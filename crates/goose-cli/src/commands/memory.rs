@@ -0,0 +1,255 @@
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Utc};
+use console::style;
+use goose_mcp::{global_memory_dir, local_memory_dir, parse_duration, MemoryEntry, MemoryStore};
+use serde::Serialize;
+
+/// Which memories a `goose memory` command works on
+#[derive(Debug, Clone, Copy)]
+pub enum MemoryScope {
+    Local,
+    Global,
+    Both,
+}
+
+impl MemoryScope {
+    pub fn from_flags(local: bool, global: bool) -> Self {
+        match (local, global) {
+            (true, false) => MemoryScope::Local,
+            (false, true) => MemoryScope::Global,
+            _ => MemoryScope::Both,
+        }
+    }
+
+    fn stores(self) -> Vec<(&'static str, MemoryStore)> {
+        let mut stores = Vec::new();
+        if !matches!(self, MemoryScope::Global) {
+            stores.push(("local", MemoryStore::new(local_memory_dir())));
+        }
+        if !matches!(self, MemoryScope::Local) {
+            stores.push(("global", MemoryStore::new(global_memory_dir())));
+        }
+        stores
+    }
+}
+
+/// Options of `goose memory list`, as given on the command line
+pub struct MemoryListArgs {
+    pub scope: MemoryScope,
+    pub category: Option<String>,
+    pub expired: bool,
+    pub format: String,
+}
+
+/// Options of `goose memory edit`, as given on the command line
+pub struct MemoryEditArgs {
+    pub id: String,
+    pub scope: MemoryScope,
+    pub content: Option<String>,
+    pub category: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub ttl: Option<String>,
+    pub no_ttl: bool,
+}
+
+/// Options of `goose memory prune`, as given on the command line
+pub struct MemoryPruneArgs {
+    pub scope: MemoryScope,
+    pub unused_for: Option<String>,
+    pub category: Option<String>,
+    pub ids: Vec<String>,
+    pub dry_run: bool,
+    pub yes: bool,
+}
+
+#[derive(Serialize)]
+struct ScopedEntry<'a> {
+    scope: &'a str,
+    #[serde(flatten)]
+    entry: &'a MemoryEntry,
+}
+
+fn parse_duration_arg(flag: &str, value: &str) -> Result<chrono::Duration> {
+    parse_duration(value).map_err(|e| anyhow!("Invalid {}: {}", flag, e))
+}
+
+fn print_entry(scope: &str, entry: &MemoryEntry, now: DateTime<Utc>) {
+    let tags = if entry.tags.is_empty() {
+        String::new()
+    } else {
+        format!(" #{}", entry.tags.join(" #"))
+    };
+    let expired = if entry.is_expired(now) {
+        style(" expired").red().to_string()
+    } else {
+        String::new()
+    };
+    println!(
+        "{} {}{}{}",
+        style(&entry.id).cyan(),
+        style(format!("{}/{}", scope, entry.category)).bold(),
+        style(tags).dim(),
+        expired
+    );
+    for line in entry.content.lines() {
+        println!("    {}", line);
+    }
+
+    let mut details = vec![format!("saved {}", entry.updated_at.format("%Y-%m-%d"))];
+    if let Some(last_used) = entry.last_used {
+        details.push(format!("last used {}", last_used.format("%Y-%m-%d")));
+    }
+    if let Some(expires_at) = entry.expires_at {
+        details.push(format!("expires {}", expires_at.format("%Y-%m-%d %H:%M")));
+    }
+    println!("    {}", style(details.join(", ")).dim());
+}
+
+/// List the saved memories
+pub fn handle_memory_list(args: MemoryListArgs) -> Result<()> {
+    let now = Utc::now();
+    let mut found = Vec::new();
+    for (scope, store) in args.scope.stores() {
+        let mut entries = store.load()?;
+        entries.retain(|entry| {
+            (args.expired || !entry.is_expired(now))
+                && args.category.as_ref().is_none_or(|c| &entry.category == c)
+        });
+        found.push((scope, store, entries));
+    }
+
+    if args.format == "json" {
+        let entries: Vec<ScopedEntry> = found
+            .iter()
+            .flat_map(|(scope, _, entries)| {
+                entries.iter().map(|entry| ScopedEntry { scope, entry })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
+
+    if found.iter().all(|(_, _, entries)| entries.is_empty()) {
+        println!("No memories found");
+        return Ok(());
+    }
+    for (scope, store, entries) in &found {
+        if entries.is_empty() {
+            continue;
+        }
+        println!(
+            "{} {}",
+            style(format!("{} memories", scope)).green().bold(),
+            style(store.dir().display()).dim()
+        );
+        for entry in entries {
+            print_entry(scope, entry, now);
+        }
+        println!();
+    }
+    Ok(())
+}
+
+/// Change a memory without going through the model
+pub fn handle_memory_edit(args: MemoryEditArgs) -> Result<()> {
+    if args.content.is_none()
+        && args.category.is_none()
+        && args.tags.is_none()
+        && args.ttl.is_none()
+        && !args.no_ttl
+    {
+        bail!("Nothing to change, pass --content, --category, --tags, --ttl or --no-ttl");
+    }
+    let ttl = args
+        .ttl
+        .as_deref()
+        .map(|ttl| parse_duration_arg("--ttl", ttl))
+        .transpose()?;
+
+    for (scope, store) in args.scope.stores() {
+        let updated = store.update(&args.id, |entry| {
+            if let Some(content) = &args.content {
+                entry.content = content.clone();
+            }
+            if let Some(category) = &args.category {
+                entry.category = category.clone();
+            }
+            if let Some(tags) = &args.tags {
+                entry.tags = tags.iter().filter(|t| !t.is_empty()).cloned().collect();
+            }
+            if let Some(ttl) = ttl {
+                entry.expires_at = Some(Utc::now() + ttl);
+            }
+            if args.no_ttl {
+                entry.expires_at = None;
+            }
+        })?;
+        if let Some(entry) = updated {
+            println!("{} Updated memory", style("✓").green().bold());
+            print_entry(scope, &entry, Utc::now());
+            return Ok(());
+        }
+    }
+    bail!("No memory with id {}", args.id)
+}
+
+/// Remove expired memories, memories unused for a while and memories picked by id
+pub fn handle_memory_prune(args: MemoryPruneArgs) -> Result<()> {
+    let now = Utc::now();
+    let unused_before = args
+        .unused_for
+        .as_deref()
+        .map(|d| parse_duration_arg("--unused-for", d).map(|d| now - d))
+        .transpose()?;
+    let is_selected = |entry: &MemoryEntry| {
+        args.category.as_ref().is_none_or(|c| &entry.category == c)
+            && (args.ids.contains(&entry.id)
+                || entry.is_expired(now)
+                || unused_before.is_some_and(|before| entry.last_active() < before))
+    };
+
+    let mut selected = Vec::new();
+    let mut seen_ids = Vec::new();
+    for (scope, store) in args.scope.stores() {
+        let entries = store.load()?;
+        seen_ids.extend(entries.iter().map(|e| e.id.clone()));
+        let entries: Vec<MemoryEntry> = entries.into_iter().filter(is_selected).collect();
+        if !entries.is_empty() {
+            selected.push((scope, store, entries));
+        }
+    }
+    for id in args.ids.iter().filter(|id| !seen_ids.contains(id)) {
+        println!("{} No memory with id {}", style("!").yellow().bold(), id);
+    }
+
+    let total: usize = selected.iter().map(|(_, _, entries)| entries.len()).sum();
+    if total == 0 {
+        println!("No memories to prune");
+        return Ok(());
+    }
+    for (scope, _, entries) in &selected {
+        for entry in entries {
+            print_entry(scope, entry, now);
+        }
+    }
+
+    if args.dry_run {
+        println!("\n{} memories would be removed", total);
+        return Ok(());
+    }
+    if !args.yes
+        && !cliclack::confirm(format!("Remove {} memories?", total))
+            .initial_value(false)
+            .interact()?
+    {
+        println!("No memories removed");
+        return Ok(());
+    }
+
+    for (_, store, entries) in &selected {
+        let ids: Vec<&str> = entries.iter().map(|e| e.id.as_str()).collect();
+        store.remove_where(|entry| ids.contains(&entry.id.as_str()))?;
+    }
+    println!("{} Removed {} memories", style("✓").green().bold(), total);
+    Ok(())
+}
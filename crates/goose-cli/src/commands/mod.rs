@@ -1,7 +1,9 @@
+pub mod audit;
 pub mod bench;
 pub mod configure;
 pub mod info;
 pub mod mcp;
+pub mod memory;
 pub mod recipe;
 pub mod schedule;
 pub mod session;
@@ -1,10 +1,31 @@
 use anyhow::Result;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
 use console::style;
+use etcetera::{choose_app_strategy, AppStrategy};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
 
-use crate::recipes::github_recipe::RecipeSource;
-use crate::recipes::recipe::load_recipe_for_validation;
-use crate::recipes::search_recipe::list_available_recipes;
+use crate::recipes::extract_from_cli::session_inputs_from_recipe;
+use crate::recipes::recipe::{
+    load_recipe_for_validation, load_recipe_headless, recipe_uses_composition, sign_recipe_content,
+};
+use crate::recipes::search_recipe::{list_available_recipes, lock_recipes, retrieve_recipe_file};
+use crate::recipes::test_harness::{
+    default_test_file, evaluate_case, CaseFixture, CaseResult, RecipeTestCase, RecipeTestFile,
+    RecipeTestReport,
+};
+use crate::session::{build_session, SessionBuilderConfig};
+use goose::config::APP_STRATEGY;
+use goose::providers::replay;
+use goose::recipe::read_recipe_file_content::read_recipe_file;
+use goose::recipe::signature::{
+    check_recipe_file_signature, generate_signing_key, SignatureStatus,
+    GOOSE_RECIPE_TRUSTED_KEYS_CONFIG_KEY,
+};
 use goose::recipe_deeplink;
+use goose_bench::bench_session::BenchAgent;
+use goose_bench::eval_suites::{collect_baseline_metrics, EvalMetricValue};
 
 /// Validates a recipe file
 ///
@@ -18,8 +39,23 @@ use goose::recipe_deeplink;
 pub fn handle_validate(recipe_name: &str) -> Result<()> {
     // Load and validate the recipe file
     match load_recipe_for_validation(recipe_name) {
-        Ok(_) => {
+        Ok(recipe) => {
+            let recipe_file = retrieve_recipe_file(recipe_name)?;
+            let check = check_recipe_file_signature(&recipe_file.content, &recipe_file.parent_dir);
+            if let SignatureStatus::Invalid { .. } = check.status {
+                println!("{} {}", style("✗").red().bold(), check.message);
+                return Err(anyhow::anyhow!(check.message));
+            }
             println!("{} recipe file is valid", style("✓").green().bold());
+            match check.status {
+                SignatureStatus::Verified { .. } => {
+                    println!("{} {}", style("✓").green().bold(), check.message)
+                }
+                _ => println!("{} {}", style("!").yellow().bold(), check.message),
+            }
+            if recipe_uses_composition(recipe_name)? {
+                println!("\nResolved recipe:\n{}", serde_yaml::to_string(&recipe)?);
+            }
             Ok(())
         }
         Err(err) => {
@@ -68,7 +104,153 @@ pub fn handle_deeplink(recipe_name: &str) -> Result<String> {
     }
 }
 
-/// Lists all available recipes from local paths and GitHub repositories
+/// Runs the cases in a recipe's sidecar test file headlessly and reports the results
+///
+/// # Arguments
+///
+/// * `recipe_name` - Recipe name or path to test
+/// * `tests` - Test file to use instead of `<recipe>.test.yaml` next to the recipe
+/// * `junit` - Where to write a JUnit XML report
+/// * `json` - Where to write a JSON report
+///
+/// # Returns
+///
+/// An error if any case fails
+pub async fn handle_test(
+    recipe_name: &str,
+    tests: Option<PathBuf>,
+    junit: Option<PathBuf>,
+    json: Option<PathBuf>,
+) -> Result<()> {
+    let recipe_file = retrieve_recipe_file(recipe_name)?;
+    let test_path = tests.unwrap_or_else(|| default_test_file(&recipe_file.file_path));
+    let test_file = RecipeTestFile::load(&test_path)?;
+    let test_dir = test_path.parent().unwrap_or(Path::new(".")).to_path_buf();
+    let recipe_path = recipe_file.file_path.to_string_lossy().to_string();
+    let suite_name = recipe_file
+        .file_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| recipe_name.to_string());
+
+    let fixtures = test_file.case_fixtures(&test_dir);
+    let mut results = Vec::new();
+    for (case, fixture) in test_file.cases.iter().zip(&fixtures) {
+        let result = run_test_case(&recipe_path, case, fixture.as_ref()).await;
+        match result.failures.as_slice() {
+            [] => println!("{} {}", style("✓").green().bold(), result.name),
+            failures => {
+                println!("{} {}", style("✗").red().bold(), result.name);
+                for failure in failures {
+                    println!("    {}", failure);
+                }
+            }
+        }
+        results.push(result);
+    }
+    for fixture in fixtures.iter().flatten() {
+        replay::release_fixture(&fixture.path);
+    }
+
+    let report = RecipeTestReport::new(suite_name, results);
+    println!(
+        "\n{} passed, {} failed",
+        style(report.passed).green(),
+        style(report.failed).red()
+    );
+    if let Some(path) = junit {
+        std::fs::write(&path, report.to_junit_xml())?;
+    }
+    if let Some(path) = json {
+        std::fs::write(&path, serde_json::to_string_pretty(&report)?)?;
+    }
+
+    if report.failed > 0 {
+        Err(anyhow::anyhow!(
+            "{} recipe test case(s) failed",
+            report.failed
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+async fn run_test_case(
+    recipe_path: &str,
+    case: &RecipeTestCase,
+    fixture: Option<&CaseFixture>,
+) -> CaseResult {
+    let start = Instant::now();
+    let mut result = CaseResult {
+        name: case.name.clone(),
+        passed: false,
+        duration_secs: 0.0,
+        failures: Vec::new(),
+        metrics: Default::default(),
+    };
+
+    let recipe = match load_recipe_headless(recipe_path, case.param_values()) {
+        Ok(recipe) => recipe,
+        Err(e) => {
+            result
+                .failures
+                .push(format!("failed to load recipe: {}", e));
+            return result;
+        }
+    };
+    let (input_config, recipe_info) = session_inputs_from_recipe(recipe);
+    let Some(prompt) = case.prompt.clone().or(input_config.contents) else {
+        result
+            .failures
+            .push("the recipe has no prompt; set `prompt` on the case".to_string());
+        return result;
+    };
+
+    // A fixture that existed before the run is replayed; otherwise the configured provider
+    // records into it
+    replay::set_fixture(fixture.map(|fixture| replay::FixtureSettings::new(&fixture.path)));
+    let provider = fixture
+        .filter(|fixture| fixture.replay)
+        .map(|_| replay::REPLAY_PROVIDER_NAME.to_string());
+
+    let session = build_session(SessionBuilderConfig {
+        identifier: None,
+        resume: false,
+        no_session: true,
+        extensions: Vec::new(),
+        remote_extensions: Vec::new(),
+        streamable_http_extensions: Vec::new(),
+        builtins: Vec::new(),
+        extensions_override: input_config.extensions_override,
+        additional_system_prompt: input_config.additional_system_prompt,
+        settings: recipe_info.session_settings,
+        provider,
+        model: None,
+        debug: false,
+        max_tool_repetitions: None,
+        max_turns: None,
+        scheduled_job_id: None,
+        interactive: false,
+        quiet: true,
+        sub_recipes: recipe_info.sub_recipes,
+        final_output_response: recipe_info.final_output_response,
+        retry_config: recipe_info.retry_config,
+    })
+    .await;
+
+    let mut agent = BenchAgent::new(Box::new(session));
+    let (conversation, metrics) = collect_baseline_metrics(&mut agent, prompt).await;
+    if let Some(EvalMetricValue::String(error)) = metrics.get("prompt_error") {
+        result.failures.push(error.clone());
+    }
+    result.failures.extend(evaluate_case(case, &conversation));
+    result.metrics = metrics.into_iter().collect();
+    result.passed = result.failures.is_empty();
+    result.duration_secs = start.elapsed().as_secs_f64();
+    result
+}
+
+/// Lists all available recipes from the configured recipe sources
 ///
 /// # Arguments
 ///
@@ -97,11 +279,6 @@ pub fn handle_list(format: &str, verbose: bool) -> Result<()> {
             } else {
                 println!("Available recipes:");
                 for recipe in recipes {
-                    let source_info = match recipe.source {
-                        RecipeSource::Local => format!("local: {}", recipe.path),
-                        RecipeSource::GitHub => format!("github: {}", recipe.path),
-                    };
-
                     let description = if let Some(desc) = &recipe.description {
                         if desc.is_empty() {
                             "(none)"
@@ -112,7 +289,7 @@ pub fn handle_list(format: &str, verbose: bool) -> Result<()> {
                         "(none)"
                     };
 
-                    let output = format!("{} - {} - {}", recipe.name, description, source_info);
+                    let output = format!("{} - {} - {}", recipe.name, description, recipe.source);
                     if verbose {
                         println!("  {}", output);
                         if let Some(title) = &recipe.title {
@@ -129,6 +306,136 @@ pub fn handle_list(format: &str, verbose: bool) -> Result<()> {
     Ok(())
 }
 
+/// Pins recipes from remote sources to their current version in `goose-recipes.lock`
+///
+/// # Arguments
+///
+/// * `recipe_names` - Recipes to pin; when empty, every recipe already in the lockfile is updated
+///
+/// # Returns
+///
+/// Result indicating success or failure
+pub fn handle_lock(recipe_names: &[String]) -> Result<()> {
+    let lock = lock_recipes(recipe_names)?;
+    let names: Vec<&String> = if recipe_names.is_empty() {
+        lock.recipes.keys().collect()
+    } else {
+        recipe_names.iter().collect()
+    };
+
+    for name in names {
+        match lock.recipes.get(name) {
+            Some(locked) => {
+                let version = locked
+                    .rev
+                    .as_deref()
+                    .map(|rev| format!("rev {}", &rev[..rev.len().min(12)]))
+                    .or_else(|| {
+                        locked
+                            .sha256
+                            .as_deref()
+                            .map(|hash| format!("sha256 {}", &hash[..hash.len().min(12)]))
+                    })
+                    .unwrap_or_default();
+                println!(
+                    "{} {} - {} - {}",
+                    style("locked").green(),
+                    name,
+                    locked.source,
+                    version
+                );
+            }
+            None => println!(
+                "{} {} - found in a local source, nothing to pin",
+                style("skipped").dim(),
+                name
+            ),
+        }
+    }
+    println!("Wrote {}", lock.path().display());
+    Ok(())
+}
+
+/// Default location of the key used to sign recipes
+pub fn default_signing_key_path() -> Result<PathBuf> {
+    Ok(choose_app_strategy(APP_STRATEGY.clone())?.in_config_dir("recipe-signing.key"))
+}
+
+/// Generates an ed25519 key for signing recipes and prints its public key
+///
+/// # Arguments
+///
+/// * `output` - Where to write the key, defaults to `recipe-signing.key` in the config directory
+pub fn handle_keygen(output: Option<PathBuf>) -> Result<()> {
+    let path = match output {
+        Some(path) => path,
+        None => default_signing_key_path()?,
+    };
+    if path.exists() {
+        return Err(anyhow::anyhow!(
+            "{} already exists, refusing to overwrite it",
+            path.display()
+        ));
+    }
+
+    let (pkcs8, public_key) = generate_signing_key()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, format!("{}\n", STANDARD.encode(pkcs8)))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600))?;
+    }
+
+    println!(
+        "{} Wrote signing key to {}",
+        style("✓").green().bold(),
+        path.display()
+    );
+    println!("Public key: {}", public_key);
+    println!(
+        "\nTo trust recipes signed with this key, add it to {} in your config:\n\n{}:\n  - name: <signer name>\n    public_key: \"{}\"",
+        GOOSE_RECIPE_TRUSTED_KEYS_CONFIG_KEY, GOOSE_RECIPE_TRUSTED_KEYS_CONFIG_KEY, public_key
+    );
+    Ok(())
+}
+
+/// Signs a recipe file in place
+///
+/// # Arguments
+///
+/// * `recipe_path` - Path to the recipe file to sign
+/// * `key` - Signing key created by `goose recipe keygen`, defaults to the one in the config
+///   directory
+pub fn handle_sign(recipe_path: &Path, key: Option<PathBuf>) -> Result<()> {
+    let key_path = match key {
+        Some(path) => path,
+        None => default_signing_key_path()?,
+    };
+    let encoded_key = fs::read_to_string(&key_path).map_err(|e| {
+        anyhow::anyhow!(
+            "Failed to read signing key {}: {}. Create one with `goose recipe keygen`.",
+            key_path.display(),
+            e
+        )
+    })?;
+    let pkcs8 = STANDARD
+        .decode(encoded_key.trim())
+        .map_err(|_| anyhow::anyhow!("{} is not a recipe signing key", key_path.display()))?;
+
+    let recipe_file = read_recipe_file(recipe_path)?;
+    let signed = sign_recipe_content(&recipe_file.content, &recipe_file.parent_dir, &pkcs8)?;
+    fs::write(&recipe_file.file_path, signed)?;
+    println!(
+        "{} Signed {}",
+        style("✓").green().bold(),
+        recipe_file.file_path.display()
+    );
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
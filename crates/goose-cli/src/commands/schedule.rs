@@ -1,11 +1,14 @@
 use anyhow::{bail, Context, Result};
 use base64::engine::{general_purpose::STANDARD as BASE64_STANDARD, Engine};
 use goose::scheduler::{
-    get_default_scheduled_recipes_dir, get_default_scheduler_storage_path, ScheduledJob,
+    get_default_scheduled_recipes_dir, get_default_scheduler_storage_path, next_fire_times,
+    normalize_cron_expression, parse_timezone, JobRetryPolicy, OverlapPolicy, ScheduledJob,
     SchedulerError,
 };
 use goose::scheduler_factory::SchedulerFactory;
+use goose::scheduler_triggers::JobTrigger;
 use goose::temporal_scheduler::TemporalScheduler;
+use std::io::IsTerminal;
 use std::path::Path;
 
 // Base64 decoding function - might be needed if recipe_source_arg can be base64
@@ -75,18 +78,60 @@ fn validate_cron_expression(cron: &str) -> Result<()> {
     Ok(())
 }
 
+/// Formats the next `count` fire times of `cron` in `timezone` (UTC when unset).
+fn format_fire_times(cron: &str, timezone: Option<&str>, count: usize) -> Result<Vec<String>> {
+    let tz = parse_timezone(timezone)?;
+    let times = next_fire_times(cron, timezone, chrono::Utc::now(), count)?;
+    Ok(times
+        .into_iter()
+        .map(|time| {
+            time.with_timezone(&tz)
+                .format("%Y-%m-%d %H:%M:%S %Z")
+                .to_string()
+        })
+        .collect())
+}
+
+fn describe_trigger(trigger: &JobTrigger) -> String {
+    match trigger {
+        JobTrigger::FileWatch { path, glob } => match glob {
+            Some(glob) => format!("file changes in {} matching {}", path, glob),
+            None => format!("file changes in {}", path),
+        },
+        JobTrigger::Webhook { .. } => "webhook (POST /schedule/<id>/webhook)".to_string(),
+        JobTrigger::AfterJob { job_id } => format!("after job '{}' succeeds", job_id),
+    }
+}
+
+/// How runs of a job are executed, see [`ScheduledJob`].
+pub struct JobRunSettings {
+    pub overlap_policy: OverlapPolicy,
+    pub max_runtime_secs: Option<u64>,
+    pub retry: Option<JobRetryPolicy>,
+}
+
 pub async fn handle_schedule_add(
     id: String,
-    cron: String,
+    cron: Option<String>,
+    timezone: Option<String>,
     recipe_source_arg: String, // This is expected to be a file path by the Scheduler
+    params: Vec<(String, String)>,
+    triggers: Vec<JobTrigger>,
+    run_settings: JobRunSettings,
 ) -> Result<()> {
+    let cron = cron.unwrap_or_default();
     println!(
         "[CLI Debug] Scheduling job ID: {}, Cron: {}, Recipe Source Path: {}",
         id, cron, recipe_source_arg
     );
 
-    // Validate cron expression and provide helpful feedback
-    validate_cron_expression(&cron)?;
+    // Validate cron expression and provide helpful feedback; jobs started only by
+    // triggers have none
+    if !cron.is_empty() || triggers.is_empty() {
+        validate_cron_expression(&cron)?;
+        let next_runs = format_fire_times(&cron, timezone.as_deref(), 3)?;
+        println!("   Next runs: {}", next_runs.join(", "));
+    }
 
     // The Scheduler's add_scheduled_job will handle copying the recipe from recipe_source_arg
     // to its internal storage and validating the path.
@@ -100,6 +145,12 @@ pub async fn handle_schedule_add(
         current_session_id: None,
         process_start_time: None,
         execution_mode: Some("background".to_string()), // Default to background for CLI
+        parameters: params.into_iter().collect(),
+        overlap_policy: run_settings.overlap_policy,
+        max_runtime_secs: run_settings.max_runtime_secs,
+        retry: run_settings.retry,
+        triggers,
+        timezone,
     };
 
     let scheduler_storage_path =
@@ -146,7 +197,7 @@ pub async fn handle_schedule_add(
     }
 }
 
-pub async fn handle_schedule_list() -> Result<()> {
+pub async fn handle_schedule_list(next: usize) -> Result<()> {
     let scheduler_storage_path =
         get_default_scheduler_storage_path().context("Failed to get scheduler storage path")?;
     let scheduler = SchedulerFactory::create(scheduler_storage_path)
@@ -171,11 +222,50 @@ pub async fn handle_schedule_list() -> Result<()> {
                 "- ID: {}\n  Status: {}\n  Cron: {}\n  Recipe Source (in store): {}\n  Last Run: {}",
                 job.id,
                 status,
-                job.cron,
+                if job.cron.is_empty() { "(triggers only)" } else { &job.cron },
                 job.source, // This source is now the path within scheduled_recipes_dir
                 job.last_run
                     .map_or_else(|| "Never".to_string(), |dt| dt.to_rfc3339())
             );
+            if !job.parameters.is_empty() {
+                let mut params: Vec<_> = job.parameters.iter().collect();
+                params.sort();
+                let params: Vec<String> = params
+                    .into_iter()
+                    .map(|(key, value)| format!("{}={}", key, value))
+                    .collect();
+                println!("  Parameters: {}", params.join(", "));
+            }
+            if let Some(timezone) = &job.timezone {
+                println!("  Timezone: {}", timezone);
+            }
+            if !job.cron.is_empty() && next > 0 {
+                match format_fire_times(&job.cron, job.timezone.as_deref(), next) {
+                    Ok(times) if !times.is_empty() => {
+                        println!("  Next Runs:");
+                        for time in times {
+                            println!("    {}", time);
+                        }
+                    }
+                    Ok(_) => println!("  Next Runs: none"),
+                    Err(e) => println!("  Next Runs: unavailable ({})", e),
+                }
+            }
+            for trigger in &job.triggers {
+                println!("  Trigger: {}", describe_trigger(trigger));
+            }
+            if job.overlap_policy != OverlapPolicy::Skip {
+                println!("  Overlap: {:?}", job.overlap_policy);
+            }
+            if let Some(secs) = job.max_runtime_secs {
+                println!("  Max Runtime: {}s", secs);
+            }
+            if let Some(retry) = &job.retry {
+                println!(
+                    "  Retries: {} (backoff {}s)",
+                    retry.max_retries, retry.backoff_secs
+                );
+            }
         }
     }
     Ok(())
@@ -210,7 +300,8 @@ pub async fn handle_schedule_sessions(id: String, limit: Option<u32>) -> Result<
         .await
         .context("Failed to initialize scheduler")?;
 
-    match scheduler.sessions(&id, limit.unwrap_or(50) as usize).await {
+    let limit = limit.unwrap_or(50) as usize;
+    match scheduler.sessions(&id, limit).await {
         Ok(sessions) => {
             if sessions.is_empty() {
                 println!("No sessions found for schedule ID '{}'.", id);
@@ -233,6 +324,27 @@ pub async fn handle_schedule_sessions(id: String, limit: Option<u32>) -> Result<
             bail!("Failed to get sessions for schedule '{}': {:?}", id, e);
         }
     }
+
+    let runs = scheduler
+        .runs(&id, limit)
+        .await
+        .with_context(|| format!("Failed to get runs for schedule '{}'", id))?;
+    if !runs.is_empty() {
+        println!("Recent runs for schedule ID '{}':", id);
+        for run in runs {
+            println!(
+                "  - {} | {} | attempt {} | {:.1}s | Session: {}{}",
+                run.started_at.to_rfc3339(),
+                run.status,
+                run.attempt,
+                run.duration_ms as f64 / 1000.0,
+                run.session_id.as_deref().unwrap_or("-"),
+                run.error
+                    .map(|e| format!(" | Error: {}", e))
+                    .unwrap_or_default()
+            );
+        }
+    }
     Ok(())
 }
 
@@ -254,6 +366,7 @@ pub async fn handle_schedule_run_now(id: String) -> Result<()> {
             SchedulerError::JobNotFound(job_id) => {
                 bail!("Error: Job with ID '{}' not found.", job_id);
             }
+            SchedulerError::JobAlreadyRunning(_) => bail!("Error: {}", e),
             _ => bail!("Failed to run schedule '{}' now: {:?}", id, e),
         },
     }
@@ -332,7 +445,34 @@ pub async fn handle_schedule_services_stop() -> Result<()> {
     Ok(())
 }
 
-pub async fn handle_schedule_cron_help() -> Result<()> {
+/// Prints how a cron expression is interpreted and when it fires next.
+fn evaluate_cron_expression(cron: &str, timezone: Option<&str>, count: usize) -> Result<()> {
+    let normalized = normalize_cron_expression(cron);
+    println!("Expression: {}", cron);
+    if normalized != cron {
+        println!("Normalized: {}", normalized);
+    }
+    let times = format_fire_times(cron, timezone, count)?;
+    if times.is_empty() {
+        println!("This expression never fires.");
+    } else {
+        println!("Next {} runs ({}):", times.len(), timezone.unwrap_or("UTC"));
+        for time in times {
+            println!("  {}", time);
+        }
+    }
+    Ok(())
+}
+
+pub async fn handle_schedule_cron_help(
+    expression: Option<String>,
+    timezone: Option<String>,
+    count: usize,
+) -> Result<()> {
+    if let Some(expression) = expression {
+        return evaluate_cron_expression(&expression, timezone.as_deref(), count);
+    }
+
     println!("📅 Cron Expression Guide for Goose Scheduler");
     println!("===========================================\\n");
 
@@ -391,6 +531,26 @@ pub async fn handle_schedule_cron_help() -> Result<()> {
         "  goose schedule add --id daily-backup --cron \"@daily\" --recipe-source backup.yaml"
     );
     println!("  goose schedule add --id weekly-summary --cron \"0 9 * * 1\" --recipe-source summary.yaml");
+    println!("  goose schedule add --id standup --cron \"0 9 * * 1-5\" --timezone Europe/Berlin --recipe-source standup.yaml\n");
+
+    if !std::io::stdin().is_terminal() {
+        return Ok(());
+    }
+
+    // Let the user try expressions until they submit an empty one
+    loop {
+        let expression: String = cliclack::input("Try a cron expression (leave empty to quit)")
+            .required(false)
+            .interact()?;
+        let expression = expression.trim();
+        if expression.is_empty() {
+            break;
+        }
+        if let Err(e) = evaluate_cron_expression(expression, timezone.as_deref(), count) {
+            println!("❌ {}", e);
+        }
+        println!();
+    }
 
     Ok(())
 }
@@ -1,7 +1,7 @@
 use std::path::PathBuf;
 
 use anyhow::{anyhow, Result};
-use goose::recipe::SubRecipe;
+use goose::recipe::{Recipe, SubRecipe};
 
 use crate::recipes::print_recipe::print_recipe_info;
 use crate::recipes::recipe::load_recipe;
@@ -16,7 +16,7 @@ pub fn extract_recipe_info_from_cli(
     params: Vec<(String, String)>,
     additional_sub_recipes: Vec<String>,
 ) -> Result<(InputConfig, RecipeInfo)> {
-    let recipe = load_recipe(&recipe_name, params.clone()).unwrap_or_else(|err| {
+    let mut recipe = load_recipe(&recipe_name, params.clone()).unwrap_or_else(|err| {
         eprintln!("{}: {}", console::style("Error").red().bold(), err);
         std::process::exit(1);
     });
@@ -34,6 +34,8 @@ pub fn extract_recipe_info_from_cli(
                         values: None,
                         sequential_when_repeated: true,
                         description: None,
+                        depends_on: None,
+                        inputs: None,
                     };
                     all_sub_recipes.push(additional_sub_recipe);
                 }
@@ -47,6 +49,13 @@ pub fn extract_recipe_info_from_cli(
             }
         }
     }
+    recipe.sub_recipes = Some(all_sub_recipes);
+
+    Ok(session_inputs_from_recipe(recipe))
+}
+
+/// Splits a loaded recipe into the prompt/instructions and the session settings it runs with
+pub fn session_inputs_from_recipe(recipe: Recipe) -> (InputConfig, RecipeInfo) {
     let input_config = InputConfig {
         contents: recipe.prompt.filter(|s| !s.trim().is_empty()),
         extensions_override: recipe.extensions,
@@ -58,13 +67,15 @@ pub fn extract_recipe_info_from_cli(
             goose_provider: s.goose_provider,
             goose_model: s.goose_model,
             temperature: s.temperature,
+            fallback_models: s.fallback_models,
+            approval: s.approval,
         }),
-        sub_recipes: Some(all_sub_recipes),
+        sub_recipes: recipe.sub_recipes,
         final_output_response: recipe.response,
         retry_config: recipe.retry,
     };
 
-    Ok((input_config, recipe_info))
+    (input_config, recipe_info)
 }
 
 fn extract_recipe_name(recipe_identifier: &str) -> String {
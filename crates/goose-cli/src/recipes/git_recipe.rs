@@ -0,0 +1,350 @@
+use anyhow::{anyhow, Result};
+use etcetera::{choose_app_strategy, AppStrategy};
+use goose::config::APP_STRATEGY;
+use goose::recipe::read_recipe_file_content::read_recipe_file;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use tar::Archive;
+
+use crate::recipes::recipe::RECIPE_FILE_EXTENSIONS;
+use crate::recipes::recipe_lock::LockedRecipe;
+use crate::recipes::recipe_source::{
+    download_dir, recipe_info_from_content, sha256_hex, write_downloaded_recipe, FetchedRecipe,
+    RecipeInfo, RecipeSource,
+};
+
+pub const GOOSE_RECIPE_GITHUB_REPO_CONFIG_KEY: &str = "GOOSE_RECIPE_GITHUB_REPO";
+const DEFAULT_GIT_REF: &str = "HEAD";
+
+/// Recipes in a git repository, fetched with plain `git` so any https or ssh remote works.
+///
+/// A recipe is either a directory holding `recipe.yaml` (its other files come along, for
+/// sub-recipes and includes) or a single `<name>.yaml` file.
+pub struct GitRecipeSource {
+    url: String,
+    git_ref: Option<String>,
+    subdir: Option<String>,
+    cache_root: Option<PathBuf>,
+}
+
+enum RecipeLayout {
+    Directory { dir: String, file_name: String },
+    File { path: String, file_name: String },
+}
+
+impl GitRecipeSource {
+    pub fn new(url: String, git_ref: Option<String>, subdir: Option<String>) -> Self {
+        Self {
+            url,
+            git_ref,
+            subdir: subdir
+                .map(|dir| dir.trim_matches('/').to_string())
+                .filter(|dir| !dir.is_empty()),
+            cache_root: None,
+        }
+    }
+
+    #[cfg(test)]
+    fn with_cache_root(mut self, cache_root: PathBuf) -> Self {
+        self.cache_root = Some(cache_root);
+        self
+    }
+
+    fn repo_dir(&self) -> Result<PathBuf> {
+        let cache_root = match &self.cache_root {
+            Some(root) => root.clone(),
+            None => choose_app_strategy(APP_STRATEGY.clone())
+                .map_err(|e| anyhow!("Failed to find the cache directory: {}", e))?
+                .cache_dir()
+                .join("recipes"),
+        };
+        Ok(cache_root.join(&sha256_hex(self.url.as_bytes())[..16]))
+    }
+
+    /// Clones the remote on first use and fetches it when `update` is set
+    fn sync(&self, update: bool) -> Result<PathBuf> {
+        let repo_dir = self.repo_dir()?;
+        if !repo_dir.join("HEAD").exists() {
+            eprintln!("📦 Cloning recipes from {}", self.url);
+            if let Some(parent) = repo_dir.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let target = repo_dir.to_string_lossy().into_owned();
+            git(None, &["clone", "--bare", "--quiet", &self.url, &target])?;
+        } else if update {
+            git(
+                Some(&repo_dir),
+                &[
+                    "fetch",
+                    "--quiet",
+                    "--force",
+                    "--prune",
+                    "origin",
+                    "+refs/heads/*:refs/heads/*",
+                    "+refs/tags/*:refs/tags/*",
+                ],
+            )?;
+        }
+        Ok(repo_dir)
+    }
+
+    /// The commit to read recipes from: the pinned one, or the configured ref after a fetch
+    fn resolve_rev(&self, pin: Option<&LockedRecipe>) -> Result<(PathBuf, String)> {
+        if let Some(rev) = pin.and_then(|pin| pin.rev.as_deref()) {
+            let commit = format!("{}^{{commit}}", rev);
+            let repo_dir = self.sync(false)?;
+            if git(Some(&repo_dir), &["cat-file", "-e", &commit]).is_ok() {
+                return Ok((repo_dir, rev.to_string()));
+            }
+            let repo_dir = self.sync(true)?;
+            git(Some(&repo_dir), &["cat-file", "-e", &commit])
+                .map_err(|_| anyhow!("Pinned revision {} is not in {}", rev, self.url))?;
+            return Ok((repo_dir, rev.to_string()));
+        }
+
+        let repo_dir = self.sync(true)?;
+        let git_ref = self.git_ref.as_deref().unwrap_or(DEFAULT_GIT_REF);
+        let rev = git(
+            Some(&repo_dir),
+            &["rev-parse", "--verify", &format!("{}^{{commit}}", git_ref)],
+        )
+        .map_err(|_| anyhow!("Ref '{}' not found in {}", git_ref, self.url))?;
+        Ok((repo_dir, rev.trim().to_string()))
+    }
+
+    fn repo_path(&self, path: &str) -> String {
+        match &self.subdir {
+            Some(subdir) => format!("{}/{}", subdir, path),
+            None => path.to_string(),
+        }
+    }
+
+    fn find_recipe(&self, repo_dir: &Path, rev: &str, recipe_name: &str) -> Option<RecipeLayout> {
+        for ext in RECIPE_FILE_EXTENSIONS {
+            let file_name = format!("recipe.{}", ext);
+            let dir = self.repo_path(recipe_name);
+            if object_exists(repo_dir, rev, &format!("{}/{}", dir, file_name)) {
+                return Some(RecipeLayout::Directory { dir, file_name });
+            }
+        }
+        for ext in RECIPE_FILE_EXTENSIONS {
+            let file_name = format!("{}.{}", recipe_name, ext);
+            let path = self.repo_path(&file_name);
+            if object_exists(repo_dir, rev, &path) {
+                return Some(RecipeLayout::File { path, file_name });
+            }
+        }
+        None
+    }
+}
+
+impl RecipeSource for GitRecipeSource {
+    fn name(&self) -> String {
+        let mut name = format!("git: {}", self.url);
+        if let Some(subdir) = &self.subdir {
+            name.push_str(&format!("#{}", subdir));
+        }
+        if let Some(git_ref) = &self.git_ref {
+            name.push_str(&format!("@{}", git_ref));
+        }
+        name
+    }
+
+    fn list(&self) -> Result<Vec<RecipeInfo>> {
+        let (repo_dir, rev) = self.resolve_rev(None)?;
+        let tree = match &self.subdir {
+            Some(subdir) => format!("{}:{}", rev, subdir),
+            None => rev.clone(),
+        };
+        let entries = git(Some(&repo_dir), &["ls-tree", "--name-only", &tree])?;
+
+        let mut recipes = Vec::new();
+        for entry in entries.lines() {
+            let name = Path::new(entry)
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .unwrap_or(entry);
+            if name.ends_with(".test") {
+                continue;
+            }
+            let path = match self.find_recipe(&repo_dir, &rev, name) {
+                Some(RecipeLayout::Directory { dir, file_name }) if dir.ends_with(entry) => {
+                    format!("{}/{}", dir, file_name)
+                }
+                Some(RecipeLayout::File { path, .. }) if path.ends_with(entry) => path,
+                _ => continue,
+            };
+            let Ok(content) = git(Some(&repo_dir), &["show", &format!("{}:{}", rev, path)]) else {
+                continue;
+            };
+            if let Ok(recipe_info) =
+                recipe_info_from_content(&content, name.to_string(), self.name(), path)
+            {
+                recipes.push(recipe_info);
+            }
+        }
+        Ok(recipes)
+    }
+
+    fn fetch(
+        &self,
+        recipe_name: &str,
+        pin: Option<&LockedRecipe>,
+    ) -> Result<Option<FetchedRecipe>> {
+        let (repo_dir, rev) = self.resolve_rev(pin)?;
+        let file = match self.find_recipe(&repo_dir, &rev, recipe_name) {
+            None => return Ok(None),
+            Some(RecipeLayout::Directory { dir, file_name }) => {
+                let output_dir = download_dir(&self.name(), recipe_name)?;
+                extract_tree(&repo_dir, &format!("{}:{}", rev, dir), &output_dir)?;
+                read_recipe_file(output_dir.join(file_name))?
+            }
+            Some(RecipeLayout::File { path, file_name }) => {
+                let content = git(Some(&repo_dir), &["show", &format!("{}:{}", rev, path)])?;
+                write_downloaded_recipe(&self.name(), recipe_name, &file_name, &content)?
+            }
+        };
+        eprintln!(
+            "⬇️  Retrieved recipe \"{}\" from {} at {}",
+            recipe_name,
+            self.url,
+            &rev[..rev.len().min(12)]
+        );
+
+        Ok(Some(FetchedRecipe {
+            file,
+            pin: Some(LockedRecipe {
+                source: self.name(),
+                rev: Some(rev),
+                sha256: None,
+            }),
+        }))
+    }
+}
+
+fn git(repo_dir: Option<&Path>, args: &[&str]) -> Result<String> {
+    let mut command = Command::new("git");
+    if let Some(dir) = repo_dir {
+        command.arg("--git-dir").arg(dir);
+    }
+    let output = command
+        .args(args)
+        .output()
+        .map_err(|e| anyhow!("Failed to run git. Make sure git is installed: {}", e))?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn object_exists(repo_dir: &Path, rev: &str, path: &str) -> bool {
+    git(
+        Some(repo_dir),
+        &["cat-file", "-e", &format!("{}:{}", rev, path)],
+    )
+    .is_ok()
+}
+
+fn extract_tree(repo_dir: &Path, tree: &str, output_dir: &Path) -> Result<()> {
+    let mut child = Command::new("git")
+        .arg("--git-dir")
+        .arg(repo_dir)
+        .args(["archive", "--format=tar", tree])
+        .stdout(Stdio::piped())
+        .spawn()?;
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| anyhow!("Failed to capture stdout from git archive"))?;
+    Archive::new(stdout).unpack(output_dir)?;
+    if !child.wait()?.success() {
+        return Err(anyhow!("git archive failed for {}", tree));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn recipe(title: &str) -> String {
+        format!(
+            "version: 1.0.0\ntitle: {}\ndescription: A test recipe\nprompt: go\n",
+            title
+        )
+    }
+
+    fn run(dir: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{:?}", output);
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    fn commit_all(dir: &Path, message: &str) -> String {
+        run(dir, &["add", "-A"]);
+        run(dir, &["commit", "-q", "-m", message]);
+        run(dir, &["rev-parse", "HEAD"])
+    }
+
+    #[test]
+    fn test_git_source_lists_fetches_and_honours_pins() {
+        let temp_dir = TempDir::new().unwrap();
+        let remote = temp_dir.path().join("remote");
+        fs::create_dir_all(remote.join("recipes/triage")).unwrap();
+        run(&remote, &["init", "-q", "-b", "main"]);
+        fs::write(
+            remote.join("recipes/triage/recipe.yaml"),
+            recipe("Triage v1"),
+        )
+        .unwrap();
+        fs::write(remote.join("recipes/triage/helper.yaml"), "extra").unwrap();
+        fs::write(remote.join("recipes/notes.yaml"), recipe("Notes")).unwrap();
+        fs::write(remote.join("recipes/notes.test.yaml"), "cases: []").unwrap();
+        let first = commit_all(&remote, "first");
+
+        let source = GitRecipeSource::new(
+            remote.to_string_lossy().into_owned(),
+            Some("main".to_string()),
+            Some("recipes/".to_string()),
+        )
+        .with_cache_root(temp_dir.path().join("cache"));
+
+        let mut names: Vec<String> = source.list().unwrap().into_iter().map(|r| r.name).collect();
+        names.sort();
+        assert_eq!(names, vec!["notes", "triage"]);
+
+        let fetched = source.fetch("triage", None).unwrap().unwrap();
+        assert!(fetched.file.content.contains("Triage v1"));
+        assert!(fetched.file.parent_dir.join("helper.yaml").exists());
+        let pin = fetched.pin.unwrap();
+        assert_eq!(pin.rev.as_deref(), Some(first.as_str()));
+        assert_eq!(pin.source, source.name());
+
+        fs::write(
+            remote.join("recipes/triage/recipe.yaml"),
+            recipe("Triage v2"),
+        )
+        .unwrap();
+        commit_all(&remote, "second");
+
+        let pinned = source.fetch("triage", Some(&pin)).unwrap().unwrap();
+        assert!(pinned.file.content.contains("Triage v1"));
+        let latest = source.fetch("triage", None).unwrap().unwrap();
+        assert!(latest.file.content.contains("Triage v2"));
+
+        let flat = source.fetch("notes", None).unwrap().unwrap();
+        assert!(flat.file.content.contains("Notes"));
+        assert!(source.fetch("missing", None).unwrap().is_none());
+    }
+}
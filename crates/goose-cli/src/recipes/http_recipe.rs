@@ -0,0 +1,235 @@
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+
+use crate::recipes::recipe::RECIPE_FILE_EXTENSIONS;
+use crate::recipes::recipe_lock::LockedRecipe;
+use crate::recipes::recipe_source::{
+    fetch_url, sha256_hex, write_downloaded_recipe, FetchedRecipe, RecipeInfo, RecipeSource,
+};
+
+/// Recipes listed in an index file served over HTTP:
+///
+/// ```yaml
+/// recipes:
+///   - name: release-notes
+///     url: release-notes.yaml   # relative to the index
+///     description: Draft release notes from merged PRs
+///     sha256: 9b74c9897bac770ffc029102a200c5de...   # optional
+/// ```
+pub struct HttpIndexRecipeSource {
+    url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RecipeIndex {
+    #[serde(default)]
+    recipes: Vec<RecipeIndexEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RecipeIndexEntry {
+    name: String,
+    url: String,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    sha256: Option<String>,
+}
+
+impl HttpIndexRecipeSource {
+    pub fn new(url: String) -> Self {
+        Self { url }
+    }
+
+    fn load_index(&self) -> Result<RecipeIndex> {
+        let content = fetch_url(&self.url)?;
+        serde_yaml::from_slice(&content)
+            .map_err(|e| anyhow!("Invalid recipe index {}: {}", self.url, e))
+    }
+
+    fn entry_url(&self, entry: &RecipeIndexEntry) -> Result<String> {
+        let base = reqwest::Url::parse(&self.url)
+            .map_err(|e| anyhow!("Invalid recipe index URL {}: {}", self.url, e))?;
+        Ok(base
+            .join(&entry.url)
+            .map_err(|e| anyhow!("Invalid URL for recipe '{}': {}", entry.name, e))?
+            .to_string())
+    }
+}
+
+impl RecipeSource for HttpIndexRecipeSource {
+    fn name(&self) -> String {
+        format!("http: {}", self.url)
+    }
+
+    fn list(&self) -> Result<Vec<RecipeInfo>> {
+        self.load_index()?
+            .recipes
+            .into_iter()
+            .map(|entry| {
+                Ok(RecipeInfo {
+                    path: self.entry_url(&entry)?,
+                    name: entry.name,
+                    source: self.name(),
+                    title: entry.title,
+                    description: entry.description,
+                })
+            })
+            .collect()
+    }
+
+    fn fetch(
+        &self,
+        recipe_name: &str,
+        pin: Option<&LockedRecipe>,
+    ) -> Result<Option<FetchedRecipe>> {
+        let index = self.load_index()?;
+        let Some(entry) = index.recipes.iter().find(|entry| entry.name == recipe_name) else {
+            return Ok(None);
+        };
+        let url = self.entry_url(entry)?;
+        let content = String::from_utf8(fetch_url(&url)?)
+            .map_err(|_| anyhow!("Recipe {} is not valid UTF-8", url))?;
+        let sha256 = sha256_hex(content.as_bytes());
+
+        if let Some(locked) = pin.and_then(|pin| pin.sha256.as_deref()) {
+            if locked != sha256 {
+                return Err(anyhow!(
+                    "Recipe '{}' from {} changed since it was locked (sha256 {} instead of {}). \
+                    Run `goose recipe lock {}` to accept the new version.",
+                    recipe_name,
+                    url,
+                    sha256,
+                    locked,
+                    recipe_name
+                ));
+            }
+        } else if let Some(expected) = entry.sha256.as_deref() {
+            if !expected.eq_ignore_ascii_case(&sha256) {
+                return Err(anyhow!(
+                    "Recipe '{}' from {} does not match the sha256 in the index",
+                    recipe_name,
+                    url
+                ));
+            }
+        }
+
+        let file_name = url
+            .rsplit('/')
+            .next()
+            .filter(|name| {
+                RECIPE_FILE_EXTENSIONS
+                    .iter()
+                    .any(|ext| name.ends_with(&format!(".{}", ext)))
+            })
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("{}.yaml", recipe_name));
+        let file = write_downloaded_recipe(&self.name(), recipe_name, &file_name, &content)?;
+        eprintln!("⬇️  Retrieved recipe \"{}\" from {}", recipe_name, url);
+
+        Ok(Some(FetchedRecipe {
+            file,
+            pin: Some(LockedRecipe {
+                source: self.name(),
+                rev: None,
+                sha256: Some(sha256),
+            }),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+
+    const RECIPE: &str = "version: 1.0.0\ntitle: Notes\ndescription: Notes\nprompt: go\n";
+
+    /// Serves the given paths until the test ends
+    fn serve(files: Arc<Mutex<HashMap<String, String>>>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { continue };
+                let mut request_line = String::new();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                reader.read_line(&mut request_line).unwrap();
+                let mut header = String::new();
+                while reader.read_line(&mut header).unwrap() > 2 {
+                    header.clear();
+                }
+                let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+                let response = match files.lock().unwrap().get(path) {
+                    Some(body) => format!(
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    ),
+                    None => {
+                        "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                            .to_string()
+                    }
+                };
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        format!("http://{}", addr)
+    }
+
+    #[test]
+    fn test_http_index_lists_fetches_and_checks_pins() {
+        let files = Arc::new(Mutex::new(HashMap::from([
+            (
+                "/recipes/index.yaml".to_string(),
+                "recipes:\n  - name: notes\n    url: notes.yaml\n    description: Release notes\n"
+                    .to_string(),
+            ),
+            ("/recipes/notes.yaml".to_string(), RECIPE.to_string()),
+        ])));
+        let base = serve(files.clone());
+        let source = HttpIndexRecipeSource::new(format!("{}/recipes/index.yaml", base));
+
+        let recipes = source.list().unwrap();
+        assert_eq!(recipes.len(), 1);
+        assert_eq!(recipes[0].path, format!("{}/recipes/notes.yaml", base));
+        assert_eq!(recipes[0].description.as_deref(), Some("Release notes"));
+
+        let fetched = source.fetch("notes", None).unwrap().unwrap();
+        assert_eq!(fetched.file.content, RECIPE);
+        assert!(fetched.file.file_path.ends_with("notes.yaml"));
+        let pin = fetched.pin.unwrap();
+        assert_eq!(pin.sha256, Some(sha256_hex(RECIPE.as_bytes())));
+        assert!(source.fetch("missing", None).unwrap().is_none());
+
+        files.lock().unwrap().insert(
+            "/recipes/notes.yaml".to_string(),
+            RECIPE.replace("go", "stop"),
+        );
+        let error = source.fetch("notes", Some(&pin)).err().unwrap();
+        assert!(error.to_string().contains("changed since it was locked"));
+        assert!(source.fetch("notes", None).is_ok());
+    }
+
+    #[test]
+    fn test_http_index_rejects_hash_mismatch() {
+        let files = Arc::new(Mutex::new(HashMap::from([
+            (
+                "/index.yaml".to_string(),
+                "recipes:\n  - name: notes\n    url: notes.yaml\n    sha256: deadbeef\n"
+                    .to_string(),
+            ),
+            ("/notes.yaml".to_string(), RECIPE.to_string()),
+        ])));
+        let source = HttpIndexRecipeSource::new(format!("{}/index.yaml", serve(files)));
+
+        let error = source.fetch("notes", None).err().unwrap();
+
+        assert!(error.to_string().contains("does not match the sha256"));
+    }
+}
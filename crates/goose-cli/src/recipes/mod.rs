@@ -1,6 +1,10 @@
 pub mod extract_from_cli;
-pub mod github_recipe;
+pub mod git_recipe;
+pub mod http_recipe;
 pub mod print_recipe;
 pub mod recipe;
+pub mod recipe_lock;
+pub mod recipe_source;
 pub mod search_recipe;
 pub mod secret_discovery;
+pub mod test_harness;
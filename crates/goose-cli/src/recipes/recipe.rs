@@ -7,13 +7,19 @@ use crate::recipes::secret_discovery::{discover_recipe_secrets, SecretRequiremen
 use anyhow::Result;
 use goose::config::Config;
 use goose::recipe::build_recipe::{
-    apply_values_to_parameters, build_recipe_from_template, validate_recipe_parameters, RecipeError,
+    apply_values_to_parameters, build_recipe_from_template, resolve_recipe_content,
+    uses_recipe_composition, validate_recipe_parameters, RecipeError,
 };
 use goose::recipe::read_recipe_file_content::RecipeFile;
+use goose::recipe::signature::{
+    check_recipe_file_signature, sign_recipe, verify_recipe_signature, SignatureAction,
+    SignatureStatus,
+};
 use goose::recipe::template_recipe::render_recipe_for_preview;
 use goose::recipe::Recipe;
 use serde_json::Value;
 use std::collections::HashMap;
+use std::path::Path;
 
 pub const RECIPE_FILE_EXTENSIONS: &[&str] = &["yaml", "json"];
 
@@ -26,7 +32,8 @@ fn create_user_prompt_callback() -> impl Fn(&str, &str) -> Result<String> {
 }
 
 fn load_recipe_file_with_dir(recipe_name: &str) -> Result<(RecipeFile, String)> {
-    let recipe_file = retrieve_recipe_file(recipe_name)?;
+    let mut recipe_file = retrieve_recipe_file(recipe_name)?;
+    recipe_file.content = resolve_recipe_content(&recipe_file.content, &recipe_file.parent_dir)?;
     let recipe_dir_str = recipe_file
         .parent_dir
         .to_str()
@@ -35,8 +42,98 @@ fn load_recipe_file_with_dir(recipe_name: &str) -> Result<(RecipeFile, String)>
     Ok((recipe_file, recipe_dir_str))
 }
 
+/// Applies the recipe signature policy before a recipe file is used. Recipes that need
+/// confirmation are refused when nobody can be asked.
+fn check_signature(recipe_file: &RecipeFile, interactive: bool) -> Result<()> {
+    let check = check_recipe_file_signature(&recipe_file.content, &recipe_file.parent_dir);
+    match check.action {
+        SignatureAction::Allow => Ok(()),
+        SignatureAction::Warn => {
+            eprintln!("⚠️  {}: {}", recipe_file.file_path.display(), check.message);
+            Ok(())
+        }
+        SignatureAction::Confirm if interactive => {
+            let proceed = cliclack::confirm(format!("{}. Run it anyway?", check.message))
+                .initial_value(false)
+                .interact()?;
+            if proceed {
+                Ok(())
+            } else {
+                Err(anyhow::anyhow!("Recipe was not run: {}", check.message))
+            }
+        }
+        SignatureAction::Confirm | SignatureAction::Block => Err(anyhow::anyhow!(
+            "Refusing to run recipe {}: {}",
+            recipe_file.file_path.display(),
+            check.message
+        )),
+    }
+}
+
+/// Adds a signature to the content of a recipe file, replacing any existing one. YAML recipes
+/// keep their comments and layout. The signature covers the recipe composed with its parents
+/// and included fragments, found relative to `recipe_dir`.
+pub fn sign_recipe_content(content: &str, recipe_dir: &Path, pkcs8: &[u8]) -> Result<String> {
+    let recipe = resolve_recipe_content(content, recipe_dir)
+        .and_then(|composed| Recipe::from_content(&composed))
+        .map_err(|e| {
+            anyhow::anyhow!(
+                "Only recipes that are valid before templating can be signed: {}",
+                e
+            )
+        })?;
+    let signature = sign_recipe(&recipe, pkcs8)?;
+
+    let signed = if let Ok(mut value) = serde_json::from_str::<Value>(content) {
+        let target = match value.get("recipe") {
+            Some(_) => &mut value["recipe"],
+            None => &mut value,
+        };
+        target["signature"] = serde_json::to_value(&signature)?;
+        format!("{}\n", serde_json::to_string_pretty(&value)?)
+    } else {
+        let mut value: serde_yaml::Value = serde_yaml::from_str(content)?;
+        if let Some(nested) = value.get_mut("recipe") {
+            nested["signature"] = serde_yaml::to_value(&signature)?;
+            serde_yaml::to_string(&value)?
+        } else {
+            let mut lines = Vec::new();
+            let mut in_signature = false;
+            for line in content.lines() {
+                if line.starts_with("signature:") {
+                    in_signature = true;
+                    continue;
+                }
+                if in_signature && (line.starts_with(' ') || line.trim().is_empty()) {
+                    continue;
+                }
+                in_signature = false;
+                lines.push(line);
+            }
+            while lines.last().is_some_and(|line| line.trim().is_empty()) {
+                lines.pop();
+            }
+            format!(
+                "{}\nsignature:\n  public_key: \"{}\"\n  value: \"{}\"\n",
+                lines.join("\n"),
+                signature.public_key,
+                signature.value
+            )
+        }
+    };
+
+    let signed_recipe = Recipe::from_content(&resolve_recipe_content(&signed, recipe_dir)?)?;
+    match verify_recipe_signature(&signed_recipe, &[]) {
+        SignatureStatus::Untrusted { .. } => Ok(signed),
+        _ => Err(anyhow::anyhow!(
+            "The signature could not be added to the recipe file"
+        )),
+    }
+}
+
 pub fn load_recipe(recipe_name: &str, params: Vec<(String, String)>) -> Result<Recipe> {
     let recipe_file = retrieve_recipe_file(recipe_name)?;
+    check_signature(&recipe_file, true)?;
     match build_recipe_from_template(recipe_file, params, Some(create_user_prompt_callback())) {
         Ok(recipe) => {
             let secret_requirements = discover_recipe_secrets(&recipe);
@@ -56,6 +153,24 @@ pub fn load_recipe(recipe_name: &str, params: Vec<(String, String)>) -> Result<R
     }
 }
 
+/// Loads a recipe without prompting for parameters or secrets, for unattended runs
+pub fn load_recipe_headless(recipe_name: &str, params: Vec<(String, String)>) -> Result<Recipe> {
+    let recipe_file = retrieve_recipe_file(recipe_name)?;
+    check_signature(&recipe_file, false)?;
+    match build_recipe_from_template(
+        recipe_file,
+        params,
+        None::<fn(&str, &str) -> Result<String>>,
+    ) {
+        Ok(recipe) => Ok(recipe),
+        Err(RecipeError::MissingParams { parameters }) => Err(anyhow::anyhow!(
+            "Missing values for parameters: {}",
+            parameters.join(", ")
+        )),
+        Err(e) => Err(anyhow::anyhow!(e.to_string())),
+    }
+}
+
 /// Collects missing secrets from the user interactively
 ///
 /// This function checks if each required secret exists in the keyring.
@@ -133,6 +248,12 @@ pub fn render_recipe_as_yaml(recipe_name: &str, params: Vec<(String, String)>) -
     }
 }
 
+/// Returns true if the recipe extends another recipe or includes instruction fragments
+pub fn recipe_uses_composition(recipe_name: &str) -> Result<bool> {
+    let recipe_file = retrieve_recipe_file(recipe_name)?;
+    Ok(uses_recipe_composition(&recipe_file.content))
+}
+
 pub fn load_recipe_for_validation(recipe_name: &str) -> Result<Recipe> {
     let (recipe_file, recipe_dir_str) = load_recipe_file_with_dir(recipe_name)?;
     let recipe_file_content = &recipe_file.content;
@@ -185,7 +306,11 @@ fn validate_json_schema(schema: &serde_json::Value) -> Result<()> {
 mod tests {
     use goose::recipe::{RecipeParameterInputType, RecipeParameterRequirement};
 
-    use crate::recipes::recipe::load_recipe;
+    use crate::recipes::recipe::{load_recipe, sign_recipe_content};
+    use goose::recipe::signature::{
+        check_recipe_file_signature, generate_signing_key, SignatureStatus,
+    };
+    use std::path::Path;
 
     mod load_recipe {
         use super::*;
@@ -227,4 +352,44 @@ mod tests {
             assert_eq!(param.description, "A test parameter");
         }
     }
+
+    #[test]
+    fn test_sign_recipe_content_keeps_yaml_layout() {
+        let (pkcs8, _) = generate_signing_key().unwrap();
+        let content = "# Release notes\nversion: 1.0.0\ntitle: Notes\ndescription: Notes\nsignature:\n  public_key: old\n  value: old\n\nprompt: |\n  Summarize {{ since }}\n";
+
+        let signed = sign_recipe_content(content, Path::new("."), &pkcs8).unwrap();
+        assert!(signed.starts_with("# Release notes\nversion: 1.0.0\n"));
+        assert_eq!(signed.matches("signature:").count(), 1);
+        assert!(!signed.contains("public_key: old"));
+        assert!(matches!(
+            check_recipe_file_signature(&signed, Path::new(".")).status,
+            SignatureStatus::Untrusted { .. }
+        ));
+
+        // Signing again replaces the signature instead of adding another one
+        let resigned = sign_recipe_content(&signed, Path::new("."), &pkcs8).unwrap();
+        assert_eq!(resigned.matches("signature:").count(), 1);
+
+        let tampered = signed.replace("Summarize", "Delete");
+        assert!(matches!(
+            check_recipe_file_signature(&tampered, Path::new(".")).status,
+            SignatureStatus::Invalid { .. }
+        ));
+    }
+
+    #[test]
+    fn test_sign_recipe_content_json() {
+        let (pkcs8, _) = generate_signing_key().unwrap();
+        let content = r#"{"recipe": {"title": "Notes", "description": "Notes", "prompt": "go"}}"#;
+
+        let signed = sign_recipe_content(content, Path::new("."), &pkcs8).unwrap();
+
+        let value: serde_json::Value = serde_json::from_str(&signed).unwrap();
+        assert!(value["recipe"]["signature"]["value"].is_string());
+        assert!(matches!(
+            check_recipe_file_signature(&signed, Path::new(".")).status,
+            SignatureStatus::Untrusted { .. }
+        ));
+    }
 }
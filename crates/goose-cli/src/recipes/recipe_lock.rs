@@ -0,0 +1,146 @@
+//! `goose-recipes.lock` pins recipes fetched from remote sources so a project keeps running the
+//! same recipe until the pin is updated with `goose recipe lock`.
+//!
+//! ```yaml
+//! recipes:
+//!   release-notes:
+//!     source: "git: https://github.com/my-org/goose-recipes.git"
+//!     rev: 3f0c2a9d51b8c1e4f7a6b2d0e9c8a7b6f5e4d3c2
+//!   triage:
+//!     source: "http: https://example.com/recipes/index.yaml"
+//!     sha256: 9b74c9897bac770ffc029102a200c5de...
+//! ```
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const RECIPE_LOCK_FILE_NAME: &str = "goose-recipes.lock";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockedRecipe {
+    /// Name of the source the recipe was fetched from
+    pub source: String,
+    /// Git commit the recipe is pinned to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rev: Option<String>,
+    /// Hash of the recipe content, for sources without revisions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RecipeLock {
+    #[serde(default)]
+    pub recipes: BTreeMap<String, LockedRecipe>,
+    #[serde(skip)]
+    path: PathBuf,
+    #[serde(skip)]
+    exists: bool,
+}
+
+impl RecipeLock {
+    /// The lockfile in the current directory
+    pub fn load_default() -> Result<Self> {
+        Self::load(Path::new(RECIPE_LOCK_FILE_NAME))
+    }
+
+    /// Reads a lockfile, or returns an empty one that has not been written yet
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self {
+                path: path.to_path_buf(),
+                ..Default::default()
+            });
+        }
+        let content = fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?;
+        let mut lock: RecipeLock = if content.trim().is_empty() {
+            RecipeLock::default()
+        } else {
+            serde_yaml::from_str(&content)
+                .map_err(|e| anyhow!("Invalid lockfile {}: {}", path.display(), e))?
+        };
+        lock.path = path.to_path_buf();
+        lock.exists = true;
+        Ok(lock)
+    }
+
+    /// Pins are only recorded automatically once a project has opted in by creating the file
+    pub fn exists(&self) -> bool {
+        self.exists
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The pin for a recipe, if it was locked from the given source
+    pub fn get(&self, recipe_name: &str, source: &str) -> Option<&LockedRecipe> {
+        self.recipes
+            .get(recipe_name)
+            .filter(|locked| locked.source == source)
+    }
+
+    /// Records a pin, returning true if it changed
+    pub fn record(&mut self, recipe_name: &str, locked: LockedRecipe) -> bool {
+        if self.recipes.get(recipe_name) == Some(&locked) {
+            return false;
+        }
+        self.recipes.insert(recipe_name.to_string(), locked);
+        true
+    }
+
+    pub fn save(&mut self) -> Result<()> {
+        let content = serde_yaml::to_string(self)?;
+        fs::write(&self.path, content)
+            .map_err(|e| anyhow!("Failed to write {}: {}", self.path.display(), e))?;
+        self.exists = true;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_lock_round_trip_and_source_match() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(RECIPE_LOCK_FILE_NAME);
+
+        let mut lock = RecipeLock::load(&path).unwrap();
+        assert!(!lock.exists());
+        let pin = LockedRecipe {
+            source: "git: https://example.com/recipes.git".to_string(),
+            rev: Some("abc123".to_string()),
+            sha256: None,
+        };
+        assert!(lock.record("notes", pin.clone()));
+        assert!(!lock.record("notes", pin.clone()));
+        lock.save().unwrap();
+
+        let lock = RecipeLock::load(&path).unwrap();
+        assert!(lock.exists());
+        assert_eq!(
+            lock.get("notes", "git: https://example.com/recipes.git"),
+            Some(&pin)
+        );
+        assert_eq!(lock.get("notes", "local: ."), None);
+    }
+
+    #[test]
+    fn test_empty_lockfile_opts_in() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(RECIPE_LOCK_FILE_NAME);
+        fs::write(&path, "").unwrap();
+
+        let lock = RecipeLock::load(&path).unwrap();
+
+        assert!(lock.exists());
+        assert!(lock.recipes.is_empty());
+    }
+}
@@ -0,0 +1,312 @@
+use anyhow::{anyhow, Result};
+use goose::config::Config;
+use goose::recipe::read_recipe_file_content::{read_recipe_file, RecipeFile};
+use goose::recipe::template_recipe::parse_recipe_content;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::recipes::git_recipe::{GitRecipeSource, GOOSE_RECIPE_GITHUB_REPO_CONFIG_KEY};
+use crate::recipes::http_recipe::HttpIndexRecipeSource;
+use crate::recipes::recipe::RECIPE_FILE_EXTENSIONS;
+use crate::recipes::recipe_lock::LockedRecipe;
+
+pub const GOOSE_RECIPE_SOURCES_CONFIG_KEY: &str = "GOOSE_RECIPE_SOURCES";
+const GOOSE_RECIPE_PATH_ENV_VAR: &str = "GOOSE_RECIPE_PATH";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecipeInfo {
+    pub name: String,
+    /// Name of the source the recipe was found in, e.g. `local: ./recipes`
+    pub source: String,
+    pub path: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+}
+
+pub struct FetchedRecipe {
+    pub file: RecipeFile,
+    /// Pin to record in the lockfile, for sources that can be pinned
+    pub pin: Option<LockedRecipe>,
+}
+
+/// A place recipes can be looked up by name.
+pub trait RecipeSource {
+    /// Identifies the source in listings and in the lockfile
+    fn name(&self) -> String;
+
+    fn list(&self) -> Result<Vec<RecipeInfo>>;
+
+    /// Fetches a recipe, honouring `pin` when given. Returns `Ok(None)` if the source does not
+    /// have a recipe with that name.
+    fn fetch(&self, recipe_name: &str, pin: Option<&LockedRecipe>)
+        -> Result<Option<FetchedRecipe>>;
+}
+
+/// An entry of `GOOSE_RECIPE_SOURCES` in the config file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum RecipeSourceConfig {
+    /// A directory of recipe files
+    Local { path: String },
+    /// A git remote over https or ssh with one recipe per directory or file
+    Git {
+        url: String,
+        /// Branch, tag or commit to use, defaults to the remote's default branch
+        #[serde(default, rename = "ref", skip_serializing_if = "Option::is_none")]
+        git_ref: Option<String>,
+        /// Directory in the repository that holds the recipes
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        path: Option<String>,
+    },
+    /// An index file listing recipe URLs
+    Http { url: String },
+}
+
+impl RecipeSourceConfig {
+    pub fn into_source(self) -> Box<dyn RecipeSource> {
+        match self {
+            RecipeSourceConfig::Local { path } => Box::new(LocalRecipeSource::new(path)),
+            RecipeSourceConfig::Git { url, git_ref, path } => {
+                Box::new(GitRecipeSource::new(url, git_ref, path))
+            }
+            RecipeSourceConfig::Http { url } => Box::new(HttpIndexRecipeSource::new(url)),
+        }
+    }
+}
+
+/// Recipe sources in lookup order: the current directory, `GOOSE_RECIPE_PATH`, the sources
+/// configured in `GOOSE_RECIPE_SOURCES`, then the legacy `GOOSE_RECIPE_GITHUB_REPO`.
+pub fn configured_recipe_sources() -> Vec<Box<dyn RecipeSource>> {
+    let mut configs = vec![RecipeSourceConfig::Local {
+        path: ".".to_string(),
+    }];
+    if let Ok(recipe_path_env) = env::var(GOOSE_RECIPE_PATH_ENV_VAR) {
+        configs.extend(
+            env::split_paths(&recipe_path_env).map(|dir| RecipeSourceConfig::Local {
+                path: dir.to_string_lossy().into_owned(),
+            }),
+        );
+    }
+
+    let config = Config::global();
+    match config.get_param::<Vec<RecipeSourceConfig>>(GOOSE_RECIPE_SOURCES_CONFIG_KEY) {
+        Ok(sources) => configs.extend(sources),
+        Err(goose::config::ConfigError::NotFound(_)) => {}
+        Err(e) => tracing::warn!(
+            "Ignoring invalid {}: {}",
+            GOOSE_RECIPE_SOURCES_CONFIG_KEY,
+            e
+        ),
+    }
+    if let Ok(Some(repo)) = config.get_param::<Option<String>>(GOOSE_RECIPE_GITHUB_REPO_CONFIG_KEY)
+    {
+        configs.push(RecipeSourceConfig::Git {
+            url: format!("https://github.com/{}.git", repo),
+            git_ref: None,
+            path: None,
+        });
+    }
+
+    configs
+        .into_iter()
+        .map(RecipeSourceConfig::into_source)
+        .collect()
+}
+
+pub struct LocalRecipeSource {
+    dir: PathBuf,
+}
+
+impl LocalRecipeSource {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+impl RecipeSource for LocalRecipeSource {
+    fn name(&self) -> String {
+        format!("local: {}", self.dir.display())
+    }
+
+    fn list(&self) -> Result<Vec<RecipeInfo>> {
+        let mut recipes = Vec::new();
+        if !self.dir.is_dir() {
+            return Ok(recipes);
+        }
+
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            let is_recipe = path.is_file()
+                && path.extension().is_some_and(|ext| {
+                    RECIPE_FILE_EXTENSIONS.contains(&ext.to_string_lossy().as_ref())
+                });
+            if !is_recipe {
+                continue;
+            }
+            let Ok(content) = fs::read_to_string(&path) else {
+                continue;
+            };
+            let name = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("unknown")
+                .to_string();
+            if let Ok(recipe_info) =
+                recipe_info_from_content(&content, name, self.name(), path.to_string_lossy())
+            {
+                recipes.push(recipe_info);
+            }
+        }
+        recipes.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(recipes)
+    }
+
+    fn fetch(
+        &self,
+        recipe_name: &str,
+        _pin: Option<&LockedRecipe>,
+    ) -> Result<Option<FetchedRecipe>> {
+        for ext in RECIPE_FILE_EXTENSIONS {
+            let recipe_path = self.dir.join(format!("{}.{}", recipe_name, ext));
+            if recipe_path.is_file() {
+                return Ok(Some(FetchedRecipe {
+                    file: read_recipe_file(recipe_path)?,
+                    pin: None,
+                }));
+            }
+        }
+        Ok(None)
+    }
+}
+
+pub fn recipe_info_from_content(
+    content: &str,
+    name: String,
+    source: String,
+    path: impl Into<String>,
+) -> Result<RecipeInfo> {
+    let path = path.into();
+    let recipe_dir = Path::new(&path)
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .to_string_lossy()
+        .to_string();
+    let (recipe, _) = parse_recipe_content(content, recipe_dir)?;
+    Ok(RecipeInfo {
+        name,
+        source,
+        path,
+        title: Some(recipe.title),
+        description: Some(recipe.description),
+    })
+}
+
+/// Writes a downloaded recipe to a scratch directory so it can be loaded like a local file
+pub fn write_downloaded_recipe(
+    source_key: &str,
+    recipe_name: &str,
+    file_name: &str,
+    content: &str,
+) -> Result<RecipeFile> {
+    let dir = download_dir(source_key, recipe_name)?;
+    let file_path = dir.join(file_name);
+    fs::write(&file_path, content)?;
+    Ok(RecipeFile {
+        content: content.to_string(),
+        parent_dir: dir,
+        file_path,
+    })
+}
+
+/// An empty scratch directory for a recipe downloaded from a source
+pub fn download_dir(source_key: &str, recipe_name: &str) -> Result<PathBuf> {
+    let dir = env::temp_dir()
+        .join("goose-recipes")
+        .join(&sha256_hex(source_key.as_bytes())[..16])
+        .join(recipe_name);
+    if dir.exists() {
+        fs::remove_dir_all(&dir)?;
+    }
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+pub fn sha256_hex(content: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(content))
+}
+
+/// Downloads a URL from synchronous code, whether or not a tokio runtime is running
+pub fn fetch_url(url: &str) -> Result<Vec<u8>> {
+    let url = url.to_string();
+    std::thread::spawn(move || {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        runtime.block_on(async {
+            let response = reqwest::get(&url)
+                .await
+                .map_err(|e| anyhow!("Failed to fetch {}: {}", url, e))?
+                .error_for_status()
+                .map_err(|e| anyhow!("Failed to fetch {}: {}", url, e))?;
+            Ok(response.bytes().await?.to_vec())
+        })
+    })
+    .join()
+    .map_err(|_| anyhow!("Download thread panicked"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const RECIPE: &str =
+        "version: 1.0.0\ntitle: Notes\ndescription: Write release notes\nprompt: go\n";
+
+    #[test]
+    fn test_source_config_from_yaml() {
+        let sources: Vec<RecipeSourceConfig> = serde_yaml::from_str(
+            "- type: local\n  path: ~/recipes\n- type: git\n  url: git@github.com:org/recipes.git\n  ref: v1.2\n- type: http\n  url: https://example.com/index.yaml\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            sources,
+            vec![
+                RecipeSourceConfig::Local {
+                    path: "~/recipes".to_string()
+                },
+                RecipeSourceConfig::Git {
+                    url: "git@github.com:org/recipes.git".to_string(),
+                    git_ref: Some("v1.2".to_string()),
+                    path: None
+                },
+                RecipeSourceConfig::Http {
+                    url: "https://example.com/index.yaml".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_local_source_lists_and_fetches() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("notes.yaml"), RECIPE).unwrap();
+        fs::write(temp_dir.path().join("readme.md"), "not a recipe").unwrap();
+        let source = LocalRecipeSource::new(temp_dir.path());
+
+        let recipes = source.list().unwrap();
+        assert_eq!(recipes.len(), 1);
+        assert_eq!(recipes[0].name, "notes");
+        assert_eq!(recipes[0].source, source.name());
+        assert_eq!(recipes[0].title.as_deref(), Some("Notes"));
+
+        let fetched = source.fetch("notes", None).unwrap().unwrap();
+        assert_eq!(fetched.file.content, RECIPE);
+        assert!(fetched.pin.is_none());
+        assert!(source.fetch("missing", None).unwrap().is_none());
+    }
+}
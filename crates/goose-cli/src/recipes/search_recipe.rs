@@ -1,19 +1,10 @@
 use anyhow::{anyhow, Result};
-use goose::config::Config;
 use goose::recipe::read_recipe_file_content::{read_recipe_file, RecipeFile};
-use goose::recipe::template_recipe::parse_recipe_content;
-use std::env;
-use std::fs;
 use std::path::{Path, PathBuf};
 
 use crate::recipes::recipe::RECIPE_FILE_EXTENSIONS;
-
-use super::github_recipe::{
-    list_github_recipes, retrieve_recipe_from_github, RecipeInfo, RecipeSource,
-    GOOSE_RECIPE_GITHUB_REPO_CONFIG_KEY,
-};
-
-const GOOSE_RECIPE_PATH_ENV_VAR: &str = "GOOSE_RECIPE_PATH";
+use crate::recipes::recipe_lock::RecipeLock;
+use crate::recipes::recipe_source::{configured_recipe_sources, RecipeInfo, RecipeSource};
 
 pub fn retrieve_recipe_file(recipe_name: &str) -> Result<RecipeFile> {
     if RECIPE_FILE_EXTENSIONS
@@ -29,13 +20,46 @@ pub fn retrieve_recipe_file(recipe_name: &str) -> Result<RecipeFile> {
             recipe_name
         ));
     }
-    retrieve_recipe_from_local_path(recipe_name).or_else(|e| {
-        if let Some(recipe_repo_full_name) = configured_github_recipe_repo() {
-            retrieve_recipe_from_github(recipe_name, &recipe_repo_full_name)
-        } else {
-            Err(e)
+
+    let mut lock = RecipeLock::load_default()?;
+    let file = retrieve_from_sources(&configured_recipe_sources(), recipe_name, &mut lock, false)?;
+    if lock.exists() {
+        lock.save()?;
+    }
+    Ok(file)
+}
+
+/// Looks a recipe up in each source in order. Pins in `lock` are honoured unless `update` is
+/// set, and the pin of the recipe that was found is recorded.
+fn retrieve_from_sources(
+    sources: &[Box<dyn RecipeSource>],
+    recipe_name: &str,
+    lock: &mut RecipeLock,
+    update: bool,
+) -> Result<RecipeFile> {
+    let mut failures = Vec::new();
+    for source in sources {
+        let source_name = source.name();
+        let pin = lock
+            .get(recipe_name, &source_name)
+            .filter(|_| !update)
+            .cloned();
+        match source.fetch(recipe_name, pin.as_ref()) {
+            Ok(Some(fetched)) => {
+                if let Some(pin) = fetched.pin {
+                    lock.record(recipe_name, pin);
+                }
+                return Ok(fetched.file);
+            }
+            Ok(None) => failures.push(format!("  {}: not found", source_name)),
+            Err(e) => failures.push(format!("  {}: {}", source_name, e)),
         }
-    })
+    }
+    Err(anyhow!(
+        "ℹ️  Failed to retrieve recipe '{}' from any recipe source:\n{}",
+        recipe_name,
+        failures.join("\n")
+    ))
 }
 
 fn is_file_path(recipe_name: &str) -> bool {
@@ -49,146 +73,126 @@ fn is_file_name(recipe_name: &str) -> bool {
     Path::new(recipe_name).extension().is_some()
 }
 
-fn read_recipe_in_dir(dir: &Path, recipe_name: &str) -> Result<RecipeFile> {
-    for ext in RECIPE_FILE_EXTENSIONS {
-        let recipe_path = dir.join(format!("{}.{}", recipe_name, ext));
-        if let Ok(result) = read_recipe_file(recipe_path) {
-            return Ok(result);
+/// Lists the recipes of every configured source, in lookup order
+pub fn list_available_recipes() -> Result<Vec<RecipeInfo>> {
+    let mut recipes = Vec::new();
+    for source in configured_recipe_sources() {
+        match source.list() {
+            Ok(source_recipes) => recipes.extend(source_recipes),
+            Err(e) => tracing::warn!("Failed to list recipes from {}: {}", source.name(), e),
         }
     }
-    Err(anyhow!(format!(
-        "No {}.yaml or {}.json recipe file found in directory: {}",
-        recipe_name,
-        recipe_name,
-        dir.display()
-    )))
+    Ok(recipes)
 }
 
-fn retrieve_recipe_from_local_path(recipe_name: &str) -> Result<RecipeFile> {
-    let mut search_dirs = vec![PathBuf::from(".")];
-    if let Ok(recipe_path_env) = env::var(GOOSE_RECIPE_PATH_ENV_VAR) {
-        let path_separator = if cfg!(windows) { ';' } else { ':' };
-        let recipe_path_env_dirs: Vec<PathBuf> = recipe_path_env
-            .split(path_separator)
-            .map(PathBuf::from)
-            .collect();
-        search_dirs.extend(recipe_path_env_dirs);
-    }
-    for dir in &search_dirs {
-        if let Ok(result) = read_recipe_in_dir(dir, recipe_name) {
-            return Ok(result);
-        }
+/// Pins recipes from remote sources to their current version in the lockfile, creating it if
+/// needed. With no names, every recipe the lockfile already pins is updated.
+pub fn lock_recipes(recipe_names: &[String]) -> Result<RecipeLock> {
+    let mut lock = RecipeLock::load_default()?;
+    let names: Vec<String> = if recipe_names.is_empty() {
+        lock.recipes.keys().cloned().collect()
+    } else {
+        recipe_names.to_vec()
+    };
+
+    let sources = configured_recipe_sources();
+    for name in &names {
+        retrieve_from_sources(&sources, name, &mut lock, true)?;
     }
-    let search_dirs_str = search_dirs
-        .iter()
-        .map(|p| p.to_string_lossy())
-        .collect::<Vec<_>>()
-        .join(":");
-    Err(anyhow!(
-        "ℹ️  Failed to retrieve {}.yaml or {}.json in {}",
-        recipe_name,
-        recipe_name,
-        search_dirs_str
-    ))
+    lock.save()?;
+    Ok(lock)
 }
 
-fn configured_github_recipe_repo() -> Option<String> {
-    let config = Config::global();
-    match config.get_param(GOOSE_RECIPE_GITHUB_REPO_CONFIG_KEY) {
-        Ok(Some(recipe_repo_full_name)) => Some(recipe_repo_full_name),
-        _ => None,
-    }
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recipes::recipe_lock::{LockedRecipe, RECIPE_LOCK_FILE_NAME};
+    use crate::recipes::recipe_source::{FetchedRecipe, LocalRecipeSource};
+    use std::fs;
+    use tempfile::TempDir;
 
-/// Lists all available recipes from local paths and GitHub repositories
-pub fn list_available_recipes() -> Result<Vec<RecipeInfo>> {
-    let mut recipes = Vec::new();
+    const RECIPE: &str = "version: 1.0.0\ntitle: Notes\ndescription: Notes\nprompt: go\n";
 
-    // Search local recipes
-    if let Ok(local_recipes) = discover_local_recipes() {
-        recipes.extend(local_recipes);
+    /// A remote-like source that pins everything it serves to one revision
+    struct PinnedSource {
+        dir: PathBuf,
+        rev: &'static str,
     }
 
-    // Search GitHub recipes if configured
-    if let Some(repo) = configured_github_recipe_repo() {
-        if let Ok(github_recipes) = list_github_recipes(&repo) {
-            recipes.extend(github_recipes);
+    impl RecipeSource for PinnedSource {
+        fn name(&self) -> String {
+            "test: remote".to_string()
         }
-    }
-
-    Ok(recipes)
-}
-
-fn discover_local_recipes() -> Result<Vec<RecipeInfo>> {
-    let mut recipes = Vec::new();
-    let mut search_dirs = vec![PathBuf::from(".")];
-
-    // Add GOOSE_RECIPE_PATH directories
-    if let Ok(recipe_path_env) = env::var(GOOSE_RECIPE_PATH_ENV_VAR) {
-        let path_separator = if cfg!(windows) { ';' } else { ':' };
-        let recipe_path_env_dirs: Vec<PathBuf> = recipe_path_env
-            .split(path_separator)
-            .map(PathBuf::from)
-            .collect();
-        search_dirs.extend(recipe_path_env_dirs);
-    }
 
-    for dir in search_dirs {
-        if let Ok(dir_recipes) = scan_directory_for_recipes(&dir) {
-            recipes.extend(dir_recipes);
+        fn list(&self) -> Result<Vec<RecipeInfo>> {
+            Ok(vec![])
         }
-    }
-
-    Ok(recipes)
-}
-
-fn scan_directory_for_recipes(dir: &Path) -> Result<Vec<RecipeInfo>> {
-    let mut recipes = Vec::new();
-
-    if !dir.exists() || !dir.is_dir() {
-        return Ok(recipes);
-    }
 
-    for entry in fs::read_dir(dir)? {
-        let entry = entry?;
-        let path = entry.path();
-
-        if path.is_file() {
-            if let Some(extension) = path.extension() {
-                if RECIPE_FILE_EXTENSIONS.contains(&extension.to_string_lossy().as_ref()) {
-                    if let Ok(recipe_info) = create_local_recipe_info(&path) {
-                        recipes.push(recipe_info);
-                    }
-                }
+        fn fetch(
+            &self,
+            recipe_name: &str,
+            pin: Option<&LockedRecipe>,
+        ) -> Result<Option<FetchedRecipe>> {
+            if pin.is_some_and(|pin| pin.rev.as_deref() != Some(self.rev)) {
+                return Err(anyhow!("pinned to another revision"));
             }
+            let Some(fetched) = LocalRecipeSource::new(&self.dir).fetch(recipe_name, None)? else {
+                return Ok(None);
+            };
+            Ok(Some(FetchedRecipe {
+                file: fetched.file,
+                pin: Some(LockedRecipe {
+                    source: self.name(),
+                    rev: Some(self.rev.to_string()),
+                    sha256: None,
+                }),
+            }))
         }
     }
 
-    Ok(recipes)
-}
-
-fn create_local_recipe_info(path: &Path) -> Result<RecipeInfo> {
-    let content = fs::read_to_string(path)?;
-    let recipe_dir = path
-        .parent()
-        .unwrap_or_else(|| Path::new("."))
-        .to_string_lossy()
-        .to_string();
-    let (recipe, _) = parse_recipe_content(&content, recipe_dir)?;
-
-    let name = path
-        .file_stem()
-        .and_then(|s| s.to_str())
-        .unwrap_or("unknown")
-        .to_string();
-
-    let path_str = path.to_string_lossy().to_string();
-
-    Ok(RecipeInfo {
-        name,
-        source: RecipeSource::Local,
-        path: path_str,
-        title: Some(recipe.title),
-        description: Some(recipe.description),
-    })
+    #[test]
+    fn test_sources_are_searched_in_order_and_pins_recorded() {
+        let temp_dir = TempDir::new().unwrap();
+        let local = temp_dir.path().join("local");
+        let remote = temp_dir.path().join("remote");
+        fs::create_dir_all(&local).unwrap();
+        fs::create_dir_all(&remote).unwrap();
+        fs::write(local.join("notes.yaml"), RECIPE).unwrap();
+        fs::write(remote.join("notes.yaml"), RECIPE).unwrap();
+        fs::write(remote.join("triage.yaml"), RECIPE).unwrap();
+
+        let sources: Vec<Box<dyn RecipeSource>> = vec![
+            Box::new(LocalRecipeSource::new(&local)),
+            Box::new(PinnedSource {
+                dir: remote.clone(),
+                rev: "v2",
+            }),
+        ];
+        let mut lock = RecipeLock::load(&temp_dir.path().join(RECIPE_LOCK_FILE_NAME)).unwrap();
+
+        let notes = retrieve_from_sources(&sources, "notes", &mut lock, false).unwrap();
+        assert!(notes.file_path.starts_with(local.canonicalize().unwrap()));
+        assert!(lock.recipes.is_empty());
+
+        retrieve_from_sources(&sources, "triage", &mut lock, false).unwrap();
+        assert_eq!(lock.recipes["triage"].rev.as_deref(), Some("v2"));
+
+        lock.record(
+            "triage",
+            LockedRecipe {
+                source: "test: remote".to_string(),
+                rev: Some("v1".to_string()),
+                sha256: None,
+            },
+        );
+        let error = retrieve_from_sources(&sources, "triage", &mut lock, false)
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(error.contains("test: remote: pinned to another revision"));
+        assert!(error.contains(&format!("local: {}: not found", local.display())));
+
+        retrieve_from_sources(&sources, "triage", &mut lock, true).unwrap();
+        assert_eq!(lock.recipes["triage"].rev.as_deref(), Some("v2"));
+    }
 }
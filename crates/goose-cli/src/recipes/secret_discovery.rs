@@ -173,6 +173,7 @@ mod tests {
             response: None,
             sub_recipes: None,
             retry: None,
+            signature: None,
         }
     }
 
@@ -217,6 +218,7 @@ mod tests {
             response: None,
             sub_recipes: None,
             retry: None,
+            signature: None,
         };
 
         let secrets = discover_recipe_secrets(&recipe);
@@ -262,6 +264,7 @@ mod tests {
             response: None,
             sub_recipes: None,
             retry: None,
+            signature: None,
         };
 
         let secrets = discover_recipe_secrets(&recipe);
@@ -307,6 +310,8 @@ mod tests {
                 values: None,
                 sequential_when_repeated: false,
                 description: None,
+                depends_on: None,
+                inputs: None,
             }]),
             context: None,
             settings: None,
@@ -315,6 +320,7 @@ mod tests {
             parameters: None,
             response: None,
             retry: None,
+            signature: None,
         };
 
         let secrets = discover_recipe_secrets(&recipe);
@@ -0,0 +1,380 @@
+//! Sidecar test files for `goose recipe test`.
+//!
+//! A recipe `my-recipe.yaml` is tested by `my-recipe.test.yaml` next to it:
+//!
+//! ```yaml
+//! fixture: fixtures/my-recipe.json   # optional recorded provider responses
+//! cases:
+//!   - name: rust project
+//!     params:
+//!       language: rust
+//!     expected_tool_calls:
+//!       - developer__shell
+//!     assertions:
+//!       - contains: "cargo test"
+//!       - matches: "\\d+ tests? passed"
+//! ```
+
+use anyhow::{anyhow, Result};
+use goose::conversation::message::Message;
+use goose::conversation::Conversation;
+use goose_bench::eval_suites::{used_tool, EvalMetricValue};
+use regex::Regex;
+use rmcp::model::Role;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+pub const TEST_FILE_SUFFIX: &str = "test.yaml";
+
+#[derive(Debug, Deserialize)]
+pub struct RecipeTestFile {
+    /// Recorded provider responses used by every case, relative to the test file
+    #[serde(default)]
+    pub fixture: Option<String>,
+    pub cases: Vec<RecipeTestCase>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RecipeTestCase {
+    pub name: String,
+    /// Recipe parameter values for this case
+    #[serde(default)]
+    pub params: BTreeMap<String, serde_yaml::Value>,
+    /// Prompt to run instead of the recipe's own prompt
+    #[serde(default)]
+    pub prompt: Option<String>,
+    /// Recorded provider responses for this case, overriding the file-level fixture
+    #[serde(default)]
+    pub fixture: Option<String>,
+    /// Tools that must be called during the run
+    #[serde(default)]
+    pub expected_tool_calls: Vec<String>,
+    /// Checks on the final assistant message
+    #[serde(default, with = "serde_yaml::with::singleton_map_recursive")]
+    pub assertions: Vec<OutputAssertion>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputAssertion {
+    Contains(String),
+    NotContains(String),
+    Matches(String),
+    Equals(String),
+}
+
+#[derive(Debug, Serialize)]
+pub struct CaseResult {
+    pub name: String,
+    pub passed: bool,
+    pub duration_secs: f64,
+    pub failures: Vec<String>,
+    /// Metrics collected by goose-bench for the run
+    pub metrics: BTreeMap<String, EvalMetricValue>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RecipeTestReport {
+    pub recipe: String,
+    pub passed: usize,
+    pub failed: usize,
+    pub cases: Vec<CaseResult>,
+}
+
+/// The fixture a case runs against, and whether it replays it or records into it
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaseFixture {
+    pub path: PathBuf,
+    pub replay: bool,
+}
+
+impl RecipeTestFile {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read test file {}: {}", path.display(), e))?;
+        let file: RecipeTestFile = serde_yaml::from_str(&content)
+            .map_err(|e| anyhow!("Invalid test file {}: {}", path.display(), e))?;
+        if file.cases.is_empty() {
+            return Err(anyhow!("Test file {} has no cases", path.display()));
+        }
+        Ok(file)
+    }
+
+    /// The fixture of each case, resolved against `test_dir`. Whether a fixture is replayed
+    /// is decided before any case runs, so the cases sharing a fixture all record into it on
+    /// the first run and all replay it on later runs.
+    pub fn case_fixtures(&self, test_dir: &Path) -> Vec<Option<CaseFixture>> {
+        self.cases
+            .iter()
+            .map(|case| {
+                let path = test_dir.join(case.fixture.as_ref().or(self.fixture.as_ref())?);
+                Some(CaseFixture {
+                    replay: path.exists(),
+                    path,
+                })
+            })
+            .collect()
+    }
+}
+
+impl RecipeTestCase {
+    /// Parameter values as the strings recipe templating expects
+    pub fn param_values(&self) -> Vec<(String, String)> {
+        self.params
+            .iter()
+            .map(|(key, value)| {
+                let value = match value {
+                    serde_yaml::Value::String(s) => s.clone(),
+                    other => serde_yaml::to_string(other)
+                        .unwrap_or_default()
+                        .trim()
+                        .to_string(),
+                };
+                (key.clone(), value)
+            })
+            .collect()
+    }
+}
+
+impl RecipeTestReport {
+    pub fn new(recipe: String, cases: Vec<CaseResult>) -> Self {
+        let passed = cases.iter().filter(|case| case.passed).count();
+        Self {
+            recipe,
+            failed: cases.len() - passed,
+            passed,
+            cases,
+        }
+    }
+
+    pub fn to_junit_xml(&self) -> String {
+        let total_time: f64 = self.cases.iter().map(|case| case.duration_secs).sum();
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str(&format!(
+            "<testsuites tests=\"{}\" failures=\"{}\" time=\"{:.3}\">\n",
+            self.cases.len(),
+            self.failed,
+            total_time
+        ));
+        xml.push_str(&format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" time=\"{:.3}\">\n",
+            xml_escape(&self.recipe),
+            self.cases.len(),
+            self.failed,
+            total_time
+        ));
+        for case in &self.cases {
+            xml.push_str(&format!(
+                "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\">\n",
+                xml_escape(&case.name),
+                xml_escape(&self.recipe),
+                case.duration_secs
+            ));
+            if !case.metrics.is_empty() {
+                xml.push_str("      <properties>\n");
+                for (name, value) in &case.metrics {
+                    xml.push_str(&format!(
+                        "        <property name=\"{}\" value=\"{}\"/>\n",
+                        xml_escape(name),
+                        xml_escape(&value.to_string())
+                    ));
+                }
+                xml.push_str("      </properties>\n");
+            }
+            if let Some(first) = case.failures.first() {
+                xml.push_str(&format!(
+                    "      <failure message=\"{}\">{}</failure>\n",
+                    xml_escape(first),
+                    xml_escape(&case.failures.join("\n"))
+                ));
+            }
+            xml.push_str("    </testcase>\n");
+        }
+        xml.push_str("  </testsuite>\n</testsuites>\n");
+        xml
+    }
+}
+
+/// The sidecar test file for a recipe: `name.yaml` is tested by `name.test.yaml`
+pub fn default_test_file(recipe_path: &Path) -> PathBuf {
+    let stem = recipe_path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("recipe");
+    recipe_path.with_file_name(format!("{}.{}", stem, TEST_FILE_SUFFIX))
+}
+
+/// Checks a finished run against the case's expectations and returns every failure
+pub fn evaluate_case(case: &RecipeTestCase, conversation: &Conversation) -> Vec<String> {
+    let messages = conversation.messages();
+    let mut failures: Vec<String> = case
+        .expected_tool_calls
+        .iter()
+        .filter(|tool| !used_tool(messages, tool))
+        .map(|tool| format!("expected a call to tool '{}'", tool))
+        .collect();
+
+    if case.assertions.is_empty() {
+        return failures;
+    }
+    let output = final_assistant_text(messages);
+    for assertion in &case.assertions {
+        if let Err(failure) = check_assertion(assertion, &output) {
+            failures.push(failure);
+        }
+    }
+    failures
+}
+
+fn final_assistant_text(messages: &[Message]) -> String {
+    messages
+        .iter()
+        .rev()
+        .filter(|message| message.role == Role::Assistant)
+        .map(|message| message.as_concat_text())
+        .find(|text| !text.trim().is_empty())
+        .unwrap_or_default()
+}
+
+fn check_assertion(assertion: &OutputAssertion, output: &str) -> Result<(), String> {
+    let passed = match assertion {
+        OutputAssertion::Contains(expected) => output.contains(expected.as_str()),
+        OutputAssertion::NotContains(unexpected) => !output.contains(unexpected.as_str()),
+        OutputAssertion::Equals(expected) => output.trim() == expected.trim(),
+        OutputAssertion::Matches(pattern) => Regex::new(pattern)
+            .map_err(|e| format!("invalid pattern '{}': {}", pattern, e))?
+            .is_match(output),
+    };
+    if passed {
+        Ok(())
+    } else {
+        Err(format!(
+            "final output failed {}",
+            serde_json::to_string(assertion).unwrap_or_default()
+        ))
+    }
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_core::tool::ToolCall;
+    use serde_json::json;
+
+    fn case_from_yaml(yaml: &str) -> RecipeTestCase {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_default_test_file_sits_next_to_recipe() {
+        assert_eq!(
+            default_test_file(Path::new("/recipes/release-notes.yaml")),
+            PathBuf::from("/recipes/release-notes.test.yaml")
+        );
+    }
+
+    #[test]
+    fn test_case_fixtures_record_until_the_fixture_exists() {
+        let dir = tempfile::tempdir().unwrap();
+        let file: RecipeTestFile = serde_yaml::from_str(
+            "fixture: shared.json\ncases:\n  - name: one\n  - name: two\n  - name: own\n    fixture: own.json\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("own.json"), "{}").unwrap();
+
+        let fixtures = file.case_fixtures(dir.path());
+        let shared = Some(CaseFixture {
+            path: dir.path().join("shared.json"),
+            replay: false,
+        });
+        assert_eq!(
+            fixtures,
+            vec![
+                shared.clone(),
+                shared,
+                Some(CaseFixture {
+                    path: dir.path().join("own.json"),
+                    replay: true,
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_param_values_stringify_scalars() {
+        let case = case_from_yaml("name: typed\nparams:\n  count: 3\n  verbose: true\n");
+        assert_eq!(
+            case.param_values(),
+            vec![
+                ("count".to_string(), "3".to_string()),
+                ("verbose".to_string(), "true".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_evaluate_case_reports_each_failure() {
+        let case = case_from_yaml(
+            "name: check\nexpected_tool_calls: [developer__shell, developer__text_editor]\nassertions:\n  - contains: done\n  - matches: '\\d+ passed'\n  - not_contains: error\n",
+        );
+        let conversation = Conversation::new_unvalidated(vec![
+            Message::user().with_text("run the tests"),
+            Message::assistant().with_tool_request(
+                "call_1",
+                Ok(ToolCall {
+                    name: "developer__shell".to_string(),
+                    arguments: json!({"command": "cargo test"}),
+                }),
+            ),
+            Message::assistant().with_text("All done: 12 passed"),
+        ]);
+
+        let failures = evaluate_case(&case, &conversation);
+
+        assert_eq!(failures.len(), 1);
+        assert!(failures[0].contains("developer__text_editor"));
+    }
+
+    #[test]
+    fn test_junit_report_escapes_and_counts() {
+        let report = RecipeTestReport::new(
+            "notes".to_string(),
+            vec![
+                CaseResult {
+                    name: "ok".to_string(),
+                    passed: true,
+                    duration_secs: 1.5,
+                    failures: vec![],
+                    metrics: BTreeMap::from([(
+                        "total_tool_calls".to_string(),
+                        EvalMetricValue::Integer(2),
+                    )]),
+                },
+                CaseResult {
+                    name: "bad <case>".to_string(),
+                    passed: false,
+                    duration_secs: 0.5,
+                    failures: vec!["expected a call to tool \"x\"".to_string()],
+                    metrics: BTreeMap::new(),
+                },
+            ],
+        );
+
+        let xml = report.to_junit_xml();
+
+        assert_eq!((report.passed, report.failed), (1, 1));
+        assert!(xml.contains("<testsuites tests=\"2\" failures=\"1\" time=\"2.000\">"));
+        assert!(xml.contains("name=\"bad &lt;case&gt;\""));
+        assert!(xml.contains("<property name=\"total_tool_calls\" value=\"2\"/>"));
+        assert!(xml.contains("<failure message=\"expected a call to tool &quot;x&quot;\">"));
+    }
+}
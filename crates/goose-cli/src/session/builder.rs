@@ -1,12 +1,17 @@
 use console::style;
 use goose::agents::types::RetryConfig;
 use goose::agents::Agent;
-use goose::config::{Config, ExtensionConfig, ExtensionConfigManager};
-use goose::providers::create;
+use goose::config::{
+    Config, ExtensionConfig, ExtensionConfigManager, ProjectPermissions, ProjectTrust,
+    ProjectTrustStore,
+};
+use goose::permission::ApprovalPolicy;
+use goose::providers::create_with_fallbacks;
 use goose::recipe::{Response, SubRecipe};
 use goose::session;
 use goose::session::Identifier;
 use rustyline::EditMode;
+use std::path::Path;
 use std::process;
 use std::sync::Arc;
 
@@ -63,6 +68,50 @@ pub struct SessionBuilderConfig {
     pub retry_config: Option<RetryConfig>,
 }
 
+/// Asks the user whether to apply a project permission file the first time it is found,
+/// or again after it changes. Untrusted files are ignored by the agent.
+fn review_project_permissions(working_dir: &Path, interactive: bool) {
+    let Some(project) = ProjectPermissions::discover(working_dir) else {
+        return;
+    };
+    let mut store = ProjectTrustStore::load();
+    if store.trust_of(&project) != ProjectTrust::Unknown {
+        return;
+    }
+
+    if !interactive {
+        eprintln!(
+            "{}",
+            style(format!(
+                "Ignoring project permissions at {} until they are trusted in an interactive session",
+                project.path.display()
+            ))
+            .yellow()
+        );
+        return;
+    }
+
+    let mode = project
+        .config
+        .mode
+        .as_ref()
+        .map(|mode| format!(" and set the goose mode to '{}'", mode))
+        .unwrap_or_default();
+    let trusted = cliclack::confirm(format!(
+        "Found project permissions at {}. They can allow or block tools{} for sessions in {}. Trust this file?",
+        style(project.path.display()).cyan(),
+        mode,
+        style(project.root.display()).cyan(),
+    ))
+    .initial_value(false)
+    .interact()
+    .unwrap_or(false);
+
+    if let Err(e) = store.set_trust(&project, trusted) {
+        output::render_error(&format!("Failed to save project trust decision: {}", e));
+    }
+}
+
 /// Offers to help debug an extension failure by creating a minimal debugging session
 async fn offer_extension_debugging_help(
     extension_name: &str,
@@ -170,12 +219,21 @@ pub struct SessionSettings {
     pub goose_model: Option<String>,
     pub goose_provider: Option<String>,
     pub temperature: Option<f32>,
+    pub fallback_models: Option<Vec<String>>,
+    pub approval: Option<ApprovalPolicy>,
 }
 
 pub async fn build_session(session_config: SessionBuilderConfig) -> Session {
     // Load config and get provider/model
     let config = Config::global();
 
+    let model_requested = session_config.provider.is_some()
+        || session_config.model.is_some()
+        || session_config
+            .settings
+            .as_ref()
+            .is_some_and(|s| s.goose_provider.is_some() || s.goose_model.is_some());
+
     let provider_name = session_config
         .provider
         .or_else(|| {
@@ -218,7 +276,12 @@ pub async fn build_session(session_config: SessionBuilderConfig) -> Session {
         agent.add_final_output_tool(final_output_response).await;
     }
 
-    let new_provider = match create(&provider_name, model_config) {
+    let fallback_models = session_config
+        .settings
+        .as_ref()
+        .and_then(|s| s.fallback_models.clone());
+
+    let new_provider = match create_with_fallbacks(&provider_name, model_config, fallback_models) {
         Ok(provider) => provider,
         Err(e) => {
             output::render_error(&format!(
@@ -340,6 +403,10 @@ pub async fn build_session(session_config: SessionBuilderConfig) -> Session {
         }
     }
 
+    if let Ok(working_dir) = std::env::current_dir() {
+        review_project_permissions(&working_dir, session_config.interactive);
+    }
+
     // Setup extensions for the agent
     // Extensions need to be added after the session is created because we change directory when resuming a session
     // If we get extensions_override, only run those extensions and none other
@@ -413,6 +480,32 @@ pub async fn build_session(session_config: SessionBuilderConfig) -> Session {
         edit_mode,
         session_config.retry_config.clone(),
     );
+    session.set_provider_name(&provider_name);
+
+    // A resumed session keeps the model it was switched to with /model, unless one is requested
+    let (provider_name, model_name, provider_for_display) = match session_file
+        .as_ref()
+        .filter(|_| session_config.resume && !model_requested)
+        .and_then(|f| session::read_metadata(f).ok())
+    {
+        Some(session::SessionMetadata {
+            provider: Some(provider),
+            model: Some(model),
+            ..
+        }) if provider != provider_name || model != model_name => {
+            match session.set_model(&provider, &model).await {
+                Ok(switched) => (provider, model, switched),
+                Err(e) => {
+                    output::render_error(&format!(
+                        "Failed to switch back to {}/{}: {}",
+                        provider, model, e
+                    ));
+                    (provider_name, model_name, provider_for_display)
+                }
+            }
+        }
+        _ => (provider_name, model_name, provider_for_display),
+    };
 
     // Add extensions if provided
     for extension_str in session_config.extensions {
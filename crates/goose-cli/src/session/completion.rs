@@ -119,6 +119,25 @@ impl GooseCompleter {
         Ok((line.len(), vec![]))
     }
 
+    /// Complete model names for the /model command, as `model` for the current provider
+    /// or `provider/model` for any provider
+    fn complete_model_names(&self, line: &str) -> Result<(usize, Vec<Pair>)> {
+        let prefix = line["/model ".len()..].trim_start();
+
+        let cache = self.completion_cache.read().unwrap();
+        let candidates: Vec<Pair> = cache
+            .models
+            .iter()
+            .filter(|model| model.starts_with(prefix))
+            .map(|model| Pair {
+                display: model.clone(),
+                replacement: model.clone(),
+            })
+            .collect();
+
+        Ok((line.len() - prefix.len(), candidates))
+    }
+
     /// Complete slash commands
     fn complete_slash_commands(&self, line: &str) -> Result<(usize, Vec<Pair>)> {
         // Define available slash commands
@@ -133,16 +152,26 @@ impl GooseCompleter {
             "/prompts",
             "/prompt",
             "/mode",
+            "/model",
             "/recipe",
+            "/recipes",
         ];
 
+        let cache = self.completion_cache.read().unwrap();
+        let custom_commands = cache
+            .custom_commands
+            .iter()
+            .map(|name| format!("/{}", name));
+
         // Find commands that match the prefix
         let matching_commands: Vec<Pair> = commands
             .iter()
+            .map(|cmd| cmd.to_string())
+            .chain(custom_commands)
             .filter(|cmd| cmd.starts_with(line))
             .map(|cmd| Pair {
-                display: cmd.to_string(),
                 replacement: format!("{} ", cmd), // Add a space after the command
+                display: cmd,
             })
             .collect();
 
@@ -259,6 +288,27 @@ impl GooseCompleter {
         Ok((line.len(), vec![]))
     }
 
+    /// Complete `@path` mentions by fuzzy matching paths under the working directory
+    fn complete_mention(&self, line: &str) -> Result<(usize, Vec<Pair>)> {
+        let query = line.rsplit(char::is_whitespace).next().unwrap_or("");
+        let Some(query) = query.strip_prefix('@') else {
+            return Ok((line.len(), vec![]));
+        };
+        let Ok(cwd) = std::env::current_dir() else {
+            return Ok((line.len(), vec![]));
+        };
+
+        let candidates = super::mentions::complete_mention(query, &cwd)
+            .into_iter()
+            .map(|path| Pair {
+                display: path.clone(),
+                replacement: path,
+            })
+            .collect();
+
+        Ok((line.len() - query.len(), candidates))
+    }
+
     /// Complete file paths
     fn complete_file_path(&self, line: &str, ctx: &Context) -> Result<(usize, Vec<Pair>)> {
         let parts: Vec<&str> = line.split_whitespace().collect();
@@ -368,6 +418,10 @@ impl Completer for GooseCompleter {
                 }
             }
 
+            if line.starts_with("/model ") {
+                return self.complete_model_names(line);
+            }
+
             if line.starts_with("/mode") {
                 return self.complete_mode_flags(line);
             }
@@ -375,6 +429,14 @@ impl Completer for GooseCompleter {
             return Ok((pos, vec![]));
         }
 
+        if line
+            .rsplit(char::is_whitespace)
+            .next()
+            .is_some_and(|word| word.starts_with('@'))
+        {
+            return self.complete_mention(line);
+        }
+
         // For normal text (not slash commands), try file path completion
         self.complete_file_path(line, ctx)
     }
@@ -531,6 +593,42 @@ mod tests {
         assert_eq!(candidates.len(), 0);
     }
 
+    #[test]
+    fn test_complete_custom_commands() {
+        let cache = create_test_cache();
+        cache.write().unwrap().custom_commands = vec!["standup".to_string()];
+        let completer = GooseCompleter::new(cache);
+
+        let (pos, candidates) = completer.complete_slash_commands("/st").unwrap();
+        assert_eq!(pos, 0);
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].display, "/standup");
+        assert_eq!(candidates[0].replacement, "/standup ");
+    }
+
+    #[test]
+    fn test_complete_model_names() {
+        let cache = create_test_cache();
+        cache.write().unwrap().models = vec![
+            "gpt-4o".to_string(),
+            "gpt-4o-mini".to_string(),
+            "anthropic/claude-sonnet-4".to_string(),
+        ];
+        let completer = GooseCompleter::new(cache);
+
+        let (pos, candidates) = completer.complete_model_names("/model ").unwrap();
+        assert_eq!(pos, 7);
+        assert_eq!(candidates.len(), 3);
+
+        let (pos, candidates) = completer.complete_model_names("/model gpt-4o-").unwrap();
+        assert_eq!(pos, 7);
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].replacement, "gpt-4o-mini");
+
+        let (_pos, candidates) = completer.complete_model_names("/model anthropic/").unwrap();
+        assert_eq!(candidates[0].display, "anthropic/claude-sonnet-4");
+    }
+
     #[test]
     fn test_complete_prompt_names() {
         let cache = create_test_cache();
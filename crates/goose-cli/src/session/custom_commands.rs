@@ -0,0 +1,496 @@
+//! User and project slash commands, loaded from markdown files.
+//!
+//! Every `<name>.md` in `~/.config/goose/commands` or `.goose/commands` defines `/<name>`. The
+//! body is a prompt template where `{{ argument }}` is replaced by the argument's value and
+//! `{{ args }}` by everything typed after the command. Optional YAML frontmatter describes the
+//! command and its arguments, limits the extensions the reply can use, sets the goose mode for
+//! the reply, or names a recipe that the command runs as a sub-recipe. The `extensions` and
+//! `mode` of project commands only apply once the user has trusted the project's permissions,
+//! and a project command can make the mode stricter but never looser.
+//!
+//! ```markdown
+//! ---
+//! description: Review a file for bugs
+//! arguments:
+//!   - name: file
+//!     required: true
+//! extensions: [developer]
+//! mode: approve
+//! ---
+//! Review {{ file }} for bugs. {{ args }}
+//! ```
+
+use anyhow::{anyhow, bail, Context, Result};
+use etcetera::{choose_app_strategy, AppStrategy};
+use goose::recipe::SubRecipe;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use super::input::BUILTIN_COMMANDS;
+use crate::recipes::search_recipe::retrieve_recipe_file;
+
+const FRONTMATTER_DELIMITER: &str = "---";
+/// Goose modes from the loosest to the strictest
+const MODES: [&str; 4] = ["auto", "smart_approve", "approve", "chat"];
+
+static PLACEHOLDER: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\{\{\s*([A-Za-z_][A-Za-z0-9_]*)\s*\}\}").unwrap());
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CommandArgument {
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+    pub default: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Frontmatter {
+    description: Option<String>,
+    #[serde(default)]
+    arguments: Vec<CommandArgument>,
+    extensions: Option<Vec<String>>,
+    mode: Option<String>,
+    recipe: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CustomCommand {
+    pub name: String,
+    pub description: Option<String>,
+    pub arguments: Vec<CommandArgument>,
+    /// Extensions whose tools are offered while the command runs, all of them when unset
+    pub extensions: Option<Vec<String>>,
+    /// Goose mode while the command runs
+    pub mode: Option<String>,
+    /// Recipe run as a sub-recipe, by name or by a path relative to the command file
+    pub recipe: Option<String>,
+    pub template: String,
+    pub path: PathBuf,
+    /// Whether the command comes from the project rather than the user's config directory
+    pub project: bool,
+}
+
+/// A directory of command files
+#[derive(Debug, Clone)]
+pub struct CommandDir {
+    pub path: PathBuf,
+    pub project: bool,
+}
+
+impl CustomCommand {
+    /// Parse a command file
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let name = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .filter(|name| {
+                !name.is_empty()
+                    && name
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+            })
+            .ok_or_else(|| {
+                anyhow!("command names may only contain letters, digits, '-' and '_'")
+            })?;
+        if BUILTIN_COMMANDS.contains(&name) {
+            bail!("/{} is a built-in command", name);
+        }
+
+        let content = std::fs::read_to_string(path)?;
+        let (frontmatter, template) = split_frontmatter(&content)?;
+
+        if let Some(mode) = frontmatter.mode.as_deref() {
+            if !MODES.contains(&mode) {
+                bail!("mode must be one of {}, not '{}'", MODES.join(", "), mode);
+            }
+        }
+        for placeholder in PLACEHOLDER.captures_iter(template) {
+            let placeholder = &placeholder[1];
+            if placeholder != "args" && !frontmatter.arguments.iter().any(|a| a.name == placeholder)
+            {
+                bail!("{{{{ {} }}}} is not a declared argument", placeholder);
+            }
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            description: frontmatter.description,
+            arguments: frontmatter.arguments,
+            extensions: frontmatter.extensions,
+            mode: frontmatter.mode,
+            recipe: frontmatter.recipe,
+            template: template.trim().to_string(),
+            path: path.to_path_buf(),
+            project: false,
+        })
+    }
+
+    /// Drop the `extensions` and `mode` of a project command while the project is not trusted,
+    /// returning a note when there was something to drop
+    pub fn drop_untrusted_settings(&mut self) -> Option<String> {
+        if !self.project || (self.extensions.is_none() && self.mode.is_none()) {
+            return None;
+        }
+        self.extensions = None;
+        self.mode = None;
+        Some(format!(
+            "Ignoring the extensions and mode of /{} until the project permissions are trusted",
+            self.name
+        ))
+    }
+
+    /// The mode to run the command in. A project command may only make `current_mode`
+    /// stricter, so a looser mode is not used.
+    pub fn mode_within(&self, current_mode: &str) -> Option<&str> {
+        let mode = self.mode.as_deref()?;
+        if self.project && strictness(mode) < strictness(current_mode) {
+            None
+        } else {
+            Some(mode)
+        }
+    }
+
+    /// How the command is typed, like `/review <file> [focus]`
+    pub fn usage(&self) -> String {
+        let mut usage = format!("/{}", self.name);
+        for argument in &self.arguments {
+            if argument.required {
+                usage.push_str(&format!(" <{}>", argument.name));
+            } else {
+                usage.push_str(&format!(" [{}]", argument.name));
+            }
+        }
+        usage
+    }
+
+    /// Values of the declared arguments from what was typed after the command. `key=value` sets
+    /// an argument by name, other words fill the remaining arguments in order.
+    pub fn parse_arguments(&self, input: &str) -> Result<HashMap<String, String>> {
+        let words = shlex::split(input).ok_or_else(|| anyhow!("Unbalanced quotes in arguments"))?;
+        let mut values = HashMap::new();
+        let mut positional = Vec::new();
+        for word in words {
+            match word.split_once('=') {
+                Some((key, value)) if self.arguments.iter().any(|a| a.name == key) => {
+                    values.insert(key.to_string(), value.to_string());
+                }
+                _ => positional.push(word),
+            }
+        }
+
+        let mut positional = positional.into_iter();
+        for argument in &self.arguments {
+            if values.contains_key(&argument.name) {
+                continue;
+            }
+            match positional.next().or_else(|| argument.default.clone()) {
+                Some(value) => {
+                    values.insert(argument.name.clone(), value);
+                }
+                None if argument.required => {
+                    bail!(
+                        "Missing argument '{}'. Usage: {}",
+                        argument.name,
+                        self.usage()
+                    );
+                }
+                None => {}
+            }
+        }
+        Ok(values)
+    }
+
+    /// The prompt for the given input, with the template's placeholders filled in
+    pub fn render(&self, input: &str, values: &HashMap<String, String>) -> String {
+        PLACEHOLDER
+            .replace_all(
+                &self.template,
+                |captures: &regex::Captures| match &captures[1] {
+                    "args" => input.trim().to_string(),
+                    name => values.get(name).cloned().unwrap_or_default(),
+                },
+            )
+            .into_owned()
+    }
+
+    /// The command's recipe as a sub-recipe. Paths are relative to the command file, anything
+    /// else is looked up in the recipe sources.
+    pub fn sub_recipe(&self) -> Result<Option<SubRecipe>> {
+        let Some(recipe) = &self.recipe else {
+            return Ok(None);
+        };
+        let relative = self
+            .path
+            .parent()
+            .map(|dir| dir.join(recipe))
+            .filter(|path| path.is_file());
+        let path = match relative {
+            Some(path) => path,
+            None => retrieve_recipe_file(recipe)?.file_path,
+        };
+
+        Ok(Some(SubRecipe {
+            name: self.name.replace('-', "_"),
+            path: path.to_string_lossy().into_owned(),
+            values: None,
+            sequential_when_repeated: false,
+            description: self.description.clone(),
+            depends_on: None,
+            inputs: None,
+        }))
+    }
+}
+
+fn strictness(mode: &str) -> usize {
+    MODES.iter().position(|known| *known == mode).unwrap_or(0)
+}
+
+fn split_frontmatter(content: &str) -> Result<(Frontmatter, &str)> {
+    let Some(rest) = content.strip_prefix(FRONTMATTER_DELIMITER) else {
+        return Ok((Frontmatter::default(), content));
+    };
+    let (yaml, body) = rest
+        .split_once(&format!("\n{}", FRONTMATTER_DELIMITER))
+        .ok_or_else(|| anyhow!("frontmatter is not closed with '---'"))?;
+    let frontmatter = serde_yaml::from_str::<Option<Frontmatter>>(yaml)
+        .context("invalid frontmatter")?
+        .unwrap_or_default();
+    Ok((frontmatter, body))
+}
+
+/// The command directories in order of precedence, lowest first: the user's, then the project's
+pub fn command_dirs(working_dir: &Path) -> Vec<CommandDir> {
+    let mut dirs = Vec::new();
+    if let Ok(strategy) = choose_app_strategy(crate::APP_STRATEGY.clone()) {
+        dirs.push(CommandDir {
+            path: strategy.in_config_dir("commands"),
+            project: false,
+        });
+    }
+    dirs.push(CommandDir {
+        path: working_dir.join(".goose").join("commands"),
+        project: true,
+    });
+    dirs
+}
+
+/// Load the commands of every directory, sorted by name. A project command replaces a user
+/// command of the same name. Files that cannot be loaded are skipped and described in the
+/// returned warnings.
+pub fn load_commands(dirs: &[CommandDir]) -> (Vec<CustomCommand>, Vec<String>) {
+    let mut commands: HashMap<String, CustomCommand> = HashMap::new();
+    let mut warnings = Vec::new();
+
+    for dir in dirs {
+        let Ok(entries) = std::fs::read_dir(&dir.path) else {
+            continue;
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "md"))
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            match CustomCommand::from_file(&path) {
+                Ok(mut command) => {
+                    command.project = dir.project;
+                    commands.insert(command.name.clone(), command);
+                }
+                Err(e) => warnings.push(format!("Skipped command file {}: {}", path.display(), e)),
+            }
+        }
+    }
+
+    let mut commands: Vec<CustomCommand> = commands.into_values().collect();
+    commands.sort_by(|a, b| a.name.cmp(&b.name));
+    (commands, warnings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(dir: &Path, name: &str, content: &str) -> PathBuf {
+        std::fs::create_dir_all(dir).unwrap();
+        let path = dir.join(name);
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    const REVIEW: &str = "---
+description: Review a file
+arguments:
+  - name: file
+    required: true
+  - name: focus
+    default: correctness
+extensions: [developer]
+mode: approve
+---
+Review {{ file }} with a focus on {{focus}}. Notes: {{ args }}
+";
+
+    #[test]
+    fn test_parse_command_file() {
+        let dir = TempDir::new().unwrap();
+        let command = CustomCommand::from_file(&write(dir.path(), "review.md", REVIEW)).unwrap();
+
+        assert_eq!(command.name, "review");
+        assert_eq!(command.description.as_deref(), Some("Review a file"));
+        assert_eq!(command.usage(), "/review <file> [focus]");
+        assert_eq!(command.extensions, Some(vec!["developer".to_string()]));
+        assert_eq!(command.mode.as_deref(), Some("approve"));
+        assert!(command.recipe.is_none());
+
+        let plain = write(dir.path(), "standup.md", "Summarize yesterday's commits.");
+        let plain = CustomCommand::from_file(&plain).unwrap();
+        assert_eq!(plain.template, "Summarize yesterday's commits.");
+        assert!(plain.arguments.is_empty());
+    }
+
+    #[test]
+    fn test_render_with_arguments() {
+        let dir = TempDir::new().unwrap();
+        let command = CustomCommand::from_file(&write(dir.path(), "review.md", REVIEW)).unwrap();
+
+        let input = "src/main.rs";
+        let values = command.parse_arguments(input).unwrap();
+        assert_eq!(
+            command.render(input, &values),
+            "Review src/main.rs with a focus on correctness. Notes: src/main.rs"
+        );
+
+        let input = "focus=\"error handling\" src/lib.rs";
+        let values = command.parse_arguments(input).unwrap();
+        assert_eq!(values["file"], "src/lib.rs");
+        assert_eq!(values["focus"], "error handling");
+
+        let error = command.parse_arguments("").unwrap_err().to_string();
+        assert_eq!(
+            error,
+            "Missing argument 'file'. Usage: /review <file> [focus]"
+        );
+    }
+
+    #[test]
+    fn test_invalid_command_files() {
+        let dir = TempDir::new().unwrap();
+        let cases = [
+            ("help.md", "Shadow help", "built-in command"),
+            ("bad name.md", "Hi", "command names may only contain"),
+            ("mode.md", "---\nmode: yolo\n---\nHi", "built-in command"),
+            ("yolo.md", "---\nmode: yolo\n---\nHi", "mode must be one of"),
+            ("typo.md", "Review {{ fiel }}", "is not a declared argument"),
+            (
+                "open.md",
+                "---\ndescription: never closed\nHi",
+                "not closed",
+            ),
+            (
+                "extra.md",
+                "---\nmodel: gpt-4o\n---\nHi",
+                "invalid frontmatter",
+            ),
+        ];
+        for (name, content, expected) in cases {
+            let error = CustomCommand::from_file(&write(dir.path(), name, content))
+                .unwrap_err()
+                .to_string();
+            assert!(error.contains(expected), "{}: {}", name, error);
+        }
+    }
+
+    #[test]
+    fn test_project_commands_replace_user_commands() {
+        let user = TempDir::new().unwrap();
+        let project = TempDir::new().unwrap();
+        write(user.path(), "standup.md", "User standup");
+        write(user.path(), "deploy.md", "Deploy");
+        write(user.path(), "notes.txt", "Not a command");
+        write(project.path(), "standup.md", "Project standup");
+        write(project.path(), "exit.md", "Shadow exit");
+
+        let (commands, warnings) = load_commands(&dirs(&user, &project));
+
+        let names: Vec<&str> = commands.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["deploy", "standup"]);
+        assert_eq!(commands[1].template, "Project standup");
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("exit.md"));
+    }
+
+    fn dirs(user: &TempDir, project: &TempDir) -> Vec<CommandDir> {
+        vec![
+            CommandDir {
+                path: user.path().to_path_buf(),
+                project: false,
+            },
+            CommandDir {
+                path: project.path().to_path_buf(),
+                project: true,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_project_commands_need_trust_and_cannot_loosen_the_mode() {
+        let user = TempDir::new().unwrap();
+        let project = TempDir::new().unwrap();
+        write(user.path(), "ship.md", "---\nmode: auto\n---\nShip it");
+        write(project.path(), "review.md", REVIEW);
+        write(project.path(), "yolo.md", "---\nmode: auto\n---\nDo it");
+
+        let (mut commands, _) = load_commands(&dirs(&user, &project));
+        let (review, ship, yolo) = (&commands[0], &commands[1], &commands[2]);
+        assert!(review.project && !ship.project && yolo.project);
+
+        assert_eq!(review.mode_within("auto"), Some("approve"));
+        assert_eq!(review.mode_within("chat"), None);
+        assert_eq!(yolo.mode_within("approve"), None);
+        assert_eq!(yolo.mode_within("auto"), Some("auto"));
+        assert_eq!(ship.mode_within("approve"), Some("auto"));
+
+        let notes: Vec<String> = commands
+            .iter_mut()
+            .filter_map(CustomCommand::drop_untrusted_settings)
+            .collect();
+        assert_eq!(notes.len(), 2);
+        assert!(commands[0].extensions.is_none() && commands[0].mode.is_none());
+        assert_eq!(commands[1].mode.as_deref(), Some("auto"));
+    }
+
+    #[test]
+    fn test_recipe_relative_to_command_file() {
+        let dir = TempDir::new().unwrap();
+        write(
+            dir.path(),
+            "triage.yaml",
+            "version: 1.0.0\ntitle: Triage\ndescription: Triage issues\ninstructions: Triage\n",
+        );
+        let path = write(
+            dir.path(),
+            "triage-issues.md",
+            "---\nrecipe: triage.yaml\n---\n",
+        );
+
+        let command = CustomCommand::from_file(&path).unwrap();
+        let sub_recipe = command.sub_recipe().unwrap().unwrap();
+
+        assert_eq!(sub_recipe.name, "triage_issues");
+        assert_eq!(
+            PathBuf::from(sub_recipe.path),
+            dir.path().join("triage.yaml")
+        );
+        assert!(command.template.is_empty());
+    }
+}
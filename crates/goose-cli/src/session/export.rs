@@ -1,3 +1,4 @@
+use super::mentions::{parse_attachment, Attachment, AttachmentKind};
 use goose::conversation::message::{Message, MessageContent, ToolRequest, ToolResponse};
 use goose::utils::safe_truncate;
 use rmcp::model::{RawContent, ResourceContents, Role};
@@ -314,12 +315,34 @@ pub fn tool_response_to_markdown(resp: &ToolResponse, export_all_content: bool)
     md
 }
 
+/// An `@path` attachment as a collapsed `<details>` block
+fn attachment_to_markdown(attachment: &Attachment) -> String {
+    if attachment.kind == AttachmentKind::Image {
+        return format!("📎 `{}`", attachment.summary());
+    }
+    // The fence has to be longer than any run of backticks in the content
+    let mut fence = "```".to_string();
+    while attachment.body.contains(&fence) {
+        fence.push('`');
+    }
+    format!(
+        "<details>\n<summary>📎 {}</summary>\n\n{}\n{}\n{}\n\n</details>",
+        attachment.summary(),
+        fence,
+        attachment.body,
+        fence
+    )
+}
+
 pub fn message_to_markdown(message: &Message, export_all_content: bool) -> String {
     let mut md = String::new();
     for content in &message.content {
         match content {
             MessageContent::Text(text) => {
-                md.push_str(&text.text);
+                match parse_attachment(&text.text) {
+                    Some(attachment) => md.push_str(&attachment_to_markdown(&attachment)),
+                    None => md.push_str(&text.text),
+                }
                 md.push_str("\n\n");
             }
             MessageContent::ToolRequest(req) => {
@@ -565,6 +588,18 @@ mod tests {
         assert_eq!(result, "Hello, this is a test message");
     }
 
+    #[test]
+    fn test_message_to_markdown_attachment() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("notes.md"), "# Notes\n```sh\nls\n```").unwrap();
+        let (message, _) = super::super::mentions::attach_mentions("read @notes.md", dir.path());
+
+        let result = message_to_markdown(&message, true);
+        assert!(result.starts_with("read @notes.md\n\n<details>\n<summary>📎 notes.md (4 lines)"));
+        assert!(result.contains("````\n# Notes\n```sh\nls\n```\n````"));
+        assert!(result.ends_with("</details>"));
+    }
+
     #[test]
     fn test_message_to_markdown_with_tool_request() {
         let tool_call = ToolCall {
@@ -1,4 +1,5 @@
 use super::completion::GooseCompleter;
+use super::custom_commands::CustomCommand;
 use anyhow::Result;
 use rustyline::Editor;
 use shlex;
@@ -16,11 +17,14 @@ pub enum InputResult {
     ListPrompts(Option<String>),
     PromptCommand(PromptCommandOptions),
     GooseMode(String),
+    SwitchModel(Option<String>),
     Plan(PlanCommandOptions),
     EndPlan,
     Clear,
     Recipe(Option<String>),
+    ListRecipes,
     Summarize,
+    CustomCommand(CustomCommandOptions),
 }
 
 #[derive(Debug)]
@@ -30,6 +34,13 @@ pub struct PromptCommandOptions {
     pub arguments: HashMap<String, String>,
 }
 
+#[derive(Debug)]
+pub struct CustomCommandOptions {
+    pub name: String,
+    /// Everything typed after the command name
+    pub input: String,
+}
+
 #[derive(Debug)]
 pub struct PlanCommandOptions {
     pub message_text: String,
@@ -56,6 +67,7 @@ impl rustyline::ConditionalEventHandler for CtrlCHandler {
 
 pub fn get_input(
     editor: &mut Editor<GooseCompleter, rustyline::history::DefaultHistory>,
+    custom_commands: &[CustomCommand],
 ) -> Result<InputResult> {
     // Ensure Ctrl-J binding is set for newlines
     editor.bind_sequence(
@@ -100,15 +112,43 @@ pub fn get_input(
     }
 
     // Handle slash commands
-    match handle_slash_command(&input) {
+    match handle_slash_command(&input, custom_commands) {
         Some(result) => Ok(result),
         None => Ok(InputResult::Message(input.trim().to_string())),
     }
 }
 
-fn handle_slash_command(input: &str) -> Option<InputResult> {
+/// Names of the built-in slash commands, which custom commands cannot replace
+pub const BUILTIN_COMMANDS: &[&str] = &[
+    "exit",
+    "quit",
+    "help",
+    "t",
+    "prompts",
+    "prompt",
+    "extension",
+    "builtin",
+    "mode",
+    "model",
+    "plan",
+    "endplan",
+    "clear",
+    "recipe",
+    "recipes",
+    "summarize",
+];
+
+fn handle_slash_command(input: &str, custom_commands: &[CustomCommand]) -> Option<InputResult> {
     let input = input.trim();
 
+    let (name, rest) = input[1..].split_once(' ').unwrap_or((&input[1..], ""));
+    if custom_commands.iter().any(|command| command.name == name) {
+        return Some(InputResult::CustomCommand(CustomCommandOptions {
+            name: name.to_string(),
+            input: rest.trim().to_string(),
+        }));
+    }
+
     // Command prefix constants
     const CMD_PROMPTS: &str = "/prompts ";
     const CMD_PROMPT: &str = "/prompt";
@@ -116,16 +156,19 @@ fn handle_slash_command(input: &str) -> Option<InputResult> {
     const CMD_EXTENSION: &str = "/extension ";
     const CMD_BUILTIN: &str = "/builtin ";
     const CMD_MODE: &str = "/mode ";
+    const CMD_MODEL: &str = "/model";
+    const CMD_MODEL_WITH_SPACE: &str = "/model ";
     const CMD_PLAN: &str = "/plan";
     const CMD_ENDPLAN: &str = "/endplan";
     const CMD_CLEAR: &str = "/clear";
     const CMD_RECIPE: &str = "/recipe";
+    const CMD_RECIPES: &str = "/recipes";
     const CMD_SUMMARIZE: &str = "/summarize";
 
     match input {
         "/exit" | "/quit" => Some(InputResult::Exit),
         "/?" | "/help" => {
-            print_help();
+            print_help(custom_commands);
             Some(InputResult::Retry)
         }
         "/t" => Some(InputResult::ToggleTheme),
@@ -173,12 +216,17 @@ fn handle_slash_command(input: &str) -> Option<InputResult> {
         s if s.starts_with(CMD_BUILTIN) => {
             Some(InputResult::AddBuiltin(s[CMD_BUILTIN.len()..].to_string()))
         }
+        s if s == CMD_MODEL => Some(InputResult::SwitchModel(None)),
+        s if s.starts_with(CMD_MODEL_WITH_SPACE) => Some(InputResult::SwitchModel(Some(
+            s[CMD_MODEL_WITH_SPACE.len()..].trim().to_string(),
+        ))),
         s if s.starts_with(CMD_MODE) => {
             Some(InputResult::GooseMode(s[CMD_MODE.len()..].to_string()))
         }
         s if s.starts_with(CMD_PLAN) => parse_plan_command(s[CMD_PLAN.len()..].trim().to_string()),
         s if s == CMD_ENDPLAN => Some(InputResult::EndPlan),
         s if s == CMD_CLEAR => Some(InputResult::Clear),
+        s if s == CMD_RECIPES => Some(InputResult::ListRecipes),
         s if s.starts_with(CMD_RECIPE) => parse_recipe_command(s),
         s if s == CMD_SUMMARIZE => Some(InputResult::Summarize),
         _ => None,
@@ -210,6 +258,23 @@ fn parse_recipe_command(s: &str) -> Option<InputResult> {
     Some(InputResult::Recipe(Some(filepath.to_string())))
 }
 
+/// Split a `/model` argument into provider and model. The provider prefix is only taken when
+/// it names a known provider, since model names such as `meta-llama/llama-3` contain slashes too.
+pub fn parse_model_spec(
+    spec: &str,
+    current_provider: &str,
+    provider_names: &[String],
+) -> (String, String) {
+    match spec.split_once('/') {
+        Some((provider, model))
+            if !model.is_empty() && provider_names.iter().any(|name| name == provider) =>
+        {
+            (provider.to_string(), model.to_string())
+        }
+        _ => (current_provider.to_string(), spec.to_string()),
+    }
+}
+
 fn parse_prompts_command(args: &str) -> Option<InputResult> {
     let parts: Vec<String> = shlex::split(args).unwrap_or_default();
 
@@ -271,7 +336,7 @@ fn parse_plan_command(input: String) -> Option<InputResult> {
     Some(InputResult::Plan(options))
 }
 
-fn print_help() {
+fn print_help(custom_commands: &[CustomCommand]) {
     println!(
         "Available commands:
 /exit or /quit - Exit the session
@@ -282,6 +347,8 @@ fn print_help() {
 /prompts [--extension <name>] - List all available prompts, optionally filtered by extension
 /prompt <n> [--info] [key=value...] - Get prompt info or execute a prompt
 /mode <name> - Set the goose mode to use ('auto', 'approve', 'chat')
+/model [provider/]model - Switch the model, and optionally the provider, for the rest of the session.
+                          Without a model, shows the model in use.
 /plan <message_text> -  Enters 'plan' mode with optional message. Create a plan based on the current messages and asks user if they want to act on it.
                         If user acts on the plan, goose mode is set to 'auto' and returns to 'normal' goose mode.
                         To warm up goose before using '/plan', we recommend setting '/mode approve' & putting appropriate context into goose.
@@ -290,6 +357,7 @@ fn print_help() {
 /endplan - Exit plan mode and return to 'normal' goose mode.
 /recipe [filepath] - Generate a recipe from the current conversation and save it to the specified filepath (must end with .yaml).
                        If no filepath is provided, it will be saved to ./recipe.yaml.
+/recipes - List the recipes available from the configured recipe sources
 /summarize - Summarize the current conversation to reduce context length while preserving key information.
 /? or /help - Display this help message
 /clear - Clears the current chat history
@@ -299,6 +367,21 @@ Ctrl+C - Clear current line if text is entered, otherwise exit the session
 Ctrl+J - Add a newline
 Up/Down arrows - Navigate through command history"
     );
+
+    if !custom_commands.is_empty() {
+        println!("\nCustom commands:");
+        for command in custom_commands {
+            match &command.description {
+                Some(description) => println!("{} - {}", command.usage(), description),
+                None => println!("{}", command.usage()),
+            }
+            for argument in &command.arguments {
+                if let Some(description) = &argument.description {
+                    println!("    {} - {}", argument.name, description);
+                }
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -309,52 +392,55 @@ mod tests {
     fn test_handle_slash_command() {
         // Test exit commands
         assert!(matches!(
-            handle_slash_command("/exit"),
+            handle_slash_command("/exit", &[]),
             Some(InputResult::Exit)
         ));
         assert!(matches!(
-            handle_slash_command("/quit"),
+            handle_slash_command("/quit", &[]),
             Some(InputResult::Exit)
         ));
 
         // Test help commands
         assert!(matches!(
-            handle_slash_command("/help"),
+            handle_slash_command("/help", &[]),
             Some(InputResult::Retry)
         ));
         assert!(matches!(
-            handle_slash_command("/?"),
+            handle_slash_command("/?", &[]),
             Some(InputResult::Retry)
         ));
 
         // Test theme toggle
         assert!(matches!(
-            handle_slash_command("/t"),
+            handle_slash_command("/t", &[]),
             Some(InputResult::ToggleTheme)
         ));
 
         // Test extension command
-        if let Some(InputResult::AddExtension(cmd)) = handle_slash_command("/extension foo bar") {
+        if let Some(InputResult::AddExtension(cmd)) =
+            handle_slash_command("/extension foo bar", &[])
+        {
             assert_eq!(cmd, "foo bar");
         } else {
             panic!("Expected AddExtension");
         }
 
         // Test builtin command
-        if let Some(InputResult::AddBuiltin(names)) = handle_slash_command("/builtin dev,git") {
+        if let Some(InputResult::AddBuiltin(names)) = handle_slash_command("/builtin dev,git", &[])
+        {
             assert_eq!(names, "dev,git");
         } else {
             panic!("Expected AddBuiltin");
         }
 
         // Test unknown commands
-        assert!(handle_slash_command("/unknown").is_none());
+        assert!(handle_slash_command("/unknown", &[]).is_none());
     }
 
     #[test]
     fn test_prompts_command() {
         // Test basic prompts command
-        if let Some(InputResult::ListPrompts(extension)) = handle_slash_command("/prompts") {
+        if let Some(InputResult::ListPrompts(extension)) = handle_slash_command("/prompts", &[]) {
             assert!(extension.is_none());
         } else {
             panic!("Expected ListPrompts");
@@ -362,7 +448,7 @@ mod tests {
 
         // Test prompts with extension filter
         if let Some(InputResult::ListPrompts(extension)) =
-            handle_slash_command("/prompts --extension test")
+            handle_slash_command("/prompts --extension test", &[])
         {
             assert_eq!(extension, Some("test".to_string()));
         } else {
@@ -374,7 +460,7 @@ mod tests {
     fn test_prompt_command() {
         // Test basic prompt info command
         if let Some(InputResult::PromptCommand(opts)) =
-            handle_slash_command("/prompt test-prompt --info")
+            handle_slash_command("/prompt test-prompt --info", &[])
         {
             assert_eq!(opts.name, "test-prompt");
             assert!(opts.info);
@@ -385,7 +471,7 @@ mod tests {
 
         // Test prompt with arguments
         if let Some(InputResult::PromptCommand(opts)) =
-            handle_slash_command("/prompt test-prompt arg1=val1 arg2=val2")
+            handle_slash_command("/prompt test-prompt arg1=val1 arg2=val2", &[])
         {
             assert_eq!(opts.name, "test-prompt");
             assert!(!opts.info);
@@ -401,7 +487,8 @@ mod tests {
     #[test]
     fn test_whitespace_handling() {
         // Leading/trailing whitespace in extension command
-        if let Some(InputResult::AddExtension(cmd)) = handle_slash_command("  /extension foo bar  ")
+        if let Some(InputResult::AddExtension(cmd)) =
+            handle_slash_command("  /extension foo bar  ", &[])
         {
             assert_eq!(cmd, "foo bar");
         } else {
@@ -409,7 +496,9 @@ mod tests {
         }
 
         // Leading/trailing whitespace in builtin command
-        if let Some(InputResult::AddBuiltin(names)) = handle_slash_command("  /builtin dev,git  ") {
+        if let Some(InputResult::AddBuiltin(names)) =
+            handle_slash_command("  /builtin dev,git  ", &[])
+        {
             assert_eq!(names, "dev,git");
         } else {
             panic!("Expected AddBuiltin");
@@ -420,7 +509,7 @@ mod tests {
     #[test]
     fn test_prompt_no_args() {
         // Test just "/prompt" with no arguments
-        if let Some(InputResult::PromptCommand(opts)) = handle_slash_command("/prompt") {
+        if let Some(InputResult::PromptCommand(opts)) = handle_slash_command("/prompt", &[]) {
             assert_eq!(opts.name, "");
             assert!(!opts.info);
             assert!(opts.arguments.is_empty());
@@ -429,7 +518,7 @@ mod tests {
         }
 
         // Test invalid prompt command
-        assert!(handle_slash_command("/promptxyz").is_none());
+        assert!(handle_slash_command("/promptxyz", &[]).is_none());
     }
 
     // Test quoted arguments
@@ -438,6 +527,7 @@ mod tests {
         // Test prompt with quoted arguments
         if let Some(InputResult::PromptCommand(opts)) = handle_slash_command(
             r#"/prompt test-prompt arg1="value with spaces" arg2="another value""#,
+            &[],
         ) {
             assert_eq!(opts.name, "test-prompt");
             assert_eq!(opts.arguments.len(), 2);
@@ -456,6 +546,7 @@ mod tests {
         // Test prompt with mixed quoted and unquoted arguments
         if let Some(InputResult::PromptCommand(opts)) = handle_slash_command(
             r#"/prompt test-prompt simple=value quoted="value with \"nested\" quotes""#,
+            &[],
         ) {
             assert_eq!(opts.name, "test-prompt");
             assert_eq!(opts.arguments.len(), 2);
@@ -473,9 +564,10 @@ mod tests {
     #[test]
     fn test_invalid_arguments() {
         // Test prompt with invalid arguments
-        if let Some(InputResult::PromptCommand(opts)) =
-            handle_slash_command(r#"/prompt test-prompt valid=value invalid_arg another_invalid"#)
-        {
+        if let Some(InputResult::PromptCommand(opts)) = handle_slash_command(
+            r#"/prompt test-prompt valid=value invalid_arg another_invalid"#,
+            &[],
+        ) {
             assert_eq!(opts.name, "test-prompt");
             assert_eq!(opts.arguments.len(), 1);
             assert_eq!(opts.arguments.get("valid"), Some(&"value".to_string()));
@@ -488,11 +580,11 @@ mod tests {
     #[test]
     fn test_plan_mode() {
         // Test plan mode with no text
-        let result = handle_slash_command("/plan");
+        let result = handle_slash_command("/plan", &[]);
         assert!(result.is_some());
 
         // Test plan mode with text
-        let result = handle_slash_command("/plan hello world");
+        let result = handle_slash_command("/plan hello world", &[]);
         assert!(result.is_some());
         let options = result.unwrap();
         match options {
@@ -506,7 +598,7 @@ mod tests {
     #[test]
     fn test_recipe_command() {
         // Test recipe with no filepath
-        if let Some(InputResult::Recipe(filepath)) = handle_slash_command("/recipe") {
+        if let Some(InputResult::Recipe(filepath)) = handle_slash_command("/recipe", &[]) {
             assert!(filepath.is_none());
         } else {
             panic!("Expected Recipe");
@@ -514,7 +606,7 @@ mod tests {
 
         // Test recipe with filepath
         if let Some(InputResult::Recipe(filepath)) =
-            handle_slash_command("/recipe /path/to/file.yaml")
+            handle_slash_command("/recipe /path/to/file.yaml", &[])
         {
             assert_eq!(filepath, Some("/path/to/file.yaml".to_string()));
         } else {
@@ -522,18 +614,97 @@ mod tests {
         }
 
         // Test recipe with invalid extension
-        let result = handle_slash_command("/recipe /path/to/file.txt");
+        let result = handle_slash_command("/recipe /path/to/file.txt", &[]);
         assert!(matches!(result, Some(InputResult::Retry)));
+
+        // Listing recipes is not mistaken for saving one
+        assert!(matches!(
+            handle_slash_command("/recipes", &[]),
+            Some(InputResult::ListRecipes)
+        ));
     }
 
     #[test]
     fn test_summarize_command() {
         // Test the summarize command
-        let result = handle_slash_command("/summarize");
+        let result = handle_slash_command("/summarize", &[]);
         assert!(matches!(result, Some(InputResult::Summarize)));
 
         // Test with whitespace
-        let result = handle_slash_command("  /summarize  ");
+        let result = handle_slash_command("  /summarize  ", &[]);
         assert!(matches!(result, Some(InputResult::Summarize)));
     }
+
+    #[test]
+    fn test_model_command() {
+        assert!(matches!(
+            handle_slash_command("/model", &[]),
+            Some(InputResult::SwitchModel(None))
+        ));
+
+        if let Some(InputResult::SwitchModel(Some(spec))) =
+            handle_slash_command("/model  anthropic/claude-sonnet-4 ", &[])
+        {
+            assert_eq!(spec, "anthropic/claude-sonnet-4");
+        } else {
+            panic!("Expected SwitchModel");
+        }
+
+        assert!(matches!(
+            handle_slash_command("/mode auto", &[]),
+            Some(InputResult::GooseMode(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_model_spec() {
+        let providers = vec!["anthropic".to_string(), "openrouter".to_string()];
+
+        assert_eq!(
+            parse_model_spec("gpt-4o", "openai", &providers),
+            ("openai".to_string(), "gpt-4o".to_string())
+        );
+        assert_eq!(
+            parse_model_spec("anthropic/claude-sonnet-4", "openai", &providers),
+            ("anthropic".to_string(), "claude-sonnet-4".to_string())
+        );
+        // Slashes that do not follow a provider name are part of the model
+        assert_eq!(
+            parse_model_spec("meta-llama/llama-3.3-70b", "openrouter", &providers),
+            (
+                "openrouter".to_string(),
+                "meta-llama/llama-3.3-70b".to_string()
+            )
+        );
+        assert_eq!(
+            parse_model_spec("openrouter/meta-llama/llama-3.3-70b", "openai", &providers),
+            (
+                "openrouter".to_string(),
+                "meta-llama/llama-3.3-70b".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_custom_command() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("standup.md");
+        std::fs::write(&path, "Summarize {{ args }}").unwrap();
+        let commands = vec![CustomCommand::from_file(&path).unwrap()];
+
+        if let Some(InputResult::CustomCommand(options)) =
+            handle_slash_command("/standup  the last two days ", &commands)
+        {
+            assert_eq!(options.name, "standup");
+            assert_eq!(options.input, "the last two days");
+        } else {
+            panic!("Expected CustomCommand");
+        }
+
+        assert!(matches!(
+            handle_slash_command("/standup", &commands),
+            Some(InputResult::CustomCommand(_))
+        ));
+        assert!(handle_slash_command("/standups", &commands).is_none());
+    }
 }
@@ -0,0 +1,236 @@
+//! Machine-readable output for `goose run --output-format json|stream-json`.
+//!
+//! In `stream-json` mode every agent event is written to stdout as one JSON line as it happens,
+//! and the run ends with a `result` line holding the [`RunSummary`]. In `json` mode only the
+//! summary is written, with the messages of the run included.
+
+use clap::ValueEnum;
+use goose::agents::MAX_TURNS_REACHED_MESSAGE;
+use goose::conversation::message::Message;
+use goose::conversation::Conversation;
+use rmcp::model::{Role, ServerNotification};
+use serde::Serialize;
+use std::io::Write;
+
+/// How a headless run writes its output
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Formatted for reading in a terminal
+    #[default]
+    Text,
+    /// A single JSON object once the run ends
+    Json,
+    /// One JSON object per line for each event, then a result object
+    StreamJson,
+}
+
+/// Why a headless run ended
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExitReason {
+    #[default]
+    Completed,
+    MaxTurns,
+    Cancelled,
+    Error,
+}
+
+impl ExitReason {
+    /// Process exit code for a run that ended this way
+    pub fn exit_code(self) -> i32 {
+        match self {
+            ExitReason::Completed => 0,
+            ExitReason::Error => 1,
+            ExitReason::MaxTurns => 2,
+            ExitReason::Cancelled => 130,
+        }
+    }
+}
+
+/// Tokens used during the run. Unknown when the run did not store a session.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TokenUsage {
+    pub input_tokens: Option<i32>,
+    pub output_tokens: Option<i32>,
+    pub total_tokens: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RunSummary {
+    pub session_id: Option<String>,
+    pub exit_reason: ExitReason,
+    /// Number of model responses during the run
+    pub turns: u32,
+    pub usage: TokenUsage,
+    pub duration_ms: u64,
+    /// Text of the last response
+    pub result: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Messages added during the run, only included in `json` output
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub messages: Option<Vec<Message>>,
+}
+
+/// One line of `stream-json` output
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StreamEvent<'a> {
+    Message {
+        message: &'a Message,
+    },
+    Notification {
+        extension: &'a str,
+        notification: &'a ServerNotification,
+    },
+    ModelChange {
+        model: &'a str,
+        mode: &'a str,
+    },
+    HistoryReplaced {
+        messages: &'a [Message],
+    },
+    Result(&'a RunSummary),
+}
+
+/// What happened during a headless run, collected from the agent's events
+#[derive(Debug, Default)]
+pub struct RunState {
+    pub turns: u32,
+    pub exit_reason: ExitReason,
+    pub error: Option<String>,
+    pub messages: Conversation,
+    /// The session's accumulated usage when the run started
+    pub usage_before: TokenUsage,
+}
+
+impl RunState {
+    /// Records a message from the agent. Streamed chunks of one response count as one turn.
+    pub fn record_message(&mut self, message: &Message) {
+        let count = self.messages.len();
+        self.messages.push(message.clone());
+        if message.role != Role::Assistant || self.messages.len() == count {
+            return;
+        }
+        if message.as_concat_text() == MAX_TURNS_REACHED_MESSAGE {
+            self.exit_reason = ExitReason::MaxTurns;
+        } else {
+            self.turns += 1;
+        }
+    }
+
+    pub fn fail(&mut self, error: String) {
+        self.exit_reason = ExitReason::Error;
+        self.error = Some(error);
+    }
+}
+
+/// Writes one value as a single line of JSON on stdout
+pub fn write_json_line<T: Serialize>(value: &T) {
+    match serde_json::to_string(value) {
+        Ok(line) => {
+            let mut stdout = std::io::stdout().lock();
+            let _ = writeln!(stdout, "{}", line);
+            let _ = stdout.flush();
+        }
+        Err(e) => eprintln!("Failed to serialize output: {}", e),
+    }
+}
+
+/// Tokens used between two readings of a session's accumulated usage
+pub fn usage_between(before: &TokenUsage, after: &TokenUsage) -> TokenUsage {
+    let delta =
+        |before: Option<i32>, after: Option<i32>| after.map(|after| after - before.unwrap_or(0));
+    TokenUsage {
+        input_tokens: delta(before.input_tokens, after.input_tokens),
+        output_tokens: delta(before.output_tokens, after.output_tokens),
+        total_tokens: delta(before.total_tokens, after.total_tokens),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_stream_events_are_tagged() {
+        let message = Message::assistant().with_text("done");
+        let value = serde_json::to_value(StreamEvent::Message { message: &message }).unwrap();
+        assert_eq!(value["type"], "message");
+        assert_eq!(value["message"]["role"], "assistant");
+
+        let summary = RunSummary {
+            session_id: Some("20250101_120000".to_string()),
+            exit_reason: ExitReason::MaxTurns,
+            turns: 3,
+            usage: TokenUsage::default(),
+            duration_ms: 10,
+            result: Some("done".to_string()),
+            error: None,
+            messages: None,
+        };
+        let value = serde_json::to_value(StreamEvent::Result(&summary)).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "type": "result",
+                "session_id": "20250101_120000",
+                "exit_reason": "max_turns",
+                "turns": 3,
+                "usage": {"input_tokens": null, "output_tokens": null, "total_tokens": null},
+                "duration_ms": 10,
+                "result": "done",
+            })
+        );
+        assert_eq!(summary.exit_reason.exit_code(), 2);
+    }
+
+    #[test]
+    fn test_run_state_counts_turns_and_max_turns() {
+        let mut run = RunState::default();
+        let first = Message::assistant()
+            .with_text("Let me look")
+            .with_id("msg_1");
+        run.record_message(&first);
+        run.record_message(&Message::assistant().with_text(" around").with_id("msg_1"));
+        run.record_message(&Message::user().with_text("tool output"));
+        run.record_message(&Message::assistant().with_text("Found it"));
+        assert_eq!(run.turns, 2);
+        assert_eq!(run.messages.len(), 3);
+        assert_eq!(run.exit_reason, ExitReason::Completed);
+
+        run.record_message(&Message::assistant().with_text(MAX_TURNS_REACHED_MESSAGE));
+        assert_eq!(run.turns, 2);
+        assert_eq!(run.exit_reason, ExitReason::MaxTurns);
+    }
+
+    #[test]
+    fn test_usage_between_resumed_readings() {
+        let before = TokenUsage {
+            input_tokens: Some(100),
+            output_tokens: None,
+            total_tokens: Some(150),
+        };
+        let after = TokenUsage {
+            input_tokens: Some(160),
+            output_tokens: Some(20),
+            total_tokens: Some(230),
+        };
+
+        let usage = usage_between(&before, &after);
+
+        assert_eq!(
+            usage,
+            TokenUsage {
+                input_tokens: Some(60),
+                output_tokens: Some(20),
+                total_tokens: Some(80),
+            }
+        );
+        assert_eq!(
+            usage_between(&after, &TokenUsage::default()),
+            TokenUsage::default()
+        );
+    }
+}
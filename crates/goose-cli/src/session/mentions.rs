@@ -0,0 +1,427 @@
+//! `@path` mentions in interactive messages.
+//!
+//! A mention of a file, directory or image in the working directory is attached to the message,
+//! so the model sees it without spending a tool call to read it. Each attachment is stored as
+//! its own text block wrapped in an `<attachment>` tag, which lets the terminal and
+//! `goose session export` show it collapsed.
+
+use base64::Engine;
+use goose::conversation::message::Message;
+use ignore::gitignore::Gitignore;
+use ignore::WalkBuilder;
+use std::path::{Path, PathBuf};
+
+/// Largest text file that is attached
+const MAX_FILE_BYTES: u64 = 256 * 1024;
+/// Largest image that is attached
+const MAX_IMAGE_BYTES: u64 = 5 * 1024 * 1024;
+/// Budget for all attachments of one message
+const MAX_TOTAL_BYTES: usize = 1024 * 1024;
+/// Entries listed for a mentioned directory
+const MAX_DIR_ENTRIES: usize = 200;
+/// Paths scanned when completing a mention
+const MAX_INDEXED_PATHS: usize = 10_000;
+/// Completions offered for a mention
+const MAX_COMPLETIONS: usize = 20;
+
+const ATTACHMENT_END: &str = "\n</attachment>";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttachmentKind {
+    File,
+    Directory,
+    Image,
+}
+
+impl AttachmentKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            AttachmentKind::File => "file",
+            AttachmentKind::Directory => "directory",
+            AttachmentKind::Image => "image",
+        }
+    }
+
+    fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "file" => Some(AttachmentKind::File),
+            "directory" => Some(AttachmentKind::Directory),
+            "image" => Some(AttachmentKind::Image),
+            _ => None,
+        }
+    }
+}
+
+/// An attachment read back from a message's text block
+#[derive(Debug, PartialEq, Eq)]
+pub struct Attachment<'a> {
+    pub path: &'a str,
+    pub kind: AttachmentKind,
+    pub body: &'a str,
+}
+
+impl Attachment<'_> {
+    /// One line describing the attachment, for showing it collapsed
+    pub fn summary(&self) -> String {
+        match self.kind {
+            AttachmentKind::File => format!("{} ({} lines)", self.path, self.body.lines().count()),
+            AttachmentKind::Directory => {
+                format!("{} ({} entries)", self.path, self.body.lines().count())
+            }
+            AttachmentKind::Image => format!("{} (image)", self.path),
+        }
+    }
+}
+
+fn format_attachment(path: &str, kind: AttachmentKind, body: &str) -> String {
+    format!(
+        "<attachment path=\"{}\" kind=\"{}\">\n{}{}",
+        path,
+        kind.as_str(),
+        body,
+        ATTACHMENT_END
+    )
+}
+
+/// Read an attachment back from a text block, or None for ordinary text
+pub fn parse_attachment(text: &str) -> Option<Attachment<'_>> {
+    let rest = text.strip_prefix("<attachment path=\"")?;
+    let (path, rest) = rest.split_once("\" kind=\"")?;
+    let (kind, rest) = rest.split_once("\">\n")?;
+    Some(Attachment {
+        path,
+        kind: AttachmentKind::parse(kind)?,
+        body: rest.strip_suffix(ATTACHMENT_END)?,
+    })
+}
+
+/// Find `@path` mentions, an `@` at the start of a word followed by the path
+pub fn find_mentions(text: &str) -> Vec<&str> {
+    let mut mentions: Vec<&str> = text
+        .split_whitespace()
+        .filter_map(|word| word.strip_prefix('@'))
+        .map(|path| path.trim_end_matches([',', ';', ':', '!', '?', ')', '.']))
+        .filter(|path| !path.is_empty() && !path.contains('"'))
+        .collect();
+    mentions.dedup();
+    mentions
+}
+
+fn image_mime_type(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_str()?.to_lowercase();
+    match extension.as_str() {
+        "png" => Some("image/png"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "gif" => Some("image/gif"),
+        "webp" => Some("image/webp"),
+        _ => None,
+    }
+}
+
+/// Resolve a mention to a path inside the working directory
+fn resolve_mention(mention: &str, cwd: &Path) -> Result<PathBuf, String> {
+    let cwd = cwd
+        .canonicalize()
+        .map_err(|e| format!("cannot read the working directory: {}", e))?;
+    let path = cwd
+        .join(mention)
+        .canonicalize()
+        .map_err(|_| "does not exist".to_string())?;
+    if !path.starts_with(&cwd) {
+        return Err("is outside the working directory".to_string());
+    }
+    Ok(path)
+}
+
+fn list_directory(dir: &Path, cwd: &Path, ignore_patterns: &Gitignore) -> String {
+    let mut entries = Vec::new();
+    let mut more = 0;
+    for entry in WalkBuilder::new(dir)
+        .add_custom_ignore_filename(".gooseignore")
+        .build()
+        .flatten()
+        .skip(1)
+    {
+        let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+        if ignore_patterns.matched(entry.path(), is_dir).is_ignore() {
+            continue;
+        }
+        if entries.len() == MAX_DIR_ENTRIES {
+            more += 1;
+            continue;
+        }
+        let relative = entry.path().strip_prefix(cwd).unwrap_or(entry.path());
+        let suffix = if is_dir { "/" } else { "" };
+        entries.push(format!("{}{}", relative.display(), suffix));
+    }
+    if more > 0 {
+        entries.push(format!("... {} more entries", more));
+    }
+    entries.join("\n")
+}
+
+/// Build the user message for `text`, attaching every mentioned path. Mentions that cannot be
+/// attached stay as plain text and are described in the returned notes.
+pub fn attach_mentions(text: &str, cwd: &Path) -> (Message, Vec<String>) {
+    let mut message = Message::user().with_text(text);
+    let mut notes = Vec::new();
+    let mentions = find_mentions(text);
+    if mentions.is_empty() {
+        return (message, notes);
+    }
+
+    let ignore_patterns = goose_mcp::load_ignore_patterns(cwd);
+    let cwd = cwd.canonicalize().unwrap_or_else(|_| cwd.to_path_buf());
+    let mut total_bytes = 0;
+
+    for mention in mentions {
+        let path = match resolve_mention(mention, &cwd) {
+            Ok(path) => path,
+            // Not every @word is a path, only mention the ones that look like one
+            Err(reason) if mention.contains(['/', '.']) => {
+                notes.push(format!("@{} {}", mention, reason));
+                continue;
+            }
+            Err(_) => continue,
+        };
+        if ignore_patterns.matched(&path, path.is_dir()).is_ignore() {
+            notes.push(format!("@{} is ignored by .gooseignore", mention));
+            continue;
+        }
+
+        let size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        let attachment = if path.is_dir() {
+            let listing = list_directory(&path, &cwd, &ignore_patterns);
+            Ok((
+                format_attachment(mention, AttachmentKind::Directory, &listing),
+                None,
+            ))
+        } else if let Some(mime_type) = image_mime_type(&path) {
+            if size > MAX_IMAGE_BYTES {
+                Err(format!(
+                    "is too large to attach ({} KB, limit {} KB)",
+                    size / 1024,
+                    MAX_IMAGE_BYTES / 1024
+                ))
+            } else {
+                std::fs::read(&path)
+                    .map(|bytes| {
+                        let data = base64::prelude::BASE64_STANDARD.encode(bytes);
+                        (
+                            format_attachment(mention, AttachmentKind::Image, ""),
+                            Some((data, mime_type)),
+                        )
+                    })
+                    .map_err(|e| format!("could not be read: {}", e))
+            }
+        } else if size > MAX_FILE_BYTES {
+            Err(format!(
+                "is too large to attach ({} KB, limit {} KB)",
+                size / 1024,
+                MAX_FILE_BYTES / 1024
+            ))
+        } else {
+            std::fs::read_to_string(&path)
+                .map(|content| {
+                    (
+                        format_attachment(mention, AttachmentKind::File, &content),
+                        None,
+                    )
+                })
+                .map_err(|_| "is not a text file".to_string())
+        };
+
+        match attachment {
+            Ok((block, image)) => {
+                let bytes = block.len() + image.as_ref().map_or(0, |(data, _)| data.len());
+                if total_bytes + bytes > MAX_TOTAL_BYTES {
+                    notes.push(format!(
+                        "@{} was not attached, the message is over the {} KB attachment limit",
+                        mention,
+                        MAX_TOTAL_BYTES / 1024
+                    ));
+                    continue;
+                }
+                total_bytes += bytes;
+                message = message.with_text(block);
+                if let Some((data, mime_type)) = image {
+                    message = message.with_image(data, mime_type);
+                }
+            }
+            Err(reason) => notes.push(format!("@{} {}", mention, reason)),
+        }
+    }
+
+    (message, notes)
+}
+
+/// How well `query` fuzzy-matches `candidate`, or None when its characters do not all appear
+/// in order. Consecutive characters and characters at the start of a path segment score higher.
+fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    let candidate_chars: Vec<char> = candidate.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous_match: Option<usize> = None;
+
+    for query_char in query.to_lowercase().chars() {
+        let found =
+            (position..candidate_chars.len()).find(|&i| candidate_chars[i] == query_char)?;
+        score += 1;
+        if previous_match.is_some_and(|previous| previous + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || matches!(candidate_chars[found - 1], '/' | '_' | '-' | '.') {
+            score += 3;
+        }
+        previous_match = Some(found);
+        position = found + 1;
+    }
+
+    Some(score * 100 - candidate_chars.len() as i64)
+}
+
+/// Paths under the working directory that fuzzy-match `query`, best first. Directories end
+/// with `/`.
+pub fn complete_mention(query: &str, cwd: &Path) -> Vec<String> {
+    let ignore_patterns = goose_mcp::load_ignore_patterns(cwd);
+    let mut matches: Vec<(i64, String)> = WalkBuilder::new(cwd)
+        .add_custom_ignore_filename(".gooseignore")
+        .build()
+        .flatten()
+        .skip(1)
+        .take(MAX_INDEXED_PATHS)
+        .filter_map(|entry| {
+            let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+            if ignore_patterns.matched(entry.path(), is_dir).is_ignore() {
+                return None;
+            }
+            let relative = entry.path().strip_prefix(cwd).ok()?.to_str()?;
+            let path = if is_dir {
+                format!("{}/", relative)
+            } else {
+                relative.to_string()
+            };
+            fuzzy_score(query, &path).map(|score| (score, path))
+        })
+        .collect();
+
+    matches.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+    matches
+        .into_iter()
+        .take(MAX_COMPLETIONS)
+        .map(|(_, path)| path)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use goose::conversation::message::MessageContent;
+    use tempfile::TempDir;
+
+    fn workspace() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join("src/session")).unwrap();
+        std::fs::write(dir.path().join("src/main.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(dir.path().join("src/session/input.rs"), "// input\n").unwrap();
+        std::fs::write(dir.path().join("secret.txt"), "hunter2").unwrap();
+        std::fs::write(dir.path().join(".gooseignore"), "secret.txt\n").unwrap();
+        std::fs::write(dir.path().join("logo.png"), [0x89, b'P', b'N', b'G']).unwrap();
+        dir
+    }
+
+    fn attachments(message: &Message) -> Vec<Attachment<'_>> {
+        message
+            .content
+            .iter()
+            .filter_map(|c| c.as_text().and_then(parse_attachment))
+            .collect()
+    }
+
+    #[test]
+    fn test_find_mentions() {
+        assert_eq!(
+            find_mentions("compare @src/main.rs and @docs/, then mail me@example.com"),
+            vec!["src/main.rs", "docs/"]
+        );
+        assert_eq!(find_mentions("(see @README.md.)"), vec!["README.md"]);
+        assert!(find_mentions("no mentions @ all").is_empty());
+    }
+
+    #[test]
+    fn test_attachment_round_trip() {
+        let block = format_attachment("src/main.rs", AttachmentKind::File, "fn main() {}");
+        assert_eq!(
+            parse_attachment(&block),
+            Some(Attachment {
+                path: "src/main.rs",
+                kind: AttachmentKind::File,
+                body: "fn main() {}",
+            })
+        );
+        let image = format_attachment("logo.png", AttachmentKind::Image, "");
+        assert_eq!(parse_attachment(&image).unwrap().body, "");
+        assert_eq!(parse_attachment("<attachment> is just text"), None);
+    }
+
+    #[test]
+    fn test_attach_files_directories_and_images() {
+        let dir = workspace();
+        let (message, notes) = attach_mentions(
+            "look at @src/main.rs, @src/session and @logo.png",
+            dir.path(),
+        );
+
+        assert!(notes.is_empty(), "{:?}", notes);
+        assert_eq!(
+            message.content[0].as_text(),
+            Some("look at @src/main.rs, @src/session and @logo.png")
+        );
+        let attached = attachments(&message);
+        assert_eq!(attached.len(), 3);
+        assert_eq!(attached[0].body, "fn main() {}\n");
+        assert_eq!(attached[1].kind, AttachmentKind::Directory);
+        assert_eq!(attached[1].body, "src/session/input.rs");
+        assert_eq!(attached[2].kind, AttachmentKind::Image);
+        assert!(matches!(
+            message.content.last(),
+            Some(MessageContent::Image(image)) if image.mime_type == "image/png"
+        ));
+    }
+
+    #[test]
+    fn test_attach_respects_ignore_and_limits() {
+        let dir = workspace();
+        std::fs::write(
+            dir.path().join("big.log"),
+            "x".repeat(MAX_FILE_BYTES as usize + 1),
+        )
+        .unwrap();
+
+        let (message, notes) = attach_mentions(
+            "@secret.txt @big.log @../outside.txt @missing.rs @someone",
+            dir.path(),
+        );
+
+        assert!(attachments(&message).is_empty());
+        assert_eq!(message.content.len(), 1);
+        assert_eq!(notes.len(), 4, "{:?}", notes);
+        assert!(notes[0].contains("ignored by .gooseignore"));
+        assert!(notes[1].contains("too large"));
+        assert!(notes[2].starts_with("@../outside.txt"));
+        assert!(notes[3].contains("does not exist"));
+    }
+
+    #[test]
+    fn test_complete_mention_is_fuzzy() {
+        let dir = workspace();
+
+        let matches = complete_mention("sesinp", dir.path());
+        assert_eq!(matches, vec!["src/session/input.rs"]);
+
+        let matches = complete_mention("main", dir.path());
+        assert_eq!(matches[0], "src/main.rs");
+
+        assert!(!complete_mention("", dir.path()).contains(&"secret.txt".to_string()));
+    }
+}
@@ -1,12 +1,16 @@
 mod builder;
 mod completion;
+mod custom_commands;
 mod export;
 mod input;
+mod json_output;
+mod mentions;
 mod output;
 mod prompt;
 mod task_execution_display;
 mod thinking;
 
+use crate::recipes::search_recipe::list_available_recipes;
 use crate::session::task_execution_display::{
     format_task_execution_notification, TASK_EXECUTION_NOTIFICATION_TYPE,
 };
@@ -18,22 +22,29 @@ pub use builder::{build_session, SessionBuilderConfig, SessionSettings};
 use console::Color;
 use goose::agents::AgentEvent;
 use goose::permission::permission_confirmation::PrincipalType;
+use goose::permission::ApprovalPolicy;
 use goose::permission::Permission;
 use goose::permission::PermissionConfirmation;
 use goose::providers::base::Provider;
 pub use goose::session::Identifier;
 use goose::utils::safe_truncate;
+pub use json_output::{write_json_line, ExitReason, OutputFormat, RunSummary, StreamEvent};
 
 use anyhow::{Context, Result};
 use completion::GooseCompleter;
+use custom_commands::{command_dirs, CustomCommand};
 use etcetera::{choose_app_strategy, AppStrategy};
 use goose::agents::extension::{Envs, ExtensionConfig};
 use goose::agents::types::RetryConfig;
-use goose::agents::{Agent, SessionConfig};
-use goose::config::Config;
+use goose::agents::{Agent, SessionConfig, SUB_RECIPE_TASK_TOOL_NAME_PREFIX};
+use goose::config::{Config, PermissionManager, ProjectPermissions};
+use goose::model::ModelConfig;
+use goose::providers::base::set_current_model;
 use goose::providers::pricing::initialize_pricing_cache;
+use goose::providers::{create_for_switch, lead_provider_from_env, providers};
 use goose::session;
 use input::InputResult;
+use json_output::{usage_between, RunState, TokenUsage};
 use rmcp::model::PromptMessage;
 use rmcp::model::ServerNotification;
 use rmcp::model::{ErrorCode, ErrorData};
@@ -45,7 +56,7 @@ use serde_json::Value;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tokio;
 use tokio_util::sync::CancellationToken;
 
@@ -66,12 +77,25 @@ pub struct Session {
     max_turns: Option<u32>,
     edit_mode: Option<EditMode>,
     retry_config: Option<RetryConfig>,
+    output_format: OutputFormat,
+    run: RunState,
+    // Provider the session runs on and the one serving the lead model, used by /model
+    provider_name: Option<String>,
+    lead_provider_name: Option<String>,
+    custom_commands: Vec<CustomCommand>,
+    // Goose mode set by the custom command that is running
+    goose_mode_override: Option<String>,
+    approval_policy: Option<ApprovalPolicy>,
+    // Goose mode the approval policy needs to see tool calls
+    approval_mode: Option<String>,
 }
 
 // Cache structure for completion data
 struct CompletionCache {
     prompts: HashMap<String, Vec<String>>,
     prompt_info: HashMap<String, output::PromptInfo>,
+    models: Vec<String>,
+    custom_commands: Vec<String>,
     last_updated: Instant,
 }
 
@@ -80,6 +104,8 @@ impl CompletionCache {
         Self {
             prompts: HashMap::new(),
             prompt_info: HashMap::new(),
+            models: Vec::new(),
+            custom_commands: Vec::new(),
             last_updated: Instant::now(),
         }
     }
@@ -152,9 +178,152 @@ impl Session {
             max_turns,
             edit_mode,
             retry_config,
+            output_format: OutputFormat::default(),
+            run: RunState::default(),
+            provider_name: None,
+            lead_provider_name: None,
+            custom_commands: Vec::new(),
+            goose_mode_override: None,
+            approval_policy: None,
+            approval_mode: None,
         }
     }
 
+    /// Directory the session works in: the one recorded in the session file, so resumed
+    /// sessions keep theirs, or the current directory
+    fn working_dir(&self) -> PathBuf {
+        self.session_file
+            .as_ref()
+            .filter(|file| file.exists())
+            .and_then(|file| session::read_metadata(file).ok())
+            .map(|metadata| metadata.working_dir)
+            .unwrap_or_else(|| std::env::current_dir().unwrap_or_default())
+    }
+
+    /// Answer tool confirmation requests with `policy` instead of asking. A policy that names
+    /// tools has to see every tool call, so the session runs in approve mode instead of auto.
+    pub fn set_approval_policy(&mut self, policy: ApprovalPolicy) {
+        if policy.is_empty() {
+            return;
+        }
+        let mode = Config::global()
+            .get_param::<String>("GOOSE_MODE")
+            .unwrap_or_else(|_| "auto".to_string());
+        if policy.has_patterns() && mode == "auto" {
+            self.approval_mode = Some("approve".to_string());
+        }
+        self.approval_policy = Some(policy);
+    }
+
+    /// Record the provider the session was built with, so /model can switch within it
+    pub fn set_provider_name(&mut self, provider_name: &str) {
+        self.lead_provider_name = Some(lead_provider_from_env(provider_name));
+        self.provider_name = Some(provider_name.to_string());
+    }
+
+    /// Switch the agent to another model for the rest of the session and record it in the
+    /// session metadata so a resumed session keeps it. In lead/worker mode the model becomes
+    /// the worker and the lead stays on its provider.
+    pub async fn set_model(
+        &mut self,
+        provider_name: &str,
+        model_name: &str,
+    ) -> Result<Arc<dyn Provider>> {
+        let current = self.agent.provider().await?;
+        // A fresh model config picks up the new model's context limit, which compaction uses
+        let model_config =
+            ModelConfig::new(model_name)?.with_temperature(current.get_model_config().temperature);
+        let lead_provider_name = self
+            .lead_provider_name
+            .clone()
+            .unwrap_or_else(|| lead_provider_from_env(provider_name));
+        let provider = create_for_switch(provider_name, model_config, &lead_provider_name)?;
+
+        self.agent.update_provider(Arc::clone(&provider)).await?;
+        set_current_model(&provider.get_active_model_name());
+        self.provider_name = Some(provider_name.to_string());
+
+        if let Some(session_file) = &self.session_file {
+            let mut metadata = if session_file.exists() {
+                session::read_metadata(session_file)?
+            } else {
+                session::SessionMetadata::new(std::env::current_dir().unwrap_or_default())
+            };
+            metadata.provider = Some(provider_name.to_string());
+            metadata.model = Some(model_name.to_string());
+            if session_file.exists() {
+                session::update_metadata(session_file, &metadata).await?;
+            } else {
+                session::storage::save_messages_with_metadata(
+                    session_file,
+                    &metadata,
+                    &self.messages,
+                )?;
+            }
+        }
+
+        self.refresh_model_completions().await;
+        Ok(provider)
+    }
+
+    /// Handle `/model [provider/]model`, or show the model in use without an argument
+    async fn switch_model(&mut self, spec: Option<String>) -> Result<()> {
+        let current = self.agent.provider().await?;
+        let current_provider = self.provider_name.clone().unwrap_or_default();
+        let Some(spec) = spec.filter(|spec| !spec.is_empty()) else {
+            output::render_model_switch(&current_provider, &current);
+            return Ok(());
+        };
+
+        let provider_names: Vec<String> = providers().into_iter().map(|p| p.name).collect();
+        let (provider_name, model_name) =
+            input::parse_model_spec(&spec, &current_provider, &provider_names);
+        if provider_name.is_empty() {
+            return Err(anyhow::anyhow!(
+                "The session's provider is unknown, use /model <provider>/<model>"
+            ));
+        }
+
+        let provider = self.set_model(&provider_name, &model_name).await?;
+        output::render_model_switch(&provider_name, &provider);
+        Ok(())
+    }
+
+    /// Fill the /model completions in the background, since listing models may call the provider
+    async fn refresh_model_completions(&self) {
+        let Ok(provider) = self.agent.provider().await else {
+            return;
+        };
+        let provider_name = self.provider_name.clone();
+        let cache = Arc::clone(&self.completion_cache);
+        tokio::spawn(async move {
+            let registry = providers();
+            let mut models = match provider.fetch_supported_models().await {
+                Ok(Some(models)) => models,
+                _ => registry
+                    .iter()
+                    .filter(|p| Some(&p.name) == provider_name.as_ref())
+                    .flat_map(|p| p.known_models.iter().map(|m| m.name.clone()))
+                    .collect(),
+            };
+            for metadata in &registry {
+                models.extend(
+                    metadata
+                        .known_models
+                        .iter()
+                        .map(|m| format!("{}/{}", metadata.name, m.name)),
+                );
+            }
+            cache.write().unwrap().models = models;
+        });
+    }
+
+    /// Switch headless output to one of the machine-readable formats
+    pub fn set_output_format(&mut self, output_format: OutputFormat) {
+        self.output_format = output_format;
+        output::set_machine_output(output_format != OutputFormat::Text);
+    }
+
     /// Helper function to summarize context messages
     async fn summarize_context_messages(
         messages: &mut Conversation,
@@ -408,6 +577,8 @@ impl Session {
                 .await?;
         }
 
+        self.load_custom_commands();
+
         // Initialize the completion cache
         self.update_completion_cache().await?;
 
@@ -465,13 +636,14 @@ impl Session {
             // Display context usage before each prompt
             self.display_context_usage().await?;
 
-            match input::get_input(&mut editor)? {
+            match input::get_input(&mut editor, &self.custom_commands)? {
                 InputResult::Message(content) => {
                     match self.run_mode {
                         RunMode::Normal => {
                             save_history(&mut editor);
 
-                            self.push_message(Message::user().with_text(&content));
+                            let message = self.user_message(&content);
+                            self.push_message(message);
 
                             let provider = self.agent.provider().await?;
 
@@ -496,7 +668,7 @@ impl Session {
                         }
                         RunMode::Plan => {
                             let mut plan_messages = self.messages.clone();
-                            plan_messages.push(Message::user().with_text(&content));
+                            plan_messages.push(self.user_message(&content));
                             let reasoner = get_reasoner()?;
                             self.plan_with_reasoner_model(plan_messages, reasoner)
                                 .await?;
@@ -572,6 +744,14 @@ impl Session {
                         Err(e) => output::render_error(&e.to_string()),
                     }
                 }
+                input::InputResult::SwitchModel(spec) => {
+                    save_history(&mut editor);
+
+                    if let Err(e) = self.switch_model(spec).await {
+                        output::render_error(&e.to_string());
+                    }
+                    continue;
+                }
                 input::InputResult::GooseMode(mode) => {
                     save_history(&mut editor);
 
@@ -632,6 +812,19 @@ impl Session {
                     save_history(&mut editor);
                     self.handle_prompt_command(opts).await?;
                 }
+                InputResult::CustomCommand(opts) => {
+                    save_history(&mut editor);
+                    self.handle_custom_command(opts).await?;
+                }
+                InputResult::ListRecipes => {
+                    save_history(&mut editor);
+
+                    match tokio::task::spawn_blocking(list_available_recipes).await {
+                        Ok(Ok(recipes)) => output::render_recipes(&recipes),
+                        Ok(Err(e)) => output::render_error(&e.to_string()),
+                        Err(e) => output::render_error(&e.to_string()),
+                    }
+                }
                 InputResult::Recipe(filepath_opt) => {
                     println!("{}", console::style("Generating Recipe").green());
 
@@ -863,12 +1056,63 @@ impl Session {
 
     /// Process a single message and exit
     pub async fn headless(&mut self, prompt: String) -> Result<()> {
+        self.run = RunState {
+            usage_before: self.accumulated_usage(),
+            ..RunState::default()
+        };
         let message = Message::user().with_text(&prompt);
-        self.process_message(message, CancellationToken::default())
-            .await?;
+        if let Err(e) = self
+            .process_message(message, CancellationToken::default())
+            .await
+        {
+            self.run.fail(e.to_string());
+            return Err(e);
+        }
         Ok(())
     }
 
+    fn accumulated_usage(&self) -> TokenUsage {
+        self.get_metadata()
+            .map(|metadata| TokenUsage {
+                input_tokens: metadata.accumulated_input_tokens,
+                output_tokens: metadata.accumulated_output_tokens,
+                total_tokens: metadata.accumulated_total_tokens,
+            })
+            .unwrap_or_default()
+    }
+
+    /// Summary of the last headless run
+    pub fn run_summary(&self, duration: Duration) -> RunSummary {
+        let result = self
+            .run
+            .messages
+            .iter()
+            .rev()
+            .find(|m| m.role == rmcp::model::Role::Assistant)
+            .map(|m| m.as_concat_text())
+            .filter(|text| !text.is_empty());
+        let usage = if self.session_file.is_some() {
+            usage_between(&self.run.usage_before, &self.accumulated_usage())
+        } else {
+            TokenUsage::default()
+        };
+        RunSummary {
+            session_id: self
+                .session_file
+                .as_ref()
+                .and_then(|f| f.file_stem())
+                .map(|stem| stem.to_string_lossy().to_string()),
+            exit_reason: self.run.exit_reason,
+            turns: self.run.turns,
+            usage,
+            duration_ms: duration.as_millis() as u64,
+            result,
+            error: self.run.error.clone(),
+            messages: (self.output_format == OutputFormat::Json)
+                .then(|| self.run.messages.messages().clone()),
+        }
+    }
+
     async fn process_agent_response(
         &mut self,
         interactive: bool,
@@ -876,14 +1120,19 @@ impl Session {
     ) -> Result<()> {
         let cancel_token_clone = cancel_token.clone();
 
+        let working_dir = self.working_dir();
         let session_config = self.session_file.as_ref().map(|s| {
             let session_id = session::Identifier::Path(s.clone());
             SessionConfig {
                 id: session_id.clone(),
-                working_dir: std::env::current_dir().unwrap_or_default(),
+                working_dir: working_dir.clone(),
                 schedule_id: self.scheduled_job_id.clone(),
                 execution_mode: None,
                 max_turns: self.max_turns,
+                goose_mode: self
+                    .goose_mode_override
+                    .clone()
+                    .or_else(|| self.approval_mode.clone()),
                 retry_config: self.retry_config.clone(),
             }
         });
@@ -908,31 +1157,56 @@ impl Session {
                             if let Some(MessageContent::ToolConfirmationRequest(confirmation)) = message.content.first() {
                                 output::hide_thinking();
 
-                                // Format the confirmation prompt
-                                let prompt = "Goose would like to call the above tool, do you allow?".to_string();
-
-                                // Get confirmation from user
-                                let permission_result = cliclack::select(prompt)
-                                    .item(Permission::AllowOnce, "Allow", "Allow the tool call once")
-                                    .item(Permission::AlwaysAllow, "Always Allow", "Always allow the tool call")
-                                    .item(Permission::DenyOnce, "Deny", "Deny the tool call")
-                                    .item(Permission::Cancel, "Cancel", "Cancel the AI response and tool call")
-                                    .interact();
-
-                                let permission = match permission_result {
-                                    Ok(p) => p, // If Ok, use the selected permission
-                                    Err(e) => {
-                                        // Check if the error is an interruption (Ctrl+C/Cmd+C, Escape)
-                                        if e.kind() == std::io::ErrorKind::Interrupted {
-                                            Permission::Cancel // If interrupted, set permission to Cancel
-                                        } else {
-                                            return Err(e.into()); // Otherwise, convert and propagate the original error
+                                let decision = self.approval_policy.as_ref().map(|policy| policy.decide(&confirmation.tool_name));
+                                let permission = if let Some(decision) = &decision {
+                                    // Answered by the approval policy, there may be nobody to ask
+                                    output::render_approval_decision(&confirmation.tool_name, decision);
+                                    decision.permission().unwrap_or_else(|| {
+                                        self.run.fail(format!(
+                                            "{} needs confirmation and the approval policy fails the run",
+                                            confirmation.tool_name
+                                        ));
+                                        Permission::Cancel
+                                    })
+                                } else {
+                                    // Show which permission layer asked for confirmation when project rules apply
+                                    let permission_manager = PermissionManager::default().with_project_for(&working_dir);
+                                    if permission_manager.project().is_some() {
+                                        output::render_text(
+                                            &format!("asking because of the {}", permission_manager.describe_decision_layer(&confirmation.tool_name)),
+                                            None,
+                                            true,
+                                        );
+                                    }
+
+                                    // Format the confirmation prompt
+                                    let prompt = "Goose would like to call the above tool, do you allow?".to_string();
+
+                                    // Get confirmation from user
+                                    let permission_result = cliclack::select(prompt)
+                                        .item(Permission::AllowOnce, "Allow", "Allow the tool call once")
+                                        .item(Permission::AlwaysAllow, "Always Allow", "Always allow the tool call")
+                                        .item(Permission::DenyOnce, "Deny", "Deny the tool call")
+                                        .item(Permission::Cancel, "Cancel", "Cancel the AI response and tool call")
+                                        .interact();
+
+                                    match permission_result {
+                                        Ok(p) => p, // If Ok, use the selected permission
+                                        Err(e) => {
+                                            // Check if the error is an interruption (Ctrl+C/Cmd+C, Escape)
+                                            if e.kind() == std::io::ErrorKind::Interrupted {
+                                                Permission::Cancel // If interrupted, set permission to Cancel
+                                            } else {
+                                                return Err(e.into()); // Otherwise, convert and propagate the original error
+                                            }
                                         }
                                     }
                                 };
 
                                 if permission == Permission::Cancel {
-                                    output::render_text("Tool call cancelled. Returning to chat...", Some(Color::Yellow), true);
+                                    if decision.is_none() {
+                                        output::render_text("Tool call cancelled. Returning to chat...", Some(Color::Yellow), true);
+                                    }
 
                                     let mut response_message = Message::user();
                                     response_message.content.push(MessageContent::tool_response(
@@ -1098,12 +1372,23 @@ impl Session {
                                     .await?;
                                 }
 
+                                self.run.record_message(&message);
+                                if self.output_format == OutputFormat::StreamJson {
+                                    write_json_line(&StreamEvent::Message { message: &message });
+                                }
+
                                 if interactive {output::hide_thinking()};
                                 let _ = progress_bars.hide();
                                 output::render_message(&message, self.debug);
                             }
                         }
-                        Some(Ok(AgentEvent::McpNotification((_id, message)))) => {
+                        Some(Ok(AgentEvent::McpNotification((id, message)))) => {
+                            if self.output_format == OutputFormat::StreamJson {
+                                write_json_line(&StreamEvent::Notification { extension: &id, notification: &message });
+                            }
+                            if output::is_machine_output() {
+                                continue;
+                            }
                             match &message {
                                 ServerNotification::LoggingMessageNotification(notification) => {
                                     let data = &notification.params.data;
@@ -1210,6 +1495,9 @@ impl Session {
                             }
                         }
                         Some(Ok(AgentEvent::HistoryReplaced(new_messages))) => {
+                            if self.output_format == OutputFormat::StreamJson {
+                                write_json_line(&StreamEvent::HistoryReplaced { messages: &new_messages });
+                            }
                             // Replace the session's message history with the compacted messages
                             self.messages = Conversation::new_unvalidated(new_messages);
 
@@ -1229,14 +1517,20 @@ impl Session {
                             }
                         }
                         Some(Ok(AgentEvent::ModelChange { model, mode })) => {
-                            // Log model change if in debug mode
-                            if self.debug {
+                            if self.output_format == OutputFormat::StreamJson {
+                                write_json_line(&StreamEvent::ModelChange { model: &model, mode: &mode });
+                            }
+                            // Fallback switches are always worth surfacing, others only in debug mode
+                            if mode == "fallback" || mode == "primary" {
+                                output::render_model_fallback(&model, &mode);
+                            } else if self.debug {
                                 eprintln!("Model changed to {} in {} mode", model, mode);
                             }
                         }
 
                         Some(Err(e)) => {
                             eprintln!("Error: {}", e);
+                            self.run.fail(e.to_string());
                             cancel_token_clone.cancel();
                             drop(stream);
                             if let Err(e) = self.handle_interrupted_messages(false).await {
@@ -1256,6 +1550,7 @@ impl Session {
                 _ = tokio::signal::ctrl_c() => {
                     cancel_token_clone.cancel();
                     drop(stream);
+                    self.run.exit_reason = ExitReason::Cancelled;
                     if let Err(e) = self.handle_interrupted_messages(true).await {
                         eprintln!("Error handling interruption: {}", e);
                     }
@@ -1263,7 +1558,9 @@ impl Session {
                 }
             }
         }
-        println!();
+        if !output::is_machine_output() {
+            println!();
+        }
 
         Ok(())
     }
@@ -1398,6 +1695,8 @@ impl Session {
     /// Update the completion cache with fresh data
     /// This should be called before the interactive session starts
     pub async fn update_completion_cache(&mut self) -> Result<()> {
+        self.refresh_model_completions().await;
+
         // Get fresh data
         let prompts = self.agent.list_extension_prompts().await;
 
@@ -1595,6 +1894,126 @@ impl Session {
         Ok(())
     }
 
+    /// Load the user and project slash commands and offer them for completion
+    fn load_custom_commands(&mut self) {
+        let working_dir = self.working_dir();
+        let (mut commands, mut warnings) =
+            custom_commands::load_commands(&command_dirs(&working_dir));
+        if ProjectPermissions::discover_trusted(&working_dir).is_none() {
+            warnings.extend(
+                commands
+                    .iter_mut()
+                    .filter_map(CustomCommand::drop_untrusted_settings),
+            );
+        }
+        for warning in warnings {
+            output::render_text(&warning, Some(Color::Yellow), true);
+        }
+        self.completion_cache.write().unwrap().custom_commands = commands
+            .iter()
+            .map(|command| command.name.clone())
+            .collect();
+        self.custom_commands = commands;
+    }
+
+    async fn handle_custom_command(&mut self, opts: input::CustomCommandOptions) -> Result<()> {
+        let Some(command) = self
+            .custom_commands
+            .iter()
+            .find(|command| command.name == opts.name)
+            .cloned()
+        else {
+            return Ok(());
+        };
+
+        let values = match command.parse_arguments(&opts.input) {
+            Ok(values) => values,
+            Err(e) => {
+                output::render_error(&e.to_string());
+                return Ok(());
+            }
+        };
+        let mut prompt = command.render(&opts.input, &values);
+
+        let sub_recipe = match command.sub_recipe() {
+            Ok(sub_recipe) => sub_recipe,
+            Err(e) => {
+                output::render_error(&format!(
+                    "Failed to load the recipe of /{}: {}",
+                    command.name, e
+                ));
+                return Ok(());
+            }
+        };
+        let mut added_sub_recipe = None;
+        if let Some(sub_recipe) = sub_recipe {
+            let instruction = format!(
+                "Run the `{}` sub-recipe once: call `{}_{}` with a single parameter set of {}, \
+                then run the task it creates with the task executor and report the result.",
+                sub_recipe.name,
+                SUB_RECIPE_TASK_TOOL_NAME_PREFIX,
+                sub_recipe.name,
+                serde_json::to_string(&values)?
+            );
+            prompt = if prompt.is_empty() {
+                instruction
+            } else {
+                format!("{}\n\n{}", instruction, prompt)
+            };
+            added_sub_recipe = Some(sub_recipe.name.clone());
+            self.agent.add_sub_recipes(vec![sub_recipe]).await;
+        }
+
+        let message = self.user_message(&prompt);
+        output::render_message(&message, self.debug);
+        self.push_message(message);
+
+        let current_mode = self.current_goose_mode();
+        let mode = command.mode_within(&current_mode);
+        if let (Some(requested), None) = (&command.mode, mode) {
+            output::render_text(
+                &format!(
+                    "/{} asks for {} mode, which is looser than the current {} mode, so it runs in {} mode",
+                    command.name, requested, current_mode, current_mode
+                ),
+                Some(Color::Yellow),
+                true,
+            );
+        }
+        self.agent
+            .set_extension_filter(command.extensions.clone())
+            .await;
+        self.goose_mode_override = mode.map(str::to_string);
+
+        output::show_thinking();
+        let result = self
+            .process_agent_response(true, CancellationToken::default())
+            .await;
+        output::hide_thinking();
+
+        self.agent.set_extension_filter(None).await;
+        self.goose_mode_override = None;
+        if let Some(name) = added_sub_recipe {
+            self.agent.remove_sub_recipes(&[name]).await;
+        }
+        result
+    }
+
+    /// The goose mode replies run in when no command overrides it
+    fn current_goose_mode(&self) -> String {
+        self.approval_mode
+            .clone()
+            .or_else(|| {
+                ProjectPermissions::discover_trusted(&self.working_dir())
+                    .and_then(|project| project.config.mode)
+            })
+            .unwrap_or_else(|| {
+                Config::global()
+                    .get_param("GOOSE_MODE")
+                    .unwrap_or_else(|_| "auto".to_string())
+            })
+    }
+
     /// Save a recipe to a file
     ///
     /// # Arguments
@@ -1641,6 +2060,14 @@ impl Session {
     fn push_message(&mut self, message: Message) {
         self.messages.push(message);
     }
+
+    /// Build a message typed by the user, attaching the paths it mentions with `@`
+    fn user_message(&self, content: &str) -> Message {
+        let cwd = std::env::current_dir().unwrap_or_default();
+        let (message, notes) = mentions::attach_mentions(content, &cwd);
+        output::render_attachments(&message, &notes);
+        message
+    }
 }
 
 fn get_reasoner() -> Result<Arc<dyn Provider>, anyhow::Error> {
@@ -1,8 +1,11 @@
+use super::mentions::{parse_attachment, Attachment, AttachmentKind};
+use crate::recipes::recipe_source::RecipeInfo;
 use anstream::println;
 use bat::WrappingMode;
 use console::{style, Color};
-use goose::config::Config;
+use goose::config::{Config, ProjectPermissions};
 use goose::conversation::message::{Message, MessageContent, ToolRequest, ToolResponse};
+use goose::permission::ApprovalDecision;
 use goose::providers::pricing::get_model_pricing;
 use goose::providers::pricing::parse_model_id;
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
@@ -14,6 +17,7 @@ use std::cell::RefCell;
 use std::collections::HashMap;
 use std::io::{Error, IsTerminal, Write};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -65,6 +69,17 @@ thread_local! {
     );
 }
 
+// Set while stdout carries JSON for `--output-format`, so text goes to stderr instead
+static MACHINE_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+pub fn set_machine_output(enabled: bool) {
+    MACHINE_OUTPUT.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_machine_output() -> bool {
+    MACHINE_OUTPUT.load(Ordering::Relaxed)
+}
+
 pub fn set_theme(theme: Theme) {
     let config = Config::global();
     config
@@ -162,13 +177,26 @@ pub fn set_thinking_message(s: &String) {
 }
 
 pub fn render_message(message: &Message, debug: bool) {
+    if is_machine_output() {
+        return;
+    }
     let theme = get_theme();
+    let mut attached_image = false;
 
     for content in &message.content {
         match content {
-            MessageContent::Text(text) => print_markdown(&text.text, theme),
+            MessageContent::Text(text) => match parse_attachment(&text.text) {
+                Some(attachment) => {
+                    render_attachment(&attachment, debug);
+                    attached_image = attachment.kind == AttachmentKind::Image;
+                    continue;
+                }
+                None => print_markdown(&text.text, theme),
+            },
             MessageContent::ToolRequest(req) => render_tool_request(req, theme, debug),
             MessageContent::ToolResponse(resp) => render_tool_response(resp, theme, debug),
+            // The image of an attachment is already shown by its summary
+            MessageContent::Image(_) if attached_image => {}
             MessageContent::Image(image) => {
                 println!("Image: [data: {}, type: {}]", image.data, image.mime_type);
             }
@@ -189,16 +217,63 @@ pub fn render_message(message: &Message, debug: bool) {
                 println!("WARNING: Message content type could not be rendered");
             }
         }
+        attached_image = false;
     }
 
     let _ = std::io::stdout().flush();
 }
 
+/// Show an `@path` attachment collapsed to one line, with its content in debug mode
+fn render_attachment(attachment: &Attachment, debug: bool) {
+    println!("{}", style(format!("📎 {}", attachment.summary())).dim());
+    if debug && !attachment.body.is_empty() {
+        println!("{}", style(attachment.body).dim());
+    }
+}
+
+/// Show the attachments of a message the user just sent, and any mentions that were not attached
+pub fn render_attachments(message: &Message, notes: &[String]) {
+    for content in &message.content {
+        if let Some(attachment) = content.as_text().and_then(parse_attachment) {
+            render_attachment(&attachment, false);
+        }
+    }
+    for note in notes {
+        println!("{}", style(format!("⚠️  {}", note)).yellow().dim());
+    }
+}
+
+/// Show how the approval policy answered a tool confirmation request
+pub fn render_approval_decision(tool_name: &str, decision: &ApprovalDecision) {
+    let (text, color) = match decision {
+        ApprovalDecision::Allow => (
+            format!("Allowed {} by the approval policy", tool_name),
+            Color::Green,
+        ),
+        ApprovalDecision::Deny => (
+            format!("Denied {} by the approval policy", tool_name),
+            Color::Yellow,
+        ),
+        ApprovalDecision::Fail => (
+            format!(
+                "{} needs confirmation, the approval policy stops the run",
+                tool_name
+            ),
+            Color::Red,
+        ),
+    };
+    render_text(&text, Some(color), true);
+}
+
 pub fn render_text(text: &str, color: Option<Color>, dim: bool) {
     render_text_no_newlines(format!("\n{}\n\n", text).as_str(), color, dim);
 }
 
 pub fn render_text_no_newlines(text: &str, color: Option<Color>, dim: bool) {
+    if is_machine_output() {
+        eprintln!("{}", text.trim());
+        return;
+    }
     if !std::io::stdout().is_terminal() {
         println!("{}", text);
         return;
@@ -291,7 +366,47 @@ fn render_tool_response(resp: &ToolResponse, theme: Theme, debug: bool) {
     }
 }
 
+pub fn render_model_fallback(model: &str, mode: &str) {
+    let message = if mode == "fallback" {
+        format!("Provider unavailable, falling back to {}", model)
+    } else {
+        format!("Primary provider recovered, switching back to {}", model)
+    };
+    if is_machine_output() {
+        eprintln!("{}", message);
+        return;
+    }
+    println!("\n{}", style(message).yellow());
+}
+
+pub fn render_model_switch(
+    provider_name: &str,
+    provider: &Arc<dyn goose::providers::base::Provider>,
+) {
+    let model_config = provider.get_model_config();
+    let models = match provider.as_lead_worker() {
+        Some(lead_worker) => {
+            let (lead_model, worker_model) = lead_worker.get_model_info();
+            format!("lead model {}, worker model {}", lead_model, worker_model)
+        }
+        None => format!("model {}", model_config.model_name),
+    };
+    println!(
+        "\n{} {}",
+        style(format!("Switched to {} {}", provider_name, models)).green(),
+        style(format!(
+            "(context limit {} tokens)",
+            model_config.context_limit()
+        ))
+        .dim()
+    );
+}
+
 pub fn render_error(message: &str) {
+    if is_machine_output() {
+        eprintln!("error: {}", message);
+        return;
+    }
     println!("\n  {} {}\n", style("error:").red().bold(), message);
 }
 
@@ -306,6 +421,29 @@ pub fn render_prompts(prompts: &HashMap<String, Vec<String>>) {
     println!();
 }
 
+pub fn render_recipes(recipes: &[RecipeInfo]) {
+    println!();
+    if recipes.is_empty() {
+        println!(" No recipes found");
+    }
+    let mut current_source = None;
+    for recipe in recipes {
+        if current_source != Some(&recipe.source) {
+            println!(" {}", style(&recipe.source).green());
+            current_source = Some(&recipe.source);
+        }
+        match recipe.description.as_deref().filter(|d| !d.is_empty()) {
+            Some(description) => println!(
+                "  - {} {}",
+                style(&recipe.name).cyan(),
+                style(description).dim()
+            ),
+            None => println!("  - {}", style(&recipe.name).cyan()),
+        }
+    }
+    println!();
+}
+
 pub fn render_prompt_info(info: &PromptInfo) {
     println!();
     if let Some(ext) = &info.extension {
@@ -702,6 +840,23 @@ pub fn display_session_info(
         );
     }
 
+    if let Some(fallback) = provider_instance.and_then(|p| p.as_fallback()) {
+        println!(
+            "    {} {}",
+            style("fallbacks:").dim(),
+            style(
+                fallback
+                    .get_chain()
+                    .into_iter()
+                    .skip(1)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+            .cyan()
+            .dim(),
+        );
+    }
+
     if let Some(session_file) = session_file {
         println!(
             "    {} {}",
@@ -717,6 +872,24 @@ pub fn display_session_info(
             .cyan()
             .dim()
     );
+
+    if let Some(project) = std::env::current_dir()
+        .ok()
+        .and_then(|dir| ProjectPermissions::discover_trusted(&dir))
+    {
+        let mode = project
+            .config
+            .mode
+            .as_ref()
+            .map(|mode| format!(" (mode: {})", mode))
+            .unwrap_or_default();
+        println!(
+            "    {} {}{}",
+            style("project permissions:").dim(),
+            style(project.path.display()).cyan().dim(),
+            style(mode).dim()
+        );
+    }
 }
 
 pub fn display_greeting() {
@@ -0,0 +1,1152 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rmcp::model::{Content, ErrorCode, ErrorData};
+use serde_json::{json, Value};
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, HashSet},
+    fs::File,
+    io::{BufRead, BufReader},
+    path::Path,
+};
+
+/// Rows returned by `sample` when no count is given
+const DEFAULT_SAMPLE_SIZE: usize = 10;
+/// Rows returned by `query` when no limit is given
+const DEFAULT_QUERY_LIMIT: usize = 100;
+/// Most rows a single call returns, whatever count or limit is asked for
+const MAX_ROWS: usize = 1000;
+/// Distinct values `schema` counts per column before it stops counting
+const MAX_DISTINCT: usize = 1000;
+const EXAMPLE_VALUES: usize = 3;
+
+pub async fn csv_tool(
+    path: &str,
+    operation: &str,
+    params: &Value,
+) -> Result<Vec<Content>, ErrorData> {
+    let result = match operation {
+        "schema" => infer_schema(path, params)?,
+        "sample" => sample_rows(path, params)?,
+        "query" => run_query(path, params)?,
+        _ => {
+            return Err(invalid_params(format!(
+                "Invalid operation: {}. Valid operations are: 'schema', 'sample', 'query'",
+                operation
+            )))
+        }
+    };
+    Ok(vec![Content::text(result)])
+}
+
+fn invalid_params(message: String) -> ErrorData {
+    ErrorData::new(ErrorCode::INVALID_PARAMS, message, None)
+}
+
+fn internal_error(message: String) -> ErrorData {
+    ErrorData::new(ErrorCode::INTERNAL_ERROR, message, None)
+}
+
+/// Guess the delimiter from the first line of the file: tab for .tsv files, otherwise
+/// whichever of `,` `;` tab and `|` occurs most often outside quotes
+pub fn detect_delimiter(path: &Path) -> anyhow::Result<u8> {
+    if path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("tsv"))
+    {
+        return Ok(b'\t');
+    }
+
+    let mut first_line = Vec::new();
+    BufReader::new(File::open(path)?).read_until(b'\n', &mut first_line)?;
+    let mut counts: HashMap<u8, usize> = HashMap::new();
+    let mut quoted = false;
+    for byte in first_line {
+        match byte {
+            b'"' => quoted = !quoted,
+            b',' | b';' | b'\t' | b'|' if !quoted => *counts.entry(byte).or_default() += 1,
+            _ => {}
+        }
+    }
+    Ok([b',', b';', b'\t', b'|']
+        .into_iter()
+        .max_by_key(|delimiter| {
+            (
+                counts.get(delimiter).copied().unwrap_or(0),
+                *delimiter == b',',
+            )
+        })
+        .unwrap_or(b','))
+}
+
+/// Parse numbers as people write them in spreadsheets, like "-1,234.5" or "$99"
+pub fn parse_number(text: &str) -> Option<f64> {
+    let text = text.trim();
+    if !text.contains(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    if let Ok(number) = text.parse::<f64>() {
+        return number.is_finite().then_some(number);
+    }
+
+    let (negative, unsigned) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text),
+    };
+    let unsigned = unsigned
+        .strip_prefix(['$', '€', '£'])
+        .unwrap_or(unsigned)
+        .trim_start();
+    let (whole, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+    let mut groups = whole.split(',');
+    let first = groups.next()?;
+    let grouped = whole.contains(',');
+    if grouped && (first.is_empty() || first.len() > 3 || !groups.all(|g| g.len() == 3)) {
+        return None;
+    }
+    let whole = whole.replace(',', "");
+    let digits_only = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+    if !digits_only(&whole) || !digits_only(fraction) {
+        return None;
+    }
+    let number: f64 = format!(
+        "{}.{}",
+        if whole.is_empty() { "0" } else { &whole },
+        if fraction.is_empty() { "0" } else { fraction }
+    )
+    .parse()
+    .ok()?;
+    Some(if negative { -number } else { number })
+}
+
+/// Parse dates and date times, telling whether the value had a time part
+fn parse_temporal(text: &str) -> Option<(NaiveDateTime, bool)> {
+    let text = text.trim();
+    for format in ["%Y-%m-%d", "%Y/%m/%d", "%m/%d/%Y", "%d.%m.%Y"] {
+        if let Ok(date) = NaiveDate::parse_from_str(text, format) {
+            return Some((date.and_hms_opt(0, 0, 0)?, false));
+        }
+    }
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(text) {
+        return Some((datetime.naive_utc(), true));
+    }
+    for format in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M"] {
+        if let Ok(datetime) = NaiveDateTime::parse_from_str(text, format) {
+            return Some((datetime, true));
+        }
+    }
+    None
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum ValueKind {
+    Integer,
+    Number,
+    Boolean,
+    Date,
+    DateTime,
+    Text,
+}
+
+fn classify(value: &str) -> ValueKind {
+    if let Some(number) = parse_number(value) {
+        let written_as_integer = !value.contains(['.', 'e', 'E']);
+        return if number.fract() == 0.0 && written_as_integer {
+            ValueKind::Integer
+        } else {
+            ValueKind::Number
+        };
+    }
+    if value.eq_ignore_ascii_case("true") || value.eq_ignore_ascii_case("false") {
+        return ValueKind::Boolean;
+    }
+    match parse_temporal(value) {
+        Some((_, false)) => ValueKind::Date,
+        Some((_, true)) => ValueKind::DateTime,
+        None => ValueKind::Text,
+    }
+}
+
+/// The narrowest type that fits every value seen in a column
+fn column_type(kinds: &HashSet<ValueKind>) -> &'static str {
+    let only = |allowed: &[ValueKind]| kinds.iter().all(|kind| allowed.contains(kind));
+    if kinds.is_empty() {
+        "empty"
+    } else if only(&[ValueKind::Integer]) {
+        "integer"
+    } else if only(&[ValueKind::Integer, ValueKind::Number]) {
+        "number"
+    } else if only(&[ValueKind::Boolean]) {
+        "boolean"
+    } else if only(&[ValueKind::Date]) {
+        "date"
+    } else if only(&[ValueKind::Date, ValueKind::DateTime]) {
+        "datetime"
+    } else {
+        "text"
+    }
+}
+
+/// Order values as numbers when both are numbers, as dates when both are dates, and as
+/// text otherwise
+fn compare_values(a: &str, b: &str) -> Ordering {
+    if let (Some(a), Some(b)) = (parse_number(a), parse_number(b)) {
+        return a.total_cmp(&b);
+    }
+    if let (Some((a, _)), Some((b, _))) = (parse_temporal(a), parse_temporal(b)) {
+        return a.cmp(&b);
+    }
+    a.cmp(b)
+}
+
+fn format_number(number: f64) -> String {
+    if number.fract() == 0.0 && number.abs() < 1e15 {
+        format!("{:.0}", number)
+    } else {
+        let rounded = format!("{:.10}", number);
+        rounded
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_string()
+    }
+}
+
+fn value_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+fn row_limit(params: &Value, key: &str, default: usize) -> usize {
+    params
+        .get(key)
+        .and_then(Value::as_u64)
+        .map(|n| n as usize)
+        .unwrap_or(default)
+        .min(MAX_ROWS)
+}
+
+/// A list parameter that may also be given as a single value
+fn list_param<'a>(params: &'a Value, key: &str) -> Vec<&'a Value> {
+    match params.get(key) {
+        None | Some(Value::Null) => Vec::new(),
+        Some(Value::Array(values)) => values.iter().collect(),
+        Some(value) => vec![value],
+    }
+}
+
+struct CsvFile {
+    headers: Vec<String>,
+    reader: csv::Reader<File>,
+}
+
+impl CsvFile {
+    fn open(path: &str, params: &Value) -> Result<Self, ErrorData> {
+        let delimiter = match params.get("delimiter").and_then(Value::as_str) {
+            Some("\\t") | Some("tab") => b'\t',
+            Some(delimiter) if delimiter.len() == 1 => delimiter.as_bytes()[0],
+            Some(delimiter) => {
+                return Err(invalid_params(format!(
+                    "The delimiter must be a single character, got '{}'",
+                    delimiter
+                )))
+            }
+            None => detect_delimiter(Path::new(path))
+                .map_err(|e| internal_error(format!("Failed to read CSV file: {}", e)))?,
+        };
+        let has_headers = params
+            .get("has_headers")
+            .and_then(Value::as_bool)
+            .unwrap_or(true);
+
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(delimiter)
+            .has_headers(has_headers)
+            .flexible(true)
+            .from_path(path)
+            .map_err(|e| internal_error(format!("Failed to open CSV file: {}", e)))?;
+        let first_row = reader
+            .headers()
+            .map_err(|e| internal_error(format!("Failed to read CSV header: {}", e)))?;
+        let headers = if has_headers {
+            first_row
+                .iter()
+                .map(|name| name.trim().to_string())
+                .collect()
+        } else {
+            (1..=first_row.len())
+                .map(|i| format!("column_{}", i))
+                .collect()
+        };
+        Ok(Self { headers, reader })
+    }
+
+    /// Rows of the file as owned values, padded or cut to the number of columns
+    fn rows(&mut self) -> impl Iterator<Item = Result<Vec<String>, ErrorData>> + '_ {
+        let width = self.headers.len();
+        self.reader.records().map(move |record| {
+            let record =
+                record.map_err(|e| internal_error(format!("Failed to read CSV row: {}", e)))?;
+            Ok((0..width)
+                .map(|i| record.get(i).unwrap_or("").to_string())
+                .collect())
+        })
+    }
+
+    fn column(&self, name: &str) -> Result<usize, ErrorData> {
+        self.headers
+            .iter()
+            .position(|header| header == name)
+            .or_else(|| {
+                self.headers
+                    .iter()
+                    .position(|header| header.eq_ignore_ascii_case(name))
+            })
+            .ok_or_else(|| {
+                invalid_params(format!(
+                    "Unknown column '{}'. Columns are: {}",
+                    name,
+                    self.headers.join(", ")
+                ))
+            })
+    }
+}
+
+fn to_csv(headers: &[String], rows: &[Vec<String>]) -> Result<String, ErrorData> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    let write_error = |e: csv::Error| internal_error(format!("Failed to write CSV: {}", e));
+    writer.write_record(headers).map_err(write_error)?;
+    for row in rows {
+        writer.write_record(row).map_err(write_error)?;
+    }
+    let bytes = writer
+        .into_inner()
+        .map_err(|e| internal_error(format!("Failed to write CSV: {}", e)))?;
+    Ok(String::from_utf8_lossy(&bytes).to_string())
+}
+
+#[derive(Default)]
+struct ColumnStats {
+    kinds: HashSet<ValueKind>,
+    empty: usize,
+    distinct: HashSet<String>,
+    too_many_distinct: bool,
+    min: Option<String>,
+    max: Option<String>,
+    examples: Vec<String>,
+}
+
+impl ColumnStats {
+    fn add(&mut self, value: &str) {
+        let value = value.trim();
+        if value.is_empty() {
+            self.empty += 1;
+            return;
+        }
+        self.kinds.insert(classify(value));
+        if !self.too_many_distinct && !self.distinct.contains(value) {
+            if self.distinct.len() < MAX_DISTINCT {
+                self.distinct.insert(value.to_string());
+                if self.examples.len() < EXAMPLE_VALUES {
+                    self.examples.push(value.to_string());
+                }
+            } else {
+                self.too_many_distinct = true;
+            }
+        }
+        if self
+            .min
+            .as_deref()
+            .is_none_or(|min| compare_values(value, min) == Ordering::Less)
+        {
+            self.min = Some(value.to_string());
+        }
+        if self
+            .max
+            .as_deref()
+            .is_none_or(|max| compare_values(value, max) == Ordering::Greater)
+        {
+            self.max = Some(value.to_string());
+        }
+    }
+
+    fn describe(&self, name: &str) -> Value {
+        let column_type = column_type(&self.kinds);
+        let mut column = json!({
+            "name": name,
+            "type": column_type,
+            "empty": self.empty,
+            "distinct": if self.too_many_distinct {
+                json!(format!("more than {}", MAX_DISTINCT))
+            } else {
+                json!(self.distinct.len())
+            },
+            "examples": self.examples,
+        });
+        if matches!(column_type, "integer" | "number" | "date" | "datetime") {
+            let bound = |value: &Option<String>| match (column_type, value) {
+                ("integer" | "number", Some(value)) => {
+                    parse_number(value).map(|n| json!(n)).unwrap_or(Value::Null)
+                }
+                (_, value) => json!(value),
+            };
+            column["min"] = bound(&self.min);
+            column["max"] = bound(&self.max);
+        }
+        column
+    }
+}
+
+fn infer_schema(path: &str, params: &Value) -> Result<String, ErrorData> {
+    let mut csv = CsvFile::open(path, params)?;
+    let mut stats: Vec<ColumnStats> = csv.headers.iter().map(|_| ColumnStats::default()).collect();
+    let mut row_count = 0;
+    for row in csv.rows() {
+        for (column, value) in stats.iter_mut().zip(row?) {
+            column.add(&value);
+        }
+        row_count += 1;
+    }
+
+    let columns: Vec<Value> = csv
+        .headers
+        .iter()
+        .zip(&stats)
+        .map(|(name, column)| column.describe(name))
+        .collect();
+    serde_json::to_string_pretty(&json!({
+        "rows": row_count,
+        "columns": columns,
+    }))
+    .map_err(|e| internal_error(format!("Failed to serialize schema: {}", e)))
+}
+
+fn sample_rows(path: &str, params: &Value) -> Result<String, ErrorData> {
+    let count = row_limit(params, "count", DEFAULT_SAMPLE_SIZE);
+    let mut csv = CsvFile::open(path, params)?;
+    let headers = csv.headers.clone();
+
+    match params
+        .get("method")
+        .and_then(Value::as_str)
+        .unwrap_or("head")
+    {
+        "head" => {
+            let offset = params.get("offset").and_then(Value::as_u64).unwrap_or(0) as usize;
+            let rows = csv
+                .rows()
+                .skip(offset)
+                .take(count)
+                .collect::<Result<Vec<_>, _>>()?;
+            if rows.is_empty() {
+                return Ok(format!("No rows after row {}", offset));
+            }
+            Ok(format!(
+                "Rows {}-{}:\n{}",
+                offset + 1,
+                offset + rows.len(),
+                to_csv(&headers, &rows)?
+            ))
+        }
+        "random" => {
+            let seed = params
+                .get("seed")
+                .and_then(Value::as_u64)
+                .unwrap_or_else(rand::random);
+            let mut rng = StdRng::seed_from_u64(seed);
+
+            // Reservoir sampling keeps memory bounded on files of any size
+            let mut reservoir: Vec<(usize, Vec<String>)> = Vec::with_capacity(count);
+            let mut total = 0;
+            for row in csv.rows() {
+                let row = row?;
+                if reservoir.len() < count {
+                    reservoir.push((total, row));
+                } else {
+                    let slot = rng.gen_range(0..=total);
+                    if slot < count {
+                        reservoir[slot] = (total, row);
+                    }
+                }
+                total += 1;
+            }
+            reservoir.sort_by_key(|(index, _)| *index);
+            let rows: Vec<Vec<String>> = reservoir.into_iter().map(|(_, row)| row).collect();
+            Ok(format!(
+                "Random sample of {} of {} rows (seed {}):\n{}",
+                rows.len(),
+                total,
+                seed,
+                to_csv(&headers, &rows)?
+            ))
+        }
+        method => Err(invalid_params(format!(
+            "Invalid sample method: {}. Use 'head' or 'random'",
+            method
+        ))),
+    }
+}
+
+enum FilterOp {
+    Eq,
+    Ne,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    Contains,
+    StartsWith,
+    EndsWith,
+    In,
+    Empty,
+    NotEmpty,
+}
+
+struct Filter {
+    column: usize,
+    op: FilterOp,
+    values: Vec<String>,
+}
+
+impl Filter {
+    fn parse(csv: &CsvFile, filter: &Value) -> Result<Self, ErrorData> {
+        let column = filter
+            .get("column")
+            .and_then(Value::as_str)
+            .ok_or_else(|| invalid_params("Each filter needs a 'column'".to_string()))?;
+        let op = match filter.get("op").and_then(Value::as_str).unwrap_or("eq") {
+            "eq" | "=" | "==" => FilterOp::Eq,
+            "ne" | "!=" => FilterOp::Ne,
+            "gt" | ">" => FilterOp::Gt,
+            "gte" | ">=" => FilterOp::Gte,
+            "lt" | "<" => FilterOp::Lt,
+            "lte" | "<=" => FilterOp::Lte,
+            "contains" => FilterOp::Contains,
+            "starts_with" => FilterOp::StartsWith,
+            "ends_with" => FilterOp::EndsWith,
+            "in" => FilterOp::In,
+            "empty" => FilterOp::Empty,
+            "not_empty" => FilterOp::NotEmpty,
+            op => return Err(invalid_params(format!("Invalid filter op: {}", op))),
+        };
+        let values = match filter.get("value") {
+            Some(Value::Array(values)) => values.iter().map(value_text).collect(),
+            Some(value) => vec![value_text(value)],
+            None if matches!(op, FilterOp::Empty | FilterOp::NotEmpty) => Vec::new(),
+            None => {
+                return Err(invalid_params(format!(
+                    "The filter on '{}' needs a 'value'",
+                    column
+                )))
+            }
+        };
+        Ok(Self {
+            column: csv.column(column)?,
+            op,
+            values,
+        })
+    }
+
+    fn matches(&self, row: &[String]) -> bool {
+        let cell = row[self.column].trim();
+        let value = self.values.first().map(|v| v.trim()).unwrap_or("");
+        let lowercase = || (cell.to_lowercase(), value.to_lowercase());
+        match self.op {
+            FilterOp::Eq => compare_values(cell, value).is_eq(),
+            FilterOp::Ne => compare_values(cell, value).is_ne(),
+            FilterOp::Gt => !cell.is_empty() && compare_values(cell, value).is_gt(),
+            FilterOp::Gte => !cell.is_empty() && compare_values(cell, value).is_ge(),
+            FilterOp::Lt => !cell.is_empty() && compare_values(cell, value).is_lt(),
+            FilterOp::Lte => !cell.is_empty() && compare_values(cell, value).is_le(),
+            FilterOp::Contains => {
+                let (cell, value) = lowercase();
+                cell.contains(&value)
+            }
+            FilterOp::StartsWith => {
+                let (cell, value) = lowercase();
+                cell.starts_with(&value)
+            }
+            FilterOp::EndsWith => {
+                let (cell, value) = lowercase();
+                cell.ends_with(&value)
+            }
+            FilterOp::In => self
+                .values
+                .iter()
+                .any(|value| compare_values(cell, value.trim()).is_eq()),
+            FilterOp::Empty => cell.is_empty(),
+            FilterOp::NotEmpty => !cell.is_empty(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum AggregateFunction {
+    Count,
+    CountDistinct,
+    Sum,
+    Avg,
+    Min,
+    Max,
+}
+
+struct Aggregate {
+    function: AggregateFunction,
+    column: Option<usize>,
+    label: String,
+}
+
+impl Aggregate {
+    /// Parse `{"function": "sum", "column": "Sales"}` or the short form "sum:Sales"
+    fn parse(csv: &CsvFile, aggregate: &Value) -> Result<Self, ErrorData> {
+        let (function, column) = match aggregate {
+            Value::String(text) => match text.split_once(':') {
+                Some((function, column)) => (function.trim(), Some(column.trim())),
+                None => (text.trim(), None),
+            },
+            Value::Object(_) => (
+                aggregate
+                    .get("function")
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| {
+                        invalid_params("Each aggregate needs a 'function'".to_string())
+                    })?,
+                aggregate.get("column").and_then(Value::as_str),
+            ),
+            _ => {
+                return Err(invalid_params(
+                    "Aggregates are objects with 'function' and 'column', or strings like \"sum:Sales\""
+                        .to_string(),
+                ))
+            }
+        };
+        let function_name = function.to_lowercase();
+        let function = match function_name.as_str() {
+            "count" => AggregateFunction::Count,
+            "count_distinct" => AggregateFunction::CountDistinct,
+            "sum" => AggregateFunction::Sum,
+            "avg" | "mean" => AggregateFunction::Avg,
+            "min" => AggregateFunction::Min,
+            "max" => AggregateFunction::Max,
+            other => {
+                return Err(invalid_params(format!(
+                "Invalid aggregate function: {}. Use count, count_distinct, sum, avg, min or max",
+                other
+            )))
+            }
+        };
+        let column = match column {
+            Some(column) => Some(csv.column(column)?),
+            None if function == AggregateFunction::Count => None,
+            None => {
+                return Err(invalid_params(format!(
+                    "The {} aggregate needs a 'column'",
+                    function_name
+                )))
+            }
+        };
+        let label = match column {
+            Some(column) => format!("{}({})", function_name, csv.headers[column]),
+            None => function_name,
+        };
+        Ok(Self {
+            function,
+            column,
+            label,
+        })
+    }
+}
+
+enum Accumulator {
+    Count(usize),
+    Distinct(HashSet<String>),
+    Sum { total: f64, values: usize },
+    Avg { total: f64, values: usize },
+    Extreme(Option<String>),
+}
+
+impl Accumulator {
+    fn new(function: AggregateFunction) -> Self {
+        match function {
+            AggregateFunction::Count => Accumulator::Count(0),
+            AggregateFunction::CountDistinct => Accumulator::Distinct(HashSet::new()),
+            AggregateFunction::Sum => Accumulator::Sum {
+                total: 0.0,
+                values: 0,
+            },
+            AggregateFunction::Avg => Accumulator::Avg {
+                total: 0.0,
+                values: 0,
+            },
+            AggregateFunction::Min | AggregateFunction::Max => Accumulator::Extreme(None),
+        }
+    }
+
+    fn add(&mut self, aggregate: &Aggregate, row: &[String]) {
+        let value = aggregate.column.map(|column| row[column].trim());
+        if value.is_some_and(str::is_empty) {
+            return;
+        }
+        match self {
+            Accumulator::Count(count) => *count += 1,
+            Accumulator::Distinct(values) => {
+                values.insert(value.unwrap_or_default().to_string());
+            }
+            Accumulator::Sum { total, values } | Accumulator::Avg { total, values } => {
+                if let Some(number) = value.and_then(parse_number) {
+                    *total += number;
+                    *values += 1;
+                }
+            }
+            Accumulator::Extreme(extreme) => {
+                let value = value.unwrap_or_default();
+                let wanted = if aggregate.function == AggregateFunction::Min {
+                    Ordering::Less
+                } else {
+                    Ordering::Greater
+                };
+                if extreme
+                    .as_deref()
+                    .is_none_or(|current| compare_values(value, current) == wanted)
+                {
+                    *extreme = Some(value.to_string());
+                }
+            }
+        }
+    }
+
+    fn result(&self) -> String {
+        match self {
+            Accumulator::Count(count) => count.to_string(),
+            Accumulator::Distinct(values) => values.len().to_string(),
+            Accumulator::Sum { values: 0, .. } | Accumulator::Avg { values: 0, .. } => {
+                String::new()
+            }
+            Accumulator::Sum { total, .. } => format_number(*total),
+            Accumulator::Avg { total, values } => format_number(*total / *values as f64),
+            Accumulator::Extreme(value) => value.clone().unwrap_or_default(),
+        }
+    }
+}
+
+/// A row kept by a sorted query. The greatest row is the one that sorts last, so a max-heap of
+/// them drops the row that would fall outside the limit first.
+struct SortedRow {
+    row: Vec<String>,
+    position: usize,
+    column: usize,
+    descending: bool,
+}
+
+impl Ord for SortedRow {
+    fn cmp(&self, other: &Self) -> Ordering {
+        compare_rows(&self.row, &other.row, self.column, self.descending)
+            .then(self.position.cmp(&other.position))
+    }
+}
+
+impl PartialOrd for SortedRow {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for SortedRow {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for SortedRow {}
+
+fn compare_rows(a: &[String], b: &[String], column: usize, descending: bool) -> Ordering {
+    let order = compare_values(a[column].trim(), b[column].trim());
+    if descending {
+        order.reverse()
+    } else {
+        order
+    }
+}
+
+fn sort_column(headers: &[String], sort_by: &str) -> Result<usize, ErrorData> {
+    headers
+        .iter()
+        .position(|header| header == sort_by)
+        .or_else(|| {
+            headers
+                .iter()
+                .position(|header| header.eq_ignore_ascii_case(sort_by))
+        })
+        .ok_or_else(|| {
+            invalid_params(format!(
+                "Cannot sort by '{}'. The result columns are: {}",
+                sort_by,
+                headers.join(", ")
+            ))
+        })
+}
+
+fn run_query(path: &str, params: &Value) -> Result<String, ErrorData> {
+    let mut csv = CsvFile::open(path, params)?;
+    let filters = list_param(params, "filter")
+        .into_iter()
+        .map(|filter| Filter::parse(&csv, filter))
+        .collect::<Result<Vec<_>, _>>()?;
+    let group_by = list_param(params, "group_by")
+        .into_iter()
+        .map(|column| csv.column(&value_text(column)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut aggregates = list_param(params, "aggregate")
+        .into_iter()
+        .map(|aggregate| Aggregate::parse(&csv, aggregate))
+        .collect::<Result<Vec<_>, _>>()?;
+    let limit = row_limit(params, "limit", DEFAULT_QUERY_LIMIT);
+    let sort_by = params.get("sort_by").and_then(Value::as_str);
+    let descending = params
+        .get("descending")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+
+    let grouped = !group_by.is_empty() || !aggregates.is_empty();
+    let (headers, mut rows, matched) = if grouped {
+        if aggregates.is_empty() {
+            aggregates.push(Aggregate::parse(&csv, &json!("count"))?);
+        }
+        let mut keys: Vec<Vec<String>> = Vec::new();
+        let mut groups: HashMap<Vec<String>, Vec<Accumulator>> = HashMap::new();
+        let mut matched = 0;
+        for row in csv.rows() {
+            let row = row?;
+            if !filters.iter().all(|filter| filter.matches(&row)) {
+                continue;
+            }
+            matched += 1;
+            let key: Vec<String> = group_by
+                .iter()
+                .map(|column| row[*column].trim().to_string())
+                .collect();
+            let accumulators = groups.entry(key.clone()).or_insert_with(|| {
+                keys.push(key);
+                aggregates
+                    .iter()
+                    .map(|aggregate| Accumulator::new(aggregate.function))
+                    .collect()
+            });
+            for (accumulator, aggregate) in accumulators.iter_mut().zip(&aggregates) {
+                accumulator.add(aggregate, &row);
+            }
+        }
+
+        let headers: Vec<String> = group_by
+            .iter()
+            .map(|column| csv.headers[*column].clone())
+            .chain(aggregates.iter().map(|aggregate| aggregate.label.clone()))
+            .collect();
+        let rows: Vec<Vec<String>> = keys
+            .into_iter()
+            .map(|key| {
+                let results = groups[&key].iter().map(Accumulator::result);
+                key.iter().cloned().chain(results).collect()
+            })
+            .collect();
+        (headers, rows, matched)
+    } else {
+        let columns = list_param(params, "columns")
+            .into_iter()
+            .map(|column| csv.column(&value_text(column)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let columns: Vec<usize> = if columns.is_empty() {
+            (0..csv.headers.len()).collect()
+        } else {
+            columns
+        };
+        let headers: Vec<String> = columns.iter().map(|c| csv.headers[*c].clone()).collect();
+        let sort_by = sort_by
+            .map(|sort_by| sort_column(&headers, sort_by))
+            .transpose()?;
+
+        let mut rows = Vec::new();
+        let mut sorted = BinaryHeap::new();
+        let mut matched = 0;
+        for row in csv.rows() {
+            let row = row?;
+            if !filters.iter().all(|filter| filter.matches(&row)) {
+                continue;
+            }
+            matched += 1;
+            // Sorting keeps the first `limit` rows in sort order, otherwise the first ones
+            // read are enough
+            if let Some(column) = sort_by {
+                sorted.push(SortedRow {
+                    row: columns.iter().map(|c| row[*c].clone()).collect(),
+                    position: matched,
+                    column,
+                    descending,
+                });
+                if sorted.len() > limit {
+                    sorted.pop();
+                }
+            } else if rows.len() < limit {
+                rows.push(columns.iter().map(|c| row[*c].clone()).collect());
+            }
+        }
+        if sort_by.is_some() {
+            rows = sorted
+                .into_sorted_vec()
+                .into_iter()
+                .map(|sorted| sorted.row)
+                .collect();
+        }
+        (headers, rows, matched)
+    };
+
+    if let Some(sort_by) = sort_by.filter(|_| grouped) {
+        let column = sort_column(&headers, sort_by)?;
+        rows.sort_by(|a, b| compare_rows(a, b, column, descending));
+    }
+
+    let (summary, available) = if grouped {
+        let groups = rows.len();
+        (
+            format!("{} groups from {} matching rows", groups, matched),
+            groups,
+        )
+    } else {
+        (format!("{} rows matched", matched), matched)
+    };
+    rows.truncate(limit);
+    let shown = if rows.len() < available {
+        format!(", showing the first {}", rows.len())
+    } else {
+        String::new()
+    };
+    Ok(format!(
+        "{}{}:\n{}",
+        summary,
+        shown,
+        to_csv(&headers, &rows)?
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const SALES: &str = "\
+region,product,units,price,date,active
+North,Widget,10,2.50,2024-01-05,true
+South,Gadget,3,10.00,2024-02-11,false
+North,Gadget,7,9.50,2024-03-02,true
+East,Widget,,2.50,2024-03-15,true
+South,Widget,12,\"1,200.00\",2024-04-01,false
+";
+
+    fn write_csv(content: &str, extension: &str) -> tempfile::NamedTempFile {
+        let mut file = tempfile::Builder::new()
+            .suffix(extension)
+            .tempfile()
+            .unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+        file
+    }
+
+    async fn run(file: &tempfile::NamedTempFile, operation: &str, params: Value) -> String {
+        let result = csv_tool(file.path().to_str().unwrap(), operation, &params)
+            .await
+            .unwrap();
+        result[0].as_text().unwrap().text.clone()
+    }
+
+    #[test]
+    fn test_parse_number() {
+        assert_eq!(parse_number("42"), Some(42.0));
+        assert_eq!(parse_number(" -1,234.5 "), Some(-1234.5));
+        assert_eq!(parse_number("$99"), Some(99.0));
+        assert_eq!(parse_number("1e3"), Some(1000.0));
+        assert_eq!(parse_number("12,34"), None);
+        assert_eq!(parse_number("abc"), None);
+        assert_eq!(parse_number("NaN"), None);
+        assert_eq!(parse_number(""), None);
+    }
+
+    #[test]
+    fn test_detect_delimiter() {
+        let file = write_csv("a;b;c\n1;2;3\n", ".csv");
+        assert_eq!(detect_delimiter(file.path()).unwrap(), b';');
+        let file = write_csv("\"x,y\"|b\n", ".csv");
+        assert_eq!(detect_delimiter(file.path()).unwrap(), b'|');
+        let file = write_csv("a,b\tc\n", ".tsv");
+        assert_eq!(detect_delimiter(file.path()).unwrap(), b'\t');
+        let file = write_csv("single\n", ".csv");
+        assert_eq!(detect_delimiter(file.path()).unwrap(), b',');
+    }
+
+    #[tokio::test]
+    async fn test_schema() {
+        let file = write_csv(SALES, ".csv");
+        let schema: Value = serde_json::from_str(&run(&file, "schema", json!({})).await).unwrap();
+        assert_eq!(schema["rows"], json!(5));
+
+        let column = |name: &str| {
+            schema["columns"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|c| c["name"] == json!(name))
+                .unwrap()
+                .clone()
+        };
+        assert_eq!(column("region")["type"], json!("text"));
+        assert_eq!(column("region")["distinct"], json!(3));
+        assert_eq!(
+            column("region")["examples"],
+            json!(["North", "South", "East"])
+        );
+        assert_eq!(column("units")["type"], json!("integer"));
+        assert_eq!(column("units")["empty"], json!(1));
+        assert_eq!(column("units")["min"], json!(3.0));
+        assert_eq!(column("units")["max"], json!(12.0));
+        assert_eq!(column("price")["type"], json!("number"));
+        assert_eq!(column("price")["max"], json!(1200.0));
+        assert_eq!(column("date")["type"], json!("date"));
+        assert_eq!(column("date")["min"], json!("2024-01-05"));
+        assert_eq!(column("active")["type"], json!("boolean"));
+    }
+
+    #[tokio::test]
+    async fn test_sample() {
+        let file = write_csv(SALES, ".csv");
+        let head = run(&file, "sample", json!({ "count": 2, "offset": 1 })).await;
+        assert_eq!(
+            head,
+            "Rows 2-3:\nregion,product,units,price,date,active\n\
+             South,Gadget,3,10.00,2024-02-11,false\n\
+             North,Gadget,7,9.50,2024-03-02,true\n"
+        );
+
+        let params = json!({ "method": "random", "count": 3, "seed": 7 });
+        let sample = run(&file, "sample", params.clone()).await;
+        assert!(sample.starts_with("Random sample of 3 of 5 rows (seed 7):\n"));
+        assert_eq!(sample.lines().count(), 5);
+        assert_eq!(sample, run(&file, "sample", params).await);
+
+        let empty = run(&file, "sample", json!({ "offset": 10 })).await;
+        assert_eq!(empty, "No rows after row 10");
+    }
+
+    #[tokio::test]
+    async fn test_query_filter_and_select() {
+        let file = write_csv(SALES, ".csv");
+        let result = run(
+            &file,
+            "query",
+            json!({
+                "filter": [
+                    { "column": "units", "op": ">=", "value": 7 },
+                    { "column": "Product", "op": "contains", "value": "WID" }
+                ],
+                "columns": ["region", "units"],
+                "sort_by": "units",
+                "descending": true
+            }),
+        )
+        .await;
+        assert_eq!(
+            result,
+            "2 rows matched:\nregion,units\nSouth,12\nNorth,10\n"
+        );
+
+        let result = run(
+            &file,
+            "query",
+            json!({ "columns": ["region", "units"], "sort_by": "units", "limit": 2 }),
+        )
+        .await;
+        assert_eq!(
+            result,
+            "5 rows matched, showing the first 2:\nregion,units\nEast,\nSouth,3\n"
+        );
+
+        let result = run(&file, "query", json!({ "limit": 1 })).await;
+        assert!(result.starts_with("5 rows matched, showing the first 1:\n"));
+        assert_eq!(result.lines().count(), 3);
+
+        let result = run(
+            &file,
+            "query",
+            json!({ "filter": { "column": "units", "op": "empty" }, "columns": "region" }),
+        )
+        .await;
+        assert_eq!(result, "1 rows matched:\nregion\nEast\n");
+    }
+
+    #[tokio::test]
+    async fn test_query_group_by() {
+        let file = write_csv(SALES, ".csv");
+        let result = run(
+            &file,
+            "query",
+            json!({
+                "group_by": "region",
+                "aggregate": [
+                    "count",
+                    { "function": "sum", "column": "units" },
+                    "avg:price",
+                    "max:date"
+                ],
+                "sort_by": "sum(units)",
+                "descending": true
+            }),
+        )
+        .await;
+        assert_eq!(
+            result,
+            "3 groups from 5 matching rows:\n\
+             region,count,sum(units),avg(price),max(date)\n\
+             North,2,17,6,2024-03-02\n\
+             South,2,15,605,2024-04-01\n\
+             East,1,,2.5,2024-03-15\n"
+        );
+
+        let result = run(
+            &file,
+            "query",
+            json!({
+                "filter": [{ "column": "region", "op": "in", "value": ["North", "East"] }],
+                "aggregate": ["count_distinct:product", "min:units"]
+            }),
+        )
+        .await;
+        assert_eq!(
+            result,
+            "1 groups from 3 matching rows:\ncount_distinct(product),min(units)\n2,7\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_query_errors() {
+        let file = write_csv(SALES, ".csv");
+        let path = file.path().to_str().unwrap();
+
+        let error = csv_tool(path, "query", &json!({ "group_by": "nope" }))
+            .await
+            .unwrap_err();
+        assert!(error.message.contains("Unknown column 'nope'"));
+        assert!(error.message.contains("region, product"));
+
+        let error = csv_tool(path, "query", &json!({ "aggregate": "median:units" }))
+            .await
+            .unwrap_err();
+        assert!(error.message.contains("Invalid aggregate function"));
+
+        let error = csv_tool(path, "query", &json!({ "sort_by": "missing" }))
+            .await
+            .unwrap_err();
+        assert!(error.message.contains("Cannot sort by 'missing'"));
+
+        assert!(csv_tool(path, "invalid", &json!({})).await.is_err());
+    }
+}
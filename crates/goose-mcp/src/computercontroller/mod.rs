@@ -24,13 +24,20 @@ use rmcp::model::{
 };
 use rmcp::object;
 
+mod csv_tool;
 mod docx_tool;
 mod pdf_tool;
+mod web_scrape;
 mod xlsx_tool;
 
 mod platform;
 use platform::{create_system_automation, SystemAutomation};
 
+/// Characters of Markdown that web_scrape returns in extract mode unless asked otherwise
+const WEB_EXTRACT_MAX_CHARS: usize = 50_000;
+/// Entries of each list in a crawl result before the rest are only counted
+const MAX_CRAWL_REPORT_LINES: usize = 20;
+
 /// An extension designed for non-developers to help them with common tasks like
 /// web scraping, data processing, and automation.
 #[derive(Clone)]
@@ -54,13 +61,18 @@ impl ComputerControllerRouter {
         let web_scrape_tool = Tool::new(
             "web_scrape",
             indoc! {r#"
-                Fetch and save content from a web page. The content can be saved as:
-                - text (for HTML pages)
-                - json (for API responses)
-                - binary (for images and other files)
+                Fetch and save content from a web page. Modes:
+                - fetch: Save the raw response, as text (for HTML pages), json (for API
+                  responses) or binary (for images and other files), depending on save_as
+                - extract: Convert the main content of an HTML page to Markdown, leaving out
+                  navigation, scripts and other page furniture, and return it. Links are kept
+                - crawl: Follow links from the page to other pages of the same site, breadth
+                  first, up to max_depth links away and at most max_pages pages, and save each
+                  page as Markdown in a snapshot directory with a sitemap.md. Pages that the
+                  site's robots.txt does not allow are skipped
 
                 The content is cached locally and can be accessed later using the cache_path
-                returned in the response.
+                returned in the response. Prefer extract over fetch to read an HTML page.
             "#},
             object!({
                 "type": "object",
@@ -70,11 +82,32 @@ impl ComputerControllerRouter {
                         "type": "string",
                         "description": "The URL to fetch content from"
                     },
+                    "mode": {
+                        "type": "string",
+                        "enum": ["fetch", "extract", "crawl"],
+                        "default": "fetch",
+                        "description": "Whether to save the raw content, extract readable Markdown, or crawl the site"
+                    },
                     "save_as": {
                         "type": "string",
                         "enum": ["text", "json", "binary"],
                         "default": "text",
-                        "description": "How to interpret and save the content"
+                        "description": "How to interpret and save the content in fetch mode"
+                    },
+                    "max_chars": {
+                        "type": "integer",
+                        "default": 50000,
+                        "description": "Most characters of Markdown returned in extract mode. The full page is saved to the cache"
+                    },
+                    "max_depth": {
+                        "type": "integer",
+                        "default": 2,
+                        "description": "How many links away from the start page to follow in crawl mode (at most 5)"
+                    },
+                    "max_pages": {
+                        "type": "integer",
+                        "default": 20,
+                        "description": "Most pages to save in crawl mode (at most 100)"
                     }
                 }
             }),
@@ -231,12 +264,26 @@ impl ComputerControllerRouter {
         let pdf_tool = Tool::new(
             "pdf_tool",
             indoc! {r#"
-                Process PDF files to extract text and images.
+                Process PDF files: extract text and images, merge, split, fill forms and create PDFs.
                 Supports operations:
-                - extract_text: Extract all text content from the PDF
+                - extract_text: Extract the text content of the PDF, marked with page numbers
+                - extract_pages: Extract the text of each page as JSON with its page number
                 - extract_images: Extract and save embedded images to PNG files
+                - merge: Combine the PDFs in params.inputs, in order, into a new PDF at path
+                - split: Write each page range in params.ranges to its own PDF
+                - list_form_fields: List the AcroForm fields of the PDF with their values and options
+                - fill_form: Fill AcroForm fields from params.fields into a new PDF
+                - markdown_to_pdf: Render the Markdown in content (headings, paragraphs, lists,
+                  code blocks, rules) to a new PDF at path
 
-                Use this when there is a .pdf file or files that need to be processed.
+                Text output is limited to params.max_chars characters (50000 by default). For large
+                documents, read a few pages at a time with params.pages, e.g. "1-10", then "11-20".
+                When output is cut, the result says which pages are left.
+
+                merge, split, fill_form and markdown_to_pdf do not replace existing files unless
+                params.overwrite is true.
+
+                Use this when there is a .pdf file or files that need to be processed or created.
             "#},
             object!({
                 "type": "object",
@@ -244,21 +291,86 @@ impl ComputerControllerRouter {
                 "properties": {
                     "path": {
                         "type": "string",
-                        "description": "Path to the PDF file"
+                        "description": "Path to the PDF file, or to the PDF to create for merge and markdown_to_pdf"
                     },
                     "operation": {
                         "type": "string",
-                        "enum": ["extract_text", "extract_images"],
+                        "enum": [
+                            "extract_text",
+                            "extract_pages",
+                            "extract_images",
+                            "merge",
+                            "split",
+                            "list_form_fields",
+                            "fill_form",
+                            "markdown_to_pdf"
+                        ],
                         "description": "Operation to perform on the PDF"
+                    },
+                    "content": {
+                        "type": "string",
+                        "description": "Markdown to render (required for markdown_to_pdf)"
+                    },
+                    "params": {
+                        "type": "object",
+                        "description": "Additional parameters for the operation",
+                        "properties": {
+                            "pages": {
+                                "type": "string",
+                                "description": "Pages to read for extract_text and extract_pages, e.g. '1-3,5' or '10-' (default: all pages)"
+                            },
+                            "max_chars": {
+                                "type": "integer",
+                                "description": "Most characters of text to return (default: 50000)"
+                            },
+                            "inputs": {
+                                "type": "array",
+                                "description": "PDFs to merge, in order. Each is a path or an object with 'path' and 'pages'",
+                                "items": {
+                                    "anyOf": [
+                                        {"type": "string"},
+                                        {
+                                            "type": "object",
+                                            "properties": {
+                                                "path": {"type": "string"},
+                                                "pages": {"type": "string"}
+                                            },
+                                            "required": ["path"]
+                                        }
+                                    ]
+                                }
+                            },
+                            "ranges": {
+                                "type": "array",
+                                "items": {"type": "string"},
+                                "description": "Page ranges for split, one output file each, e.g. ['1-3', '4-']"
+                            },
+                            "output_dir": {
+                                "type": "string",
+                                "description": "Directory for the files written by split (default: next to the PDF)"
+                            },
+                            "fields": {
+                                "type": "object",
+                                "description": "Field names and values for fill_form. Checkboxes take true/false, radio buttons and choices one of their options"
+                            },
+                            "output_path": {
+                                "type": "string",
+                                "description": "Where fill_form writes the filled PDF (default: <name>_filled.pdf next to the PDF)"
+                            },
+                            "overwrite": {
+                                "type": "boolean",
+                                "description": "Replace files that already exist (default: false)"
+                            }
+                        }
                     }
                 }
             }),
         )
         .annotate(ToolAnnotations {
             title: Some("PDF process".to_string()),
-            read_only_hint: Some(true),
-            destructive_hint: Some(false),
-            idempotent_hint: Some(true),
+            read_only_hint: Some(false),
+            destructive_hint: Some(true),
+            idempotent_hint: Some(false),
             open_world_hint: Some(false),
         });
 
@@ -372,8 +484,22 @@ impl ComputerControllerRouter {
                 - update_cell: Update a single cell's value (returns confirmation message)
                 - get_cell: Get value and formula from a specific cell (returns both value and formula if present)
                 - save: Save changes back to the file (returns confirmation message)
-
-                Use this when working with Excel spreadsheets to analyze or modify data.
+                - write_range: Write a 2D array of values starting at start_cell (default A1)
+                - append_rows: Write a 2D array of values below the last row that has data
+                - create_sheet: Add a new worksheet, creating the workbook if the file does not exist
+                - export_csv: Write a worksheet, or a range of it, to the CSV file at csv_path.
+                  An existing file is only replaced with overwrite
+                - import_csv: Read the CSV file at csv_path into a worksheet, creating it if needed.
+                  Without start_cell the worksheet is cleared first, which needs overwrite when it
+                  has data. CSV fields are stored as text
+
+                Values written as text by write_range and append_rows are stored as numbers or
+                booleans when they look like one, except numbers with leading zeros. Text starting
+                with '=' is stored as a formula.
+                Writes create the workbook when the file does not exist.
+
+                Use this when working with Excel spreadsheets to analyze or modify data. To analyze a
+                large sheet, export it to CSV and use csv_tool instead of reading it range by range.
             "#},
             object!({
                 "type": "object",
@@ -385,7 +511,20 @@ impl ComputerControllerRouter {
                     },
                     "operation": {
                         "type": "string",
-                        "enum": ["list_worksheets", "get_columns", "get_range", "find_text", "update_cell", "get_cell", "save"],
+                        "enum": [
+                            "list_worksheets",
+                            "get_columns",
+                            "get_range",
+                            "find_text",
+                            "update_cell",
+                            "get_cell",
+                            "save",
+                            "write_range",
+                            "append_rows",
+                            "create_sheet",
+                            "export_csv",
+                            "import_csv"
+                        ],
                         "description": "Operation to perform on the XLSX file"
                     },
                     "worksheet": {
@@ -394,7 +533,7 @@ impl ComputerControllerRouter {
                     },
                     "range": {
                         "type": "string",
-                        "description": "Cell range in A1 notation (e.g., 'A1:C10') for get_range operation"
+                        "description": "Cell range in A1 notation (e.g., 'A1:C10') for get_range and export_csv operations"
                     },
                     "search_text": {
                         "type": "string",
@@ -416,11 +555,140 @@ impl ComputerControllerRouter {
                     "value": {
                         "type": "string",
                         "description": "New value for update_cell operation"
+                    },
+                    "values": {
+                        "type": "array",
+                        "items": {"type": "array"},
+                        "description": "Rows of values for write_range and append_rows, e.g. [[\"Name\", \"Total\"], [\"East\", 12]]. null leaves a cell unchanged"
+                    },
+                    "start_cell": {
+                        "type": "string",
+                        "description": "Top left cell for write_range and import_csv (e.g., 'B2')"
+                    },
+                    "csv_path": {
+                        "type": "string",
+                        "description": "CSV file to write for export_csv or to read for import_csv"
+                    },
+                    "overwrite": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Let export_csv replace an existing CSV file and import_csv without start_cell clear a worksheet that has data"
                     }
                 }
             }),
         );
 
+        let csv_tool = Tool::new(
+            "csv_tool",
+            indoc! {r#"
+                Analyze CSV and TSV files of any size without reading them whole.
+                Supports operations:
+                - schema: Count rows and infer each column's type (integer, number, boolean, date,
+                  datetime, text) with empty and distinct counts, min/max and example values
+                - sample: Return rows as CSV, either the first rows after an offset (method "head")
+                  or a random sample (method "random", reproducible with a seed)
+                - query: Filter rows, select columns, group and aggregate, sort, and return up to
+                  limit rows as CSV (100 by default, at most 1000)
+
+                Filters are objects like {"column": "Region", "op": "eq", "value": "East"}, with ops
+                eq, ne, gt, gte, lt, lte, contains, starts_with, ends_with, in (value is a list),
+                empty and not_empty. All filters must match. Values compare as numbers when both
+                sides are numbers, as dates when both are dates, and as text otherwise.
+
+                Aggregates are objects like {"function": "sum", "column": "Sales"} or short strings
+                like "sum:Sales", with functions count, count_distinct, sum, avg, min and max.
+                Without group_by they summarize all matching rows. Sort grouped results by a group
+                column or an aggregate label like "sum(Sales)".
+
+                Start with schema to learn the columns, then query. The delimiter is detected from
+                the first line unless given.
+            "#},
+            object!({
+                "type": "object",
+                "required": ["path", "operation"],
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Path to the CSV or TSV file"
+                    },
+                    "operation": {
+                        "type": "string",
+                        "enum": ["schema", "sample", "query"],
+                        "description": "Operation to perform on the CSV file"
+                    },
+                    "delimiter": {
+                        "type": "string",
+                        "description": "Field delimiter, a single character or 'tab' (default: detected)"
+                    },
+                    "has_headers": {
+                        "type": "boolean",
+                        "default": true,
+                        "description": "Whether the first row holds column names. Without headers, columns are named column_1, column_2, ..."
+                    },
+                    "method": {
+                        "type": "string",
+                        "enum": ["head", "random"],
+                        "default": "head",
+                        "description": "How sample picks rows"
+                    },
+                    "count": {
+                        "type": "integer",
+                        "default": 10,
+                        "description": "Number of rows for sample"
+                    },
+                    "offset": {
+                        "type": "integer",
+                        "default": 0,
+                        "description": "Rows to skip for sample with method head"
+                    },
+                    "seed": {
+                        "type": "integer",
+                        "description": "Seed for sample with method random, to get the same rows again"
+                    },
+                    "filter": {
+                        "type": "array",
+                        "items": {"type": "object"},
+                        "description": "Conditions rows must match for query"
+                    },
+                    "columns": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "description": "Columns to return for query without aggregates (default: all)"
+                    },
+                    "group_by": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "description": "Columns to group by for query"
+                    },
+                    "aggregate": {
+                        "type": "array",
+                        "description": "Aggregates to compute for query (default with group_by: count)"
+                    },
+                    "sort_by": {
+                        "type": "string",
+                        "description": "Result column to sort by for query"
+                    },
+                    "descending": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Sort from largest to smallest"
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "default": 100,
+                        "description": "Most rows query returns"
+                    }
+                }
+            }),
+        )
+        .annotate(ToolAnnotations {
+            title: Some("CSV analysis".to_string()),
+            read_only_hint: Some(true),
+            destructive_hint: Some(false),
+            idempotent_hint: Some(true),
+            open_world_hint: Some(false),
+        });
+
         // choose_app_strategy().cache_dir()
         // - macOS/Linux: ~/.cache/goose/computer_controller/
         // - Windows:     ~\AppData\Local\Block\goose\cache\computer_controller\
@@ -512,6 +780,7 @@ impl ComputerControllerRouter {
             data processing, and automation without requiring programming expertise.
 
             You can use scripting as needed to work with text files of data, such as csvs, json, or text files etc.
+            For large csv files and spreadsheets, prefer csv_tool to learn the columns and to filter, group and sample rows instead of reading the whole file.
             Using the developer extension is allowed for more sophisticated tasks or instructed to (js or py can be helpful for more complex tasks if tools are available).
 
             Accessing web sites, even apis, may be common (you can use scripting to do this) without troubling them too much (they won't know what limits are).
@@ -525,6 +794,7 @@ impl ComputerControllerRouter {
             web_scrape
               - Fetch content from html websites and APIs
               - Save as text, JSON, or binary files
+              - Extract the readable content of a page as Markdown, or crawl a site into a snapshot
               - Content is cached locally for later use
               - This is not optimised for complex websites, so don't use this as the first tool.
             cache
@@ -548,6 +818,7 @@ impl ComputerControllerRouter {
                 pdf_tool,
                 docx_tool,
                 xlsx_tool,
+                csv_tool,
             ],
             cache_dir,
             active_resources: Arc::new(Mutex::new(HashMap::new())),
@@ -613,6 +884,26 @@ impl ComputerControllerRouter {
                 data: None,
             })?;
 
+        match params
+            .get("mode")
+            .and_then(|v| v.as_str())
+            .unwrap_or("fetch")
+        {
+            "fetch" => {}
+            "extract" => return self.web_extract(url, &params).await,
+            "crawl" => return self.web_crawl(url, &params).await,
+            mode => {
+                return Err(ErrorData {
+                    code: ErrorCode::INVALID_PARAMS,
+                    message: Cow::from(format!(
+                        "Invalid 'mode' parameter: {}. Valid options are: 'fetch', 'extract', 'crawl'",
+                        mode
+                    )),
+                    data: None,
+                });
+            }
+        }
+
         let save_as = params
             .get("save_as")
             .and_then(|v| v.as_str())
@@ -695,6 +986,138 @@ impl ComputerControllerRouter {
         ))])
     }
 
+    fn web_url(url: &str) -> Result<Url, ErrorData> {
+        Url::parse(url)
+            .ok()
+            .filter(|url| matches!(url.scheme(), "http" | "https"))
+            .ok_or_else(|| ErrorData {
+                code: ErrorCode::INVALID_PARAMS,
+                message: Cow::from(format!("Invalid 'url' parameter: {}", url)),
+                data: None,
+            })
+    }
+
+    async fn web_extract(&self, url: &str, params: &Value) -> Result<Vec<Content>, ErrorData> {
+        let url = Self::web_url(url)?;
+        let max_chars = params
+            .get("max_chars")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(WEB_EXTRACT_MAX_CHARS as u64) as usize;
+
+        let (final_url, html) = web_scrape::fetch_html(&self.http_client, &url)
+            .await
+            .map_err(|e| ErrorData {
+                code: ErrorCode::INTERNAL_ERROR,
+                message: Cow::from(format!("{:#}", e)),
+                data: None,
+            })?;
+        let page = web_scrape::extract_page(&html, &final_url);
+
+        let cache_path = self
+            .save_to_cache(page.markdown.as_bytes(), "web", "md")
+            .await?;
+        self.register_as_resource(&cache_path, "text")?;
+
+        let length = page.markdown.chars().count();
+        let mut result = format!(
+            "Content of {} saved to: {}\n\n",
+            final_url,
+            cache_path.display()
+        );
+        if length > max_chars {
+            result.extend(page.markdown.chars().take(max_chars));
+            result.push_str(&format!(
+                "\n\n[Showing the first {} of {} characters, the rest is in the saved file]",
+                max_chars, length
+            ));
+        } else {
+            result.push_str(&page.markdown);
+        }
+        Ok(vec![Content::text(result)])
+    }
+
+    async fn web_crawl(&self, url: &str, params: &Value) -> Result<Vec<Content>, ErrorData> {
+        let url = Self::web_url(url)?;
+        let limit = |key: &str, default: u64, max: u64| {
+            params
+                .get(key)
+                .and_then(|v| v.as_u64())
+                .unwrap_or(default)
+                .min(max) as usize
+        };
+        let options = web_scrape::CrawlOptions {
+            max_depth: limit("max_depth", 2, 5),
+            max_pages: limit("max_pages", 20, 100).max(1),
+        };
+
+        let site: String = url
+            .host_str()
+            .unwrap_or("site")
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+        let output_dir = self.cache_dir.join(format!("crawl_{}_{}", site, timestamp));
+        let report = web_scrape::crawl(&self.http_client, &url, &options, &output_dir)
+            .await
+            .map_err(|e| ErrorData {
+                code: ErrorCode::INTERNAL_ERROR,
+                message: Cow::from(format!("Crawl failed: {:#}", e)),
+                data: None,
+            })?;
+        self.register_as_resource(&report.sitemap, "text")?;
+
+        let mut result = format!(
+            "Saved {} pages to: {}\nSitemap: {}\n",
+            report.pages.len(),
+            output_dir.display(),
+            report.sitemap.display()
+        );
+        // The sitemap lists every saved page, so long lists are cut here
+        let mut push_list = |title: &str, lines: Vec<String>| {
+            if lines.is_empty() {
+                return;
+            }
+            result.push_str(title);
+            for line in lines.iter().take(MAX_CRAWL_REPORT_LINES) {
+                result.push_str(&format!("- {}\n", line));
+            }
+            if lines.len() > MAX_CRAWL_REPORT_LINES {
+                result.push_str(&format!(
+                    "- ... and {} more\n",
+                    lines.len() - MAX_CRAWL_REPORT_LINES
+                ));
+            }
+        };
+        push_list(
+            "",
+            report
+                .pages
+                .iter()
+                .map(|page| format!("{} -> {}", page.url, page.path.display()))
+                .collect(),
+        );
+        push_list(
+            "\nNot allowed by robots.txt:\n",
+            report.blocked.iter().map(|url| url.to_string()).collect(),
+        );
+        push_list(
+            "\nSkipped:\n",
+            report
+                .skipped
+                .iter()
+                .map(|(url, reason)| format!("{}: {}", url, reason))
+                .collect(),
+        );
+        if report.not_visited > 0 {
+            result.push_str(&format!(
+                "\n{} more linked pages were not fetched because the crawl reached max_pages or its request limit\n",
+                report.not_visited
+            ));
+        }
+        Ok(vec![Content::text(result)])
+    }
+
     // Implement quick_script tool functionality
     async fn quick_script(&self, params: Value) -> Result<Vec<Content>, ErrorData> {
         let language = params
@@ -1122,6 +1545,93 @@ impl ComputerControllerRouter {
                     })?;
                 Ok(vec![Content::text(format!("{:#?}", cell_value))])
             }
+            "write_range" | "append_rows" => {
+                let values = xlsx_values_parameter(&params)?;
+                let mut xlsx = xlsx_tool::XlsxTool::open_or_create(path).map_err(xlsx_error)?;
+                let worksheet_name = xlsx
+                    .worksheet_name(params.get("worksheet").and_then(|v| v.as_str()))
+                    .map_err(xlsx_error)?;
+                let range = if operation == "write_range" {
+                    let start_cell = params
+                        .get("start_cell")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("A1");
+                    xlsx.write_range(&worksheet_name, start_cell, &values)
+                } else {
+                    xlsx.append_rows(&worksheet_name, &values)
+                }
+                .map_err(xlsx_error)?;
+                xlsx.save(path).map_err(xlsx_error)?;
+                Ok(vec![Content::text(format!(
+                    "Wrote {} rows to {} in worksheet '{}'",
+                    values.len(),
+                    range,
+                    worksheet_name
+                ))])
+            }
+            "create_sheet" => {
+                let worksheet_name = require_str_parameter(&params, "worksheet")?;
+                let mut xlsx = xlsx_tool::XlsxTool::open_or_create(path).map_err(xlsx_error)?;
+                xlsx.create_worksheet(worksheet_name).map_err(xlsx_error)?;
+                xlsx.save(path).map_err(xlsx_error)?;
+                Ok(vec![Content::text(format!(
+                    "Created worksheet '{}'",
+                    worksheet_name
+                ))])
+            }
+            "export_csv" => {
+                let csv_path = require_str_parameter(&params, "csv_path")?;
+                let overwrite = params
+                    .get("overwrite")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+                let xlsx = xlsx_tool::XlsxTool::new(path).map_err(xlsx_error)?;
+                let worksheet = if let Some(name) = params.get("worksheet").and_then(|v| v.as_str())
+                {
+                    xlsx.get_worksheet_by_name(name).map_err(xlsx_error)?
+                } else {
+                    xlsx.get_worksheet_by_index(0).map_err(xlsx_error)?
+                };
+                let rows = xlsx
+                    .export_csv(
+                        worksheet,
+                        params.get("range").and_then(|v| v.as_str()),
+                        csv_path,
+                        overwrite,
+                    )
+                    .map_err(xlsx_error)?;
+                Ok(vec![Content::text(format!(
+                    "Exported {} rows of worksheet '{}' to {}",
+                    rows,
+                    worksheet.get_name(),
+                    csv_path
+                ))])
+            }
+            "import_csv" => {
+                let csv_path = require_str_parameter(&params, "csv_path")?;
+                let overwrite = params
+                    .get("overwrite")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+                let mut xlsx = xlsx_tool::XlsxTool::open_or_create(path).map_err(xlsx_error)?;
+                let worksheet_name = match params.get("worksheet").and_then(|v| v.as_str()) {
+                    Some(name) => name.to_string(),
+                    None => xlsx.worksheet_name(None).map_err(xlsx_error)?,
+                };
+                let range = xlsx
+                    .import_csv(
+                        &worksheet_name,
+                        csv_path,
+                        params.get("start_cell").and_then(|v| v.as_str()),
+                        overwrite,
+                    )
+                    .map_err(xlsx_error)?;
+                xlsx.save(path).map_err(xlsx_error)?;
+                Ok(vec![Content::text(format!(
+                    "Imported {} into {} of worksheet '{}'",
+                    csv_path, range, worksheet_name
+                ))])
+            }
             _ => Err(ErrorData {
                 code: ErrorCode::INVALID_PARAMS,
                 message: Cow::from(format!("Invalid operation: {}", operation)),
@@ -1130,6 +1640,12 @@ impl ComputerControllerRouter {
         }
     }
 
+    async fn csv_tool(&self, params: Value) -> Result<Vec<Content>, ErrorData> {
+        let path = require_str_parameter(&params, "path")?;
+        let operation = require_str_parameter(&params, "operation")?;
+        crate::computercontroller::csv_tool::csv_tool(path, operation, &params).await
+    }
+
     // Implement cache tool functionality
     async fn docx_tool(&self, params: Value) -> Result<Vec<Content>, ErrorData> {
         let path = params
@@ -1178,7 +1694,14 @@ impl ComputerControllerRouter {
                 data: None,
             })?;
 
-        crate::computercontroller::pdf_tool::pdf_tool(path, operation, &self.cache_dir).await
+        crate::computercontroller::pdf_tool::pdf_tool(
+            path,
+            operation,
+            params.get("content").and_then(|v| v.as_str()),
+            params.get("params"),
+            &self.cache_dir,
+        )
+        .await
     }
 
     async fn cache(&self, params: Value) -> Result<Vec<Content>, ErrorData> {
@@ -1287,6 +1810,32 @@ impl ComputerControllerRouter {
     }
 }
 
+fn xlsx_error(e: anyhow::Error) -> ErrorData {
+    ErrorData {
+        code: ErrorCode::INTERNAL_ERROR,
+        message: Cow::from(e.to_string()),
+        data: None,
+    }
+}
+
+fn xlsx_values_parameter(params: &Value) -> Result<Vec<Vec<Value>>, ErrorData> {
+    params
+        .get("values")
+        .and_then(|v| v.as_array())
+        .and_then(|rows| {
+            rows.iter()
+                .map(|row| row.as_array().cloned())
+                .collect::<Option<Vec<_>>>()
+        })
+        .ok_or_else(|| ErrorData {
+            code: ErrorCode::INVALID_PARAMS,
+            message: Cow::from(
+                "The 'values' parameter must be an array of rows, each an array of values",
+            ),
+            data: None,
+        })
+}
+
 impl Router for ComputerControllerRouter {
     fn name(&self) -> String {
         "ComputerControllerExtension".to_string()
@@ -1324,6 +1873,7 @@ impl Router for ComputerControllerRouter {
                 "pdf_tool" => this.pdf_tool(arguments).await,
                 "docx_tool" => this.docx_tool(arguments).await,
                 "xlsx_tool" => this.xlsx_tool(arguments).await,
+                "csv_tool" => this.csv_tool(arguments).await,
                 _ => Err(ErrorData {
                     code: ErrorCode::INVALID_REQUEST,
                     message: Cow::from(format!("Tool {} not found", tool_name)),
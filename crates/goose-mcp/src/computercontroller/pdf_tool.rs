@@ -1,122 +1,83 @@
-use lopdf::{content::Content as PdfContent, Document, Object};
+use lopdf::{
+    content::{Content as PdfContent, Operation},
+    decode_text_string, dictionary, text_string, Dictionary, Document, Object, ObjectId, Stream,
+    StringFormat,
+};
 use rmcp::model::{Content, ErrorCode, ErrorData};
-use std::{fs, path::Path};
+use serde_json::{json, Value};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+const OPERATIONS: [&str; 8] = [
+    "extract_text",
+    "extract_pages",
+    "extract_images",
+    "merge",
+    "split",
+    "list_form_fields",
+    "fill_form",
+    "markdown_to_pdf",
+];
+
+/// How many characters of text an operation returns when `max_chars` is not given
+const DEFAULT_MAX_CHARS: usize = 50_000;
+
+/// Page attributes a page can inherit from the page tree above it
+const INHERITABLE_PAGE_KEYS: [&[u8]; 4] = [b"Resources", b"MediaBox", b"CropBox", b"Rotate"];
 
 pub async fn pdf_tool(
     path: &str,
     operation: &str,
+    content: Option<&str>,
+    params: Option<&Value>,
     cache_dir: &Path,
 ) -> Result<Vec<Content>, ErrorData> {
-    // Open and parse the PDF file
-    let doc = Document::load(path).map_err(|e| {
-        ErrorData::new(
-            ErrorCode::INTERNAL_ERROR,
-            format!("Failed to open PDF file: {}", e),
-            None,
-        )
-    })?;
-
     let result = match operation {
         "extract_text" => {
-            let mut text = String::new();
-
-            // Iterate over each page in the document
-            for (page_num, page_id) in doc.get_pages() {
-                text.push_str(&format!("Page {}:\n", page_num));
-
-                // Try to get text from page contents
-                if let Ok(page_obj) = doc.get_object(page_id) {
-                    if let Ok(page_dict) = page_obj.as_dict() {
-                        // Try to get text from Contents stream
-                        if let Ok(contents) =
-                            page_dict.get(b"Contents").and_then(|c| c.as_reference())
-                        {
-                            if let Ok(content_obj) = doc.get_object(contents) {
-                                if let Ok(stream) = content_obj.as_stream() {
-                                    if let Ok(content_data) = stream.get_plain_content() {
-                                        if let Ok(content) = PdfContent::decode(&content_data) {
-                                            // Process each operation in the content stream
-                                            for operation in content.operations {
-                                                match operation.operator.as_ref() {
-                                                    // "Tj" operator: show text
-                                                    "Tj" => {
-                                                        for operand in operation.operands {
-                                                            if let Object::String(ref bytes, _) =
-                                                                operand
-                                                            {
-                                                                if let Ok(s) =
-                                                                    std::str::from_utf8(bytes)
-                                                                {
-                                                                    text.push_str(s);
-                                                                }
-                                                            }
-                                                        }
-                                                        text.push(' ');
-                                                    }
-                                                    // "TJ" operator: show text with positioning
-                                                    "TJ" => {
-                                                        if let Some(Object::Array(ref arr)) =
-                                                            operation.operands.first()
-                                                        {
-                                                            let mut last_was_text = false;
-                                                            for element in arr {
-                                                                match element {
-                                                                    Object::String(
-                                                                        ref bytes,
-                                                                        _,
-                                                                    ) => {
-                                                                        if let Ok(s) =
-                                                                            std::str::from_utf8(
-                                                                                bytes,
-                                                                            )
-                                                                        {
-                                                                            if last_was_text {
-                                                                                text.push(' ');
-                                                                            }
-                                                                            text.push_str(s);
-                                                                            last_was_text = true;
-                                                                        }
-                                                                    }
-                                                                    Object::Integer(offset) => {
-                                                                        // Large negative offsets often indicate word spacing
-                                                                        if *offset < -100 {
-                                                                            text.push(' ');
-                                                                            last_was_text = false;
-                                                                        }
-                                                                    }
-                                                                    Object::Real(offset) => {
-                                                                        if *offset < -100.0 {
-                                                                            text.push(' ');
-                                                                            last_was_text = false;
-                                                                        }
-                                                                    }
-                                                                    _ => {}
-                                                                }
-                                                            }
-                                                            text.push(' ');
-                                                        }
-                                                    }
-                                                    _ => (), // Ignore other operators
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
+            let doc = load_pdf(path)?;
+            let texts = page_texts(&doc, params)?;
+            if texts.pages.iter().all(|(_, text)| text.is_empty()) {
+                "No text found in PDF".to_string()
+            } else {
+                let mut text = String::new();
+                for (page_num, page_text) in &texts.pages {
+                    text.push_str(&format!("Page {}:\n{}\n", page_num, page_text));
                 }
-                text.push('\n');
+                format!("Extracted text from PDF:\n\n{}{}", text, texts.note())
             }
+        }
 
-            if text.trim().is_empty() {
-                "No text found in PDF".to_string()
-            } else {
-                format!("Extracted text from PDF:\n\n{}", text)
+        "extract_pages" => {
+            let doc = load_pdf(path)?;
+            let texts = page_texts(&doc, params)?;
+            let pages: Vec<Value> = texts
+                .pages
+                .iter()
+                .map(|(page, text)| json!({ "page": page, "text": text }))
+                .collect();
+            let mut result = json!({
+                "page_count": doc.get_pages().len(),
+                "pages": pages,
+            });
+            if let Some(page) = texts.cut_short {
+                result["cut_short"] = json!(page);
+            }
+            if !texts.remaining.is_empty() {
+                result["remaining_pages"] = json!(format_page_ranges(&texts.remaining));
             }
+            serde_json::to_string_pretty(&result).map_err(|e| {
+                ErrorData::new(
+                    ErrorCode::INTERNAL_ERROR,
+                    format!("Failed to serialize pages: {}", e),
+                    None,
+                )
+            })?
         }
 
         "extract_images" => {
+            let doc = load_pdf(path)?;
             let cache_dir = cache_dir.join("pdf_images");
             fs::create_dir_all(&cache_dir).map_err(|e| {
                 ErrorData::new(
@@ -344,12 +305,46 @@ pub async fn pdf_tool(
             }
         }
 
+        "merge" => merge_pdfs(path, params)?,
+
+        "split" => split_pdf(path, params)?,
+
+        "list_form_fields" => {
+            let doc = load_pdf(path)?;
+            let fields = form_fields(&doc);
+            if fields.is_empty() {
+                "No form fields found in PDF".to_string()
+            } else {
+                let lines: Vec<String> = fields.iter().map(|field| field.describe(&doc)).collect();
+                truncate_chars(
+                    format!("Found {} form fields:\n{}", fields.len(), lines.join("\n")),
+                    max_chars(params),
+                )
+            }
+        }
+
+        "fill_form" => fill_form(path, params)?,
+
+        "markdown_to_pdf" => {
+            let markdown = content.ok_or_else(|| {
+                ErrorData::new(
+                    ErrorCode::INVALID_PARAMS,
+                    "Missing 'content' with the Markdown to render".to_string(),
+                    None,
+                )
+            })?;
+            check_output(Path::new(path), params)?;
+            let page_count = markdown_to_pdf(markdown, Path::new(path))?;
+            format!("Wrote {} pages to {}", page_count, path)
+        }
+
         _ => {
             return Err(ErrorData::new(
                 ErrorCode::INVALID_PARAMS,
                 format!(
-                    "Invalid operation: {}. Valid operations are: 'extract_text', 'extract_images'",
-                    operation
+                    "Invalid operation: {}. Valid operations are: '{}'",
+                    operation,
+                    OPERATIONS.join("', '")
                 ),
                 None,
             ))
@@ -359,11 +354,1338 @@ pub async fn pdf_tool(
     Ok(vec![Content::text(result)])
 }
 
+/// Existing files are only replaced when the caller sets `overwrite`
+fn check_output(output: &Path, params: Option<&Value>) -> Result<(), ErrorData> {
+    let overwrite = param(params, "overwrite")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    if output.exists() && !overwrite {
+        return Err(invalid_params(format!(
+            "{} already exists. Set params.overwrite to true to replace it",
+            output.display()
+        )));
+    }
+    Ok(())
+}
+
+fn load_pdf(path: &str) -> Result<Document, ErrorData> {
+    Document::load(path).map_err(|e| {
+        ErrorData::new(
+            ErrorCode::INTERNAL_ERROR,
+            format!("Failed to open PDF file: {}", e),
+            None,
+        )
+    })
+}
+
+fn save_pdf(doc: &mut Document, path: &Path) -> Result<(), ErrorData> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).map_err(|e| {
+            ErrorData::new(
+                ErrorCode::INTERNAL_ERROR,
+                format!("Failed to create directory {}: {}", parent.display(), e),
+                None,
+            )
+        })?;
+    }
+    doc.save(path).map(|_| ()).map_err(|e| {
+        ErrorData::new(
+            ErrorCode::INTERNAL_ERROR,
+            format!("Failed to write PDF file {}: {}", path.display(), e),
+            None,
+        )
+    })
+}
+
+fn invalid_params(message: String) -> ErrorData {
+    ErrorData::new(ErrorCode::INVALID_PARAMS, message, None)
+}
+
+fn param<'a>(params: Option<&'a Value>, key: &str) -> Option<&'a Value> {
+    params.and_then(|p| p.get(key))
+}
+
+fn max_chars(params: Option<&Value>) -> usize {
+    param(params, "max_chars")
+        .and_then(Value::as_u64)
+        .map(|n| n as usize)
+        .unwrap_or(DEFAULT_MAX_CHARS)
+}
+
+fn truncate_chars(text: String, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!(
+            "{}\n\n[Output truncated at {} characters]",
+            &text[..end],
+            max_chars
+        ),
+        None => text,
+    }
+}
+
+/// Parse page ranges like "1-3,5,8-" into page numbers, in the order they are given
+fn parse_page_ranges(spec: &str, page_count: u32) -> Result<Vec<u32>, String> {
+    let parse_number = |text: &str, default: u32| {
+        let text = text.trim();
+        if text.is_empty() {
+            return Ok(default);
+        }
+        text.parse::<u32>()
+            .map_err(|_| format!("Invalid page number '{}' in '{}'", text, spec))
+    };
+
+    let mut pages = Vec::new();
+    for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let (start, end) = match part.split_once('-') {
+            Some((start, end)) => (parse_number(start, 1)?, parse_number(end, page_count)?),
+            None => {
+                let page = parse_number(part, 0)?;
+                (page, page)
+            }
+        };
+        if start == 0 || end > page_count {
+            return Err(format!(
+                "Page range '{}' is outside the document's {} pages",
+                part, page_count
+            ));
+        }
+        if start > end {
+            return Err(format!("Page range '{}' ends before it starts", part));
+        }
+        pages.extend(start..=end);
+    }
+    if pages.is_empty() {
+        return Err(format!("No pages given in '{}'", spec));
+    }
+    Ok(pages)
+}
+
+/// Write page numbers back as compact ranges, like "1-3,5"
+fn format_page_ranges(pages: &[u32]) -> String {
+    let mut ranges: Vec<(u32, u32)> = Vec::new();
+    for &page in pages {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == page => *end = page,
+            _ => ranges.push((page, page)),
+        }
+    }
+    ranges
+        .iter()
+        .map(|(start, end)| {
+            if start == end {
+                start.to_string()
+            } else {
+                format!("{}-{}", start, end)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// The pages picked by the `pages` parameter, or all pages of the document
+fn selected_pages(
+    doc: &Document,
+    pages: Option<&Value>,
+) -> Result<Vec<(u32, ObjectId)>, ErrorData> {
+    let all_pages = doc.get_pages();
+    let spec = match pages {
+        None | Some(Value::Null) => return Ok(all_pages.into_iter().collect()),
+        Some(Value::String(spec)) => spec.clone(),
+        Some(Value::Number(page)) => page.to_string(),
+        Some(_) => {
+            return Err(invalid_params(
+                "'pages' must be a page range like \"1-3,5\"".to_string(),
+            ))
+        }
+    };
+    let numbers = parse_page_ranges(&spec, all_pages.len() as u32).map_err(invalid_params)?;
+    Ok(numbers
+        .into_iter()
+        .filter_map(|page| all_pages.get(&page).map(|id| (page, *id)))
+        .collect())
+}
+
+/// Text of the selected pages, limited to `max_chars` characters in total
+struct PageTexts {
+    pages: Vec<(u32, String)>,
+    /// The last returned page, when its text did not fit completely
+    cut_short: Option<u32>,
+    /// Selected pages left out to stay within the limit
+    remaining: Vec<u32>,
+}
+
+impl PageTexts {
+    fn note(&self) -> String {
+        let mut notes = Vec::new();
+        if let Some(page) = self.cut_short {
+            notes.push(format!("the text of page {} was cut short", page));
+        }
+        if !self.remaining.is_empty() {
+            notes.push(format!(
+                "{} more pages were left out, request them with pages \"{}\"",
+                self.remaining.len(),
+                format_page_ranges(&self.remaining)
+            ));
+        }
+        if notes.is_empty() {
+            String::new()
+        } else {
+            format!("\n[Output limited by max_chars: {}]", notes.join("; "))
+        }
+    }
+}
+
+fn page_texts(doc: &Document, params: Option<&Value>) -> Result<PageTexts, ErrorData> {
+    let pages = selected_pages(doc, param(params, "pages"))?;
+    let max_chars = max_chars(params);
+
+    let mut texts = PageTexts {
+        pages: Vec::new(),
+        cut_short: None,
+        remaining: Vec::new(),
+    };
+    let mut used = 0;
+    for (page_num, page_id) in pages {
+        if used >= max_chars || texts.cut_short.is_some() {
+            texts.remaining.push(page_num);
+            continue;
+        }
+        let mut text = page_text(doc, page_id);
+        let size = text.chars().count();
+        if used + size > max_chars {
+            if !texts.pages.is_empty() {
+                texts.remaining.push(page_num);
+                used = max_chars;
+                continue;
+            }
+            if let Some((end, _)) = text.char_indices().nth(max_chars - used) {
+                text.truncate(end);
+            }
+            texts.cut_short = Some(page_num);
+        }
+        used += text.chars().count();
+        texts.pages.push((page_num, text));
+    }
+    Ok(texts)
+}
+
+/// Text shown by the text operators in the content streams of a page
+fn page_text(doc: &Document, page_id: ObjectId) -> String {
+    let mut text = String::new();
+    let Ok(content) = doc
+        .get_page_content(page_id)
+        .and_then(|data| PdfContent::decode(&data))
+    else {
+        return text;
+    };
+
+    for operation in content.operations {
+        match operation.operator.as_ref() {
+            // "Tj" shows text, "'" and "\"" move to the next line and show text
+            "Tj" | "'" | "\"" => {
+                for operand in operation.operands {
+                    if let Object::String(ref bytes, _) = operand {
+                        if let Ok(s) = std::str::from_utf8(bytes) {
+                            text.push_str(s);
+                        }
+                    }
+                }
+                text.push(' ');
+            }
+            // "TJ" shows text with positioning
+            "TJ" => {
+                if let Some(Object::Array(ref arr)) = operation.operands.first() {
+                    let mut last_was_text = false;
+                    for element in arr {
+                        match element {
+                            Object::String(ref bytes, _) => {
+                                if let Ok(s) = std::str::from_utf8(bytes) {
+                                    if last_was_text {
+                                        text.push(' ');
+                                    }
+                                    text.push_str(s);
+                                    last_was_text = true;
+                                }
+                            }
+                            // Large negative offsets often indicate word spacing
+                            Object::Integer(offset) if *offset < -100 => {
+                                text.push(' ');
+                                last_was_text = false;
+                            }
+                            Object::Real(offset) if *offset < -100.0 => {
+                                text.push(' ');
+                                last_was_text = false;
+                            }
+                            _ => {}
+                        }
+                    }
+                    text.push(' ');
+                }
+            }
+            _ => (), // Ignore other operators
+        }
+    }
+    text.trim_end().to_string()
+}
+
+/// Walk up the page tree for an attribute the page inherits from its parents
+fn inherited_attribute(doc: &Document, page: &Dictionary, key: &[u8]) -> Option<Object> {
+    let mut parent = page.get(b"Parent").and_then(Object::as_reference).ok();
+    for _ in 0..64 {
+        let node = doc.get_dictionary(parent?).ok()?;
+        if let Ok(value) = node.get(key) {
+            return Some(value.clone());
+        }
+        parent = node.get(b"Parent").and_then(Object::as_reference).ok();
+    }
+    None
+}
+
+/// Build a new document out of the given pages of each source document, in order
+fn assemble_pages(sources: Vec<(Document, Vec<u32>)>) -> Result<Document, ErrorData> {
+    let mut output = Document::with_version("1.5");
+    let pages_id = output.new_object_id();
+    let mut kids = Vec::new();
+
+    for (mut source, page_numbers) in sources {
+        source.renumber_objects_with(output.max_id + 1);
+        let pages = source.get_pages();
+        for page_num in page_numbers {
+            let page_id = *pages.get(&page_num).ok_or_else(|| {
+                invalid_params(format!("Page {} is not in the document", page_num))
+            })?;
+            let mut page = source.get_dictionary(page_id).cloned().map_err(|e| {
+                ErrorData::new(
+                    ErrorCode::INTERNAL_ERROR,
+                    format!("Failed to get page {}: {}", page_num, e),
+                    None,
+                )
+            })?;
+            // The page leaves its page tree behind, so it has to carry what it inherited
+            for key in INHERITABLE_PAGE_KEYS {
+                if !page.has(key) {
+                    if let Some(value) = inherited_attribute(&source, &page, key) {
+                        page.set(key, value);
+                    }
+                }
+            }
+            if !page.has(b"MediaBox") {
+                page.set("MediaBox", vec![0.into(), 0.into(), 612.into(), 792.into()]);
+            }
+            page.set("Parent", pages_id);
+            source.objects.insert(page_id, Object::Dictionary(page));
+            kids.push(Object::Reference(page_id));
+        }
+
+        if let Some(max_id) = source.objects.keys().map(|id| id.0).max() {
+            output.max_id = output.max_id.max(max_id);
+        }
+        for (id, object) in source.objects {
+            let is_page_tree = object
+                .as_dict()
+                .is_ok_and(|dict| dict.has_type(b"Catalog") || dict.has_type(b"Pages"));
+            if !is_page_tree {
+                output.objects.insert(id, object);
+            }
+        }
+    }
+
+    let count = kids.len() as i64;
+    output.objects.insert(
+        pages_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => kids,
+            "Count" => count,
+        }),
+    );
+    let catalog_id = output.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+    });
+    output.trailer.set("Root", catalog_id);
+    output.prune_objects();
+    output.renumber_objects();
+    output.max_id = output.objects.len() as u32;
+    output.compress();
+    Ok(output)
+}
+
+fn merge_pdfs(path: &str, params: Option<&Value>) -> Result<String, ErrorData> {
+    let inputs = param(params, "inputs")
+        .and_then(Value::as_array)
+        .filter(|inputs| !inputs.is_empty())
+        .ok_or_else(|| {
+            invalid_params("The merge operation needs 'inputs', a list of PDF files".to_string())
+        })?;
+    check_output(Path::new(path), params)?;
+
+    let mut sources = Vec::new();
+    let mut page_count = 0;
+    for input in inputs {
+        let (input_path, pages) = match input {
+            Value::String(input_path) => (input_path.as_str(), None),
+            Value::Object(input) => (
+                input
+                    .get("path")
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| invalid_params("Each input needs a 'path'".to_string()))?,
+                input.get("pages"),
+            ),
+            _ => {
+                return Err(invalid_params(
+                    "Inputs must be paths or objects with 'path' and 'pages'".to_string(),
+                ))
+            }
+        };
+        let doc = load_pdf(input_path)?;
+        let pages: Vec<u32> = selected_pages(&doc, pages)?
+            .into_iter()
+            .map(|(page_num, _)| page_num)
+            .collect();
+        page_count += pages.len();
+        sources.push((doc, pages));
+    }
+
+    let mut merged = assemble_pages(sources)?;
+    save_pdf(&mut merged, Path::new(path))?;
+    Ok(format!(
+        "Merged {} PDFs ({} pages) into {}",
+        inputs.len(),
+        page_count,
+        path
+    ))
+}
+
+fn split_pdf(path: &str, params: Option<&Value>) -> Result<String, ErrorData> {
+    let ranges: Vec<&str> = param(params, "ranges")
+        .and_then(Value::as_array)
+        .map(|ranges| ranges.iter().filter_map(Value::as_str).collect())
+        .filter(|ranges: &Vec<&str>| !ranges.is_empty())
+        .ok_or_else(|| {
+            invalid_params(
+                "The split operation needs 'ranges', a list of page ranges like [\"1-3\", \"4-\"]"
+                    .to_string(),
+            )
+        })?;
+
+    let doc = load_pdf(path)?;
+    let page_count = doc.get_pages().len() as u32;
+    let parts = ranges
+        .iter()
+        .map(|range| parse_page_ranges(range, page_count).map(|pages| (*range, pages)))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(invalid_params)?;
+
+    let input = Path::new(path);
+    let output_dir = param(params, "output_dir")
+        .and_then(Value::as_str)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| input.parent().map(Path::to_path_buf).unwrap_or_default());
+    let stem = input
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "document".to_string());
+
+    let outputs: Vec<PathBuf> = parts
+        .iter()
+        .map(|(range, _)| {
+            let label: String = range
+                .chars()
+                .filter(|c| !c.is_whitespace())
+                .map(|c| if c == ',' { '_' } else { c })
+                .collect();
+            output_dir.join(format!("{}_pages_{}.pdf", stem, label))
+        })
+        .collect();
+    for output in &outputs {
+        check_output(output, params)?;
+    }
+
+    let mut written = Vec::new();
+    for ((range, pages), output) in parts.into_iter().zip(outputs) {
+        let count = pages.len();
+        let mut part = assemble_pages(vec![(doc.clone(), pages)])?;
+        save_pdf(&mut part, &output)?;
+        written.push(format!(
+            "Wrote pages {} ({} pages) to {}",
+            range,
+            count,
+            output.display()
+        ));
+    }
+    Ok(format!(
+        "Split {} into {} files:\n{}",
+        path,
+        written.len(),
+        written.join("\n")
+    ))
+}
+
+const FIELD_FLAG_RADIO: i64 = 1 << 15;
+const FIELD_FLAG_PUSHBUTTON: i64 = 1 << 16;
+
+/// A terminal field of the document's AcroForm
+struct FormField {
+    id: ObjectId,
+    name: String,
+    kind: Vec<u8>,
+    flags: i64,
+    value: Option<String>,
+    /// Widget annotations showing the field, which can be the field itself
+    widgets: Vec<ObjectId>,
+}
+
+impl FormField {
+    fn kind_name(&self) -> &'static str {
+        match self.kind.as_slice() {
+            b"Tx" => "text",
+            b"Ch" => "choice",
+            b"Sig" => "signature",
+            b"Btn" if self.flags & FIELD_FLAG_PUSHBUTTON != 0 => "button",
+            b"Btn" if self.flags & FIELD_FLAG_RADIO != 0 => "radio",
+            b"Btn" => "checkbox",
+            _ => "unknown",
+        }
+    }
+
+    /// Names of the appearance states the widgets of a button can be switched to
+    fn on_states(&self, doc: &Document) -> Vec<String> {
+        let mut states = Vec::new();
+        for widget in &self.widgets {
+            for state in widget_states(doc, *widget) {
+                if !states.contains(&state) {
+                    states.push(state);
+                }
+            }
+        }
+        states
+    }
+
+    fn options(&self, doc: &Document) -> Vec<String> {
+        let Ok(options) = doc
+            .get_dictionary(self.id)
+            .and_then(|field| field.get(b"Opt"))
+            .and_then(Object::as_array)
+        else {
+            return Vec::new();
+        };
+        options
+            .iter()
+            .filter_map(|option| match option {
+                Object::Array(pair) => pair.get(1).and_then(|o| decode_text_string(o).ok()),
+                other => decode_text_string(other).ok(),
+            })
+            .collect()
+    }
+
+    fn describe(&self, doc: &Document) -> String {
+        let mut line = format!("- {} ({})", self.name, self.kind_name());
+        if let Some(value) = &self.value {
+            line.push_str(&format!(" = {:?}", value));
+        }
+        let choices = match self.kind.as_slice() {
+            b"Btn" => self.on_states(doc),
+            b"Ch" => self.options(doc),
+            _ => Vec::new(),
+        };
+        if !choices.is_empty() {
+            line.push_str(&format!(" [options: {}]", choices.join(", ")));
+        }
+        line
+    }
+}
+
+fn field_value(value: &Object) -> Option<String> {
+    match value {
+        Object::Name(name) => Some(String::from_utf8_lossy(name).to_string()),
+        Object::Array(values) => Some(
+            values
+                .iter()
+                .filter_map(field_value)
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+        other => decode_text_string(other).ok(),
+    }
+}
+
+fn widget_states(doc: &Document, widget: ObjectId) -> Vec<String> {
+    let normal = doc
+        .get_dictionary(widget)
+        .and_then(|w| w.get(b"AP"))
+        .and_then(|ap| doc.dereference(ap))
+        .and_then(|(_, ap)| ap.as_dict())
+        .and_then(|ap| ap.get(b"N"))
+        .and_then(|n| doc.dereference(n))
+        .and_then(|(_, n)| n.as_dict());
+    match normal {
+        Ok(normal) => normal
+            .iter()
+            .map(|(state, _)| String::from_utf8_lossy(state).to_string())
+            .filter(|state| state != "Off")
+            .collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+fn acroform_dict(doc: &Document) -> Option<&Dictionary> {
+    let acroform = doc.catalog().ok()?.get(b"AcroForm").ok()?;
+    doc.dereference(acroform).ok()?.1.as_dict().ok()
+}
+
+/// The fields of the document's AcroForm, with their fully qualified names
+fn form_fields(doc: &Document) -> Vec<FormField> {
+    let mut fields = Vec::new();
+    if let Some(roots) = acroform_dict(doc).and_then(|form| form.get(b"Fields").ok()) {
+        if let Ok(roots) = roots.as_array() {
+            let inherited = FormField {
+                id: (0, 0),
+                name: String::new(),
+                kind: Vec::new(),
+                flags: 0,
+                value: None,
+                widgets: Vec::new(),
+            };
+            for root in roots.iter().filter_map(|r| r.as_reference().ok()) {
+                collect_fields(doc, root, &inherited, &mut fields, 0);
+            }
+        }
+    }
+    fields
+}
+
+fn collect_fields(
+    doc: &Document,
+    id: ObjectId,
+    parent: &FormField,
+    fields: &mut Vec<FormField>,
+    depth: usize,
+) {
+    let Ok(dict) = doc.get_dictionary(id) else {
+        return;
+    };
+    if depth > 32 {
+        return;
+    }
+
+    let name = match dict.get(b"T").ok().and_then(|t| decode_text_string(t).ok()) {
+        Some(partial) if !parent.name.is_empty() => format!("{}.{}", parent.name, partial),
+        Some(partial) => partial,
+        None => parent.name.clone(),
+    };
+    let field = FormField {
+        id,
+        name,
+        kind: dict
+            .get(b"FT")
+            .and_then(Object::as_name)
+            .map(<[u8]>::to_vec)
+            .unwrap_or_else(|_| parent.kind.clone()),
+        flags: dict
+            .get(b"Ff")
+            .and_then(Object::as_i64)
+            .unwrap_or(parent.flags),
+        value: dict
+            .get(b"V")
+            .ok()
+            .and_then(field_value)
+            .or_else(|| parent.value.clone()),
+        widgets: Vec::new(),
+    };
+
+    let kids: Vec<ObjectId> = dict
+        .get(b"Kids")
+        .and_then(Object::as_array)
+        .map(|kids| kids.iter().filter_map(|k| k.as_reference().ok()).collect())
+        .unwrap_or_default();
+    let child_fields: Vec<ObjectId> = kids
+        .iter()
+        .copied()
+        .filter(|kid| doc.get_dictionary(*kid).is_ok_and(|k| k.has(b"T")))
+        .collect();
+    if !child_fields.is_empty() {
+        for kid in child_fields {
+            collect_fields(doc, kid, &field, fields, depth + 1);
+        }
+        return;
+    }
+
+    // Kids without names are the widgets of this field
+    let widgets = if kids.is_empty() { vec![id] } else { kids };
+    fields.push(FormField { widgets, ..field });
+}
+
+fn fill_form(path: &str, params: Option<&Value>) -> Result<String, ErrorData> {
+    let values = param(params, "fields")
+        .and_then(Value::as_object)
+        .filter(|values| !values.is_empty())
+        .ok_or_else(|| {
+            invalid_params(
+                "The fill_form operation needs 'fields', an object of field names and values"
+                    .to_string(),
+            )
+        })?;
+    let output = match param(params, "output_path").and_then(Value::as_str) {
+        Some(output) => PathBuf::from(output),
+        None => {
+            let input = Path::new(path);
+            let stem = input
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_else(|| "form".to_string());
+            input.with_file_name(format!("{}_filled.pdf", stem))
+        }
+    };
+    check_output(&output, params)?;
+
+    let mut doc = load_pdf(path)?;
+    let fields = form_fields(&doc);
+    if fields.is_empty() {
+        return Err(invalid_params(format!("{} has no form fields", path)));
+    }
+    let unknown: Vec<&str> = values
+        .keys()
+        .map(String::as_str)
+        .filter(|name| !fields.iter().any(|field| field.name == *name))
+        .collect();
+    if !unknown.is_empty() {
+        let known: Vec<&str> = fields.iter().map(|f| f.name.as_str()).collect();
+        return Err(invalid_params(format!(
+            "Unknown form fields: {}. The form has: {}",
+            unknown.join(", "),
+            known.join(", ")
+        )));
+    }
+
+    for (name, value) in values {
+        let field = fields.iter().find(|field| &field.name == name).unwrap();
+        match field.kind_name() {
+            "text" | "choice" => set_text_field(&mut doc, field, value)?,
+            "checkbox" | "radio" => set_button_field(&mut doc, field, value)?,
+            kind => {
+                return Err(invalid_params(format!(
+                    "Field {} is a {} field and cannot be filled",
+                    name, kind
+                )))
+            }
+        }
+    }
+
+    // Ask viewers to draw the new values, since the old appearances were removed
+    let acroform = doc
+        .catalog()
+        .and_then(|catalog| catalog.get(b"AcroForm"))
+        .cloned();
+    let acroform = match acroform {
+        Ok(Object::Reference(id)) => doc.get_dictionary_mut(id),
+        _ => doc
+            .catalog_mut()
+            .and_then(|catalog| catalog.get_mut(b"AcroForm"))
+            .and_then(Object::as_dict_mut),
+    };
+    if let Ok(acroform) = acroform {
+        acroform.set("NeedAppearances", true);
+    }
+
+    save_pdf(&mut doc, &output)?;
+    Ok(format!(
+        "Filled {} form fields of {} into {}",
+        values.len(),
+        path,
+        output.display()
+    ))
+}
+
+fn value_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+fn set_text_field(doc: &mut Document, field: &FormField, value: &Value) -> Result<(), ErrorData> {
+    let text = value_text(value);
+    let dict = doc.get_dictionary_mut(field.id).map_err(|e| {
+        ErrorData::new(
+            ErrorCode::INTERNAL_ERROR,
+            format!("Failed to update field {}: {}", field.name, e),
+            None,
+        )
+    })?;
+    dict.set("V", text_string(&text));
+    for widget in &field.widgets {
+        if let Ok(widget) = doc.get_dictionary_mut(*widget) {
+            widget.remove(b"AP");
+        }
+    }
+    Ok(())
+}
+
+fn set_button_field(doc: &mut Document, field: &FormField, value: &Value) -> Result<(), ErrorData> {
+    let on_states = field.on_states(doc);
+    let first_on = on_states
+        .first()
+        .cloned()
+        .unwrap_or_else(|| "Yes".to_string());
+    let state = match value {
+        Value::Bool(true) => first_on,
+        Value::Bool(false) | Value::Null => "Off".to_string(),
+        other => {
+            let text = value_text(other);
+            if let Some(state) = on_states.iter().find(|s| s.eq_ignore_ascii_case(&text)) {
+                state.clone()
+            } else {
+                match text.to_lowercase().as_str() {
+                    "off" | "false" | "no" | "0" | "" => "Off".to_string(),
+                    "on" | "true" | "yes" | "1" | "checked" if field.kind_name() == "checkbox" => {
+                        first_on
+                    }
+                    _ => {
+                        return Err(invalid_params(format!(
+                            "'{}' is not an option of {}, use one of: {}, Off",
+                            text,
+                            field.name,
+                            on_states.join(", ")
+                        )))
+                    }
+                }
+            }
+        }
+    };
+
+    let widget_states: Vec<(ObjectId, Vec<String>)> = field
+        .widgets
+        .iter()
+        .map(|widget| (*widget, widget_states(doc, *widget)))
+        .collect();
+    if let Ok(dict) = doc.get_dictionary_mut(field.id) {
+        dict.set("V", Object::Name(state.clone().into_bytes()));
+    }
+    for (widget, states) in widget_states {
+        let shown = if states.contains(&state) {
+            state.as_str()
+        } else {
+            "Off"
+        };
+        if let Ok(widget) = doc.get_dictionary_mut(widget) {
+            widget.set("AS", Object::Name(shown.as_bytes().to_vec()));
+        }
+    }
+    Ok(())
+}
+
+const PAGE_WIDTH: f32 = 612.0;
+const PAGE_HEIGHT: f32 = 792.0;
+const MARGIN: f32 = 72.0;
+const BODY_SIZE: f32 = 11.0;
+const CODE_SIZE: f32 = 9.5;
+const LINE_SPACING: f32 = 1.35;
+
+#[derive(Clone, Copy)]
+enum Font {
+    Regular,
+    Bold,
+    Mono,
+}
+
+impl Font {
+    const ALL: [Font; 3] = [Font::Regular, Font::Bold, Font::Mono];
+
+    fn resource_name(self) -> &'static str {
+        match self {
+            Font::Regular => "F1",
+            Font::Bold => "F2",
+            Font::Mono => "F3",
+        }
+    }
+
+    fn base_font(self) -> &'static str {
+        match self {
+            Font::Regular => "Helvetica",
+            Font::Bold => "Helvetica-Bold",
+            Font::Mono => "Courier",
+        }
+    }
+
+    /// Approximate width of the text in points, erring on the wide side
+    fn text_width(self, text: &str, size: f32) -> f32 {
+        let em: f32 = match self {
+            Font::Mono => text.chars().count() as f32 * 0.6,
+            Font::Regular | Font::Bold => text
+                .chars()
+                .map(|c| match c {
+                    'i' | 'j' | 'l' | '.' | ',' | ';' | ':' | '\'' | '!' | '|' => 0.28,
+                    ' ' | 'f' | 't' | 'r' | 'I' | '(' | ')' | '[' | ']' | '-' => 0.34,
+                    'm' | 'w' | 'M' | 'W' | '@' => 0.89,
+                    c if c.is_uppercase() => 0.72,
+                    _ => 0.56,
+                })
+                .sum(),
+        };
+        let weight = if matches!(self, Font::Bold) {
+            1.06
+        } else {
+            1.0
+        };
+        em * size * weight
+    }
+}
+
+/// Encode text for the standard fonts, which use WinAnsiEncoding
+fn win_ansi(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match c {
+            '\u{20}'..='\u{7e}' | '\u{a0}'..='\u{ff}' => c as u8,
+            '€' => 0x80,
+            '…' => 0x85,
+            '‘' => 0x91,
+            '’' => 0x92,
+            '“' => 0x93,
+            '”' => 0x94,
+            '•' => 0x95,
+            '–' => 0x96,
+            '—' => 0x97,
+            '\t' => b' ',
+            _ => b'?',
+        })
+        .collect()
+}
+
+/// Break text into lines that fit the width, splitting words that are too long on their own
+fn wrap_text(text: &str, font: Font, size: f32, width: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let candidate = if line.is_empty() {
+            word.to_string()
+        } else {
+            format!("{} {}", line, word)
+        };
+        if font.text_width(&candidate, size) <= width {
+            line = candidate;
+            continue;
+        }
+        if !line.is_empty() {
+            lines.push(std::mem::take(&mut line));
+        }
+        for c in word.chars() {
+            if !line.is_empty() && font.text_width(&format!("{}{}", line, c), size) > width {
+                lines.push(std::mem::take(&mut line));
+            }
+            line.push(c);
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// Drop inline Markdown markup the renderer does not style, keeping link targets visible
+fn plain_inline(text: &str) -> String {
+    let mut plain = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('[') {
+        let link = rest[start + 1..]
+            .split_once("](")
+            .and_then(|(label, tail)| tail.split_once(')').map(|(url, after)| (label, url, after)));
+        match link {
+            Some((label, url, after)) if !label.contains('[') => {
+                plain.push_str(rest[..start].trim_end_matches('!'));
+                if label == url || url.is_empty() {
+                    plain.push_str(label);
+                } else {
+                    plain.push_str(&format!("{} ({})", label, url));
+                }
+                rest = after;
+            }
+            _ => {
+                plain.push_str(&rest[..=start]);
+                rest = &rest[start + 1..];
+            }
+        }
+    }
+    plain.push_str(rest);
+    plain.replace("**", "").replace("__", "").replace('`', "")
+}
+
+/// Lays out lines top to bottom, starting a new page when one is full
+struct PageLayout {
+    pages: Vec<Vec<Operation>>,
+    y: f32,
+}
+
+impl PageLayout {
+    fn new() -> Self {
+        Self {
+            pages: vec![Vec::new()],
+            y: PAGE_HEIGHT - MARGIN,
+        }
+    }
+
+    fn at_top(&self) -> bool {
+        self.y >= PAGE_HEIGHT - MARGIN
+    }
+
+    fn reserve(&mut self, height: f32) {
+        if self.y - height < MARGIN && !self.at_top() {
+            self.pages.push(Vec::new());
+            self.y = PAGE_HEIGHT - MARGIN;
+        }
+    }
+
+    fn space(&mut self, height: f32) {
+        if !self.at_top() {
+            self.y -= height;
+        }
+    }
+
+    fn operations(&mut self) -> &mut Vec<Operation> {
+        self.pages.last_mut().expect("layout always has a page")
+    }
+
+    fn line(&mut self, spans: &[(f32, &str)], font: Font, size: f32) {
+        self.reserve(size * LINE_SPACING);
+        self.y -= size * LINE_SPACING;
+        let y = self.y;
+        let operations = self.operations();
+        for (x, text) in spans {
+            operations.extend([
+                Operation::new("BT", vec![]),
+                Operation::new("Tf", vec![font.resource_name().into(), size.into()]),
+                Operation::new("Td", vec![(*x).into(), y.into()]),
+                Operation::new(
+                    "Tj",
+                    vec![Object::String(win_ansi(text), StringFormat::Literal)],
+                ),
+                Operation::new("ET", vec![]),
+            ]);
+        }
+    }
+
+    fn paragraph(&mut self, text: &str, font: Font, size: f32, indent: f32) {
+        let x = MARGIN + indent;
+        for line in wrap_text(text, font, size, PAGE_WIDTH - MARGIN - x) {
+            self.line(&[(x, &line)], font, size);
+        }
+    }
+
+    fn list_item(&mut self, marker: &str, text: &str, indent: f32) {
+        let x = MARGIN + indent;
+        let text_x = x + 14.0;
+        let lines = wrap_text(text, Font::Regular, BODY_SIZE, PAGE_WIDTH - MARGIN - text_x);
+        for (index, line) in lines.iter().enumerate() {
+            if index == 0 {
+                self.line(&[(x, marker), (text_x, line)], Font::Regular, BODY_SIZE);
+            } else {
+                self.line(&[(text_x, line)], Font::Regular, BODY_SIZE);
+            }
+        }
+    }
+
+    fn rule(&mut self) {
+        self.reserve(BODY_SIZE);
+        self.y -= BODY_SIZE / 2.0;
+        let y = self.y;
+        self.operations().extend([
+            Operation::new("w", vec![0.5.into()]),
+            Operation::new("G", vec![0.6.into()]),
+            Operation::new("m", vec![MARGIN.into(), y.into()]),
+            Operation::new("l", vec![(PAGE_WIDTH - MARGIN).into(), y.into()]),
+            Operation::new("S", vec![]),
+        ]);
+        self.y -= BODY_SIZE / 2.0;
+    }
+}
+
+fn heading_level(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    let text = line[level..].strip_prefix(' ')?;
+    (1..=6).contains(&level).then_some((level, text.trim()))
+}
+
+fn list_marker(line: &str) -> Option<(String, &str)> {
+    if let Some(text) = ["- ", "* ", "+ "]
+        .iter()
+        .find_map(|bullet| line.strip_prefix(bullet))
+    {
+        return Some(("•".to_string(), text));
+    }
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    let text = line[digits..].strip_prefix(". ")?;
+    (digits > 0).then(|| (format!("{}.", &line[..digits]), text))
+}
+
+fn is_rule(line: &str) -> bool {
+    let marks: Vec<char> = line.chars().filter(|c| !c.is_whitespace()).collect();
+    marks.len() >= 3 && matches!(marks[0], '-' | '*' | '_') && marks.iter().all(|c| *c == marks[0])
+}
+
+/// Lay out headings, paragraphs, lists, code blocks and rules of a Markdown document
+fn layout_markdown(markdown: &str) -> Vec<Vec<Operation>> {
+    let mut layout = PageLayout::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut code: Option<Vec<&str>> = None;
+
+    fn flush_paragraph(layout: &mut PageLayout, paragraph: &mut Vec<&str>) {
+        if !paragraph.is_empty() {
+            layout.paragraph(
+                &plain_inline(&paragraph.join(" ")),
+                Font::Regular,
+                BODY_SIZE,
+                0.0,
+            );
+            layout.space(BODY_SIZE * 0.6);
+            paragraph.clear();
+        }
+    }
+
+    fn flush_code(layout: &mut PageLayout, code: &[&str]) {
+        for line in code {
+            let line = line.replace('\t', "    ");
+            if line.trim().is_empty() {
+                layout.line(&[], Font::Mono, CODE_SIZE);
+            } else {
+                layout.paragraph(&line, Font::Mono, CODE_SIZE, 12.0);
+            }
+        }
+        layout.space(BODY_SIZE * 0.6);
+    }
+
+    for line in markdown.lines() {
+        let trimmed = line.trim();
+        if let Some(code_lines) = code.as_mut() {
+            if trimmed.starts_with("```") {
+                flush_code(&mut layout, code_lines);
+                code = None;
+            } else {
+                code_lines.push(line);
+            }
+            continue;
+        }
+        if trimmed.starts_with("```") {
+            flush_paragraph(&mut layout, &mut paragraph);
+            code = Some(Vec::new());
+        } else if trimmed.is_empty() {
+            flush_paragraph(&mut layout, &mut paragraph);
+        } else if let Some((level, text)) = heading_level(trimmed) {
+            flush_paragraph(&mut layout, &mut paragraph);
+            let size = match level {
+                1 => 22.0,
+                2 => 17.0,
+                3 => 14.0,
+                _ => 12.0,
+            };
+            layout.space(size * 0.5);
+            layout.reserve(size * LINE_SPACING * 2.0);
+            layout.paragraph(&plain_inline(text), Font::Bold, size, 0.0);
+            layout.space(size * 0.3);
+        } else if is_rule(trimmed) {
+            flush_paragraph(&mut layout, &mut paragraph);
+            layout.rule();
+        } else if let Some((marker, text)) = list_marker(trimmed) {
+            flush_paragraph(&mut layout, &mut paragraph);
+            let depth = (line.len() - line.trim_start().len()) / 2;
+            layout.list_item(&marker, &plain_inline(text), 18.0 * depth as f32);
+        } else {
+            paragraph.push(trimmed.trim_start_matches('>').trim_start());
+        }
+    }
+    flush_paragraph(&mut layout, &mut paragraph);
+    if let Some(code_lines) = code {
+        flush_code(&mut layout, &code_lines);
+    }
+    layout.pages
+}
+
+/// Render a Markdown document to a PDF using the standard fonts. Returns the number of pages.
+fn markdown_to_pdf(markdown: &str, path: &Path) -> Result<usize, ErrorData> {
+    let mut doc = Document::with_version("1.5");
+    let pages_id = doc.new_object_id();
+
+    let mut fonts = Dictionary::new();
+    for font in Font::ALL {
+        let font_id = doc.add_object(dictionary! {
+            "Type" => "Font",
+            "Subtype" => "Type1",
+            "BaseFont" => font.base_font(),
+            "Encoding" => "WinAnsiEncoding",
+        });
+        fonts.set(font.resource_name(), font_id);
+    }
+    let resources_id = doc.add_object(dictionary! { "Font" => fonts });
+
+    let mut kids = Vec::new();
+    for operations in layout_markdown(markdown) {
+        let content = PdfContent { operations }.encode().map_err(|e| {
+            ErrorData::new(
+                ErrorCode::INTERNAL_ERROR,
+                format!("Failed to encode page content: {}", e),
+                None,
+            )
+        })?;
+        let content_id = doc.add_object(Stream::new(dictionary! {}, content));
+        let page_id = doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "Contents" => content_id,
+            "Resources" => resources_id,
+            "MediaBox" => vec![0.into(), 0.into(), PAGE_WIDTH.into(), PAGE_HEIGHT.into()],
+        });
+        kids.push(Object::Reference(page_id));
+    }
+
+    let page_count = kids.len();
+    doc.objects.insert(
+        pages_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => kids,
+            "Count" => page_count as i64,
+        }),
+    );
+    let catalog_id = doc.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+    });
+    doc.trailer.set("Root", catalog_id);
+    doc.compress();
+    save_pdf(&mut doc, path)?;
+    Ok(page_count)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::path::PathBuf;
 
+    async fn run(
+        path: &Path,
+        operation: &str,
+        content: Option<&str>,
+        params: Value,
+    ) -> Result<String, ErrorData> {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let result = pdf_tool(
+            path.to_str().unwrap(),
+            operation,
+            content,
+            Some(&params),
+            cache_dir.path(),
+        )
+        .await?;
+        Ok(result[0].as_text().unwrap().text.clone())
+    }
+
+    fn page_count(path: &Path) -> usize {
+        Document::load(path).unwrap().get_pages().len()
+    }
+
+    fn long_markdown(title: &str, paragraphs: usize) -> String {
+        let mut markdown = format!("# {}\n\n", title);
+        for i in 1..=paragraphs {
+            markdown.push_str(&format!(
+                "Paragraph {} of {} with enough words to wrap across the width of the page \
+                 at least once or twice before it ends.\n\n",
+                i, title
+            ));
+        }
+        markdown
+    }
+
+    /// A one page form with a text field, a checkbox and a radio group with two buttons
+    fn write_form(path: &Path) {
+        let mut doc = Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+        let page_id = doc.new_object_id();
+        fn states(doc: &mut Document, on: &str) -> Dictionary {
+            let mut normal = Dictionary::new();
+            for state in [on, "Off"] {
+                normal.set(
+                    state,
+                    doc.add_object(Stream::new(dictionary! {}, Vec::new())),
+                );
+            }
+            dictionary! { "N" => normal }
+        }
+
+        let name_id = doc.add_object(dictionary! {
+            "Type" => "Annot",
+            "Subtype" => "Widget",
+            "FT" => "Tx",
+            "T" => Object::string_literal("name"),
+            "V" => Object::string_literal("old"),
+            "Rect" => vec![72.into(), 700.into(), 300.into(), 720.into()],
+            "P" => page_id,
+        });
+        let agree_states = states(&mut doc, "Yes");
+        let agree_id = doc.add_object(dictionary! {
+            "Type" => "Annot",
+            "Subtype" => "Widget",
+            "FT" => "Btn",
+            "T" => Object::string_literal("agree"),
+            "V" => "Off",
+            "AS" => "Off",
+            "AP" => agree_states,
+            "Rect" => vec![72.into(), 660.into(), 90.into(), 678.into()],
+            "P" => page_id,
+        });
+        let size_id = doc.new_object_id();
+        let mut kids = Vec::new();
+        for (on, y) in [("Small", 620), ("Large", 600)] {
+            let button_states = states(&mut doc, on);
+            kids.push(Object::Reference(doc.add_object(dictionary! {
+                "Type" => "Annot",
+                "Subtype" => "Widget",
+                "Parent" => size_id,
+                "AS" => "Off",
+                "AP" => button_states,
+                "Rect" => vec![72.into(), y.into(), 90.into(), (y + 18).into()],
+                "P" => page_id,
+            })));
+        }
+        doc.objects.insert(
+            size_id,
+            Object::Dictionary(dictionary! {
+                "FT" => "Btn",
+                "Ff" => FIELD_FLAG_RADIO,
+                "T" => Object::string_literal("size"),
+                "Kids" => kids.clone(),
+            }),
+        );
+
+        let mut annots = vec![Object::Reference(name_id), Object::Reference(agree_id)];
+        annots.extend(kids);
+        doc.objects.insert(
+            page_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Page",
+                "Parent" => pages_id,
+                "MediaBox" => vec![0.into(), 0.into(), 612.into(), 792.into()],
+                "Annots" => annots,
+            }),
+        );
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => vec![Object::Reference(page_id)],
+                "Count" => 1,
+            }),
+        );
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+            "AcroForm" => dictionary! {
+                "Fields" => vec![name_id.into(), agree_id.into(), size_id.into()],
+            },
+        });
+        doc.trailer.set("Root", catalog_id);
+        doc.save(path).unwrap();
+    }
+
     #[tokio::test]
     async fn test_pdf_text_extraction() {
         let test_pdf_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
@@ -372,7 +1694,14 @@ mod tests {
 
         println!("Testing text extraction from: {}", test_pdf_path.display());
 
-        let result = pdf_tool(test_pdf_path.to_str().unwrap(), "extract_text", &cache_dir).await;
+        let result = pdf_tool(
+            test_pdf_path.to_str().unwrap(),
+            "extract_text",
+            None,
+            None,
+            &cache_dir,
+        )
+        .await;
 
         assert!(result.is_ok(), "PDF text extraction should succeed");
         let content = result.unwrap();
@@ -398,6 +1727,8 @@ mod tests {
         let result = pdf_tool(
             test_pdf_path.to_str().unwrap(),
             "extract_images",
+            None,
+            None,
             &cache_dir,
         )
         .await;
@@ -437,7 +1768,7 @@ mod tests {
     #[tokio::test]
     async fn test_pdf_invalid_path() {
         let cache_dir = tempfile::tempdir().unwrap().into_path();
-        let result = pdf_tool("nonexistent.pdf", "extract_text", &cache_dir).await;
+        let result = pdf_tool("nonexistent.pdf", "extract_text", None, None, &cache_dir).await;
 
         assert!(result.is_err(), "Should fail with invalid path");
     }
@@ -451,10 +1782,343 @@ mod tests {
         let result = pdf_tool(
             test_pdf_path.to_str().unwrap(),
             "invalid_operation",
+            None,
+            None,
             &cache_dir,
         )
         .await;
 
         assert!(result.is_err(), "Should fail with invalid operation");
     }
+
+    #[test]
+    fn test_parse_page_ranges() {
+        assert_eq!(parse_page_ranges("1-3,5", 10).unwrap(), vec![1, 2, 3, 5]);
+        assert_eq!(parse_page_ranges("8-", 10).unwrap(), vec![8, 9, 10]);
+        assert_eq!(parse_page_ranges(" -2 , 4 ", 10).unwrap(), vec![1, 2, 4]);
+        assert!(parse_page_ranges("0", 10).is_err());
+        assert!(parse_page_ranges("9-12", 10).is_err());
+        assert!(parse_page_ranges("5-3", 10).is_err());
+        assert!(parse_page_ranges("a-b", 10).is_err());
+        assert!(parse_page_ranges(",", 10).is_err());
+
+        assert_eq!(format_page_ranges(&[1, 2, 3, 5, 7, 8]), "1-3,5,7-8");
+    }
+
+    #[tokio::test]
+    async fn test_markdown_to_pdf() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.pdf");
+        let markdown = indoc::indoc! {"
+            # Release notes
+
+            Some **bold** words and a [link](https://example.com).
+
+            - first item
+            - second item
+              1. nested step
+
+            ---
+
+            ```
+            cargo build --release
+            ```
+        "};
+
+        let result = run(&path, "markdown_to_pdf", Some(markdown), json!({}))
+            .await
+            .unwrap();
+        assert_eq!(result, format!("Wrote 1 pages to {}", path.display()));
+
+        let text = run(&path, "extract_text", None, json!({})).await.unwrap();
+        assert!(text.contains("Page 1:"));
+        for expected in [
+            "Release notes",
+            "Some bold words and a link (https://example.com).",
+            "first item",
+            "nested step",
+            "cargo build --release",
+        ] {
+            assert!(
+                text.contains(expected),
+                "missing {:?} in {}",
+                expected,
+                text
+            );
+        }
+
+        let result = run(&path, "markdown_to_pdf", None, json!({})).await;
+        assert!(result.is_err(), "markdown_to_pdf needs content");
+        let result = run(&path, "markdown_to_pdf", Some("# Other"), json!({})).await;
+        assert!(
+            result.is_err(),
+            "markdown_to_pdf does not replace an existing file"
+        );
+        run(
+            &path,
+            "markdown_to_pdf",
+            Some("# Other"),
+            json!({ "overwrite": true }),
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_extract_pages_with_ranges_and_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("long.pdf");
+        run(
+            &path,
+            "markdown_to_pdf",
+            Some(&long_markdown("Long", 120)),
+            json!({}),
+        )
+        .await
+        .unwrap();
+        let pages = page_count(&path);
+        assert!(pages > 3, "expected several pages, got {}", pages);
+
+        let result = run(&path, "extract_pages", None, json!({ "pages": "2-3" }))
+            .await
+            .unwrap();
+        let result: Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(result["page_count"], json!(pages));
+        let numbers: Vec<u64> = result["pages"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|page| page["page"].as_u64().unwrap())
+            .collect();
+        assert_eq!(numbers, vec![2, 3]);
+        assert!(result["pages"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("Paragraph"));
+        assert!(result.get("remaining_pages").is_none());
+
+        let doc = Document::load(&path).unwrap();
+        let limit = page_text(&doc, doc.get_pages()[&1]).chars().count() + 10;
+        let result = run(&path, "extract_pages", None, json!({ "max_chars": limit }))
+            .await
+            .unwrap();
+        let result: Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(result["pages"].as_array().unwrap().len(), 1);
+        assert_eq!(result["remaining_pages"], json!(format!("2-{}", pages)));
+
+        let text = run(&path, "extract_text", None, json!({ "max_chars": 50 }))
+            .await
+            .unwrap();
+        assert!(text.contains("the text of page 1 was cut short"));
+        assert!(text.contains(&format!("request them with pages \"2-{}\"", pages)));
+
+        let result = run(&path, "extract_pages", None, json!({ "pages": "0-2" })).await;
+        assert!(result.is_err(), "page 0 does not exist");
+    }
+
+    #[tokio::test]
+    async fn test_merge_and_split() {
+        let dir = tempfile::tempdir().unwrap();
+        let short = dir.path().join("short.pdf");
+        let long = dir.path().join("long.pdf");
+        run(
+            &short,
+            "markdown_to_pdf",
+            Some("# Alpha\n\nOnly page"),
+            json!({}),
+        )
+        .await
+        .unwrap();
+        run(
+            &long,
+            "markdown_to_pdf",
+            Some(&long_markdown("Beta", 60)),
+            json!({}),
+        )
+        .await
+        .unwrap();
+        let long_pages = page_count(&long);
+
+        let merged = dir.path().join("out/merged.pdf");
+        let result = run(
+            &merged,
+            "merge",
+            None,
+            json!({ "inputs": [
+                short.to_str().unwrap(),
+                { "path": long.to_str().unwrap(), "pages": "2-" },
+                short.to_str().unwrap(),
+            ]}),
+        )
+        .await
+        .unwrap();
+        assert!(result.starts_with(&format!("Merged 3 PDFs ({} pages)", long_pages + 1)));
+        assert_eq!(page_count(&merged), long_pages + 1);
+
+        let doc = Document::load(&merged).unwrap();
+        let pages = doc.get_pages();
+        assert!(page_text(&doc, pages[&1]).contains("Alpha"));
+        assert!(page_text(&doc, pages[&2]).contains("Beta"));
+        assert!(page_text(&doc, pages[&(long_pages as u32 + 1)]).contains("Alpha"));
+
+        let parts = dir.path().join("parts");
+        let result = run(
+            &merged,
+            "split",
+            None,
+            json!({ "ranges": ["1", "2-3", "2,4-"], "output_dir": parts.to_str().unwrap() }),
+        )
+        .await
+        .unwrap();
+        assert!(result.starts_with(&format!("Split {} into 3 files", merged.display())));
+        assert_eq!(page_count(&parts.join("merged_pages_1.pdf")), 1);
+        assert_eq!(page_count(&parts.join("merged_pages_2-3.pdf")), 2);
+        assert_eq!(
+            page_count(&parts.join("merged_pages_2_4-.pdf")),
+            long_pages - 1
+        );
+
+        let data =
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/computercontroller/tests/data");
+        let fixtures = dir.path().join("fixtures.pdf");
+        run(
+            &fixtures,
+            "merge",
+            None,
+            json!({ "inputs": [
+                data.join("test_image.pdf").to_str().unwrap(),
+                data.join("test.pdf").to_str().unwrap(),
+            ]}),
+        )
+        .await
+        .unwrap();
+        let text = run(&fixtures, "extract_text", None, json!({}))
+            .await
+            .unwrap();
+        assert!(text.contains("This is a test PDF"));
+        assert_eq!(
+            page_count(&fixtures),
+            page_count(&data.join("test_image.pdf")) + page_count(&data.join("test.pdf"))
+        );
+
+        let result = run(
+            &merged,
+            "split",
+            None,
+            json!({ "ranges": ["1"], "output_dir": parts.to_str().unwrap() }),
+        )
+        .await;
+        let error = result.unwrap_err();
+        assert!(error.message.contains("merged_pages_1.pdf already exists"));
+        let result = run(
+            &merged,
+            "merge",
+            None,
+            json!({ "inputs": [short.to_str().unwrap()] }),
+        )
+        .await;
+        assert!(result.is_err(), "merge does not replace an existing file");
+        assert_eq!(page_count(&merged), long_pages + 1);
+        run(
+            &merged,
+            "merge",
+            None,
+            json!({ "inputs": [short.to_str().unwrap()], "overwrite": true }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(page_count(&merged), 1);
+
+        let result = run(&merged, "split", None, json!({ "ranges": ["1-999"] })).await;
+        assert!(result.is_err(), "ranges past the end are rejected");
+        let result = run(&merged, "merge", None, json!({})).await;
+        assert!(result.is_err(), "merge needs inputs");
+    }
+
+    #[tokio::test]
+    async fn test_fill_form() {
+        let dir = tempfile::tempdir().unwrap();
+        let form = dir.path().join("form.pdf");
+        write_form(&form);
+
+        let fields = run(&form, "list_form_fields", None, json!({}))
+            .await
+            .unwrap();
+        assert!(fields.contains("Found 3 form fields"));
+        assert!(fields.contains("- name (text) = \"old\""));
+        assert!(fields.contains("- agree (checkbox) = \"Off\" [options: Yes]"));
+        assert!(fields.contains("- size (radio) [options: Small, Large]"));
+
+        let result = run(
+            &form,
+            "fill_form",
+            None,
+            json!({ "fields": { "name": "Zoë", "agree": true, "size": "large" } }),
+        )
+        .await
+        .unwrap();
+        let filled = dir.path().join("form_filled.pdf");
+        assert_eq!(
+            result,
+            format!(
+                "Filled 3 form fields of {} into {}",
+                form.display(),
+                filled.display()
+            )
+        );
+
+        let fields = run(&filled, "list_form_fields", None, json!({}))
+            .await
+            .unwrap();
+        assert!(fields.contains("- name (text) = \"Zoë\""));
+        assert!(fields.contains("- agree (checkbox) = \"Yes\""));
+        assert!(fields.contains("- size (radio) = \"Large\""));
+
+        let doc = Document::load(&filled).unwrap();
+        let appearance: Vec<String> = form_fields(&doc)
+            .iter()
+            .flat_map(|field| field.widgets.clone())
+            .map(|widget| {
+                doc.get_dictionary(widget)
+                    .and_then(|w| w.get(b"AS"))
+                    .and_then(Object::as_name)
+                    .map(|name| String::from_utf8_lossy(name).to_string())
+                    .unwrap_or_default()
+            })
+            .collect();
+        assert_eq!(appearance, vec!["", "Yes", "Off", "Large"]);
+        assert!(acroform_dict(&doc)
+            .and_then(|form| form.get(b"NeedAppearances").ok())
+            .is_some_and(|v| v.as_bool().unwrap()));
+
+        let result = run(
+            &form,
+            "fill_form",
+            None,
+            json!({ "fields": { "name": "Other" } }),
+        )
+        .await;
+        assert!(
+            result.is_err(),
+            "fill_form does not replace an existing file"
+        );
+
+        let result = run(
+            &form,
+            "fill_form",
+            None,
+            json!({ "fields": { "missing": "x" }, "overwrite": true }),
+        )
+        .await;
+        let error = result.unwrap_err();
+        assert!(error.message.contains("Unknown form fields: missing"));
+        let result = run(
+            &form,
+            "fill_form",
+            None,
+            json!({ "fields": { "size": "Medium" }, "overwrite": true }),
+        )
+        .await;
+        assert!(result.is_err(), "radio values must be one of the options");
+    }
 }
@@ -0,0 +1,1141 @@
+use anyhow::{bail, Context, Result};
+use reqwest::{header::CONTENT_TYPE, Client, Url};
+use scraper::{ElementRef, Html, Node, Selector};
+use std::{
+    collections::{HashSet, VecDeque},
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// Product token matched against the User-agent lines of robots.txt
+const ROBOTS_AGENT: &str = "goose";
+/// Longest pause between requests that a Crawl-delay can ask for
+const MAX_CRAWL_DELAY: Duration = Duration::from_secs(10);
+/// Largest page body that is read into memory
+const MAX_PAGE_BYTES: usize = 5 * 1024 * 1024;
+/// Requests a crawl may make for each page it is allowed to save, so that a site full of
+/// broken or non-HTML links cannot keep it going
+const FETCHES_PER_PAGE: usize = 3;
+/// Elements that never hold page content
+const SKIPPED_TAGS: &[&str] = &[
+    "head", "script", "style", "noscript", "template", "svg", "canvas", "iframe", "object",
+    "embed", "nav", "aside", "form", "button", "select", "input", "textarea", "dialog",
+];
+/// Roles of elements that hold navigation or page furniture rather than content
+const SKIPPED_ROLES: &[&str] = &[
+    "navigation",
+    "complementary",
+    "contentinfo",
+    "search",
+    "dialog",
+    "alertdialog",
+];
+/// Words in a class or id that mark an element as navigation, ads or other boilerplate
+const BOILERPLATE_HINTS: &[&str] = &[
+    "nav",
+    "navbar",
+    "navigation",
+    "menu",
+    "sidebar",
+    "footer",
+    "cookie",
+    "cookies",
+    "consent",
+    "banner",
+    "breadcrumb",
+    "breadcrumbs",
+    "ad",
+    "ads",
+    "advert",
+    "advertisement",
+    "share",
+    "social",
+    "popup",
+    "modal",
+    "skip",
+    "hidden",
+];
+
+/// The readable part of an HTML page
+pub struct ExtractedPage {
+    pub title: Option<String>,
+    pub markdown: String,
+    /// Every http(s) link of the page, including navigation, without fragments
+    pub links: Vec<Url>,
+}
+
+fn selector(css: &str) -> Selector {
+    Selector::parse(css).unwrap()
+}
+
+/// Convert the main content of an HTML page to Markdown. The content is taken from
+/// `<main>`, or from the only `<article>`, or else from the body with navigation, scripts,
+/// forms and other page furniture left out. Relative links are resolved against the page.
+pub fn extract_page(html: &str, url: &Url) -> ExtractedPage {
+    let document = Html::parse_document(html);
+    let base = document
+        .select(&selector("base[href]"))
+        .next()
+        .and_then(|base| url.join(base.value().attr("href")?).ok())
+        .unwrap_or_else(|| url.clone());
+
+    let title = document
+        .select(&selector("title"))
+        .next()
+        .map(|title| collapse_whitespace(&title.text().collect::<String>()))
+        .filter(|title| !title.is_empty());
+
+    let has_text = |element: &ElementRef| element.text().any(|text| !text.trim().is_empty());
+    let articles: Vec<ElementRef> = document.select(&selector("article")).collect();
+    let main = document
+        .select(&selector("main, [role=main]"))
+        .find(has_text)
+        .or_else(|| match articles.as_slice() {
+            [article] => Some(*article).filter(has_text),
+            _ => None,
+        });
+
+    let mut writer = MarkdownWriter::new(&base, main.is_some());
+    let root = main
+        .or_else(|| document.select(&selector("body")).next())
+        .unwrap_or_else(|| document.root_element());
+    writer.children(root);
+    let mut markdown = writer.finish();
+    if let Some(title) = &title {
+        if !markdown.lines().any(|line| line.starts_with("# ")) {
+            markdown = format!("# {}\n\n{}", title, markdown)
+                .trim_end()
+                .to_string();
+        }
+    }
+
+    let mut links = Vec::new();
+    let mut seen = HashSet::new();
+    for anchor in document.select(&selector("a[href]")) {
+        let Some(mut link) = anchor
+            .value()
+            .attr("href")
+            .and_then(|href| base.join(href.trim()).ok())
+        else {
+            continue;
+        };
+        if !matches!(link.scheme(), "http" | "https") {
+            continue;
+        }
+        link.set_fragment(None);
+        if seen.insert(link.to_string()) {
+            links.push(link);
+        }
+    }
+
+    ExtractedPage {
+        title,
+        markdown,
+        links,
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn is_boilerplate(element: &ElementRef, in_content: bool) -> bool {
+    let value = element.value();
+    let name = value.name();
+    if SKIPPED_TAGS.contains(&name) || (matches!(name, "header" | "footer") && !in_content) {
+        return true;
+    }
+    if value.attr("hidden").is_some()
+        || value.attr("aria-hidden") == Some("true")
+        || value
+            .attr("style")
+            .is_some_and(|style| style.replace(' ', "").contains("display:none"))
+        || value
+            .attr("role")
+            .is_some_and(|role| SKIPPED_ROLES.contains(&role) || (role == "banner" && !in_content))
+    {
+        return true;
+    }
+    if matches!(name, "html" | "body" | "main" | "article") {
+        return false;
+    }
+    value
+        .id()
+        .into_iter()
+        .chain(value.classes())
+        .flat_map(|name| name.split(|c: char| !c.is_ascii_alphanumeric()))
+        .any(|word| BOILERPLATE_HINTS.contains(&word.to_ascii_lowercase().as_str()))
+}
+
+/// Renders HTML nodes as Markdown. Nested blocks such as list items and quotes are
+/// rendered with a writer of their own and then indented or prefixed.
+struct MarkdownWriter<'a> {
+    base: &'a Url,
+    out: String,
+    /// Inside `<main>`, `<article>` or `<section>`, where headers and footers belong to
+    /// the content instead of the page
+    in_content: bool,
+}
+
+impl<'a> MarkdownWriter<'a> {
+    fn new(base: &'a Url, in_content: bool) -> Self {
+        Self {
+            base,
+            out: String::new(),
+            in_content,
+        }
+    }
+
+    fn nested(&self) -> Self {
+        Self::new(self.base, self.in_content)
+    }
+
+    fn children(&mut self, element: ElementRef) {
+        for child in element.children() {
+            match child.value() {
+                Node::Text(text) => self.text(text),
+                Node::Element(_) => {
+                    if let Some(element) = ElementRef::wrap(child) {
+                        self.element(element);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Markdown of the element's children as one block of text
+    fn render(&self, element: ElementRef) -> String {
+        let mut writer = self.nested();
+        writer.children(element);
+        writer.finish()
+    }
+
+    /// Markdown of the element's children on a single line
+    fn render_inline(&self, element: ElementRef) -> String {
+        collapse_whitespace(&self.render(element))
+    }
+
+    fn text(&mut self, text: &str) {
+        for c in text.chars() {
+            if c.is_whitespace() {
+                if self.out.ends_with(|last: char| !last.is_whitespace()) {
+                    self.out.push(' ');
+                }
+            } else {
+                self.out.push(c);
+            }
+        }
+    }
+
+    fn push(&mut self, markdown: &str) {
+        self.out.push_str(markdown);
+    }
+
+    fn block(&mut self) {
+        self.out.truncate(self.out.trim_end_matches(' ').len());
+        if self.out.is_empty() || self.out.ends_with("\n\n") {
+            return;
+        }
+        self.out.push_str(if self.out.ends_with('\n') {
+            "\n"
+        } else {
+            "\n\n"
+        });
+    }
+
+    fn resolve(&self, href: &str) -> Option<Url> {
+        let href = href.trim();
+        if href.is_empty() || href.starts_with('#') {
+            return None;
+        }
+        let url = self.base.join(href).ok()?;
+        matches!(url.scheme(), "http" | "https" | "mailto").then_some(url)
+    }
+
+    fn element(&mut self, element: ElementRef) {
+        if is_boilerplate(&element, self.in_content) {
+            return;
+        }
+        let name = element.value().name();
+        match name {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let text = self.render_inline(element);
+                if !text.is_empty() {
+                    let level = name[1..].parse().unwrap_or(1);
+                    self.block();
+                    self.push(&format!("{} {}", "#".repeat(level), text));
+                    self.block();
+                }
+            }
+            "main" | "article" | "section" => {
+                let in_content = std::mem::replace(&mut self.in_content, true);
+                self.block();
+                self.children(element);
+                self.block();
+                self.in_content = in_content;
+            }
+            "p" | "div" | "header" | "footer" | "figure" | "figcaption" | "address" | "details"
+            | "summary" | "dl" | "dt" | "dd" | "center" | "body" | "html" | "li" | "fieldset" => {
+                self.block();
+                self.children(element);
+                self.block();
+            }
+            "br" => {
+                self.out.truncate(self.out.trim_end_matches(' ').len());
+                self.push("\n");
+            }
+            "hr" => {
+                self.block();
+                self.push("---");
+                self.block();
+            }
+            "ul" | "ol" => self.list(element),
+            "blockquote" => {
+                let quote = self.render(element);
+                if !quote.is_empty() {
+                    self.block();
+                    let lines: Vec<String> = quote
+                        .lines()
+                        .map(|line| format!("> {}", line).trim_end().to_string())
+                        .collect();
+                    self.push(&lines.join("\n"));
+                    self.block();
+                }
+            }
+            "pre" => self.code_block(element),
+            "code" | "kbd" | "samp" => {
+                let code = collapse_whitespace(&element.text().collect::<String>());
+                if !code.is_empty() {
+                    let fence = if code.contains('`') { "``" } else { "`" };
+                    self.push(&format!("{fence}{code}{fence}"));
+                }
+            }
+            "strong" | "b" => self.emphasis(element, "**"),
+            "em" | "i" => self.emphasis(element, "*"),
+            "del" | "s" | "strike" => self.emphasis(element, "~~"),
+            "a" => {
+                let text = self.render_inline(element);
+                if text.is_empty() {
+                    return;
+                }
+                match element
+                    .value()
+                    .attr("href")
+                    .and_then(|href| self.resolve(href))
+                {
+                    Some(url) => self.push(&format!("[{}]({})", text, url)),
+                    None => self.push(&text),
+                }
+            }
+            "img" => {
+                let value = element.value();
+                let source = value
+                    .attr("src")
+                    .filter(|src| !src.starts_with("data:"))
+                    .or_else(|| value.attr("data-src"))
+                    .and_then(|src| self.resolve(src));
+                if let Some(url) = source {
+                    let alt = collapse_whitespace(value.attr("alt").unwrap_or_default());
+                    self.push(&format!("![{}]({})", alt, url));
+                }
+            }
+            "table" => self.table(element),
+            _ => self.children(element),
+        }
+    }
+
+    fn emphasis(&mut self, element: ElementRef, marker: &str) {
+        let text = self.render_inline(element);
+        if !text.is_empty() {
+            self.push(&format!("{marker}{text}{marker}"));
+        }
+    }
+
+    fn list(&mut self, element: ElementRef) {
+        let ordered = element.value().name() == "ol";
+        let mut number: usize = element
+            .value()
+            .attr("start")
+            .and_then(|start| start.trim().parse().ok())
+            .unwrap_or(1);
+        let mut items = Vec::new();
+        for child in element.children().filter_map(ElementRef::wrap) {
+            if child.value().name() != "li" {
+                let mut writer = self.nested();
+                writer.element(child);
+                let rendered = writer.finish();
+                if !rendered.is_empty() {
+                    items.push(rendered);
+                }
+                continue;
+            }
+            if is_boilerplate(&child, self.in_content) {
+                continue;
+            }
+            let content = self.render(child);
+            if content.is_empty() {
+                continue;
+            }
+            let marker = if ordered {
+                format!("{}. ", number)
+            } else {
+                "- ".to_string()
+            };
+            number += 1;
+            let indent = " ".repeat(marker.len());
+            let lines: Vec<String> = content
+                .lines()
+                .enumerate()
+                .map(|(index, line)| match (index, line.is_empty()) {
+                    (0, _) => format!("{}{}", marker, line),
+                    (_, true) => String::new(),
+                    _ => format!("{}{}", indent, line),
+                })
+                .collect();
+            items.push(lines.join("\n"));
+        }
+        if !items.is_empty() {
+            self.block();
+            self.push(&items.join("\n"));
+            self.block();
+        }
+    }
+
+    fn code_block(&mut self, element: ElementRef) {
+        let code: String = element.text().collect();
+        let code = code.trim_matches('\n').trim_end();
+        if code.trim().is_empty() {
+            return;
+        }
+        let language = element
+            .select(&selector("code"))
+            .next()
+            .into_iter()
+            .flat_map(|code| code.value().classes())
+            .chain(element.value().classes())
+            .find_map(|class| {
+                class
+                    .strip_prefix("language-")
+                    .or_else(|| class.strip_prefix("lang-"))
+            })
+            .unwrap_or_default();
+        let fence = if code.contains("```") { "~~~" } else { "```" };
+        self.block();
+        self.push(&format!("{fence}{language}\n{code}\n{fence}"));
+        self.block();
+    }
+
+    fn table(&mut self, element: ElementRef) {
+        let rows: Vec<Vec<String>> = element
+            .select(&selector("tr"))
+            .map(|row| {
+                row.children()
+                    .filter_map(ElementRef::wrap)
+                    .filter(|cell| matches!(cell.value().name(), "th" | "td"))
+                    .map(|cell| self.render_inline(cell).replace('|', "\\|"))
+                    .collect::<Vec<_>>()
+            })
+            .filter(|row| !row.is_empty())
+            .collect();
+        let Some(width) = rows.iter().map(Vec::len).max() else {
+            return;
+        };
+
+        let line = |cells: &[String]| {
+            let mut cells = cells.to_vec();
+            cells.resize(width, String::new());
+            format!("| {} |", cells.join(" | "))
+        };
+        let mut lines = vec![line(&rows[0]), line(&vec!["---".to_string(); width])];
+        lines.extend(rows[1..].iter().map(|row| line(row)));
+        self.block();
+        self.push(&lines.join("\n"));
+        self.block();
+    }
+
+    /// The Markdown without trailing spaces and runs of blank lines outside code blocks
+    fn finish(self) -> String {
+        let mut lines: Vec<&str> = Vec::new();
+        let mut in_code = false;
+        for line in self.out.lines() {
+            let line = line.trim_end();
+            if line.starts_with("```") || line.starts_with("~~~") {
+                in_code = !in_code;
+            }
+            if line.is_empty() && !in_code && lines.last().is_none_or(|last| last.is_empty()) {
+                continue;
+            }
+            lines.push(line);
+        }
+        lines.join("\n").trim_end().to_string()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct RobotsRule {
+    allow: bool,
+    pattern: String,
+}
+
+/// The robots.txt rules that apply to goose on one site
+#[derive(Debug, Default)]
+pub struct RobotsRules {
+    rules: Vec<RobotsRule>,
+    crawl_delay: Option<Duration>,
+}
+
+impl RobotsRules {
+    fn disallow_all() -> Self {
+        Self {
+            rules: vec![RobotsRule {
+                allow: false,
+                pattern: "/".to_string(),
+            }],
+            crawl_delay: None,
+        }
+    }
+
+    /// Parse robots.txt, keeping the groups for goose or, when there are none, for `*`
+    pub fn parse(text: &str) -> Self {
+        #[derive(Default)]
+        struct Group {
+            agents: Vec<String>,
+            rules: Vec<RobotsRule>,
+            crawl_delay: Option<Duration>,
+            has_rules: bool,
+        }
+
+        let mut groups: Vec<Group> = Vec::new();
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match key.trim().to_ascii_lowercase().as_str() {
+                "user-agent" => {
+                    if groups.last().is_none_or(|group| group.has_rules) {
+                        groups.push(Group::default());
+                    }
+                    if let Some(group) = groups.last_mut() {
+                        group.agents.push(value.to_ascii_lowercase());
+                    }
+                }
+                key @ ("allow" | "disallow") => {
+                    let Some(group) = groups.last_mut() else {
+                        continue;
+                    };
+                    group.has_rules = true;
+                    if !value.is_empty() {
+                        group.rules.push(RobotsRule {
+                            allow: key == "allow",
+                            pattern: value.to_string(),
+                        });
+                    }
+                }
+                "crawl-delay" => {
+                    if let Some(group) = groups.last_mut() {
+                        group.has_rules = true;
+                        group.crawl_delay = value
+                            .parse::<f64>()
+                            .ok()
+                            .filter(|delay| delay.is_finite() && *delay >= 0.0)
+                            .map(|delay| Duration::from_secs_f64(delay).min(MAX_CRAWL_DELAY));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let applies_to = |agent: &str| {
+            groups
+                .iter()
+                .filter(|group| group.agents.iter().any(|a| a == agent))
+                .collect::<Vec<_>>()
+        };
+        let mut matching = applies_to(ROBOTS_AGENT);
+        if matching.is_empty() {
+            matching = applies_to("*");
+        }
+        Self {
+            rules: matching
+                .iter()
+                .flat_map(|group| group.rules.iter().cloned())
+                .collect(),
+            crawl_delay: matching.iter().find_map(|group| group.crawl_delay),
+        }
+    }
+
+    /// Whether the URL may be fetched. The longest matching rule wins, and Allow wins ties.
+    pub fn allows(&self, url: &Url) -> bool {
+        let path = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        };
+        self.rules
+            .iter()
+            .filter(|rule| pattern_matches(&rule.pattern, &path))
+            .max_by_key(|rule| (rule.pattern.len(), rule.allow))
+            .is_none_or(|rule| rule.allow)
+    }
+}
+
+/// Match a robots.txt path pattern, where `*` matches any characters and a trailing `$`
+/// anchors the end of the path
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let Some(rest) = path.strip_prefix(parts[0]) else {
+        return false;
+    };
+    if parts.len() == 1 {
+        return !anchored || rest.is_empty();
+    }
+
+    let mut rest = rest;
+    for (index, part) in parts.iter().enumerate().skip(1) {
+        if anchored && index == parts.len() - 1 {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(position) => rest = &rest[position + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+/// Fetch the robots.txt of the URL's site. A missing file allows everything, and a file
+/// that cannot be fetched for any other reason disallows everything.
+pub async fn fetch_robots(client: &Client, url: &Url) -> RobotsRules {
+    let Ok(robots_url) = url.join("/robots.txt") else {
+        return RobotsRules::default();
+    };
+    match client.get(robots_url).send().await {
+        Ok(response) if response.status().is_success() => match response.text().await {
+            Ok(text) => RobotsRules::parse(&text),
+            Err(_) => RobotsRules::disallow_all(),
+        },
+        Ok(response) if response.status().is_client_error() => RobotsRules::default(),
+        _ => RobotsRules::disallow_all(),
+    }
+}
+
+/// Fetch an HTML page of at most `MAX_PAGE_BYTES`. Returns the URL it was served from,
+/// after redirects, and the HTML.
+pub async fn fetch_html(client: &Client, url: &Url) -> Result<(Url, String)> {
+    let mut response = client
+        .get(url.clone())
+        .send()
+        .await
+        .context("Failed to fetch URL")?;
+    let status = response.status();
+    if !status.is_success() {
+        bail!("HTTP request failed with status: {}", status);
+    }
+    if let Some(content_type) = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+    {
+        if !content_type.contains("html") {
+            bail!("Not an HTML page (content type {})", content_type);
+        }
+    }
+    let too_large = || anyhow::anyhow!("Page is larger than {} MB", MAX_PAGE_BYTES >> 20);
+    if response
+        .content_length()
+        .is_some_and(|length| length > MAX_PAGE_BYTES as u64)
+    {
+        return Err(too_large());
+    }
+    let final_url = response.url().clone();
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await.context("Failed to get text")? {
+        if body.len() + chunk.len() > MAX_PAGE_BYTES {
+            return Err(too_large());
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok((final_url, String::from_utf8_lossy(&body).into_owned()))
+}
+
+pub struct CrawlOptions {
+    /// How many links away from the start page to follow
+    pub max_depth: usize,
+    pub max_pages: usize,
+}
+
+pub struct CrawledPage {
+    pub url: Url,
+    pub title: Option<String>,
+    pub path: PathBuf,
+}
+
+/// What a crawl saved and what it left out
+pub struct CrawlReport {
+    pub pages: Vec<CrawledPage>,
+    pub sitemap: PathBuf,
+    /// Pages that robots.txt does not allow goose to fetch
+    pub blocked: Vec<Url>,
+    /// Pages that could not be fetched or were not HTML, with the reason
+    pub skipped: Vec<(Url, String)>,
+    /// Pages that were found but not fetched because max_pages or the request limit was
+    /// reached
+    pub not_visited: usize,
+}
+
+/// Crawl the pages of the start URL's site breadth first, following links to the same
+/// origin up to `max_depth` links away and saving at most `max_pages` pages as Markdown
+/// files in `output_dir`, along with a `sitemap.md` that lists them. At most
+/// `FETCHES_PER_PAGE` requests are made for each page it may save.
+pub async fn crawl(
+    client: &Client,
+    start: &Url,
+    options: &CrawlOptions,
+    output_dir: &Path,
+) -> Result<CrawlReport> {
+    fs::create_dir_all(output_dir).context("Failed to create snapshot directory")?;
+    let robots = fetch_robots(client, start).await;
+    let origin = start.origin();
+
+    let mut start = start.clone();
+    start.set_fragment(None);
+    let mut seen = HashSet::from([start.to_string()]);
+    let mut queue = VecDeque::from([(start.clone(), 0)]);
+    let mut file_names = HashSet::new();
+    let mut report = CrawlReport {
+        pages: Vec::new(),
+        sitemap: output_dir.join("sitemap.md"),
+        blocked: Vec::new(),
+        skipped: Vec::new(),
+        not_visited: 0,
+    };
+
+    let max_fetches = options.max_pages.saturating_mul(FETCHES_PER_PAGE);
+    let mut fetched = 0;
+    while let Some((url, depth)) = queue.pop_front() {
+        if report.pages.len() >= options.max_pages || fetched >= max_fetches {
+            report.not_visited = queue.len() + 1;
+            break;
+        }
+        if !robots.allows(&url) {
+            report.blocked.push(url);
+            continue;
+        }
+        if let Some(delay) = robots.crawl_delay.filter(|_| fetched > 0) {
+            tokio::time::sleep(delay).await;
+        }
+        fetched += 1;
+
+        let (final_url, html) = match fetch_html(client, &url).await {
+            Ok(page) => page,
+            Err(e) => {
+                report.skipped.push((url, format!("{:#}", e)));
+                continue;
+            }
+        };
+        if final_url.origin() != origin {
+            report
+                .skipped
+                .push((url, format!("Redirected to another site: {}", final_url)));
+            continue;
+        }
+        if !robots.allows(&final_url) {
+            report.blocked.push(final_url);
+            continue;
+        }
+        seen.insert(final_url.to_string());
+
+        let page = extract_page(&html, &final_url);
+        let path = output_dir.join(unique_file_name(&final_url, &mut file_names));
+        fs::write(
+            &path,
+            format!("Source: {}\n\n{}\n", final_url, page.markdown),
+        )
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+
+        if depth < options.max_depth {
+            for link in page.links {
+                if link.origin() == origin && seen.insert(link.to_string()) {
+                    queue.push_back((link, depth + 1));
+                }
+            }
+        }
+        report.pages.push(CrawledPage {
+            url: final_url,
+            title: page.title,
+            path,
+        });
+    }
+
+    let mut sitemap = format!("# Snapshot of {}\n\n", start);
+    for page in &report.pages {
+        let file_name = page.path.file_name().unwrap_or_default().to_string_lossy();
+        sitemap.push_str(&format!(
+            "- [{}]({}) {}\n",
+            page.title.as_deref().unwrap_or(page.url.as_str()),
+            file_name,
+            page.url
+        ));
+    }
+    fs::write(&report.sitemap, sitemap).context("Failed to write sitemap")?;
+    Ok(report)
+}
+
+/// A file name for the page built from its path and query, such as `docs_intro.md`
+fn unique_file_name(url: &Url, taken: &mut HashSet<String>) -> String {
+    let path = url.path().trim_matches('/');
+    let path = path
+        .strip_suffix(".html")
+        .or_else(|| path.strip_suffix(".htm"))
+        .unwrap_or(path);
+    let mut name = if path.is_empty() {
+        "index".to_string()
+    } else {
+        path.replace('/', "_")
+    };
+    if let Some(query) = url.query() {
+        name = format!("{}_{}", name, query);
+    }
+    let name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .take(100)
+        .collect();
+
+    let mut candidate = name.clone();
+    let mut counter = 1;
+    while !taken.insert(candidate.clone()) || candidate == "sitemap" {
+        counter += 1;
+        candidate = format!("{}_{}", name, counter);
+    }
+    format!("{}.md", candidate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    const ARTICLE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+  <title>Release notes</title>
+  <style>body { color: red; }</style>
+  <script>console.log("tracking")</script>
+</head>
+<body>
+  <header class="site-header"><a href="/">Home</a></header>
+  <nav><a href="/docs">Docs</a> <a href="/blog">Blog</a></nav>
+  <div class="cookie-banner">We use cookies</div>
+  <main>
+    <h1>Release   notes</h1>
+    <p>Version <strong>1.2</strong> adds <a href="features.html#new">new features</a>
+       and <em>fixes</em> bugs.</p>
+    <ul>
+      <li>Faster startup</li>
+      <li>Plugins
+        <ol start="3"><li>Loading</li><li>Unloading</li></ol>
+      </li>
+    </ul>
+    <blockquote><p>Upgrade soon.</p></blockquote>
+    <pre><code class="language-rust">fn main() {
+
+    println!("hi");
+}</code></pre>
+    <table>
+      <tr><th>Name</th><th>Value</th></tr>
+      <tr><td>a|b</td><td>1</td></tr>
+    </table>
+    <img src="/img/chart.png" alt="Chart">
+    <aside>Related posts</aside>
+  </main>
+  <footer>Copyright</footer>
+</body>
+</html>"#;
+
+    #[test]
+    fn test_extract_page() {
+        let url = Url::parse("https://example.com/news/release.html").unwrap();
+        let page = extract_page(ARTICLE, &url);
+
+        assert_eq!(page.title.as_deref(), Some("Release notes"));
+        assert_eq!(
+            page.markdown,
+            indoc::indoc! {r#"
+                # Release notes
+
+                Version **1.2** adds [new features](https://example.com/news/features.html#new) and *fixes* bugs.
+
+                - Faster startup
+                - Plugins
+
+                  3. Loading
+                  4. Unloading
+
+                > Upgrade soon.
+
+                ```rust
+                fn main() {
+
+                    println!("hi");
+                }
+                ```
+
+                | Name | Value |
+                | --- | --- |
+                | a\|b | 1 |
+
+                ![Chart](https://example.com/img/chart.png)"#}
+        );
+
+        let links: Vec<&str> = page.links.iter().map(Url::as_str).collect();
+        assert_eq!(
+            links,
+            vec![
+                "https://example.com/",
+                "https://example.com/docs",
+                "https://example.com/blog",
+                "https://example.com/news/features.html",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_page_without_main() {
+        let html = r#"<html><head><title>Plain</title><base href="https://cdn.example.com/"></head>
+            <body>
+              <div id="sidebar">Archive</div>
+              <header>Site name</header>
+              <div class="content"><p>First<br>Second</p><p hidden>Secret</p></div>
+              <p><a href="javascript:void(0)">Click</a> or <a href="page">visit</a></p>
+            </body></html>"#;
+        let page = extract_page(html, &Url::parse("https://example.com/a/b").unwrap());
+        assert_eq!(
+            page.markdown,
+            "# Plain\n\nFirst\nSecond\n\nClick or [visit](https://cdn.example.com/page)"
+        );
+    }
+
+    #[test]
+    fn test_robots_rules() {
+        let robots = RobotsRules::parse(indoc::indoc! {"
+            User-agent: *
+            Disallow: /
+
+            User-agent: Goose
+            User-agent: other
+            Disallow: /private
+            Allow: /private/public
+            Disallow: /*.pdf$
+            Disallow: /search?
+            Crawl-delay: 0.5
+        "});
+        let allows = |path: &str| {
+            robots.allows(
+                &Url::parse("https://example.com")
+                    .unwrap()
+                    .join(path)
+                    .unwrap(),
+            )
+        };
+
+        assert!(allows("/"));
+        assert!(allows("/docs/intro"));
+        assert!(!allows("/private"));
+        assert!(!allows("/private/notes"));
+        assert!(allows("/private/public/page"));
+        assert!(!allows("/files/report.pdf"));
+        assert!(allows("/files/report.pdf.html"));
+        assert!(!allows("/search?q=goose"));
+        assert!(allows("/search"));
+        assert_eq!(robots.crawl_delay, Some(Duration::from_millis(500)));
+
+        let robots = RobotsRules::parse("User-agent: *\nDisallow: /tmp/\nDisallow:\n");
+        assert!(!robots.allows(&Url::parse("https://example.com/tmp/x").unwrap()));
+        assert!(robots.allows(&Url::parse("https://example.com/tmp").unwrap()));
+        assert!(RobotsRules::parse("").allows(&Url::parse("https://example.com/").unwrap()));
+    }
+
+    fn html_page(body: &str) -> ResponseTemplate {
+        ResponseTemplate::new(200).set_body_raw(
+            format!("<html><head><title>{body}</title></head><body>{body}</body></html>"),
+            "text/html",
+        )
+    }
+
+    async fn mount(server: &MockServer, route: &str, response: ResponseTemplate) {
+        Mock::given(method("GET"))
+            .and(path(route))
+            .respond_with(response)
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_crawl_site() {
+        let server = MockServer::start().await;
+        mount(
+            &server,
+            "/robots.txt",
+            ResponseTemplate::new(200).set_body_string("User-agent: *\nDisallow: /private\n"),
+        )
+        .await;
+        mount(
+            &server,
+            "/",
+            html_page(
+                r#"<a href="/docs/">Docs</a> <a href="/private/x">Private</a>
+                   <a href="/missing">Missing</a> <a href="/data.json">Data</a>
+                   <a href="https://elsewhere.example/">Elsewhere</a> <a href="/#top">Top</a>"#,
+            ),
+        )
+        .await;
+        mount(
+            &server,
+            "/docs/",
+            html_page(r#"<a href="/docs/intro?lang=en">Intro</a> <a href="/">Home</a>"#),
+        )
+        .await;
+        mount(
+            &server,
+            "/docs/intro",
+            html_page(r#"<a href="/docs/deep">Too deep</a>"#),
+        )
+        .await;
+        mount(
+            &server,
+            "/data.json",
+            ResponseTemplate::new(200).set_body_raw("{}", "application/json"),
+        )
+        .await;
+
+        let client = Client::new();
+        let dir = tempfile::tempdir().unwrap();
+        let start = Url::parse(&server.uri()).unwrap();
+        let options = CrawlOptions {
+            max_depth: 2,
+            max_pages: 10,
+        };
+        let report = crawl(&client, &start, &options, dir.path()).await.unwrap();
+
+        let saved: Vec<&str> = report.pages.iter().map(|p| p.url.path()).collect();
+        assert_eq!(saved, vec!["/", "/docs/", "/docs/intro"]);
+        let blocked: Vec<&str> = report.blocked.iter().map(Url::path).collect();
+        assert_eq!(blocked, vec!["/private/x"]);
+        let skipped: Vec<&str> = report.skipped.iter().map(|(url, _)| url.path()).collect();
+        assert_eq!(skipped, vec!["/missing", "/data.json"]);
+        assert!(report.skipped[0].1.contains("404"));
+        assert_eq!(report.not_visited, 0);
+
+        let intro = fs::read_to_string(dir.path().join("docs_intro_lang-en.md")).unwrap();
+        assert!(intro.starts_with(&format!("Source: {}/docs/intro?lang=en", server.uri())));
+        assert!(intro.contains("[Too deep]("));
+        assert!(dir.path().join("index.md").exists());
+        assert!(dir.path().join("docs.md").exists());
+        let sitemap = fs::read_to_string(&report.sitemap).unwrap();
+        assert_eq!(sitemap.lines().filter(|l| l.starts_with("- ")).count(), 3);
+
+        let options = CrawlOptions {
+            max_depth: 2,
+            max_pages: 1,
+        };
+        let report = crawl(&client, &start, &options, dir.path()).await.unwrap();
+        assert_eq!(report.pages.len(), 1);
+        assert_eq!(report.not_visited, 4);
+    }
+
+    #[tokio::test]
+    async fn test_crawl_respects_robots_disallow_all() {
+        let server = MockServer::start().await;
+        mount(
+            &server,
+            "/robots.txt",
+            ResponseTemplate::new(200).set_body_string("User-agent: goose\nDisallow: /\n"),
+        )
+        .await;
+        mount(&server, "/", html_page("Home")).await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let start = Url::parse(&server.uri()).unwrap();
+        let options = CrawlOptions {
+            max_depth: 1,
+            max_pages: 5,
+        };
+        let report = crawl(&Client::new(), &start, &options, dir.path())
+            .await
+            .unwrap();
+        assert!(report.pages.is_empty());
+        assert_eq!(report.blocked.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_crawl_limits() {
+        let server = MockServer::start().await;
+        mount(
+            &server,
+            "/robots.txt",
+            ResponseTemplate::new(200).set_body_string("User-agent: *\nDisallow: /private\n"),
+        )
+        .await;
+        let links: String = (1..=10)
+            .map(|i| format!(r#"<a href="/missing{i}">Missing</a> "#))
+            .collect();
+        mount(
+            &server,
+            "/",
+            html_page(&format!(r#"<a href="/old">Old</a> {links}"#)),
+        )
+        .await;
+        mount(
+            &server,
+            "/old",
+            ResponseTemplate::new(301).insert_header("Location", "/private/new"),
+        )
+        .await;
+        mount(&server, "/private/new", html_page("Private")).await;
+        mount(&server, "/huge", html_page(&"x".repeat(MAX_PAGE_BYTES + 1))).await;
+
+        let client = Client::new();
+        let dir = tempfile::tempdir().unwrap();
+        let start = Url::parse(&server.uri()).unwrap();
+        let options = CrawlOptions {
+            max_depth: 1,
+            max_pages: 2,
+        };
+        let report = crawl(&client, &start, &options, dir.path()).await.unwrap();
+
+        assert_eq!(report.pages.len(), 1);
+        let blocked: Vec<&str> = report.blocked.iter().map(Url::path).collect();
+        assert_eq!(
+            blocked,
+            vec!["/private/new"],
+            "robots.txt applies after redirects"
+        );
+        assert_eq!(report.skipped.len(), 4);
+        assert_eq!(
+            report.not_visited, 6,
+            "failed requests count toward the limit"
+        );
+
+        let error = fetch_html(&client, &start.join("/huge").unwrap())
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("larger than 5 MB"));
+    }
+}
@@ -1,7 +1,10 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::path::Path;
-use umya_spreadsheet::{Spreadsheet, Worksheet};
+use umya_spreadsheet::{Cell, Spreadsheet, Worksheet};
+
+use super::csv_tool::detect_delimiter;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct WorksheetInfo {
@@ -38,6 +41,25 @@ impl XlsxTool {
         Ok(Self { workbook })
     }
 
+    /// Open the workbook, or start an empty one when the file does not exist yet
+    pub fn open_or_create<P: AsRef<Path>>(path: P) -> Result<Self> {
+        if path.as_ref().exists() {
+            Self::new(path)
+        } else {
+            Ok(Self {
+                workbook: umya_spreadsheet::new_file_empty_worksheet(),
+            })
+        }
+    }
+
+    /// The name of the given worksheet, or of the first one when no name is given
+    pub fn worksheet_name(&self, name: Option<&str>) -> Result<String> {
+        match name {
+            Some(name) => Ok(self.get_worksheet_by_name(name)?.get_name().to_string()),
+            None => Ok(self.get_worksheet_by_index(0)?.get_name().to_string()),
+        }
+    }
+
     pub fn list_worksheets(&self) -> Result<Vec<WorksheetInfo>> {
         let mut worksheets = Vec::new();
         for (index, worksheet) in self.workbook.get_sheet_collection().iter().enumerate() {
@@ -151,6 +173,172 @@ impl XlsxTool {
         Ok(())
     }
 
+    /// Write rows of values starting at the given cell. Null values leave their cell as it is.
+    /// Returns the range that was written.
+    pub fn write_range(
+        &mut self,
+        worksheet_name: &str,
+        start_cell: &str,
+        values: &[Vec<Value>],
+    ) -> Result<String> {
+        self.write_cells(worksheet_name, start_cell, values, set_cell_value)
+    }
+
+    fn write_cells<T>(
+        &mut self,
+        worksheet_name: &str,
+        start_cell: &str,
+        values: &[Vec<T>],
+        set_value: impl Fn(&mut Cell, &T),
+    ) -> Result<String> {
+        let (start_col, start_row) = parse_cell_reference(start_cell)?;
+        let worksheet = self
+            .workbook
+            .get_sheet_by_name_mut(worksheet_name)
+            .context("Worksheet not found")?;
+
+        let mut end_col = start_col;
+        for (row_offset, row) in values.iter().enumerate() {
+            for (col_offset, value) in row.iter().enumerate() {
+                let coordinate = (start_col + col_offset as u32, start_row + row_offset as u32);
+                set_value(worksheet.get_cell_mut(coordinate), value);
+                end_col = end_col.max(coordinate.0);
+            }
+        }
+
+        let end_row = start_row + values.len().saturating_sub(1) as u32;
+        Ok(format!(
+            "{}{}:{}{}",
+            column_number_to_letter(start_col),
+            start_row,
+            column_number_to_letter(end_col),
+            end_row
+        ))
+    }
+
+    /// Write rows below the last row that has a value. Returns the range that was written.
+    pub fn append_rows(&mut self, worksheet_name: &str, rows: &[Vec<Value>]) -> Result<String> {
+        let worksheet = self.get_worksheet_by_name(worksheet_name)?;
+        let last_row = worksheet
+            .get_cell_collection()
+            .iter()
+            .filter(|cell| !cell.get_value().is_empty() || !cell.get_formula().is_empty())
+            .map(|cell| *cell.get_coordinate().get_row_num())
+            .max()
+            .unwrap_or(0);
+        self.write_range(worksheet_name, &format!("A{}", last_row + 1), rows)
+    }
+
+    pub fn create_worksheet(&mut self, name: &str) -> Result<()> {
+        self.workbook
+            .new_sheet(name)
+            .map_err(|e| anyhow::anyhow!("Failed to create worksheet '{}': {}", name, e))?;
+        Ok(())
+    }
+
+    /// Write the used area of the worksheet, or the given range, to a CSV file.
+    /// An existing file is only replaced with `overwrite`. Returns how many rows were written.
+    pub fn export_csv<P: AsRef<Path>>(
+        &self,
+        worksheet: &Worksheet,
+        range: Option<&str>,
+        csv_path: P,
+        overwrite: bool,
+    ) -> Result<usize> {
+        let csv_path = csv_path.as_ref();
+        if csv_path.exists() && !overwrite {
+            anyhow::bail!(
+                "{} already exists. Set overwrite to true to replace it",
+                csv_path.display()
+            );
+        }
+        let (start_col, start_row, end_col, end_row) = match range {
+            Some(range) => parse_range(range)?,
+            None => {
+                let (end_col, end_row) = worksheet.get_highest_column_and_row();
+                (1, 1, end_col, end_row)
+            }
+        };
+
+        let mut writer = csv::Writer::from_path(csv_path).context("Failed to create CSV file")?;
+        for row in start_row..=end_row {
+            let record: Vec<String> = (start_col..=end_col)
+                .map(|col| {
+                    worksheet
+                        .get_cell((col, row))
+                        .map(|cell| cell.get_value().into_owned())
+                        .unwrap_or_default()
+                })
+                .collect();
+            writer.write_record(&record)?;
+        }
+        writer.flush().context("Failed to write CSV file")?;
+        Ok((end_row + 1).saturating_sub(start_row) as usize)
+    }
+
+    /// Read a CSV file into the worksheet, creating the worksheet when it does not exist.
+    /// Without a start cell the worksheet is cleared first, which needs `overwrite` when it has
+    /// data, and the data starts at A1.
+    /// Fields are stored as text, never as formulas. Returns the range that was written.
+    pub fn import_csv<P: AsRef<Path>>(
+        &mut self,
+        worksheet_name: &str,
+        csv_path: P,
+        start_cell: Option<&str>,
+        overwrite: bool,
+    ) -> Result<String> {
+        let csv_path = csv_path.as_ref();
+        if start_cell.is_none() && !overwrite {
+            if let Some(worksheet) = self.workbook.get_sheet_by_name(worksheet_name) {
+                if !worksheet.get_cell_collection().is_empty() {
+                    anyhow::bail!(
+                        "Worksheet '{}' already has data. Set overwrite to true to replace it, or give a start_cell",
+                        worksheet_name
+                    );
+                }
+            }
+        }
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .delimiter(detect_delimiter(csv_path)?)
+            .from_path(csv_path)
+            .context("Failed to open CSV file")?;
+        let rows = reader
+            .records()
+            .map(|record| record.map(|record| record.iter().map(str::to_string).collect()))
+            .collect::<Result<Vec<Vec<String>>, _>>()
+            .context("Failed to read CSV file")?;
+
+        if self.workbook.get_sheet_by_name(worksheet_name).is_none() {
+            self.create_worksheet(worksheet_name)?;
+        } else if start_cell.is_none() {
+            let worksheet = self
+                .workbook
+                .get_sheet_by_name_mut(worksheet_name)
+                .context("Worksheet not found")?;
+            let coordinates: Vec<(u32, u32)> = worksheet
+                .get_cell_collection()
+                .iter()
+                .map(|cell| {
+                    let coordinate = cell.get_coordinate();
+                    (*coordinate.get_col_num(), *coordinate.get_row_num())
+                })
+                .collect();
+            for coordinate in coordinates {
+                worksheet.remove_cell(coordinate);
+            }
+        }
+        self.write_cells(
+            worksheet_name,
+            start_cell.unwrap_or("A1"),
+            &rows,
+            |cell, field| {
+                cell.set_value_string(field.clone());
+            },
+        )
+    }
+
     pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         umya_spreadsheet::writer::xlsx::write(&self.workbook, path)
             .context("Failed to save Excel file")?;
@@ -205,6 +393,54 @@ impl XlsxTool {
     }
 }
 
+/// Store a JSON value in a cell. Text that looks like a number or boolean is stored as one,
+/// except numbers with leading zeros such as ids and zip codes, and text starting with '='
+/// becomes a formula.
+fn set_cell_value(cell: &mut Cell, value: &Value) {
+    match value {
+        Value::Null => {}
+        Value::Bool(value) => {
+            cell.set_value_bool(*value);
+        }
+        Value::Number(number) => {
+            cell.set_value_number(number.as_f64().unwrap_or_default());
+        }
+        Value::String(text) => {
+            if let Some(formula) = text.strip_prefix('=').filter(|f| !f.is_empty()) {
+                cell.set_formula(formula);
+            } else if text.eq_ignore_ascii_case("true") || text.eq_ignore_ascii_case("false") {
+                cell.set_value_bool(text.eq_ignore_ascii_case("true"));
+            } else if let Some(number) = plain_number(text) {
+                cell.set_value_number(number);
+            } else {
+                cell.set_value_string(text.clone());
+            }
+        }
+        other => {
+            cell.set_value_string(other.to_string());
+        }
+    }
+}
+
+fn plain_number(text: &str) -> Option<f64> {
+    let digits = text.strip_prefix('-').unwrap_or(text);
+    let leading_zero = digits.len() > 1 && digits.starts_with('0') && !digits.starts_with("0.");
+    if leading_zero || !digits.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    text.parse::<f64>().ok().filter(|number| number.is_finite())
+}
+
+fn column_number_to_letter(mut column: u32) -> String {
+    let mut letters = Vec::new();
+    while column > 0 {
+        let remainder = (column - 1) % 26;
+        letters.push((b'A' + remainder as u8) as char);
+        column = (column - 1) / 26;
+    }
+    letters.iter().rev().collect()
+}
+
 fn parse_range(range: &str) -> Result<(u32, u32, u32, u32)> {
     // Handle ranges like "A1:B10"
     let parts: Vec<&str> = range.split(':').collect();
@@ -258,6 +494,7 @@ fn column_letter_to_number(column: &str) -> Result<u32> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serde_json::json;
     use std::path::PathBuf;
 
     fn get_test_file() -> PathBuf {
@@ -328,4 +565,117 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn test_write_and_append_rows() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("report.xlsx");
+
+        let mut xlsx = XlsxTool::open_or_create(&path)?;
+        xlsx.create_worksheet("Report")?;
+        let range = xlsx.write_range(
+            "Report",
+            "B2",
+            &[
+                vec![json!("Region"), json!("Units"), json!("Zip")],
+                vec![json!("East"), json!(12), json!("02134")],
+            ],
+        )?;
+        assert_eq!(range, "B2:D3");
+        let range = xlsx.append_rows(
+            "Report",
+            &[vec![json!(null), json!("West"), json!("7.5"), json!(true)]],
+        )?;
+        assert_eq!(range, "A4:D4");
+        xlsx.write_range("Report", "E3", &[vec![json!("=C3*2")]])?;
+        xlsx.save(&path)?;
+
+        let xlsx = XlsxTool::new(&path)?;
+        let worksheet = xlsx.get_worksheet_by_name("Report")?;
+        let cell = |row, col| worksheet.get_cell((col, row)).unwrap();
+        assert_eq!(cell(3, 2).get_value(), "East");
+        assert_eq!(cell(3, 3).get_data_type(), "n");
+        assert_eq!(cell(3, 4).get_value(), "02134");
+        assert_eq!(cell(3, 4).get_data_type(), "s");
+        assert_eq!(cell(4, 2).get_value(), "West");
+        assert_eq!(cell(4, 3).get_value(), "7.5");
+        assert_eq!(cell(4, 4).get_data_type(), "b");
+        assert!(worksheet.get_cell((1, 4)).is_none());
+        assert_eq!(cell(3, 5).get_formula(), "C3*2");
+
+        let mut xlsx = xlsx;
+        assert!(xlsx.create_worksheet("Report").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_csv_export_and_import() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let csv_path = dir.path().join("sample.csv");
+        let xlsx = XlsxTool::new(get_test_file())?;
+        let worksheet = xlsx.get_worksheet_by_index(0)?;
+        let rows = xlsx.export_csv(worksheet, Some("A1:C3"), &csv_path, false)?;
+        assert_eq!(rows, 3);
+        assert!(xlsx.export_csv(worksheet, None, &csv_path, false).is_err());
+        let exported = std::fs::read_to_string(&csv_path)?;
+        assert!(exported.starts_with("Segment,Country,"));
+        assert_eq!(exported.lines().count(), 3);
+
+        let path = dir.path().join("imported.xlsx");
+        let mut xlsx = XlsxTool::open_or_create(&path)?;
+        xlsx.create_worksheet("Data")?;
+        xlsx.write_range("Data", "A1", &vec![vec![json!("stale"); 5]; 5])?;
+        assert!(
+            xlsx.import_csv("Data", &csv_path, None, false).is_err(),
+            "a sheet with data is not cleared without overwrite"
+        );
+        let range = xlsx.import_csv("Data", &csv_path, None, true)?;
+        assert_eq!(range, "A1:C3");
+        let range = xlsx.import_csv("Copy", &csv_path, Some("B2"), false)?;
+        assert_eq!(range, "B2:D4");
+        xlsx.save(&path)?;
+
+        let xlsx = XlsxTool::new(&path)?;
+        let worksheet = xlsx.get_worksheet_by_name("Data")?;
+        assert_eq!(xlsx.get_cell_value(worksheet, 1, 1)?.value, "Segment");
+        assert!(
+            worksheet.get_cell((5, 5)).is_none(),
+            "old cells are cleared"
+        );
+        let copy = xlsx.get_worksheet_by_name("Copy")?;
+        assert_eq!(copy.get_cell((3, 2)).unwrap().get_value(), "Country");
+        Ok(())
+    }
+
+    #[test]
+    fn test_csv_import_stores_literal_text() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let csv_path = dir.path().join("untrusted.csv");
+        std::fs::write(&csv_path, "=HYPERLINK(\"http://x\"),TRUE,1e5,007\n")?;
+
+        let path = dir.path().join("imported.xlsx");
+        let mut xlsx = XlsxTool::open_or_create(&path)?;
+        xlsx.import_csv("Data", &csv_path, None, false)?;
+        xlsx.write_range("Data", "A2", &[vec![json!("=1+1"), json!("TRUE")]])?;
+        xlsx.save(&path)?;
+
+        let xlsx = XlsxTool::new(&path)?;
+        let worksheet = xlsx.get_worksheet_by_name("Data")?;
+        for (col, expected) in ["=HYPERLINK(\"http://x\")", "TRUE", "1e5", "007"]
+            .into_iter()
+            .enumerate()
+        {
+            let cell = worksheet.get_cell((col as u32 + 1, 1)).unwrap();
+            assert!(
+                cell.get_formula().is_empty(),
+                "column {} is no formula",
+                col
+            );
+            assert_eq!(cell.get_value(), expected);
+            assert_eq!(cell.get_data_type(), "s");
+        }
+        assert_eq!(worksheet.get_cell((1, 2)).unwrap().get_formula(), "1+1");
+        assert_eq!(worksheet.get_cell((2, 2)).unwrap().get_data_type(), "b");
+        Ok(())
+    }
 }
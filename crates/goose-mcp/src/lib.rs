@@ -13,6 +13,9 @@ mod memory;
 mod tutorial;
 
 pub use computercontroller::ComputerControllerRouter;
-pub use developer::DeveloperRouter;
-pub use memory::MemoryRouter;
+pub use developer::{load_ignore_patterns, DeveloperRouter};
+pub use memory::{
+    global_memory_dir, local_memory_dir, parse_duration, MemoryEmbedder, MemoryEntry, MemoryRouter,
+    MemoryStore,
+};
 pub use tutorial::TutorialRouter;
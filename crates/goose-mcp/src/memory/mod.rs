@@ -1,6 +1,9 @@
+mod retrieval;
+mod store;
+
 use async_trait::async_trait;
 use etcetera::{choose_app_strategy, AppStrategy};
-use indoc::formatdoc;
+use indoc::{formatdoc, indoc};
 use mcp_core::{
     handler::{PromptError, ResourceError},
     protocol::ServerCapabilities,
@@ -12,17 +15,55 @@ use rmcp::model::{
     Content, ErrorCode, ErrorData, JsonRpcMessage, Prompt, Resource, Tool, ToolAnnotations,
 };
 use rmcp::object;
+use serde::Deserialize;
 use serde_json::Value;
 use std::{
     collections::HashMap,
-    fs,
     future::Future,
-    io::{self, Read, Write},
+    io,
     path::PathBuf,
     pin::Pin,
+    sync::{Arc, Mutex},
 };
 use tokio::sync::mpsc;
 
+pub use retrieval::MemoryEmbedder;
+use retrieval::{
+    bm25_rank, cluster_similar, cosine_similarity, embedding_rank, jaccard_similarity,
+    take_within_budget, DEFAULT_RETRIEVE_LIMIT,
+};
+pub use store::{parse_duration, MemoryEntry, MemoryStore};
+
+/// How many memories are put into the instructions at most, unless GOOSE_MEMORY_INJECT_LIMIT is set
+const DEFAULT_INJECT_LIMIT: usize = 20;
+/// How many characters of memories are put into the instructions at most, unless
+/// GOOSE_MEMORY_INJECT_CHARS is set
+const DEFAULT_INJECT_CHARS: usize = 4000;
+/// Similarity from which memories count as near-duplicates, by shared words or by embeddings
+const KEYWORD_DUPLICATE_THRESHOLD: f32 = 0.5;
+const EMBEDDING_DUPLICATE_THRESHOLD: f32 = 0.9;
+
+/// Directory of the memories of the current project
+pub fn local_memory_dir() -> PathBuf {
+    // Check for .goose/memory in current directory
+    std::env::var("GOOSE_WORKING_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| std::env::current_dir().unwrap())
+        .join(".goose")
+        .join("memory")
+}
+
+/// Directory of the memories shared by all projects
+pub fn global_memory_dir() -> PathBuf {
+    // choose_app_strategy().config_dir()
+    // - macOS/Linux: ~/.config/goose/memory/
+    // - Windows:     ~\AppData\Roaming\Block\goose\config\memory
+    // if it fails, fall back to `.config/goose/memory` (relative to the current dir)
+    choose_app_strategy(crate::APP_STRATEGY.clone())
+        .map(|strategy| strategy.in_config_dir("memory"))
+        .unwrap_or_else(|_| PathBuf::from(".config/goose/memory"))
+}
+
 // MemoryRouter implementation
 #[derive(Clone)]
 pub struct MemoryRouter {
@@ -30,6 +71,9 @@ pub struct MemoryRouter {
     instructions: String,
     global_memory_dir: PathBuf,
     local_memory_dir: PathBuf,
+    embedder: Option<Arc<dyn MemoryEmbedder>>,
+    // Embeddings of the memories ranked so far, by their search text
+    embeddings: Arc<Mutex<HashMap<String, Vec<f32>>>>,
 }
 
 impl Default for MemoryRouter {
@@ -49,6 +93,7 @@ impl MemoryRouter {
                     "category": {"type": "string"},
                     "data": {"type": "string"},
                     "tags": {"type": "array", "items": {"type": "string"}},
+                    "ttl": {"type": "string", "description": "How long the memory stays useful, like 12h, 30d or 2w. Kept until removed when not set"},
                     "is_global": {"type": "boolean"}
                 },
                 "required": ["category", "data", "is_global"]
@@ -64,11 +109,13 @@ impl MemoryRouter {
 
         let retrieve_memories = Tool::new(
             "retrieve_memories",
-            "Retrieves all memories from a specified category",
+            "Retrieves memories from a specified category, or with a query only the memories most relevant to it",
             object!({
                 "type": "object",
                 "properties": {
-                    "category": {"type": "string"},
+                    "category": {"type": "string", "description": "The category to retrieve, or \"*\" for all categories"},
+                    "query": {"type": "string", "description": "What to look for. The most relevant memories are returned first"},
+                    "limit": {"type": "integer", "description": "How many memories to return for a query (default 10)"},
                     "is_global": {"type": "boolean"}
                 },
                 "required": ["category", "is_global"]
@@ -104,15 +151,16 @@ impl MemoryRouter {
 
         let remove_specific_memory = Tool::new(
             "remove_specific_memory",
-            "Removes a specific memory within a specified category",
+            "Removes a specific memory by its id, or the memories of a category containing the given content",
             object!({
                 "type": "object",
                 "properties": {
                     "category": {"type": "string"},
+                    "id": {"type": "string", "description": "The id shown in brackets when memories are retrieved"},
                     "memory_content": {"type": "string"},
                     "is_global": {"type": "boolean"}
                 },
-                "required": ["category", "memory_content", "is_global"]
+                "required": ["category", "is_global"]
             }),
         )
         .annotate(ToolAnnotations {
@@ -123,6 +171,45 @@ impl MemoryRouter {
             open_world_hint: Some(false),
         });
 
+        let consolidate_memories = Tool::new(
+            "consolidate_memories",
+            indoc! {r#"
+                Finds groups of near-duplicate memories and merges them.
+                Call it without merges to list the groups. Show them to the user and only call it
+                with merges once the user confirmed how each group should be merged. Every merge
+                replaces the memories with the given ids by one memory with the given content.
+            "#},
+            object!({
+                "type": "object",
+                "properties": {
+                    "is_global": {"type": "boolean"},
+                    "category": {"type": "string", "description": "Only look at this category"},
+                    "threshold": {"type": "number", "description": "How similar memories have to be to be grouped, from 0 to 1"},
+                    "merges": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "ids": {"type": "array", "items": {"type": "string"}},
+                                "content": {"type": "string"},
+                                "category": {"type": "string"},
+                                "tags": {"type": "array", "items": {"type": "string"}}
+                            },
+                            "required": ["ids", "content"]
+                        }
+                    }
+                },
+                "required": ["is_global"]
+            }),
+        )
+        .annotate(ToolAnnotations {
+            title: Some("Consolidate Memories".to_string()),
+            read_only_hint: Some(false),
+            destructive_hint: Some(true),
+            idempotent_hint: Some(false),
+            open_world_hint: Some(false),
+        });
+
         let instructions = formatdoc! {r#"
              This extension allows storage and retrieval of categorized information with tagging support. It's designed to help
              manage important information across sessions in a systematic and organized manner.
@@ -155,6 +242,8 @@ impl MemoryRouter {
                   - Global storage (~/.config/goose/memory) for user-wide data.
                 - Use the remember_memory tool to store the information.
                   - `remember_memory(category, data, tags, is_global)`
+                - For information that only matters for a while, like a release freeze or a temporary workaround, set a ttl.
+                  - `remember_memory(category="development", data="main is frozen until the release", ttl="7d", is_global=False)`
              Keywords that trigger memory tools:
              - "remember"
              - "forget"
@@ -196,6 +285,10 @@ impl MemoryRouter {
                - Use: `retrieve_memories(category="development", is_global=False)`
                - Note: If you want to retrieve all local memories, use `retrieve_memories(category="*", is_global=False)`
                - Note: If you want to retrieve all global memories, use `retrieve_memories(category="*", is_global=True)`
+             - **Search by Query**:
+               - Returns the memories most relevant to a question, best first.
+               - Use: `retrieve_memories(category="*", query="code formatting", is_global=False)`
+               - Note: Only part of the saved memories is listed below, search when the user refers to something you do not see.
              - **Filter by Tags**:
                - Enables targeted retrieval based on specific tags.
                - Use: Provide tag filters to refine search.
@@ -205,6 +298,13 @@ impl MemoryRouter {
               - Use: `remove_memory_category(category="development", is_global=False)`
               - Note: If you want to remove all local memories, use `remove_memory_category(category="*", is_global=False)`
               - Note: If you want to remove all global memories, use `remove_memory_category(category="*", is_global=True)`
+            - **Remove a Single Memory**:
+              - Every memory has an id, shown in brackets when it is listed.
+              - Use: `remove_specific_memory(category="development", id="3f2a9c1e", is_global=False)`
+            To clean up duplicate memories, use the following protocol:
+            1. Call `consolidate_memories(is_global=False)` to find groups of near-duplicates.
+            2. Show the groups to the user and propose one merged text for each.
+            3. Only after the user agreed, call `consolidate_memories(is_global=False, merges=[{{"ids": ["3f2a9c1e", "b81d0e44"], "content": "merged text"}}])`.
             The Protocol is:
              1. Confirm what kind of information the user seeks by category or keyword.
              2. Suggest categories or relevant tags based on the user's request.
@@ -226,76 +326,82 @@ impl MemoryRouter {
              - Acknowledge the user about what is stored and where, for transparency and ease of future retrieval.
             "#};
 
-        // Check for .goose/memory in current directory
-        let local_memory_dir = std::env::var("GOOSE_WORKING_DIR")
-            .map(PathBuf::from)
-            .unwrap_or_else(|_| std::env::current_dir().unwrap())
-            .join(".goose")
-            .join("memory");
-
-        // choose_app_strategy().config_dir()
-        // - macOS/Linux: ~/.config/goose/memory/
-        // - Windows:     ~\AppData\Roaming\Block\goose\config\memory
-        // if it fails, fall back to `.config/goose/memory` (relative to the current dir)
-        let global_memory_dir = choose_app_strategy(crate::APP_STRATEGY.clone())
-            .map(|strategy| strategy.in_config_dir("memory"))
-            .unwrap_or_else(|_| PathBuf::from(".config/goose/memory"));
-
         let mut memory_router = Self {
             tools: vec![
                 remember_memory,
                 retrieve_memories,
                 remove_memory_category,
                 remove_specific_memory,
+                consolidate_memories,
             ],
             instructions: instructions.clone(),
-            global_memory_dir,
-            local_memory_dir,
+            global_memory_dir: global_memory_dir(),
+            local_memory_dir: local_memory_dir(),
+            embedder: None,
+            embeddings: Arc::new(Mutex::new(HashMap::new())),
         };
 
-        let retrieved_global_memories = memory_router.retrieve_all(true);
-        let retrieved_local_memories = memory_router.retrieve_all(false);
-
         let mut updated_instructions = instructions;
 
         let memories_follow_up_instructions = formatdoc! {r#"
-            **Here are the user's currently saved memories:**
+            **Here are the user's most recently saved memories:**
             Please keep this information in mind when answering future questions.
             Do not bring up memories unless relevant.
             Note: if the user has not saved any memories, this section will be empty.
             Note: if the user removes a memory that was previously loaded into the system, please remove it from the system instructions.
+            Note: only the memories that fit are listed, use retrieve_memories with a query to find the others.
             "#};
 
         updated_instructions.push_str("\n\n");
         updated_instructions.push_str(&memories_follow_up_instructions);
+        updated_instructions.push_str(&memory_router.injected_memories(
+            env_usize("GOOSE_MEMORY_INJECT_LIMIT", DEFAULT_INJECT_LIMIT),
+            env_usize("GOOSE_MEMORY_INJECT_CHARS", DEFAULT_INJECT_CHARS),
+        ));
 
-        if let Ok(global_memories) = retrieved_global_memories {
-            if !global_memories.is_empty() {
-                updated_instructions.push_str("\n\nGlobal Memories:\n");
-                for (category, memories) in global_memories {
-                    updated_instructions.push_str(&format!("\nCategory: {}\n", category));
-                    for memory in memories {
-                        updated_instructions.push_str(&format!("- {}\n", memory));
-                    }
-                }
-            }
-        }
+        memory_router.set_instructions(updated_instructions);
 
-        if let Ok(local_memories) = retrieved_local_memories {
-            if !local_memories.is_empty() {
-                updated_instructions.push_str("\n\nLocal Memories:\n");
-                for (category, memories) in local_memories {
-                    updated_instructions.push_str(&format!("\nCategory: {}\n", category));
-                    for memory in memories {
-                        updated_instructions.push_str(&format!("- {}\n", memory));
-                    }
-                }
+        memory_router
+    }
+
+    /// Rank memories with embeddings from this embedder, instead of BM25
+    pub fn with_embedder(mut self, embedder: Arc<dyn MemoryEmbedder>) -> Self {
+        self.embedder = Some(embedder);
+        self
+    }
+
+    /// The most recently used memories, local ones first, as sections for the instructions. At most
+    /// `limit` memories and `max_chars` characters are included.
+    fn injected_memories(&self, limit: usize, max_chars: usize) -> String {
+        let mut result = String::new();
+        let mut remaining_limit = limit;
+        let mut remaining_chars = max_chars;
+        let mut skipped = 0;
+
+        for (is_global, title) in [(false, "Local Memories"), (true, "Global Memories")] {
+            let mut memories = self.retrieve_all(is_global).unwrap_or_default();
+            memories.sort_by_key(|m| std::cmp::Reverse(m.last_active()));
+            let (taken, left_out) = take_within_budget(
+                memories.iter().map(|m| format!("- {}", m.render())),
+                remaining_limit,
+                remaining_chars,
+            );
+            skipped += left_out;
+            if taken.is_empty() {
+                continue;
             }
+            remaining_limit -= taken.len();
+            remaining_chars -= taken.iter().map(|t| t.chars().count()).sum::<usize>();
+            result.push_str(&format!("\n\n{}:\n{}\n", title, taken.join("\n")));
         }
 
-        memory_router.set_instructions(updated_instructions);
-
-        memory_router
+        if skipped > 0 {
+            result.push_str(&format!(
+                "\n{} more memories are saved, search them with retrieve_memories.\n",
+                skipped
+            ));
+        }
+        result
     }
 
     // Add a setter method for instructions
@@ -307,37 +413,18 @@ impl MemoryRouter {
         &self.instructions
     }
 
-    fn get_memory_file(&self, category: &str, is_global: bool) -> PathBuf {
+    fn store(&self, is_global: bool) -> MemoryStore {
         // Defaults to local memory if no is_global flag is provided
-        let base_dir = if is_global {
-            &self.global_memory_dir
+        if is_global {
+            MemoryStore::new(&self.global_memory_dir)
         } else {
-            &self.local_memory_dir
-        };
-        base_dir.join(format!("{}.txt", category))
+            MemoryStore::new(&self.local_memory_dir)
+        }
     }
 
-    pub fn retrieve_all(&self, is_global: bool) -> io::Result<HashMap<String, Vec<String>>> {
-        let base_dir = if is_global {
-            &self.global_memory_dir
-        } else {
-            &self.local_memory_dir
-        };
-        let mut memories = HashMap::new();
-        if base_dir.exists() {
-            for entry in fs::read_dir(base_dir)? {
-                let entry = entry?;
-                if entry.file_type()?.is_file() {
-                    let category = entry.file_name().to_string_lossy().replace(".txt", "");
-                    let category_memories = self.retrieve(&category, is_global)?;
-                    memories.insert(
-                        category,
-                        category_memories.into_iter().flat_map(|(_, v)| v).collect(),
-                    );
-                }
-            }
-        }
-        Ok(memories)
+    /// The memories of the scope that have not expired
+    pub fn retrieve_all(&self, is_global: bool) -> io::Result<Vec<MemoryEntry>> {
+        self.store(is_global).active()
     }
 
     pub fn remember(
@@ -347,110 +434,170 @@ impl MemoryRouter {
         data: &str,
         tags: &[&str],
         is_global: bool,
-    ) -> io::Result<()> {
-        let memory_file_path = self.get_memory_file(category, is_global);
+    ) -> io::Result<MemoryEntry> {
+        self.store(is_global).add(category, data, tags, None)
+    }
 
-        if let Some(parent) = memory_file_path.parent() {
-            fs::create_dir_all(parent)?;
-        }
+    pub fn retrieve(&self, category: &str, is_global: bool) -> io::Result<Vec<MemoryEntry>> {
+        let mut memories = self.retrieve_all(is_global)?;
+        memories.retain(|m| m.category == category);
+        Ok(memories)
+    }
 
-        let mut file = fs::OpenOptions::new()
-            .append(true)
-            .create(true)
-            .open(&memory_file_path)?;
-        if !tags.is_empty() {
-            writeln!(file, "# {}", tags.join(" "))?;
-        }
-        writeln!(file, "{}\n", data)?;
+    /// The memories most relevant to the query, best first. Uses embeddings when an
+    /// embedder is set and BM25 otherwise, or when the embedder fails.
+    pub async fn search(
+        &self,
+        query: &str,
+        category: Option<&str>,
+        is_global: bool,
+        limit: usize,
+    ) -> io::Result<Vec<MemoryEntry>> {
+        let mut memories = match category {
+            Some(category) => self.retrieve(category, is_global)?,
+            None => self.retrieve_all(is_global)?,
+        };
+        let texts: Vec<String> = memories.iter().map(MemoryEntry::search_text).collect();
+
+        let ranked = match &self.embedder {
+            Some(embedder) => match self.rank_with_embeddings(embedder, query, &texts).await {
+                Ok(ranked) => ranked,
+                Err(e) => {
+                    tracing::warn!("Falling back to keyword search for memories: {}", e);
+                    bm25_rank(query, &texts)
+                }
+            },
+            None => bm25_rank(query, &texts),
+        };
 
-        Ok(())
+        let mut slots: Vec<Option<MemoryEntry>> = memories.drain(..).map(Some).collect();
+        Ok(ranked
+            .into_iter()
+            .take(limit)
+            .filter_map(|(index, _)| slots[index].take())
+            .collect())
     }
 
-    pub fn retrieve(
+    async fn rank_with_embeddings(
         &self,
-        category: &str,
-        is_global: bool,
-    ) -> io::Result<HashMap<String, Vec<String>>> {
-        let memory_file_path = self.get_memory_file(category, is_global);
-        if !memory_file_path.exists() {
-            return Ok(HashMap::new());
-        }
+        embedder: &Arc<dyn MemoryEmbedder>,
+        query: &str,
+        texts: &[String],
+    ) -> anyhow::Result<Vec<(usize, f32)>> {
+        let query_embedding = embedder
+            .embed(vec![query.to_string()])
+            .await?
+            .pop()
+            .ok_or_else(|| anyhow::anyhow!("no embedding returned for the query"))?;
+        let documents = self.embed_cached(embedder, texts).await?;
+        Ok(embedding_rank(&query_embedding, &documents))
+    }
 
-        let mut file = fs::File::open(memory_file_path)?;
-        let mut content = String::new();
-        file.read_to_string(&mut content)?;
-
-        let mut memories = HashMap::new();
-        for entry in content.split("\n\n") {
-            let mut lines = entry.lines();
-            if let Some(first_line) = lines.next() {
-                if let Some(stripped) = first_line.strip_prefix('#') {
-                    let tags = stripped
-                        .split_whitespace()
-                        .map(String::from)
-                        .collect::<Vec<_>>();
-                    memories.insert(tags.join(" "), lines.map(String::from).collect());
-                } else {
-                    let entry_data: Vec<String> = std::iter::once(first_line.to_string())
-                        .chain(lines.map(String::from))
-                        .collect();
-                    memories
-                        .entry("untagged".to_string())
-                        .or_insert_with(Vec::new)
-                        .extend(entry_data);
-                }
+    /// Embeddings of the texts, asking the embedder only for texts it has not embedded yet
+    async fn embed_cached(
+        &self,
+        embedder: &Arc<dyn MemoryEmbedder>,
+        texts: &[String],
+    ) -> anyhow::Result<Vec<Vec<f32>>> {
+        let missing: Vec<String> = {
+            let cache = self.embeddings.lock().unwrap();
+            let mut missing: Vec<String> = texts
+                .iter()
+                .filter(|text| !cache.contains_key(*text))
+                .cloned()
+                .collect();
+            missing.dedup();
+            missing
+        };
+
+        if !missing.is_empty() {
+            let vectors = embedder.embed(missing.clone()).await?;
+            if vectors.len() != missing.len() {
+                anyhow::bail!(
+                    "expected {} embeddings, got {}",
+                    missing.len(),
+                    vectors.len()
+                );
             }
+            self.embeddings
+                .lock()
+                .unwrap()
+                .extend(missing.into_iter().zip(vectors));
         }
 
-        Ok(memories)
+        let cache = self.embeddings.lock().unwrap();
+        Ok(texts
+            .iter()
+            .map(|text| cache.get(text).cloned().unwrap_or_default())
+            .collect())
     }
 
-    pub fn remove_specific_memory(
+    /// Groups of memories with nearly the same content, compared by embeddings when an
+    /// embedder is set and by shared words otherwise
+    pub async fn find_near_duplicates(
         &self,
-        category: &str,
-        memory_content: &str,
+        category: Option<&str>,
         is_global: bool,
-    ) -> io::Result<()> {
-        let memory_file_path = self.get_memory_file(category, is_global);
-        if !memory_file_path.exists() {
-            return Ok(());
-        }
-
-        let mut file = fs::File::open(&memory_file_path)?;
-        let mut content = String::new();
-        file.read_to_string(&mut content)?;
+        threshold: Option<f32>,
+    ) -> io::Result<Vec<Vec<MemoryEntry>>> {
+        let memories = match category {
+            Some(category) => self.retrieve(category, is_global)?,
+            None => self.retrieve_all(is_global)?,
+        };
+        let texts: Vec<String> = memories.iter().map(|m| m.content.clone()).collect();
+
+        let embeddings = match &self.embedder {
+            Some(embedder) => match self.embed_cached(embedder, &texts).await {
+                Ok(embeddings) => Some(embeddings),
+                Err(e) => {
+                    tracing::warn!("Falling back to keyword comparison for memories: {}", e);
+                    None
+                }
+            },
+            None => None,
+        };
+        let groups = match embeddings {
+            Some(embeddings) => cluster_similar(
+                texts.len(),
+                |a, b| cosine_similarity(&embeddings[a], &embeddings[b]),
+                threshold.unwrap_or(EMBEDDING_DUPLICATE_THRESHOLD),
+            ),
+            None => cluster_similar(
+                texts.len(),
+                |a, b| jaccard_similarity(&texts[a], &texts[b]),
+                threshold.unwrap_or(KEYWORD_DUPLICATE_THRESHOLD),
+            ),
+        };
 
-        let memories: Vec<&str> = content.split("\n\n").collect();
-        let new_content: Vec<String> = memories
+        Ok(groups
             .into_iter()
-            .filter(|entry| !entry.contains(memory_content))
-            .map(|s| s.to_string())
-            .collect();
+            .map(|group| group.into_iter().map(|i| memories[i].clone()).collect())
+            .collect())
+    }
 
-        fs::write(memory_file_path, new_content.join("\n\n"))?;
+    /// Remove the memories of the category containing the content, returning how many were removed
+    pub fn remove_specific_memory(
+        &self,
+        category: &str,
+        memory_content: &str,
+        is_global: bool,
+    ) -> io::Result<usize> {
+        self.store(is_global)
+            .remove_where(|m| m.category == category && m.content.contains(memory_content))
+    }
 
-        Ok(())
+    pub fn remove_memory_by_id(&self, id: &str, is_global: bool) -> io::Result<usize> {
+        self.store(is_global).remove_where(|m| m.id == id)
     }
 
     pub fn clear_memory(&self, category: &str, is_global: bool) -> io::Result<()> {
-        let memory_file_path = self.get_memory_file(category, is_global);
-        if memory_file_path.exists() {
-            fs::remove_file(memory_file_path)?;
-        }
-
+        self.store(is_global)
+            .remove_where(|m| m.category == category)?;
         Ok(())
     }
 
     pub fn clear_all_global_or_local_memories(&self, is_global: bool) -> io::Result<()> {
-        let base_dir = if is_global {
-            &self.global_memory_dir
-        } else {
-            &self.local_memory_dir
-        };
-        if base_dir.exists() {
-            fs::remove_dir_all(base_dir)?;
-        }
-        Ok(())
+        self.store(is_global).clear()
     }
 
     async fn execute_tool_call(&self, tool_call: ToolCall) -> Result<String, io::Error> {
@@ -463,17 +610,40 @@ impl MemoryRouter {
                         "Data must exist when remembering a memory",
                     )
                 })?;
-                self.remember("context", args.category, data, &args.tags, args.is_global)?;
-                Ok(format!("Stored memory in category: {}", args.category))
+                let ttl = args
+                    .ttl
+                    .map(parse_duration)
+                    .transpose()
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+                let entry = self
+                    .store(args.is_global)
+                    .add(args.category, data, &args.tags, ttl)?;
+                Ok(format!(
+                    "Stored memory {} in category: {}",
+                    entry.id, args.category
+                ))
             }
             "retrieve_memories" => {
                 let args = MemoryArgs::from_value(&tool_call.arguments)?;
-                let memories = if args.category == "*" {
-                    self.retrieve_all(args.is_global)?
-                } else {
-                    self.retrieve(args.category, args.is_global)?
+                let category = (args.category != "*").then_some(args.category);
+                let memories = match (args.query, category) {
+                    (Some(query), category) => {
+                        let limit = args.limit.unwrap_or(DEFAULT_RETRIEVE_LIMIT);
+                        self.search(query, category, args.is_global, limit).await?
+                    }
+                    (None, Some(category)) => self.retrieve(category, args.is_global)?,
+                    (None, None) => self.retrieve_all(args.is_global)?,
                 };
-                Ok(format!("Retrieved memories: {:?}", memories))
+                if memories.is_empty() {
+                    return Ok("No memories found".to_string());
+                }
+                let ids: Vec<&str> = memories.iter().map(|m| m.id.as_str()).collect();
+                self.store(args.is_global).touch(&ids)?;
+                let lines: Vec<String> = memories
+                    .iter()
+                    .map(|m| format!("- {}", m.render()))
+                    .collect();
+                Ok(format!("Retrieved memories:\n{}", lines.join("\n")))
             }
             "remove_memory_category" => {
                 let args = MemoryArgs::from_value(&tool_call.arguments)?;
@@ -490,13 +660,89 @@ impl MemoryRouter {
             }
             "remove_specific_memory" => {
                 let args = MemoryArgs::from_value(&tool_call.arguments)?;
-                let memory_content = tool_call.arguments["memory_content"].as_str().unwrap();
-                self.remove_specific_memory(args.category, memory_content, args.is_global)?;
+                let removed = match (args.id, args.memory_content) {
+                    (Some(id), _) => self.remove_memory_by_id(id, args.is_global)?,
+                    (None, Some(content)) => {
+                        self.remove_specific_memory(args.category, content, args.is_global)?
+                    }
+                    (None, None) => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidInput,
+                            "Either id or memory_content is required to remove a memory",
+                        ))
+                    }
+                };
+                if removed == 0 {
+                    return Ok(format!(
+                        "No matching memory found in category: {}",
+                        args.category
+                    ));
+                }
                 Ok(format!(
                     "Removed specific memory from category: {}",
                     args.category
                 ))
             }
+            "consolidate_memories" => {
+                let arguments = &tool_call.arguments;
+                let is_global = is_global_arg(arguments)?;
+                let merges = arguments
+                    .get("merges")
+                    .filter(|m| m.as_array().is_some_and(|m| !m.is_empty()));
+                if let Some(merges) = merges {
+                    let merges: Vec<MemoryMerge> = serde_json::from_value(merges.clone())
+                        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+                    let store = self.store(is_global);
+                    let mut lines = Vec::new();
+                    for merge in merges {
+                        if merge.ids.len() < 2 {
+                            return Err(io::Error::new(
+                                io::ErrorKind::InvalidInput,
+                                "A merge needs the ids of at least two memories",
+                            ));
+                        }
+                        let ids: Vec<&str> = merge.ids.iter().map(String::as_str).collect();
+                        let entry = store.merge(
+                            &ids,
+                            &merge.content,
+                            merge.category.as_deref(),
+                            merge.tags,
+                        )?;
+                        lines.push(format!(
+                            "Merged {} memories into:\n- {}",
+                            ids.len(),
+                            entry.render()
+                        ));
+                    }
+                    return Ok(lines.join("\n"));
+                }
+
+                let category = arguments
+                    .get("category")
+                    .and_then(|c| c.as_str())
+                    .filter(|c| !c.is_empty() && *c != "*");
+                let threshold = arguments
+                    .get("threshold")
+                    .and_then(|t| t.as_f64())
+                    .map(|t| t as f32);
+                let groups = self
+                    .find_near_duplicates(category, is_global, threshold)
+                    .await?;
+                if groups.is_empty() {
+                    return Ok("No near-duplicate memories found".to_string());
+                }
+                let mut result = format!("Found {} groups of similar memories:\n", groups.len());
+                for (index, group) in groups.iter().enumerate() {
+                    result.push_str(&format!("\nGroup {}:\n", index + 1));
+                    for memory in group {
+                        result.push_str(&format!("- {}\n", memory.render()));
+                    }
+                }
+                result.push_str(
+                    "\nAsk the user which groups to merge and how before calling consolidate_memories with merges.",
+                );
+                Ok(result)
+            }
             _ => Err(io::Error::new(io::ErrorKind::InvalidInput, "Unknown tool")),
         }
     }
@@ -578,7 +824,12 @@ struct MemoryArgs<'a> {
     category: &'a str,
     data: Option<&'a str>,
     tags: Vec<&'a str>,
+    ttl: Option<&'a str>,
     is_global: bool,
+    query: Option<&'a str>,
+    limit: Option<usize>,
+    id: Option<&'a str>,
+    memory_content: Option<&'a str>,
 }
 
 impl<'a> MemoryArgs<'a> {
@@ -596,6 +847,11 @@ impl<'a> MemoryArgs<'a> {
         }
 
         let data = args.get("data").and_then(|d| d.as_str());
+        let non_empty = |key: &str| {
+            args.get(key)
+                .and_then(|v| v.as_str())
+                .filter(|s| !s.trim().is_empty())
+        };
 
         let tags = match &args["tags"] {
             Value::Array(arr) => arr.iter().filter_map(|v| v.as_str()).collect(),
@@ -603,44 +859,78 @@ impl<'a> MemoryArgs<'a> {
             _ => Vec::new(),
         };
 
-        let is_global = match &args.get("is_global") {
-            // Default to false if no is_global flag is provided
-            Some(Value::Bool(b)) => *b,
-            Some(Value::String(s)) => s.to_lowercase() == "true",
-            None => false,
-            _ => {
-                return Err(io::Error::new(
-                    io::ErrorKind::InvalidInput,
-                    "is_global must be a boolean or string 'true'/'false'",
-                ))
-            }
-        };
+        let is_global = is_global_arg(args)?;
 
         Ok(Self {
             category,
             data,
             tags,
+            ttl: non_empty("ttl"),
             is_global,
+            query: non_empty("query"),
+            limit: args
+                .get("limit")
+                .and_then(|l| l.as_u64())
+                .map(|l| l as usize),
+            id: non_empty("id"),
+            memory_content: non_empty("memory_content"),
         })
     }
 }
 
+fn is_global_arg(args: &Value) -> Result<bool, io::Error> {
+    match &args.get("is_global") {
+        // Default to false if no is_global flag is provided
+        Some(Value::Bool(b)) => Ok(*b),
+        Some(Value::String(s)) => Ok(s.to_lowercase() == "true"),
+        None => Ok(false),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "is_global must be a boolean or string 'true'/'false'",
+        )),
+    }
+}
+
+/// One merge of `consolidate_memories`
+#[derive(Debug, Deserialize)]
+struct MemoryMerge {
+    ids: Vec<String>,
+    content: String,
+    #[serde(default)]
+    category: Option<String>,
+    #[serde(default)]
+    tags: Option<Vec<String>>,
+}
+
+fn env_usize(key: &str, default: usize) -> usize {
+    std::env::var(key)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use tempfile::tempdir;
 
+    fn test_router(memory_base: &std::path::Path) -> MemoryRouter {
+        MemoryRouter {
+            tools: vec![],
+            instructions: String::new(),
+            global_memory_dir: memory_base.join("global"),
+            local_memory_dir: memory_base.join("local"),
+            embedder: None,
+            embeddings: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
     #[test]
     fn test_lazy_directory_creation() {
         let temp_dir = tempdir().unwrap();
         let memory_base = temp_dir.path().join("test_memory");
 
-        let router = MemoryRouter {
-            tools: vec![],
-            instructions: String::new(),
-            global_memory_dir: memory_base.join("global"),
-            local_memory_dir: memory_base.join("local"),
-        };
+        let router = test_router(&memory_base);
 
         assert!(!router.global_memory_dir.exists());
         assert!(!router.local_memory_dir.exists());
@@ -676,12 +966,7 @@ mod tests {
         let temp_dir = tempdir().unwrap();
         let memory_base = temp_dir.path().join("nonexistent_memory");
 
-        let router = MemoryRouter {
-            tools: vec![],
-            instructions: String::new(),
-            global_memory_dir: memory_base.join("global"),
-            local_memory_dir: memory_base.join("local"),
-        };
+        let router = test_router(&memory_base);
 
         assert!(router.clear_all_global_or_local_memories(false).is_ok());
         assert!(router.clear_all_global_or_local_memories(true).is_ok());
@@ -692,12 +977,7 @@ mod tests {
         let temp_dir = tempdir().unwrap();
         let memory_base = temp_dir.path().join("workflow_test");
 
-        let router = MemoryRouter {
-            tools: vec![],
-            instructions: String::new(),
-            global_memory_dir: memory_base.join("global"),
-            local_memory_dir: memory_base.join("local"),
-        };
+        let router = test_router(&memory_base);
 
         router
             .remember(
@@ -712,10 +992,9 @@ mod tests {
         let memories = router.retrieve("test_category", false).unwrap();
         assert!(!memories.is_empty());
 
-        let has_content = memories.values().any(|v| {
-            v.iter()
-                .any(|content| content.contains("test_data_content"))
-        });
+        let has_content = memories
+            .iter()
+            .any(|m| m.content.contains("test_data_content"));
         assert!(has_content);
 
         router.clear_memory("test_category", false).unwrap();
@@ -729,12 +1008,7 @@ mod tests {
         let temp_dir = tempdir().unwrap();
         let memory_base = temp_dir.path().join("write_test");
 
-        let router = MemoryRouter {
-            tools: vec![],
-            instructions: String::new(),
-            global_memory_dir: memory_base.join("global"),
-            local_memory_dir: memory_base.join("local"),
-        };
+        let router = test_router(&memory_base);
 
         assert!(!router.local_memory_dir.exists());
 
@@ -743,7 +1017,7 @@ mod tests {
             .unwrap();
 
         assert!(router.local_memory_dir.exists());
-        assert!(router.local_memory_dir.join("category.txt").exists());
+        assert!(router.local_memory_dir.join(store::MEMORY_FILE).exists());
     }
 
     #[test]
@@ -751,12 +1025,7 @@ mod tests {
         let temp_dir = tempdir().unwrap();
         let memory_base = temp_dir.path().join("remove_test");
 
-        let router = MemoryRouter {
-            tools: vec![],
-            instructions: String::new(),
-            global_memory_dir: memory_base.join("global"),
-            local_memory_dir: memory_base.join("local"),
-        };
+        let router = test_router(&memory_base);
 
         router
             .remember("context", "category", "keep_this", &[], false)
@@ -766,7 +1035,7 @@ mod tests {
             .unwrap();
 
         let memories = router.retrieve("category", false).unwrap();
-        assert_eq!(memories.len(), 1);
+        assert_eq!(memories.len(), 2);
 
         router
             .remove_specific_memory("category", "remove_this", false)
@@ -774,13 +1043,152 @@ mod tests {
 
         let memories_after = router.retrieve("category", false).unwrap();
         let has_removed = memories_after
-            .values()
-            .any(|v| v.iter().any(|content| content.contains("remove_this")));
+            .iter()
+            .any(|m| m.content.contains("remove_this"));
         assert!(!has_removed);
 
         let has_kept = memories_after
-            .values()
-            .any(|v| v.iter().any(|content| content.contains("keep_this")));
+            .iter()
+            .any(|m| m.content.contains("keep_this"));
         assert!(has_kept);
     }
+
+    struct KeywordEmbedder;
+
+    #[async_trait]
+    impl MemoryEmbedder for KeywordEmbedder {
+        // One dimension per keyword, enough to tell the memories apart
+        async fn embed(&self, texts: Vec<String>) -> anyhow::Result<Vec<Vec<f32>>> {
+            Ok(texts
+                .iter()
+                .map(|text| {
+                    ["format", "deploy"]
+                        .iter()
+                        .map(|word| text.contains(word) as u8 as f32)
+                        .collect()
+                })
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_ranks_by_relevance() {
+        let temp_dir = tempdir().unwrap();
+        let router = test_router(&temp_dir.path().join("search_test"));
+
+        router
+            .remember("context", "development", "we format with black", &[], false)
+            .unwrap();
+        let deploy = router
+            .remember(
+                "context",
+                "ops",
+                "deploy with the make release target",
+                &["deploy"],
+                false,
+            )
+            .unwrap();
+
+        let found = router.search("deploy steps", None, false, 5).await.unwrap();
+        assert_eq!(found, vec![deploy.clone()]);
+
+        let router = router.with_embedder(Arc::new(KeywordEmbedder));
+        let found = router
+            .search("release steps to deploy", None, false, 1)
+            .await
+            .unwrap();
+        assert_eq!(found, vec![deploy]);
+
+        let found = router
+            .search("release steps", Some("development"), false, 5)
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].category, "development");
+    }
+
+    #[test]
+    fn test_injected_memories_respect_budget() {
+        let temp_dir = tempdir().unwrap();
+        let router = test_router(&temp_dir.path().join("inject_test"));
+
+        for i in 0..5 {
+            router
+                .remember("context", "notes", &format!("local note {}", i), &[], false)
+                .unwrap();
+        }
+        router
+            .remember("context", "notes", "global note", &[], true)
+            .unwrap();
+
+        let injected = router.injected_memories(3, 10_000);
+        assert!(injected.contains("Local Memories:"));
+        assert!(!injected.contains("Global Memories:"));
+        assert_eq!(injected.matches("local note").count(), 3);
+        assert!(injected.contains("3 more memories are saved"));
+
+        let injected = router.injected_memories(10, 10_000);
+        assert!(injected.contains("Global Memories:\n- ["));
+        assert!(!injected.contains("more memories are saved"));
+    }
+
+    #[tokio::test]
+    async fn test_consolidate_memories_lists_then_merges() {
+        let temp_dir = tempdir().unwrap();
+        let router = test_router(&temp_dir.path().join("consolidate_test"));
+
+        let a = router
+            .remember(
+                "context",
+                "development",
+                "we format python with black",
+                &["python"],
+                false,
+            )
+            .unwrap();
+        let b = router
+            .remember("context", "tools", "format python with black", &[], false)
+            .unwrap();
+        router
+            .remember("context", "personal", "my name is Sam", &[], false)
+            .unwrap();
+
+        let call = |arguments: Value| ToolCall {
+            name: "consolidate_memories".to_string(),
+            arguments,
+        };
+        let listed = router
+            .execute_tool_call(call(serde_json::json!({"is_global": false})))
+            .await
+            .unwrap();
+        assert!(listed.contains("Found 1 groups"));
+        assert!(listed.contains(&a.id) && listed.contains(&b.id));
+        assert!(!listed.contains("Sam"));
+        // Listing does not change anything
+        assert_eq!(router.retrieve_all(false).unwrap().len(), 3);
+
+        router
+            .execute_tool_call(call(serde_json::json!({
+                "is_global": false,
+                "merges": [{"ids": [a.id, b.id], "content": "format python code with black"}]
+            })))
+            .await
+            .unwrap();
+
+        let memories = router.retrieve_all(false).unwrap();
+        assert_eq!(memories.len(), 2);
+        assert_eq!(memories[0].content, "format python code with black");
+        assert_eq!(memories[0].category, "development");
+
+        let retrieved = router
+            .execute_tool_call(ToolCall {
+                name: "retrieve_memories".to_string(),
+                arguments: serde_json::json!({"category": "personal", "is_global": false}),
+            })
+            .await
+            .unwrap();
+        assert!(retrieved.contains("my name is Sam"));
+        let personal = router.retrieve("personal", false).unwrap();
+        assert!(personal[0].last_used.is_some());
+    }
 }
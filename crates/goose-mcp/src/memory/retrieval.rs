@@ -0,0 +1,236 @@
+use async_trait::async_trait;
+use std::collections::{HashMap, HashSet};
+
+/// How many memories `retrieve_memories` returns for a query when no limit is given
+pub const DEFAULT_RETRIEVE_LIMIT: usize = 10;
+
+const BM25_K1: f32 = 1.2;
+const BM25_B: f32 = 0.75;
+
+/// Computes embeddings for ranking memories, usually backed by the configured provider
+#[async_trait]
+pub trait MemoryEmbedder: Send + Sync {
+    async fn embed(&self, texts: Vec<String>) -> anyhow::Result<Vec<Vec<f32>>>;
+}
+
+/// Lowercase alphanumeric words of the text
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .collect()
+}
+
+/// Rank documents against the query with BM25. Returns (index, score) pairs, best first,
+/// leaving out documents that share no word with the query.
+pub fn bm25_rank(query: &str, documents: &[String]) -> Vec<(usize, f32)> {
+    let query_terms: HashSet<String> = tokenize(query).into_iter().collect();
+    if query_terms.is_empty() || documents.is_empty() {
+        return Vec::new();
+    }
+
+    let documents: Vec<Vec<String>> = documents.iter().map(|d| tokenize(d)).collect();
+    let count = documents.len() as f32;
+    let average_length = (documents.iter().map(Vec::len).sum::<usize>() as f32 / count).max(1.0);
+
+    let mut document_frequency: HashMap<&str, usize> = HashMap::new();
+    for terms in &documents {
+        let unique: HashSet<&str> = terms.iter().map(String::as_str).collect();
+        for term in unique {
+            if query_terms.contains(term) {
+                *document_frequency.entry(term).or_default() += 1;
+            }
+        }
+    }
+
+    let mut scores: Vec<(usize, f32)> = documents
+        .iter()
+        .enumerate()
+        .filter_map(|(index, terms)| {
+            let length = terms.len() as f32;
+            let score: f32 = query_terms
+                .iter()
+                .filter_map(|term| {
+                    let frequency = terms.iter().filter(|t| *t == term).count() as f32;
+                    if frequency == 0.0 {
+                        return None;
+                    }
+                    let df = document_frequency[term.as_str()] as f32;
+                    let idf = ((count - df + 0.5) / (df + 0.5) + 1.0).ln();
+                    let norm = BM25_K1 * (1.0 - BM25_B + BM25_B * length / average_length);
+                    Some(idf * frequency * (BM25_K1 + 1.0) / (frequency + norm))
+                })
+                .sum();
+            (score > 0.0).then_some((index, score))
+        })
+        .collect();
+    sort_by_score(&mut scores);
+    scores
+}
+
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+/// Rank document embeddings by similarity to the query embedding, best first
+pub fn embedding_rank(query: &[f32], documents: &[Vec<f32>]) -> Vec<(usize, f32)> {
+    let mut scores: Vec<(usize, f32)> = documents
+        .iter()
+        .enumerate()
+        .map(|(index, document)| (index, cosine_similarity(query, document)))
+        .collect();
+    sort_by_score(&mut scores);
+    scores
+}
+
+fn sort_by_score(scores: &mut [(usize, f32)]) {
+    scores.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+}
+
+/// How many words two texts share, relative to all the words they use
+pub fn jaccard_similarity(a: &str, b: &str) -> f32 {
+    let a: HashSet<String> = tokenize(a).into_iter().collect();
+    let b: HashSet<String> = tokenize(b).into_iter().collect();
+    let union = a.union(&b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(&b).count() as f32 / union as f32
+}
+
+/// Group items whose similarity reaches the threshold, also through other items of the
+/// group. Only groups of two or more items are returned, in the order of their first item.
+pub fn cluster_similar(
+    count: usize,
+    similarity: impl Fn(usize, usize) -> f32,
+    threshold: f32,
+) -> Vec<Vec<usize>> {
+    fn root(parents: &mut [usize], mut item: usize) -> usize {
+        while parents[item] != item {
+            parents[item] = parents[parents[item]];
+            item = parents[item];
+        }
+        item
+    }
+
+    let mut parents: Vec<usize> = (0..count).collect();
+    for a in 0..count {
+        for b in a + 1..count {
+            if similarity(a, b) >= threshold {
+                let (root_a, root_b) = (root(&mut parents, a), root(&mut parents, b));
+                parents[root_a.max(root_b)] = root_a.min(root_b);
+            }
+        }
+    }
+
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    let mut group_of_root: HashMap<usize, usize> = HashMap::new();
+    for item in 0..count {
+        let item_root = root(&mut parents, item);
+        match group_of_root.get(&item_root) {
+            Some(&group) => groups[group].push(item),
+            None => {
+                group_of_root.insert(item_root, groups.len());
+                groups.push(vec![item]);
+            }
+        }
+    }
+    groups.retain(|group| group.len() > 1);
+    groups
+}
+
+/// Take rendered memories in order while they fit in `limit` entries and `max_chars`
+/// characters, skipping the ones that do not. Returns them and how many were left out.
+pub fn take_within_budget(
+    rendered: impl IntoIterator<Item = String>,
+    limit: usize,
+    max_chars: usize,
+) -> (Vec<String>, usize) {
+    let mut taken = Vec::new();
+    let mut used = 0;
+    let mut skipped = 0;
+    for text in rendered {
+        let size = text.chars().count();
+        if taken.len() >= limit || used + size > max_chars {
+            skipped += 1;
+            continue;
+        }
+        used += size;
+        taken.push(text);
+    }
+    (taken, skipped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bm25_prefers_matching_documents() {
+        let documents = vec![
+            "development formatting use black for python code".to_string(),
+            "github gh pr view --comments".to_string(),
+            "personal my name is Sam".to_string(),
+        ];
+
+        let ranked = bm25_rank("how do we format python code?", &documents);
+        assert_eq!(ranked.first().map(|(i, _)| *i), Some(0));
+        assert!(ranked.iter().all(|(i, _)| *i != 2));
+
+        let ranked = bm25_rank("GH comments", &documents);
+        assert_eq!(ranked, vec![(1, ranked[0].1)]);
+
+        assert!(bm25_rank("", &documents).is_empty());
+    }
+
+    #[test]
+    fn test_embedding_rank() {
+        let documents = vec![vec![0.0, 1.0], vec![1.0, 0.1], vec![0.0, 0.0]];
+        let ranked = embedding_rank(&[1.0, 0.0], &documents);
+        assert_eq!(
+            ranked.iter().map(|(i, _)| *i).collect::<Vec<_>>(),
+            vec![1, 0, 2]
+        );
+    }
+
+    #[test]
+    fn test_cluster_similar() {
+        let texts = [
+            "we format python with black",
+            "my name is Sam",
+            "python is formatted with black",
+            "format python code with black please",
+            "the user is called Sam",
+        ];
+        let groups = cluster_similar(
+            texts.len(),
+            |a, b| jaccard_similarity(texts[a], texts[b]),
+            0.4,
+        );
+        assert_eq!(groups, vec![vec![0, 2, 3]]);
+
+        assert!(cluster_similar(3, |_, _| 0.0, 0.5).is_empty());
+        assert_eq!(cluster_similar(3, |_, _| 1.0, 0.5), vec![vec![0, 1, 2]]);
+    }
+
+    #[test]
+    fn test_take_within_budget() {
+        let rendered = vec![
+            "short".to_string(),
+            "long ".repeat(50),
+            "also short".to_string(),
+            "short again".to_string(),
+        ];
+
+        let (taken, skipped) = take_within_budget(rendered, 2, 100);
+        assert_eq!(taken, vec!["short", "also short"]);
+        assert_eq!(skipped, 2);
+    }
+}
@@ -0,0 +1,544 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+/// Name of the file holding the memories of one scope
+pub const MEMORY_FILE: &str = "memories.json";
+
+const MEMORY_FILE_VERSION: u32 = 1;
+
+/// Held while a change is read, applied and saved, so processes sharing a scope keep each
+/// other's changes
+const LOCK_FILE: &str = ".memories.lock";
+
+/// A single remembered piece of information
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemoryEntry {
+    pub id: String,
+    pub category: String,
+    pub content: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// When the memory was last returned by `retrieve_memories`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_used: Option<DateTime<Utc>>,
+    /// When the memory stops being used, set from a TTL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl MemoryEntry {
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
+    /// The last time the memory was saved, edited or used
+    pub fn last_active(&self) -> DateTime<Utc> {
+        self.last_used
+            .map_or(self.updated_at, |used| used.max(self.updated_at))
+    }
+
+    /// The text used to rank the entry against a query
+    pub fn search_text(&self) -> String {
+        format!("{} {} {}", self.category, self.tags.join(" "), self.content)
+    }
+
+    /// One line (or more, for multi-line content) describing the entry to the model
+    pub fn render(&self) -> String {
+        let tags = if self.tags.is_empty() {
+            String::new()
+        } else {
+            format!(
+                " #{}",
+                self.tags
+                    .iter()
+                    .map(|t| t.trim_start_matches('#'))
+                    .collect::<Vec<_>>()
+                    .join(" #")
+            )
+        };
+        let expires = self
+            .expires_at
+            .map(|e| format!(", expires {}", e.format("%Y-%m-%d")))
+            .unwrap_or_default();
+        format!(
+            "[{}] ({}{}, {}{}) {}",
+            self.id,
+            self.category,
+            tags,
+            self.updated_at.format("%Y-%m-%d"),
+            expires,
+            self.content
+        )
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct MemoryFile {
+    version: u32,
+    memories: Vec<MemoryEntry>,
+}
+
+/// The memories of one scope, stored as `memories.json` in the scope's directory
+#[derive(Debug, Clone)]
+pub struct MemoryStore {
+    dir: PathBuf,
+}
+
+impl MemoryStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn path(&self) -> PathBuf {
+        self.dir.join(MEMORY_FILE)
+    }
+
+    /// Load every entry of the scope. Category files written by earlier versions
+    /// (`<category>.txt`) are imported the first time, so that they get stable ids.
+    pub fn load(&self) -> io::Result<Vec<MemoryEntry>> {
+        let path = self.path();
+        if path.exists() {
+            let content = fs::read_to_string(&path)?;
+            let file: MemoryFile = serde_json::from_str(&content).map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Failed to parse {}: {}", path.display(), e),
+                )
+            })?;
+            return Ok(file.memories);
+        }
+
+        let entries = self.import_legacy()?;
+        if !entries.is_empty() {
+            self.save(&entries)?;
+        }
+        Ok(entries)
+    }
+
+    /// The entries that have not expired
+    pub fn active(&self) -> io::Result<Vec<MemoryEntry>> {
+        let now = Utc::now();
+        let mut entries = self.load()?;
+        entries.retain(|entry| !entry.is_expired(now));
+        Ok(entries)
+    }
+
+    pub fn save(&self, entries: &[MemoryEntry]) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let file = MemoryFile {
+            version: MEMORY_FILE_VERSION,
+            memories: entries.to_vec(),
+        };
+        let json = serde_json::to_string_pretty(&file)?;
+
+        // Write next to the target and rename, so a crash never leaves a truncated file
+        let mut tmp = tempfile::NamedTempFile::new_in(&self.dir)?;
+        tmp.write_all(json.as_bytes())?;
+        tmp.persist(self.path()).map_err(|e| e.error)?;
+        Ok(())
+    }
+
+    pub fn add(
+        &self,
+        category: &str,
+        content: &str,
+        tags: &[&str],
+        ttl: Option<Duration>,
+    ) -> io::Result<MemoryEntry> {
+        self.locked(|| {
+            let mut entries = self.load()?;
+            let now = Utc::now();
+            let entry = MemoryEntry {
+                id: new_id(&entries),
+                category: category.to_string(),
+                content: content.to_string(),
+                tags: tags.iter().map(|t| t.to_string()).collect(),
+                created_at: now,
+                updated_at: now,
+                last_used: None,
+                expires_at: ttl.map(|ttl| now + ttl),
+            };
+            entries.push(entry.clone());
+            self.save(&entries)?;
+            Ok(entry)
+        })
+    }
+
+    /// Change the entry with the given id, returning it as saved or None when there is none
+    pub fn update(
+        &self,
+        id: &str,
+        change: impl FnOnce(&mut MemoryEntry),
+    ) -> io::Result<Option<MemoryEntry>> {
+        self.locked(|| {
+            let mut entries = self.load()?;
+            let Some(entry) = entries.iter_mut().find(|entry| entry.id == id) else {
+                return Ok(None);
+            };
+            change(entry);
+            entry.updated_at = Utc::now();
+            let entry = entry.clone();
+            self.save(&entries)?;
+            Ok(Some(entry))
+        })
+    }
+
+    /// Record that the entries were just used
+    pub fn touch(&self, ids: &[&str]) -> io::Result<()> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        let now = Utc::now();
+        self.locked(|| {
+            let mut entries = self.load()?;
+            for entry in entries.iter_mut().filter(|e| ids.contains(&e.id.as_str())) {
+                entry.last_used = Some(now);
+            }
+            self.save(&entries)
+        })
+    }
+
+    /// Replace the entries with the given ids by a single one with the merged content. The
+    /// merged entry keeps the earliest creation time and the latest use and expiry, takes the
+    /// category of the first entry unless one is given and the tags of all of them unless
+    /// tags are given.
+    pub fn merge(
+        &self,
+        ids: &[&str],
+        content: &str,
+        category: Option<&str>,
+        tags: Option<Vec<String>>,
+    ) -> io::Result<MemoryEntry> {
+        self.locked(|| {
+            let mut entries = self.load()?;
+            let merged: Vec<&MemoryEntry> = ids
+                .iter()
+                .map(|id| {
+                    entries.iter().find(|e| e.id == *id).ok_or_else(|| {
+                        io::Error::new(io::ErrorKind::NotFound, format!("No memory with id {}", id))
+                    })
+                })
+                .collect::<io::Result<_>>()?;
+            let Some(first) = merged.first() else {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "At least one memory id is needed to merge",
+                ));
+            };
+
+            let tags = tags.unwrap_or_else(|| {
+                let mut all: Vec<String> = Vec::new();
+                for tag in merged.iter().flat_map(|e| &e.tags) {
+                    if !all.contains(tag) {
+                        all.push(tag.clone());
+                    }
+                }
+                all
+            });
+            // A memory without expiry keeps the merged one too
+            let expires_at = merged
+                .iter()
+                .map(|e| e.expires_at)
+                .collect::<Option<Vec<_>>>()
+                .and_then(|all| all.into_iter().max());
+            let entry = MemoryEntry {
+                id: new_id(&entries),
+                category: category.unwrap_or(&first.category).to_string(),
+                content: content.to_string(),
+                tags,
+                created_at: merged
+                    .iter()
+                    .map(|e| e.created_at)
+                    .min()
+                    .unwrap_or(first.created_at),
+                updated_at: Utc::now(),
+                last_used: merged.iter().filter_map(|e| e.last_used).max(),
+                expires_at,
+            };
+
+            let position = entries.iter().position(|e| e.id == first.id).unwrap_or(0);
+            entries.retain(|e| !ids.contains(&e.id.as_str()));
+            entries.insert(position.min(entries.len()), entry.clone());
+            self.save(&entries)?;
+            Ok(entry)
+        })
+    }
+
+    /// Remove the entries matching the predicate and return how many were removed
+    pub fn remove_where(&self, predicate: impl Fn(&MemoryEntry) -> bool) -> io::Result<usize> {
+        self.locked(|| {
+            let mut entries = self.load()?;
+            let before = entries.len();
+            entries.retain(|entry| !predicate(entry));
+            let removed = before - entries.len();
+            if removed > 0 {
+                self.save(&entries)?;
+            }
+            Ok(removed)
+        })
+    }
+
+    /// Run a load-modify-save of the scope while holding its lock file
+    fn locked<T>(&self, change: impl FnOnce() -> io::Result<T>) -> io::Result<T> {
+        fs::create_dir_all(&self.dir)?;
+        let lock = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.dir.join(LOCK_FILE))?;
+        fs2::FileExt::lock_exclusive(&lock)?;
+        let result = change();
+        fs2::FileExt::unlock(&lock)?;
+        result
+    }
+
+    /// Remove the whole scope, including category files of earlier versions
+    pub fn clear(&self) -> io::Result<()> {
+        if self.dir.exists() {
+            fs::remove_dir_all(&self.dir)?;
+        }
+        Ok(())
+    }
+
+    fn import_legacy(&self) -> io::Result<Vec<MemoryEntry>> {
+        let mut entries = Vec::new();
+        if !self.dir.exists() {
+            return Ok(entries);
+        }
+
+        let mut files: Vec<PathBuf> = fs::read_dir(&self.dir)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "txt"))
+            .collect();
+        files.sort();
+
+        for path in files {
+            let Some(category) = path.file_stem().map(|s| s.to_string_lossy().to_string()) else {
+                continue;
+            };
+            let content = fs::read_to_string(&path)?;
+            let saved_at = fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .map(DateTime::<Utc>::from)
+                .unwrap_or_else(|_| Utc::now());
+            for (tags, content) in parse_legacy(&content) {
+                entries.push(MemoryEntry {
+                    id: new_id(&entries),
+                    category: category.clone(),
+                    content,
+                    tags,
+                    created_at: saved_at,
+                    updated_at: saved_at,
+                    last_used: None,
+                    expires_at: None,
+                });
+            }
+        }
+        Ok(entries)
+    }
+}
+
+/// Split a category file of earlier versions into (tags, content) pairs. Entries are
+/// separated by blank lines and may start with a `# tag1 tag2` line.
+fn parse_legacy(content: &str) -> Vec<(Vec<String>, String)> {
+    content
+        .split("\n\n")
+        .filter_map(|block| {
+            let mut lines = block.lines().peekable();
+            let tags = match lines.peek() {
+                Some(first) if first.starts_with('#') => {
+                    let tags = first[1..].split_whitespace().map(String::from).collect();
+                    lines.next();
+                    tags
+                }
+                _ => Vec::new(),
+            };
+            let text = lines.collect::<Vec<_>>().join("\n").trim().to_string();
+            (!text.is_empty()).then_some((tags, text))
+        })
+        .collect()
+}
+
+/// Parse a duration like `90m`, `12h`, `30d` or `2w`
+pub fn parse_duration(text: &str) -> Result<Duration, String> {
+    let text = text.trim();
+    let split = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let number: i64 = number.parse().map_err(|_| {
+        format!(
+            "Invalid duration '{}', expected a number and a unit like 30d",
+            text
+        )
+    })?;
+    match unit.trim() {
+        "m" | "min" => Ok(Duration::minutes(number)),
+        "h" => Ok(Duration::hours(number)),
+        "d" => Ok(Duration::days(number)),
+        "w" => Ok(Duration::weeks(number)),
+        _ => Err(format!(
+            "Invalid duration '{}', the unit has to be m, h, d or w",
+            text
+        )),
+    }
+}
+
+/// A short id that is not used by any of the entries yet
+fn new_id(entries: &[MemoryEntry]) -> String {
+    loop {
+        let id = uuid::Uuid::new_v4().simple().to_string()[..8].to_string();
+        if !entries.iter().any(|entry| entry.id == id) {
+            return id;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_add_assigns_unique_ids() {
+        let dir = tempdir().unwrap();
+        let store = MemoryStore::new(dir.path().join("memory"));
+
+        let first = store
+            .add("development", "use black", &["python"], None)
+            .unwrap();
+        let second = store.add("development", "use ruff", &[], None).unwrap();
+
+        assert_ne!(first.id, second.id);
+        let entries = store.load().unwrap();
+        assert_eq!(entries, vec![first, second]);
+        assert_eq!(entries[0].tags, vec!["python"]);
+    }
+
+    #[test]
+    fn test_concurrent_adds_keep_every_memory() {
+        let dir = tempdir().unwrap();
+        let memory_dir = dir.path().join("memory");
+
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let store = MemoryStore::new(&memory_dir);
+                std::thread::spawn(move || {
+                    for j in 0..5 {
+                        store
+                            .add("notes", &format!("memory {} {}", i, j), &[], None)
+                            .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(MemoryStore::new(&memory_dir).load().unwrap().len(), 40);
+    }
+
+    #[test]
+    fn test_imports_legacy_category_files() {
+        let dir = tempdir().unwrap();
+        let memory_dir = dir.path().join("memory");
+        fs::create_dir_all(&memory_dir).unwrap();
+        fs::write(
+            memory_dir.join("github.txt"),
+            "# comments gh\ngh pr view --comments\n\nuntagged entry\n\n",
+        )
+        .unwrap();
+
+        let store = MemoryStore::new(&memory_dir);
+        let entries = store.load().unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].category, "github");
+        assert_eq!(entries[0].tags, vec!["comments", "gh"]);
+        assert_eq!(entries[0].content, "gh pr view --comments");
+        assert!(entries[1].tags.is_empty());
+        assert_eq!(entries[1].content, "untagged entry");
+
+        // The import is written once so the ids stay the same
+        assert!(store.path().exists());
+        assert_eq!(store.load().unwrap(), entries);
+    }
+
+    #[test]
+    fn test_ttl_and_touch() {
+        let dir = tempdir().unwrap();
+        let store = MemoryStore::new(dir.path());
+
+        let kept = store.add("notes", "kept", &[], None).unwrap();
+        let expiring = store
+            .add("notes", "expiring", &[], Some(Duration::days(1)))
+            .unwrap();
+        assert!(expiring.expires_at.is_some());
+        store
+            .update(&expiring.id, |e| e.expires_at = Some(Utc::now()))
+            .unwrap();
+
+        let active = store.active().unwrap();
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].id, kept.id);
+        assert_eq!(store.load().unwrap().len(), 2);
+
+        store.touch(&[&kept.id]).unwrap();
+        let touched = store.active().unwrap().remove(0);
+        assert!(touched.last_used.is_some());
+        assert_eq!(touched.last_active(), touched.last_used.unwrap());
+    }
+
+    #[test]
+    fn test_merge_replaces_entries() {
+        let dir = tempdir().unwrap();
+        let store = MemoryStore::new(dir.path());
+
+        let a = store
+            .add("development", "use black", &["python"], None)
+            .unwrap();
+        let other = store.add("personal", "name is Sam", &[], None).unwrap();
+        let b = store
+            .add(
+                "tools",
+                "format with black",
+                &["format"],
+                Some(Duration::days(1)),
+            )
+            .unwrap();
+
+        let merged = store
+            .merge(&[&a.id, &b.id], "format python with black", None, None)
+            .unwrap();
+
+        assert_eq!(merged.category, "development");
+        assert_eq!(merged.tags, vec!["python", "format"]);
+        assert_eq!(merged.created_at, a.created_at);
+        assert_eq!(merged.expires_at, None);
+        let ids: Vec<String> = store.load().unwrap().into_iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec![merged.id.clone(), other.id.clone()]);
+
+        assert!(store.merge(&["missing"], "x", None, None).is_err());
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("30d"), Ok(Duration::days(30)));
+        assert_eq!(parse_duration("12h"), Ok(Duration::hours(12)));
+        assert_eq!(parse_duration("2w"), Ok(Duration::weeks(2)));
+        assert!(parse_duration("soon").is_err());
+        assert!(parse_duration("5y").is_err());
+    }
+}
@@ -389,6 +389,7 @@ impl<'__s> ToSchema<'__s> for AnnotatedSchema {
         super::routes::schedule::kill_running_job,
         super::routes::schedule::inspect_running_job,
         super::routes::schedule::sessions_handler,
+        super::routes::schedule::webhook_handler,
         super::routes::recipe::create_recipe,
         super::routes::recipe::encode_recipe,
         super::routes::recipe::decode_recipe,
@@ -449,8 +450,15 @@ impl<'__s> ToSchema<'__s> for AnnotatedSchema {
         super::routes::schedule::KillJobResponse,
         super::routes::schedule::InspectJobResponse,
         goose::scheduler::ScheduledJob,
+        goose::scheduler::OverlapPolicy,
+        goose::scheduler::JobRetryPolicy,
+        goose::scheduler::JobRunRecord,
+        goose::scheduler::JobRunStatus,
+        goose::scheduler_triggers::JobTrigger,
         super::routes::schedule::RunNowResponse,
         super::routes::schedule::ListSchedulesResponse,
+        super::routes::schedule::ScheduledJobResponse,
+        super::routes::schedule::TriggerInfo,
         super::routes::schedule::SessionsQuery,
         super::routes::schedule::SessionDisplayInfo,
         super::routes::recipe::CreateRecipeRequest,
@@ -465,11 +473,17 @@ impl<'__s> ToSchema<'__s> for AnnotatedSchema {
         goose::recipe::Recipe,
         goose::recipe::Author,
         goose::recipe::Settings,
+        goose::permission::ApprovalPolicy,
+        goose::permission::OnConfirmation,
         goose::recipe::RecipeParameter,
         goose::recipe::RecipeParameterInputType,
         goose::recipe::RecipeParameterRequirement,
         goose::recipe::Response,
         goose::recipe::SubRecipe,
+        goose::recipe::signature::RecipeSignature,
+        goose::recipe::signature::SignatureCheck,
+        goose::recipe::signature::SignatureStatus,
+        goose::recipe::signature::SignatureAction,
         goose::agents::types::RetryConfig,
         goose::agents::types::SuccessCheck,
         super::routes::agent::AddSubRecipesRequest,
@@ -1,14 +1,21 @@
 use axum::{routing::get, Json, Router};
+use goose::providers::rate_limit::{rate_limit_snapshots, RateLimitSnapshot};
 use serde::Serialize;
 
 #[derive(Serialize)]
 struct StatusResponse {
     status: &'static str,
+    /// Provider rate limiter state, one entry per provider and model in use
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    rate_limits: Vec<RateLimitSnapshot>,
 }
 
 /// Simple status endpoint that returns 200 OK when the server is running
 async fn status() -> Json<StatusResponse> {
-    Json(StatusResponse { status: "ok" })
+    Json(StatusResponse {
+        status: "ok",
+        rate_limits: rate_limit_snapshots(),
+    })
 }
 
 /// Configure health check routes
@@ -2,8 +2,9 @@ use std::sync::Arc;
 
 use axum::{extract::State, http::StatusCode, routing::post, Json, Router};
 use goose::conversation::{message::Message, Conversation};
+use goose::recipe::signature::{check_recipe_signature_from_config, SignatureCheck};
 use goose::recipe::Recipe;
-use goose::recipe_deeplink;
+use goose::recipe_deeplink::{self, DecodeError};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
@@ -54,6 +55,7 @@ pub struct DecodeRecipeRequest {
 #[derive(Debug, Serialize, ToSchema)]
 pub struct DecodeRecipeResponse {
     recipe: Recipe,
+    signature: SignatureCheck,
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -64,6 +66,7 @@ pub struct ScanRecipeRequest {
 #[derive(Debug, Serialize, ToSchema)]
 pub struct ScanRecipeResponse {
     has_security_warnings: bool,
+    signature: SignatureCheck,
 }
 
 #[utoipa::path(
@@ -158,15 +161,20 @@ async fn encode_recipe(
     request_body = DecodeRecipeRequest,
     responses(
         (status = 200, description = "Recipe decoded successfully", body = DecodeRecipeResponse),
-        (status = 400, description = "Bad request")
+        (status = 400, description = "Bad request"),
+        (status = 403, description = "Recipe rejected by the signature policy")
     ),
     tag = "Recipe Management"
 )]
 async fn decode_recipe(
     Json(request): Json<DecodeRecipeRequest>,
 ) -> Result<Json<DecodeRecipeResponse>, StatusCode> {
-    match recipe_deeplink::decode(&request.deeplink) {
-        Ok(recipe) => Ok(Json(DecodeRecipeResponse { recipe })),
+    match recipe_deeplink::decode_verified(&request.deeplink) {
+        Ok((recipe, signature)) => Ok(Json(DecodeRecipeResponse { recipe, signature })),
+        Err(DecodeError::SignatureRejected(message)) => {
+            tracing::warn!("Rejected recipe deeplink: {}", message);
+            Err(StatusCode::FORBIDDEN)
+        }
         Err(err) => {
             tracing::error!("Failed to decode deeplink: {}", err);
             Err(StatusCode::BAD_REQUEST)
@@ -187,9 +195,11 @@ async fn scan_recipe(
     Json(request): Json<ScanRecipeRequest>,
 ) -> Result<Json<ScanRecipeResponse>, StatusCode> {
     let has_security_warnings = request.recipe.check_for_security_warnings();
+    let signature = check_recipe_signature_from_config(&request.recipe);
 
     Ok(Json(ScanRecipeResponse {
         has_security_warnings,
+        signature,
     }))
 }
 
@@ -219,6 +219,7 @@ async fn reply_handler(
             schedule_id: request.scheduled_job_id.clone(),
             execution_mode: None,
             max_turns: None,
+            goose_mode: None,
             retry_config: None,
         };
 
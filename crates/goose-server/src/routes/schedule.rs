@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use axum::{
@@ -8,31 +9,117 @@ use axum::{
 };
 use serde::{Deserialize, Serialize};
 
-use chrono::NaiveDateTime;
+use chrono::{DateTime, NaiveDateTime, Utc};
 
 use crate::routes::utils::verify_secret_key;
 use crate::state::AppState;
-use goose::scheduler::ScheduledJob;
+use goose::scheduler::{
+    next_fire_times, JobRetryPolicy, JobRunRecord, OverlapPolicy, ScheduledJob,
+};
+use goose::scheduler_triggers::{
+    verify_webhook_secret, webhook_parameters, JobTrigger, WEBHOOK_SECRET_HEADER,
+};
 
 #[derive(Deserialize, Serialize, utoipa::ToSchema)]
 pub struct CreateScheduleRequest {
     id: String,
     recipe_source: String,
+    #[serde(default)]
     cron: String,
     #[serde(default)]
     execution_mode: Option<String>, // "foreground" or "background"
+    #[serde(default)]
+    parameters: HashMap<String, String>,
+    #[serde(default)]
+    overlap_policy: OverlapPolicy,
+    #[serde(default)]
+    max_runtime_secs: Option<u64>,
+    #[serde(default)]
+    retry: Option<JobRetryPolicy>,
+    #[serde(default)]
+    triggers: Vec<JobTrigger>,
+    /// IANA timezone the cron expression is evaluated in; UTC when omitted
+    #[serde(default)]
+    timezone: Option<String>,
 }
 
 #[derive(Deserialize, Serialize, utoipa::ToSchema)]
 pub struct UpdateScheduleRequest {
     cron: String,
+    /// IANA timezone the cron expression is evaluated in; the current timezone is kept when omitted
+    #[serde(default)]
+    timezone: Option<String>,
+}
+
+/// A job trigger as returned by the API; webhook secrets are never sent back to clients
+#[derive(Serialize, Debug, PartialEq, Eq, utoipa::ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TriggerInfo {
+    FileWatch {
+        path: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        glob: Option<String>,
+    },
+    Webhook {
+        has_secret: bool,
+    },
+    AfterJob {
+        job_id: String,
+    },
+}
+
+impl From<JobTrigger> for TriggerInfo {
+    fn from(trigger: JobTrigger) -> Self {
+        match trigger {
+            JobTrigger::FileWatch { path, glob } => TriggerInfo::FileWatch { path, glob },
+            JobTrigger::Webhook { secret } => TriggerInfo::Webhook {
+                has_secret: !secret.is_empty(),
+            },
+            JobTrigger::AfterJob { job_id } => TriggerInfo::AfterJob { job_id },
+        }
+    }
+}
+
+/// A scheduled job as returned by the API, with its triggers redacted
+#[derive(Serialize, utoipa::ToSchema)]
+pub struct ScheduledJobResponse {
+    #[serde(flatten)]
+    job: ScheduledJob,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    triggers: Vec<TriggerInfo>,
+}
+
+impl From<ScheduledJob> for ScheduledJobResponse {
+    fn from(mut job: ScheduledJob) -> Self {
+        let triggers = std::mem::take(&mut job.triggers)
+            .into_iter()
+            .map(TriggerInfo::from)
+            .collect();
+        ScheduledJobResponse { job, triggers }
+    }
 }
 
 #[derive(Serialize, utoipa::ToSchema)]
 pub struct ListSchedulesResponse {
-    jobs: Vec<ScheduledJob>,
+    jobs: Vec<ScheduledJobResponse>,
+    /// Upcoming fire times for each job with a cron expression, keyed by job ID
+    next_runs: HashMap<String, Vec<DateTime<Utc>>>,
 }
 
+// Query parameters for the list endpoint
+#[derive(Deserialize, utoipa::IntoParams)]
+pub struct ListSchedulesQuery {
+    /// Number of upcoming fire times to return for each job
+    #[serde(default = "default_next_runs")]
+    next: usize,
+}
+
+fn default_next_runs() -> usize {
+    3
+}
+
+const MAX_NEXT_RUNS: usize = 50;
+
 // Response for the kill endpoint
 #[derive(Serialize, utoipa::ToSchema)]
 pub struct KillJobResponse {
@@ -81,6 +168,8 @@ pub struct SessionDisplayInfo {
     accumulated_total_tokens: Option<i32>,
     accumulated_input_tokens: Option<i32>,
     accumulated_output_tokens: Option<i32>,
+    /// Outcome of the scheduled run that produced this session, if recorded
+    run: Option<JobRunRecord>,
 }
 
 fn parse_session_name_to_iso(session_name: &str) -> String {
@@ -94,7 +183,7 @@ fn parse_session_name_to_iso(session_name: &str) -> String {
     path = "/schedule/create",
     request_body = CreateScheduleRequest,
     responses(
-        (status = 200, description = "Scheduled job created successfully", body = ScheduledJob),
+        (status = 200, description = "Scheduled job created successfully", body = ScheduledJobResponse),
         (status = 400, description = "Invalid cron expression or recipe file"),
         (status = 409, description = "Job ID already exists"),
         (status = 500, description = "Internal server error")
@@ -106,7 +195,7 @@ async fn create_schedule(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
     Json(req): Json<CreateScheduleRequest>,
-) -> Result<Json<ScheduledJob>, StatusCode> {
+) -> Result<Json<ScheduledJobResponse>, StatusCode> {
     verify_secret_key(&headers, &state)?;
     let scheduler = state
         .scheduler()
@@ -127,6 +216,12 @@ async fn create_schedule(
         current_session_id: None,
         process_start_time: None,
         execution_mode: req.execution_mode.or(Some("background".to_string())), // Default to background
+        parameters: req.parameters,
+        overlap_policy: req.overlap_policy,
+        max_runtime_secs: req.max_runtime_secs,
+        retry: req.retry,
+        triggers: req.triggers,
+        timezone: req.timezone,
     };
     scheduler
         .add_scheduled_job(job.clone())
@@ -141,12 +236,13 @@ async fn create_schedule(
                 _ => StatusCode::INTERNAL_SERVER_ERROR,
             }
         })?;
-    Ok(Json(job))
+    Ok(Json(job.into()))
 }
 
 #[utoipa::path(
     get,
     path = "/schedule/list",
+    params(ListSchedulesQuery),
     responses(
         (status = 200, description = "A list of scheduled jobs", body = ListSchedulesResponse),
         (status = 500, description = "Internal server error")
@@ -157,6 +253,7 @@ async fn create_schedule(
 async fn list_schedules(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
+    Query(query): Query<ListSchedulesQuery>,
 ) -> Result<Json<ListSchedulesResponse>, StatusCode> {
     verify_secret_key(&headers, &state)?;
     let scheduler = state
@@ -169,7 +266,19 @@ async fn list_schedules(
         eprintln!("Error listing schedules: {:?}", e);
         StatusCode::INTERNAL_SERVER_ERROR
     })?;
-    Ok(Json(ListSchedulesResponse { jobs }))
+    let now = Utc::now();
+    let count = query.next.min(MAX_NEXT_RUNS);
+    let next_runs = jobs
+        .iter()
+        .filter(|job| !job.cron.is_empty())
+        .filter_map(|job| {
+            next_fire_times(&job.cron, job.timezone.as_deref(), now, count)
+                .ok()
+                .map(|times| (job.id.clone(), times))
+        })
+        .collect();
+    let jobs = jobs.into_iter().map(ScheduledJobResponse::from).collect();
+    Ok(Json(ListSchedulesResponse { jobs, next_runs }))
 }
 
 #[utoipa::path(
@@ -215,6 +324,7 @@ async fn delete_schedule(
     responses(
         (status = 200, description = "Scheduled job triggered successfully, returns new session ID", body = RunNowResponse),
         (status = 404, description = "Scheduled job not found"),
+        (status = 409, description = "Scheduled job is already running and does not queue runs"),
         (status = 500, description = "Internal server error when trying to run the job")
     ),
     tag = "schedule"
@@ -239,6 +349,7 @@ async fn run_now_handler(
             eprintln!("Error running schedule '{}' now: {:?}", id, e);
             match e {
                 goose::scheduler::SchedulerError::JobNotFound(_) => Err(StatusCode::NOT_FOUND),
+                goose::scheduler::SchedulerError::JobAlreadyRunning(_) => Err(StatusCode::CONFLICT),
                 goose::scheduler::SchedulerError::AnyhowError(ref err) => {
                     // Check if this is a cancellation error
                     if err.to_string().contains("was successfully cancelled") {
@@ -256,6 +367,71 @@ async fn run_now_handler(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/schedule/{id}/webhook",
+    params(
+        ("id" = String, Path, description = "ID of the schedule to trigger")
+    ),
+    request_body(content = Object, description = "Optional JSON payload; object fields are passed as recipe parameters"),
+    responses(
+        (status = 202, description = "Scheduled job triggered"),
+        (status = 400, description = "Payload is not valid JSON"),
+        (status = 401, description = "Missing or invalid webhook secret"),
+        (status = 404, description = "Scheduled job not found or has no webhook trigger"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "schedule"
+)]
+async fn webhook_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    body: axum::body::Bytes,
+) -> Result<StatusCode, StatusCode> {
+    // Webhook callers authenticate with the job's own secret rather than the server key
+    let scheduler = state
+        .scheduler()
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let job = scheduler
+        .list_scheduled_jobs()
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .into_iter()
+        .find(|job| job.id == id)
+        .ok_or(StatusCode::NOT_FOUND)?;
+    if !job
+        .triggers
+        .iter()
+        .any(|trigger| matches!(trigger, JobTrigger::Webhook { .. }))
+    {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    let provided = headers
+        .get(WEBHOOK_SECRET_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+    if !verify_webhook_secret(&job, provided) {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    let payload = if body.is_empty() {
+        serde_json::Value::Null
+    } else {
+        serde_json::from_slice(&body).map_err(|_| StatusCode::BAD_REQUEST)?
+    };
+    let params = webhook_parameters(&payload);
+
+    tracing::info!("Server: webhook triggered schedule '{}'", id);
+    tokio::spawn(async move {
+        if let Err(e) = scheduler.trigger_job(&id, params).await {
+            tracing::error!("Webhook run of schedule '{}' failed: {}", id, e);
+        }
+    });
+    Ok(StatusCode::ACCEPTED)
+}
+
 #[utoipa::path(
     get,
     path = "/schedule/{id}/sessions",
@@ -287,6 +463,15 @@ async fn sessions_handler(
         .await
     {
         Ok(session_tuples) => {
+            let runs = scheduler
+                .runs(&schedule_id_param, usize::MAX)
+                .await
+                .unwrap_or_default();
+            let run_for_session = |session_name: &str| {
+                runs.iter()
+                    .find(|run| run.session_id.as_deref() == Some(session_name))
+                    .cloned()
+            };
             // Expecting Vec<(String, goose::session::storage::SessionMetadata)>
             let display_infos: Vec<SessionDisplayInfo> = session_tuples
                 .into_iter()
@@ -303,6 +488,7 @@ async fn sessions_handler(
                     accumulated_total_tokens: metadata.accumulated_total_tokens,
                     accumulated_input_tokens: metadata.accumulated_input_tokens,
                     accumulated_output_tokens: metadata.accumulated_output_tokens,
+                    run: run_for_session(&session_name),
                 })
                 .collect();
             Ok(Json(display_infos))
@@ -397,7 +583,7 @@ async fn unpause_schedule(
     ),
     request_body = UpdateScheduleRequest,
     responses(
-        (status = 200, description = "Scheduled job updated successfully", body = ScheduledJob),
+        (status = 200, description = "Scheduled job updated successfully", body = ScheduledJobResponse),
         (status = 404, description = "Scheduled job not found"),
         (status = 400, description = "Cannot update a currently running job or invalid request"),
         (status = 500, description = "Internal server error")
@@ -410,7 +596,7 @@ async fn update_schedule(
     headers: HeaderMap,
     Path(id): Path<String>,
     Json(req): Json<UpdateScheduleRequest>,
-) -> Result<Json<ScheduledJob>, StatusCode> {
+) -> Result<Json<ScheduledJobResponse>, StatusCode> {
     verify_secret_key(&headers, &state)?;
     let scheduler = state
         .scheduler()
@@ -418,7 +604,7 @@ async fn update_schedule(
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
     scheduler
-        .update_schedule(&id, req.cron)
+        .update_schedule(&id, req.cron, req.timezone)
         .await
         .map_err(|e| {
             eprintln!("Error updating schedule '{}': {:?}", id, e);
@@ -440,7 +626,7 @@ async fn update_schedule(
         .find(|job| job.id == id)
         .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
 
-    Ok(Json(updated_job))
+    Ok(Json(updated_job.into()))
 }
 
 #[utoipa::path(
@@ -540,6 +726,38 @@ pub fn routes(state: Arc<AppState>) -> Router {
         .route("/schedule/{id}/unpause", post(unpause_schedule))
         .route("/schedule/{id}/kill", post(kill_running_job))
         .route("/schedule/{id}/inspect", get(inspect_running_job))
+        .route("/schedule/{id}/webhook", post(webhook_handler))
         .route("/schedule/{id}/sessions", get(sessions_handler)) // Corrected
         .with_state(state)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_job_response_redacts_webhook_secret() {
+        let job: ScheduledJob = serde_json::from_value(serde_json::json!({
+            "id": "nightly",
+            "source": "/tmp/nightly.yaml",
+            "cron": "",
+            "triggers": [
+                {"type": "webhook", "secret": "s3cret"},
+                {"type": "after_job", "job_id": "build"}
+            ]
+        }))
+        .unwrap();
+
+        let response = serde_json::to_value(ScheduledJobResponse::from(job)).unwrap();
+
+        assert!(!response.to_string().contains("s3cret"));
+        assert_eq!(
+            response["triggers"],
+            serde_json::json!([
+                {"type": "webhook", "has_secret": true},
+                {"type": "after_job", "job_id": "build"}
+            ])
+        );
+        assert_eq!(response["id"], "nightly");
+    }
+}
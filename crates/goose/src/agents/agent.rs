@@ -20,7 +20,7 @@ use crate::agents::prompt_manager::PromptManager;
 use crate::agents::recipe_tools::dynamic_task_tools::{
     create_dynamic_task, create_dynamic_task_tool, DYNAMIC_TASK_TOOL_NAME_PREFIX,
 };
-use crate::agents::retry::{RetryManager, RetryResult};
+use crate::agents::retry::{RetryManager, RetryResult, SuccessCheckContext};
 use crate::agents::router_tools::ROUTER_LLM_SEARCH_TOOL_NAME;
 use crate::agents::sub_recipe_manager::SubRecipeManager;
 use crate::agents::subagent_execution_tool::subagent_execute_task_tool::{
@@ -31,11 +31,12 @@ use crate::agents::tool_route_manager::ToolRouteManager;
 use crate::agents::tool_router_index_manager::ToolRouterIndexManager;
 use crate::agents::types::SessionConfig;
 use crate::agents::types::{FrontendTool, ToolResultReceiver};
-use crate::config::{Config, ExtensionConfigManager, PermissionManager};
+use crate::config::extensions::name_to_key;
+use crate::config::{Config, ExtensionConfigManager, PermissionManager, ProjectPermissions};
 use crate::context_mgmt::auto_compact;
 use crate::conversation::{debug_conversation_fix, fix_conversation, Conversation};
 use crate::permission::permission_judge::{check_tool_permissions, PermissionCheckResult};
-use crate::permission::PermissionConfirmation;
+use crate::permission::{PermissionConfirmation, PermissionDecisionSource};
 use crate::providers::base::Provider;
 use crate::providers::errors::ProviderError;
 use crate::recipe::{Author, Recipe, Response, Settings, SubRecipe};
@@ -55,17 +56,24 @@ use tracing::{debug, error, info, instrument};
 
 use super::final_output_tool::FinalOutputTool;
 use super::platform_tools;
-use super::tool_execution::{ToolCallResult, CHAT_MODE_TOOL_SKIPPED_RESPONSE, DECLINED_RESPONSE};
+use super::tool_execution::{
+    ToolAuditor, ToolCallResult, CHAT_MODE_TOOL_SKIPPED_RESPONSE, DECLINED_RESPONSE,
+};
 use crate::agents::subagent_task_config::TaskConfig;
 use crate::agents::todo_tools::{
     // todo_read_tool, todo_write_tool, // TODO: Re-enable after next release
     TODO_READ_TOOL_NAME,
     TODO_WRITE_TOOL_NAME,
 };
+use crate::agents::tool_audit::{AuditDecision, ToolAuditLog};
 use crate::conversation::message::{Message, ToolRequest};
 
 const DEFAULT_MAX_TURNS: u32 = 1000;
 
+/// Sent as the last assistant message when a reply stops at the turn limit
+pub const MAX_TURNS_REACHED_MESSAGE: &str =
+    "I've reached the maximum number of actions I can do without user input. Would you like me to continue?";
+
 /// Context needed for the reply function
 pub struct ReplyContext {
     pub messages: Conversation,
@@ -104,6 +112,10 @@ pub struct Agent {
     pub(super) scheduler_service: Mutex<Option<Arc<dyn SchedulerTrait>>>,
     pub(super) retry_manager: RetryManager,
     pub(super) todo_list: Arc<Mutex<String>>,
+    pub(super) audit_log: Option<Arc<ToolAuditLog>>,
+    /// Why the enabled audit log could not be opened. Replies fail while this is set.
+    pub(super) audit_log_error: Option<String>,
+    pub(super) extension_filter: Mutex<Option<Vec<String>>>,
 }
 
 #[derive(Clone, Debug)]
@@ -172,6 +184,14 @@ impl Agent {
         let tool_monitor = Arc::new(Mutex::new(None));
         let retry_manager = RetryManager::with_tool_monitor(tool_monitor.clone());
 
+        let (audit_log, audit_log_error) = match ToolAuditLog::from_config() {
+            Ok(audit_log) => (audit_log, None),
+            Err(e) => {
+                tracing::error!("{:#}", e);
+                (None, Some(format!("{:#}", e)))
+            }
+        };
+
         Self {
             provider: Mutex::new(None),
             extension_manager: Arc::new(RwLock::new(ExtensionManager::new())),
@@ -190,6 +210,9 @@ impl Agent {
             scheduler_service: Mutex::new(None),
             retry_manager,
             todo_list: Arc::new(Mutex::new(String::new())),
+            audit_log,
+            audit_log_error,
+            extension_filter: Mutex::new(None),
         }
     }
 
@@ -219,10 +242,30 @@ impl Agent {
         messages: &mut Conversation,
         session: &Option<SessionConfig>,
         initial_messages: &[Message],
+        final_text: &str,
     ) -> Result<bool> {
+        let (final_output, response_schema) = match self.final_output_tool.lock().await.as_ref() {
+            Some(tool) => (tool.final_output.clone(), tool.response.json_schema.clone()),
+            None => (None, None),
+        };
+        let final_text = final_text.trim();
+        let check_context = SuccessCheckContext {
+            final_output: final_output
+                .or_else(|| (!final_text.is_empty()).then(|| final_text.to_string())),
+            response_schema,
+            provider: self.provider().await.ok(),
+            working_dir: session.as_ref().map(|s| s.working_dir.clone()),
+        };
+
         let result = self
             .retry_manager
-            .handle_retry_logic(messages, session, initial_messages, &self.final_output_tool)
+            .handle_retry_logic(
+                messages,
+                session,
+                initial_messages,
+                &self.final_output_tool,
+                &check_context,
+            )
             .await?;
 
         match result {
@@ -292,28 +335,44 @@ impl Agent {
         permission_check_result: &PermissionCheckResult,
         message_tool_response: Arc<Mutex<Message>>,
         cancel_token: Option<tokio_util::sync::CancellationToken>,
+        auditor: Option<&ToolAuditor>,
     ) -> Result<Vec<(String, ToolStream)>> {
         let mut tool_futures: Vec<(String, ToolStream)> = Vec::new();
+        let source_of = |request: &ToolRequest| {
+            permission_check_result
+                .sources
+                .get(&request.id)
+                .copied()
+                .unwrap_or(PermissionDecisionSource::Rule)
+        };
 
         // Handle pre-approved and read-only tools
         for request in &permission_check_result.approved {
             if let Ok(tool_call) = request.tool_call.clone() {
+                // The call is on record before the tool starts
+                let pending = auditor
+                    .map(|auditor| auditor.start(request, source_of(request)))
+                    .transpose()?;
                 let (req_id, tool_result) = self
                     .dispatch_tool_call(tool_call, request.id.clone(), cancel_token.clone())
                     .await;
 
+                let stream = match tool_result {
+                    Ok(result) => tool_stream(
+                        result
+                            .notification_stream
+                            .unwrap_or_else(|| Box::new(stream::empty())),
+                        result.result,
+                    ),
+                    Err(e) => {
+                        tool_stream(Box::new(stream::empty()), futures::future::ready(Err(e)))
+                    }
+                };
                 tool_futures.push((
                     req_id,
-                    match tool_result {
-                        Ok(result) => tool_stream(
-                            result
-                                .notification_stream
-                                .unwrap_or_else(|| Box::new(stream::empty())),
-                            result.result,
-                        ),
-                        Err(e) => {
-                            tool_stream(Box::new(stream::empty()), futures::future::ready(Err(e)))
-                        }
+                    match pending {
+                        Some(pending) => pending.track(stream),
+                        None => stream,
                     },
                 ));
             }
@@ -321,6 +380,9 @@ impl Agent {
 
         // Handle denied tools
         for request in &permission_check_result.denied {
+            if let Some(auditor) = auditor {
+                auditor.record_not_run(request, AuditDecision::Denied, source_of(request))?;
+            }
             let mut response = message_tool_response.lock().await;
             *response = response.clone().with_tool_response(
                 request.id.clone(),
@@ -372,6 +434,12 @@ impl Agent {
         sub_recipe_manager.add_sub_recipe_tools(sub_recipes);
     }
 
+    /// Stop offering the sub-recipes with these names
+    pub async fn remove_sub_recipes(&self, sub_recipe_names: &[String]) {
+        let mut sub_recipe_manager = self.sub_recipe_manager.lock().await;
+        sub_recipe_manager.remove_sub_recipe_tools(sub_recipe_names);
+    }
+
     /// Dispatch a single tool call to the appropriate client
     #[instrument(skip(self, tool_call, request_id), fields(input, output))]
     pub async fn dispatch_tool_call(
@@ -786,6 +854,30 @@ impl Agent {
         prefixed_tools
     }
 
+    /// Only offer the model the tools of these extensions, until cleared with `None`. Tools
+    /// that do not come from an extension, like the platform tools, are always offered.
+    pub async fn set_extension_filter(&self, extensions: Option<Vec<String>>) {
+        *self.extension_filter.lock().await =
+            extensions.map(|names| names.iter().map(|name| name_to_key(name)).collect());
+    }
+
+    /// Drops the tools of extensions left out by the extension filter
+    pub(super) async fn filter_extension_tools(&self, tools: Vec<Tool>) -> Vec<Tool> {
+        let Some(allowed) = self.extension_filter.lock().await.clone() else {
+            return tools;
+        };
+        let extensions = self.list_extensions().await;
+        tools
+            .into_iter()
+            .filter(|tool| match tool.name.split_once("__") {
+                Some((prefix, _)) if extensions.iter().any(|name| name == prefix) => {
+                    allowed.iter().any(|name| name == prefix)
+                }
+                _ => true,
+            })
+            .collect()
+    }
+
     pub async fn list_tools_for_router(&self) -> Vec<Tool> {
         self.tool_route_manager
             .list_tools_for_router(&self.extension_manager)
@@ -896,6 +988,14 @@ impl Agent {
         session: Option<SessionConfig>,
         cancel_token: Option<CancellationToken>,
     ) -> Result<BoxStream<'_, Result<AgentEvent>>> {
+        if let Some(error) = &self.audit_log_error {
+            return Err(anyhow!(
+                "The tool audit log is enabled (GOOSE_AUDIT_LOG) but unavailable, so no tools \
+                 can run: {}. Repair or move the log, or disable GOOSE_AUDIT_LOG.",
+                error
+            ));
+        }
+
         // Handle auto-compaction before processing
         let (messages, compaction_msg, _summarization_usage) = match self
             .handle_auto_compaction(unfixed_conversation.messages(), &session)
@@ -947,6 +1047,13 @@ impl Agent {
         } = context;
         let reply_span = tracing::Span::current();
         self.reset_retry_attempts().await;
+        let auditor = self.audit_log.clone().map(|log| {
+            let session_id = session.as_ref().map(|s| match &s.id {
+                session::Identifier::Name(name) => name.clone(),
+                session::Identifier::Path(path) => path.display().to_string(),
+            });
+            ToolAuditor::new(log, session_id)
+        });
 
         if let Some(content) = messages
             .last()
@@ -983,9 +1090,7 @@ impl Agent {
 
                 turns_taken += 1;
                 if turns_taken > max_turns {
-                    yield AgentEvent::Message(Message::assistant().with_text(
-                        "I've reached the maximum number of actions I can do without user input. Would you like me to continue?"
-                    ));
+                    yield AgentEvent::Message(Message::assistant().with_text(MAX_TURNS_REACHED_MESSAGE));
                     break;
                 }
 
@@ -999,6 +1104,7 @@ impl Agent {
 
                 let mut added_message = false;
                 let mut messages_to_add = Vec::new();
+                let mut final_text = String::new();
                 let mut tools_updated = false;
 
                 while let Some(next) = stream.next().await {
@@ -1028,6 +1134,11 @@ impl Agent {
                                     };
                                 }
                             }
+                            if let Some(fallback) = provider.as_fallback() {
+                                if let Some((model, mode)) = fallback.take_model_change() {
+                                    yield AgentEvent::ModelChange { model, mode };
+                                }
+                            }
 
                             // Record usage for the session
                             if let Some(ref session_config) = &session {
@@ -1055,6 +1166,7 @@ impl Agent {
 
                                 let num_tool_requests = frontend_requests.len() + remaining_requests.len();
                                 if num_tool_requests == 0 {
+                                    final_text.push_str(&filtered_response.as_concat_text());
                                     continue;
                                 }
 
@@ -1075,6 +1187,9 @@ impl Agent {
                                 if mode.as_str() == "chat" {
                                     // Skip all tool calls in chat mode
                                     for request in remaining_requests {
+                                        if let Some(auditor) = &auditor {
+                                            auditor.record_not_run(&request, AuditDecision::Skipped, PermissionDecisionSource::Rule)?;
+                                        }
                                         let mut response = message_tool_response.lock().await;
                                         *response = response.clone().with_tool_response(
                                             request.id.clone(),
@@ -1082,7 +1197,10 @@ impl Agent {
                                         );
                                     }
                                 } else {
-                                    let mut permission_manager = PermissionManager::default();
+                                    let mut permission_manager = match &session {
+                                        Some(session) => PermissionManager::default().with_project_for(&session.working_dir),
+                                        None => PermissionManager::default(),
+                                    };
                                     let (permission_check_result, enable_extension_request_ids) =
                                         check_tool_permissions(
                                             &remaining_requests,
@@ -1092,11 +1210,17 @@ impl Agent {
                                             &mut permission_manager,
                                             self.provider().await?,
                                         ).await;
+                                    if let Some(auditor) = &auditor {
+                                        for request in remaining_requests.iter().filter(|r| r.tool_call.is_err()) {
+                                            auditor.record_not_run(request, AuditDecision::Skipped, PermissionDecisionSource::Rule)?;
+                                        }
+                                    }
 
                                     let mut tool_futures = self.handle_approved_and_denied_tools(
                                         &permission_check_result,
                                         message_tool_response.clone(),
-                                        cancel_token.clone()
+                                        cancel_token.clone(),
+                                        auditor.as_ref(),
                                     ).await?;
 
                                     let tool_futures_arc = Arc::new(Mutex::new(tool_futures));
@@ -1108,6 +1232,7 @@ impl Agent {
                                         &mut permission_manager,
                                         message_tool_response.clone(),
                                         cancel_token.clone(),
+                                        auditor.clone(),
                                     );
 
                                     while let Some(msg) = tool_approval_stream.try_next().await? {
@@ -1157,6 +1282,11 @@ impl Agent {
                                     }
                                 }
 
+                                // Stop before the model sees results whose outcome is not on record
+                                if let Some(auditor) = &auditor {
+                                    auditor.check()?;
+                                }
+
                                 let final_message_tool_resp = message_tool_response.lock().await.clone();
                                 yield AgentEvent::Message(final_message_tool_resp.clone());
 
@@ -1198,8 +1328,11 @@ impl Agent {
                         }
                     }
 
-                    match self.handle_retry_logic(&mut messages, &session, &initial_messages).await {
+                    match self.handle_retry_logic(&mut messages, &session, &initial_messages, &final_text).await {
                         Ok(should_retry) => {
+                            if let Some(report) = self.retry_manager.take_check_report().await {
+                                yield AgentEvent::Message(report);
+                            }
                             if should_retry {
                                 info!("Retry logic triggered, restarting agent loop");
                                 continue;
@@ -1223,14 +1356,22 @@ impl Agent {
     }
 
     fn determine_goose_mode(session: Option<&SessionConfig>, config: &Config) -> String {
+        if let Some(mode) = session.and_then(|s| s.goose_mode.clone()) {
+            return mode;
+        }
         let mode = session.and_then(|s| s.execution_mode.as_deref());
 
         match mode {
             Some("foreground") => "chat".to_string(),
             Some("background") => "auto".to_string(),
-            _ => config
-                .get_param("GOOSE_MODE")
-                .unwrap_or_else(|_| "auto".to_string()),
+            _ => session
+                .and_then(|s| ProjectPermissions::discover_trusted(&s.working_dir))
+                .and_then(|project| project.config.mode)
+                .unwrap_or_else(|| {
+                    config
+                        .get_param("GOOSE_MODE")
+                        .unwrap_or_else(|_| "auto".to_string())
+                }),
         }
     }
 
@@ -1456,6 +1597,8 @@ impl Agent {
             goose_provider: Some(provider_name.clone()),
             goose_model: Some(model_name.clone()),
             temperature: Some(model_config.temperature.unwrap_or(0.0)),
+            fallback_models: None,
+            approval: None,
         };
 
         let recipe = Recipe::builder()
@@ -1514,6 +1657,130 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_session_goose_mode_takes_precedence() {
+        let mut session = SessionConfig {
+            id: session::Identifier::Name("test".to_string()),
+            working_dir: std::env::temp_dir(),
+            schedule_id: None,
+            execution_mode: Some("background".to_string()),
+            max_turns: None,
+            goose_mode: Some("approve".to_string()),
+            retry_config: None,
+        };
+        let config = Config::global();
+
+        assert_eq!(
+            Agent::determine_goose_mode(Some(&session), config),
+            "approve"
+        );
+        session.goose_mode = None;
+        assert_eq!(Agent::determine_goose_mode(Some(&session), config), "auto");
+    }
+
+    struct StubClient;
+
+    #[async_trait::async_trait]
+    impl mcp_client::client::McpClientTrait for StubClient {
+        fn get_info(&self) -> Option<&rmcp::model::InitializeResult> {
+            None
+        }
+
+        async fn list_resources(
+            &self,
+            _next_cursor: Option<String>,
+            _cancellation_token: CancellationToken,
+        ) -> Result<rmcp::model::ListResourcesResult, mcp_client::client::Error> {
+            Err(mcp_client::client::Error::TransportClosed)
+        }
+
+        async fn read_resource(
+            &self,
+            _uri: &str,
+            _cancellation_token: CancellationToken,
+        ) -> Result<rmcp::model::ReadResourceResult, mcp_client::client::Error> {
+            Err(mcp_client::client::Error::TransportClosed)
+        }
+
+        async fn list_tools(
+            &self,
+            _next_cursor: Option<String>,
+            _cancellation_token: CancellationToken,
+        ) -> Result<rmcp::model::ListToolsResult, mcp_client::client::Error> {
+            Ok(rmcp::model::ListToolsResult {
+                tools: vec![Tool::new(
+                    "lookup",
+                    "Look something up",
+                    serde_json::json!({"type": "object"})
+                        .as_object()
+                        .unwrap()
+                        .clone(),
+                )],
+                next_cursor: None,
+            })
+        }
+
+        async fn call_tool(
+            &self,
+            _name: &str,
+            _arguments: Value,
+            _cancellation_token: CancellationToken,
+        ) -> Result<rmcp::model::CallToolResult, mcp_client::client::Error> {
+            Err(mcp_client::client::Error::TransportClosed)
+        }
+
+        async fn list_prompts(
+            &self,
+            _next_cursor: Option<String>,
+            _cancellation_token: CancellationToken,
+        ) -> Result<rmcp::model::ListPromptsResult, mcp_client::client::Error> {
+            Err(mcp_client::client::Error::TransportClosed)
+        }
+
+        async fn get_prompt(
+            &self,
+            _name: &str,
+            _arguments: Value,
+            _cancellation_token: CancellationToken,
+        ) -> Result<rmcp::model::GetPromptResult, mcp_client::client::Error> {
+            Err(mcp_client::client::Error::TransportClosed)
+        }
+
+        async fn subscribe(&self) -> mpsc::Receiver<rmcp::model::ServerNotification> {
+            mpsc::channel(1).1
+        }
+    }
+
+    #[tokio::test]
+    async fn test_extension_filter_keeps_platform_tools() {
+        let agent = Agent::new();
+        agent
+            .extension_manager
+            .write()
+            .await
+            .add_client("stub".to_string(), Box::new(StubClient));
+        let tools = agent.list_tools(None).await;
+        assert!(tools.iter().any(|tool| tool.name == "stub__lookup"));
+
+        agent
+            .set_extension_filter(Some(vec!["developer".to_string()]))
+            .await;
+        let filtered = agent.filter_extension_tools(tools.clone()).await;
+        assert!(!filtered.iter().any(|tool| tool.name == "stub__lookup"));
+        assert_eq!(filtered.len(), tools.len() - 1);
+        assert!(filtered
+            .iter()
+            .any(|tool| tool.name == PLATFORM_MANAGE_SCHEDULE_TOOL_NAME));
+
+        agent
+            .set_extension_filter(Some(vec!["stub".to_string()]))
+            .await;
+        assert_eq!(
+            agent.filter_extension_tools(tools.clone()).await.len(),
+            tools.len()
+        );
+    }
+
     #[tokio::test]
     #[ignore] // TODO: Re-enable after next release when TODO tools are re-enabled
     async fn test_todo_tools_integration() -> Result<()> {
@@ -17,20 +17,35 @@ pub const SUB_RECIPE_TASK_TOOL_NAME_PREFIX: &str = "subrecipe__create_task";
 pub fn create_sub_recipe_task_tool(sub_recipe: &SubRecipe) -> Tool {
     let input_schema = get_input_schema(sub_recipe).unwrap();
 
+    let mut description = format!(
+        "Create one or more tasks to run the '{}' sub recipe. \
+        Provide an array of parameter sets in the 'task_parameters' field:\n\
+        - For a single task: provide an array with one parameter set\n\
+        - For multiple tasks: provide an array with multiple parameter sets, each with different values\n\n\
+        Each task will run the same sub recipe but with different parameter values. \
+        This is useful when you need to execute the same sub recipe multiple times with varying inputs. \
+        After creating the tasks and execution_mode is provided, pass them to the task executor to run these tasks",
+        sub_recipe.name
+    );
+    if let Some(depends_on) = sub_recipe
+        .depends_on
+        .as_ref()
+        .filter(|deps| !deps.is_empty())
+    {
+        description.push_str(&format!(
+            "\n\nThis sub recipe depends on: {}. Create tasks for those sub recipes as well and pass \
+            all of the task ids to the task executor in a single call; it runs them in dependency order \
+            and passes their outputs along.",
+            depends_on.join(", ")
+        ));
+    }
+
     Tool::new(
         format!("{}_{}", SUB_RECIPE_TASK_TOOL_NAME_PREFIX, sub_recipe.name),
-        format!(
-            "Create one or more tasks to run the '{}' sub recipe. \
-            Provide an array of parameter sets in the 'task_parameters' field:\n\
-            - For a single task: provide an array with one parameter set\n\
-            - For multiple tasks: provide an array with multiple parameter sets, each with different values\n\n\
-            Each task will run the same sub recipe but with different parameter values. \
-            This is useful when you need to execute the same sub recipe multiple times with varying inputs. \
-            After creating the tasks and execution_mode is provided, pass them to the task executor to run these tasks",
-            sub_recipe.name
-        ),
-        Arc::new(input_schema.as_object().unwrap().clone())
-    ).annotate(ToolAnnotations {
+        description,
+        Arc::new(input_schema.as_object().unwrap().clone()),
+    )
+    .annotate(ToolAnnotations {
         title: Some(format!(
             "create multiple sub recipe tasks for {}",
             sub_recipe.name
@@ -62,7 +77,9 @@ fn create_tasks_from_params(
                     "name": sub_recipe.name.clone(),
                     "command_parameters": task_command_param,
                     "recipe_path": sub_recipe.path.clone(),
-                    "sequential_when_repeated": sub_recipe.sequential_when_repeated
+                    "sequential_when_repeated": sub_recipe.sequential_when_repeated,
+                    "depends_on": sub_recipe.depends_on.clone().unwrap_or_default(),
+                    "inputs": sub_recipe.inputs.clone().unwrap_or_default()
                 }
             });
             Task {
@@ -121,6 +138,10 @@ fn get_params_with_values(sub_recipe: &SubRecipe) -> HashSet<String> {
             sub_recipe_params_with_values.insert(param_name.clone());
         }
     }
+    // Parameters mapped from a dependency's output are filled in when the task runs
+    if let Some(inputs) = &sub_recipe.inputs {
+        sub_recipe_params_with_values.extend(inputs.keys().cloned());
+    }
     sub_recipe_params_with_values
 }
 
@@ -44,6 +44,7 @@ impl Agent {
         if !router_enabled && tools.is_empty() {
             tools = self.list_tools(None).await;
         }
+        let mut tools = self.filter_extension_tools(tools).await;
 
         // Add frontend tools
         let frontend_tools = self.frontend_tools.lock().await;
@@ -1,4 +1,6 @@
 use anyhow::Result;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
 use std::sync::Arc;
 use std::time::Duration;
@@ -8,11 +10,14 @@ use tracing::{debug, info, warn};
 
 use crate::agents::types::SessionConfig;
 use crate::agents::types::{
-    RetryConfig, SuccessCheck, DEFAULT_ON_FAILURE_TIMEOUT_SECONDS, DEFAULT_RETRY_TIMEOUT_SECONDS,
+    RetryConfig, SuccessCheck, SuccessCheckResult, DEFAULT_ON_FAILURE_TIMEOUT_SECONDS,
+    DEFAULT_RETRY_TIMEOUT_SECONDS,
 };
 use crate::config::Config;
 use crate::conversation::message::Message;
 use crate::conversation::Conversation;
+use crate::prompt_template::render_global_file;
+use crate::providers::base::Provider;
 use crate::tool_monitor::ToolMonitor;
 
 /// Result of a retry logic evaluation
@@ -28,6 +33,27 @@ pub enum RetryResult {
     Retried,
 }
 
+/// Longest reason text kept for a single check result
+const MAX_CHECK_REASON_CHARS: usize = 2000;
+
+/// Inputs available to success checks beyond the retry configuration
+#[derive(Clone, Default)]
+pub struct SuccessCheckContext {
+    /// Final output of the run: the final output tool's result or the last assistant text
+    pub final_output: Option<String>,
+    /// JSON schema from the recipe's `response` section
+    pub response_schema: Option<serde_json::Value>,
+    /// Provider used to grade LLM judge checks
+    pub provider: Option<Arc<dyn Provider>>,
+    /// Directory that relative file check paths are resolved against
+    pub working_dir: Option<PathBuf>,
+}
+
+#[derive(Serialize)]
+struct SuccessJudgeContext {
+    rubric: String,
+}
+
 /// Environment variable for configuring retry timeout globally
 const GOOSE_RECIPE_RETRY_TIMEOUT_SECONDS: &str = "GOOSE_RECIPE_RETRY_TIMEOUT_SECONDS";
 
@@ -41,6 +67,8 @@ pub struct RetryManager {
     attempts: Arc<Mutex<u32>>,
     /// Optional tool monitor for reset operations
     tool_monitor: Option<Arc<Mutex<Option<ToolMonitor>>>>,
+    /// Report of failed success checks from the last evaluation, waiting to be surfaced
+    check_report: Arc<Mutex<Option<Message>>>,
 }
 
 impl Default for RetryManager {
@@ -55,6 +83,7 @@ impl RetryManager {
         Self {
             attempts: Arc::new(Mutex::new(0)),
             tool_monitor: None,
+            check_report: Arc::new(Mutex::new(None)),
         }
     }
 
@@ -63,6 +92,7 @@ impl RetryManager {
         Self {
             attempts: Arc::new(Mutex::new(0)),
             tool_monitor: Some(tool_monitor),
+            check_report: Arc::new(Mutex::new(None)),
         }
     }
 
@@ -91,6 +121,12 @@ impl RetryManager {
         *self.attempts.lock().await
     }
 
+    /// Take the report of failed success checks from the last evaluation, if any.
+    /// The caller surfaces it so the results are recorded in the session.
+    pub async fn take_check_report(&self) -> Option<Message> {
+        self.check_report.lock().await.take()
+    }
+
     /// Reset status for retry: clear message history and final output tool state
     async fn reset_status_for_retry(
         messages: &mut Conversation,
@@ -113,6 +149,7 @@ impl RetryManager {
         session: &Option<SessionConfig>,
         initial_messages: &[Message],
         final_output_tool: &Arc<Mutex<Option<crate::agents::final_output_tool::FinalOutputTool>>>,
+        check_context: &SuccessCheckContext,
     ) -> Result<RetryResult> {
         let Some(session_config) = session else {
             return Ok(RetryResult::Skipped);
@@ -122,20 +159,23 @@ impl RetryManager {
             return Ok(RetryResult::Skipped);
         };
 
-        let success = execute_success_checks(&retry_config.checks, retry_config).await?;
+        let results =
+            execute_success_checks(&retry_config.checks, retry_config, check_context).await?;
 
-        if success {
+        if results.iter().all(|result| result.passed) {
             info!("All success checks passed, no retry needed");
             return Ok(RetryResult::SuccessChecksPassed);
         }
+        let failures = format_failed_checks(&results);
 
         let current_attempts = self.get_attempts().await;
         if current_attempts >= retry_config.max_retries {
             let error_msg = Message::assistant().with_text(format!(
-                "Maximum retry attempts ({}) exceeded. Unable to complete the task successfully.",
-                retry_config.max_retries
+                "Maximum retry attempts ({}) exceeded. Unable to complete the task successfully.\n\nFailed success checks:\n{}",
+                retry_config.max_retries, failures
             ));
-            messages.push(error_msg);
+            messages.push(error_msg.clone());
+            *self.check_report.lock().await = Some(error_msg);
             warn!(
                 "Maximum retry attempts ({}) exceeded",
                 retry_config.max_retries
@@ -153,6 +193,14 @@ impl RetryManager {
         let new_attempts = self.increment_attempts().await;
         info!("Incrementing retry attempts to {}", new_attempts);
 
+        let feedback = Message::user().with_text(format!(
+            "The previous attempt did not pass the recipe's success checks (retry {} of {}). \
+             Fix the following and complete the task again:\n{}",
+            new_attempts, retry_config.max_retries, failures
+        ));
+        messages.push(feedback.clone());
+        *self.check_report.lock().await = Some(feedback);
+
         Ok(RetryResult::Retried)
     }
 }
@@ -187,34 +235,218 @@ fn get_on_failure_timeout(retry_config: &RetryConfig) -> Duration {
     Duration::from_secs(timeout_seconds)
 }
 
-/// Execute all success checks and return true if all pass
+fn format_failed_checks(results: &[SuccessCheckResult]) -> String {
+    results
+        .iter()
+        .filter(|result| !result.passed)
+        .map(|result| format!("- {}: {}", result.check, result.reason))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn truncate_reason(reason: &str) -> String {
+    let reason = reason.trim();
+    match reason.char_indices().nth(MAX_CHECK_REASON_CHARS) {
+        Some((index, _)) => format!("{}...", &reason[..index]),
+        None => reason.to_string(),
+    }
+}
+
+/// Execute all success checks and return the result of each one
 pub async fn execute_success_checks(
     checks: &[SuccessCheck],
     retry_config: &RetryConfig,
-) -> Result<bool> {
+    context: &SuccessCheckContext,
+) -> Result<Vec<SuccessCheckResult>> {
     let timeout = get_retry_timeout(retry_config);
+    let mut results = Vec::with_capacity(checks.len());
 
     for check in checks {
-        match check {
-            SuccessCheck::Shell { command } => {
-                let result = execute_shell_command(command, timeout).await?;
-                if !result.status.success() {
-                    warn!(
-                        "Success check failed: command '{}' exited with status {}, stderr: {}",
-                        command,
-                        result.status,
-                        String::from_utf8_lossy(&result.stderr)
-                    );
-                    return Ok(false);
-                }
-                info!(
-                    "Success check passed: command '{}' completed successfully",
-                    command
-                );
+        let outcome = match check {
+            SuccessCheck::Shell { command } => check_shell(command, timeout).await,
+            SuccessCheck::File { path, pattern } => {
+                check_file(path, pattern.as_deref(), context.working_dir.as_deref())
+            }
+            SuccessCheck::JsonSchema {} => check_json_schema(context),
+            SuccessCheck::Http {
+                url,
+                expected_status,
+            } => check_http(url, *expected_status, timeout).await,
+            SuccessCheck::LlmJudge { rubric, threshold } => {
+                check_llm_judge(rubric, *threshold, context).await
             }
+        };
+        let (passed, reason) = outcome.unwrap_or_else(|e| (false, e.to_string()));
+        let result = SuccessCheckResult {
+            check: check.describe(),
+            passed,
+            reason: truncate_reason(&reason),
+        };
+        if result.passed {
+            info!("Success check passed: {} ({})", result.check, result.reason);
+        } else {
+            warn!("Success check failed: {} ({})", result.check, result.reason);
+        }
+        results.push(result);
+    }
+    Ok(results)
+}
+
+async fn check_shell(command: &str, timeout: Duration) -> Result<(bool, String)> {
+    let output = execute_shell_command(command, timeout).await?;
+    if output.status.success() {
+        Ok((true, "command completed successfully".to_string()))
+    } else {
+        Ok((
+            false,
+            format!(
+                "command exited with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr)
+            ),
+        ))
+    }
+}
+
+fn check_file(
+    path: &str,
+    pattern: Option<&str>,
+    working_dir: Option<&Path>,
+) -> Result<(bool, String)> {
+    let full_path = match working_dir {
+        Some(dir) => dir.join(path),
+        None => PathBuf::from(path),
+    };
+    if !full_path.exists() {
+        return Ok((false, format!("{} does not exist", full_path.display())));
+    }
+    let Some(pattern) = pattern else {
+        return Ok((true, format!("{} exists", full_path.display())));
+    };
+    let regex = regex::Regex::new(pattern)?;
+    let content = std::fs::read_to_string(&full_path)?;
+    if regex.is_match(&content) {
+        Ok((true, format!("{} matches the pattern", full_path.display())))
+    } else {
+        Ok((
+            false,
+            format!("{} does not match /{}/", full_path.display(), pattern),
+        ))
+    }
+}
+
+fn check_json_schema(context: &SuccessCheckContext) -> Result<(bool, String)> {
+    let Some(schema) = &context.response_schema else {
+        return Ok((false, "the recipe has no response.json_schema".to_string()));
+    };
+    let Some(output) = &context.final_output else {
+        return Ok((false, "there was no final output to validate".to_string()));
+    };
+    let instance: serde_json::Value = match serde_json::from_str(output.trim()) {
+        Ok(instance) => instance,
+        Err(e) => return Ok((false, format!("final output is not valid JSON: {}", e))),
+    };
+    let validator = jsonschema::validator_for(schema)
+        .map_err(|e| anyhow::anyhow!("invalid response schema: {}", e))?;
+    let errors: Vec<String> = validator
+        .iter_errors(&instance)
+        .map(|e| format!("{} at {}", e, e.instance_path))
+        .collect();
+    if errors.is_empty() {
+        Ok((true, "final output matches the schema".to_string()))
+    } else {
+        Ok((false, errors.join("; ")))
+    }
+}
+
+const MAX_HTTP_CHECK_REDIRECTS: usize = 5;
+
+async fn check_http(url: &str, expected_status: u16, timeout: Duration) -> Result<(bool, String)> {
+    // The url was validated as local; only follow redirects that stay on the same host
+    let host = reqwest::Url::parse(url)?.host_str().map(str::to_string);
+    let policy = reqwest::redirect::Policy::custom(move |attempt| {
+        if attempt.previous().len() >= MAX_HTTP_CHECK_REDIRECTS
+            || attempt.url().host_str() != host.as_deref()
+        {
+            attempt.stop()
+        } else {
+            attempt.follow()
         }
+    });
+    let client = reqwest::Client::builder()
+        .timeout(timeout)
+        .redirect(policy)
+        .build()?;
+    let status = client.get(url).send().await?.status();
+    if status.as_u16() == expected_status {
+        Ok((true, format!("responded with {}", status)))
+    } else {
+        Ok((
+            false,
+            format!("responded with {}, expected {}", status, expected_status),
+        ))
     }
-    Ok(true)
+}
+
+async fn check_llm_judge(
+    rubric: &str,
+    threshold: f64,
+    context: &SuccessCheckContext,
+) -> Result<(bool, String)> {
+    let Some(provider) = &context.provider else {
+        return Ok((
+            false,
+            "no provider is available to judge the output".to_string(),
+        ));
+    };
+    let Some(output) = &context.final_output else {
+        return Ok((
+            false,
+            "there was no final assistant message to judge".to_string(),
+        ));
+    };
+
+    let system_prompt = render_global_file(
+        "success_judge.md",
+        &SuccessJudgeContext {
+            rubric: rubric.to_string(),
+        },
+    )?;
+    let request =
+        Message::user().with_text(format!("Grade this final assistant message:\n\n{}", output));
+    let (response, _usage) = provider.complete(&system_prompt, &[request], &[]).await?;
+    let (score, reason) = parse_judge_verdict(&response.as_concat_text())?;
+
+    Ok((
+        score >= threshold,
+        format!(
+            "scored {:.2} (threshold {:.2}): {}",
+            score, threshold, reason
+        ),
+    ))
+}
+
+/// Extracts `{"score": <0.0-1.0>, "reason": "..."}` from the judge's reply
+fn parse_judge_verdict(text: &str) -> Result<(f64, String)> {
+    let start = text.find('{');
+    let end = text.rfind('}');
+    let (Some(start), Some(end)) = (start, end) else {
+        return Err(anyhow::anyhow!("judge reply did not contain a verdict"));
+    };
+    if end < start {
+        return Err(anyhow::anyhow!("judge reply did not contain a verdict"));
+    }
+    let verdict: serde_json::Value = serde_json::from_str(&text[start..=end])?;
+    let score = verdict
+        .get("score")
+        .and_then(|score| score.as_f64())
+        .ok_or_else(|| anyhow::anyhow!("judge verdict is missing a numeric score"))?;
+    let reason = verdict
+        .get("reason")
+        .and_then(|reason| reason.as_str())
+        .unwrap_or_default()
+        .to_string();
+    Ok((score.clamp(0.0, 1.0), reason))
 }
 
 /// Execute a shell command with cross-platform compatibility and mandatory timeout
@@ -312,7 +544,7 @@ pub async fn execute_on_failure_command(command: &str, retry_config: &RetryConfi
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::agents::types::SuccessCheck;
+    use crate::agents::types::{SuccessCheck, DEFAULT_JUDGE_THRESHOLD};
 
     fn create_test_retry_config() -> RetryConfig {
         RetryConfig {
@@ -356,9 +588,9 @@ mod tests {
         ];
         let retry_config = create_test_retry_config();
 
-        let result = execute_success_checks(&checks, &retry_config).await;
+        let result = execute_success_checks(&checks, &retry_config, &Default::default()).await;
         assert!(result.is_ok());
-        assert!(result.unwrap());
+        assert!(result.unwrap().iter().all(|r| r.passed));
     }
 
     #[tokio::test]
@@ -373,9 +605,183 @@ mod tests {
         ];
         let retry_config = create_test_retry_config();
 
-        let result = execute_success_checks(&checks, &retry_config).await;
+        let result = execute_success_checks(&checks, &retry_config, &Default::default()).await;
         assert!(result.is_ok());
-        assert!(!result.unwrap());
+        let results = result.unwrap();
+        assert!(results[0].passed);
+        assert!(!results[1].passed);
+        assert!(results[1].reason.contains("exited with"));
+    }
+
+    #[tokio::test]
+    async fn test_file_and_json_schema_checks() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::fs::write(temp_dir.path().join("report.md"), "status: done\n").unwrap();
+        let checks = vec![
+            SuccessCheck::File {
+                path: "report.md".to_string(),
+                pattern: Some(r"status:\s+done".to_string()),
+            },
+            SuccessCheck::File {
+                path: "missing.md".to_string(),
+                pattern: None,
+            },
+            SuccessCheck::JsonSchema {},
+        ];
+        let context = SuccessCheckContext {
+            final_output: Some(r#"{"count": "three"}"#.to_string()),
+            response_schema: Some(serde_json::json!({
+                "type": "object",
+                "properties": {"count": {"type": "integer"}},
+                "required": ["count"]
+            })),
+            working_dir: Some(temp_dir.path().to_path_buf()),
+            ..Default::default()
+        };
+
+        let results = execute_success_checks(&checks, &create_test_retry_config(), &context)
+            .await
+            .unwrap();
+
+        assert!(results[0].passed);
+        assert!(!results[1].passed);
+        assert!(results[1].reason.contains("does not exist"));
+        assert!(!results[2].passed);
+        assert!(results[2].reason.contains("/count"));
+    }
+
+    #[tokio::test]
+    async fn test_http_check_against_local_server() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/health", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            use tokio::io::{AsyncReadExt, AsyncWriteExt};
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut buf = [0u8; 1024];
+                let _ = socket.read(&mut buf).await;
+                let _ = socket
+                    .write_all(b"HTTP/1.1 204 No Content\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+                    .await;
+            }
+        });
+        let checks = vec![
+            SuccessCheck::Http {
+                url: url.clone(),
+                expected_status: 204,
+            },
+            SuccessCheck::Http {
+                url,
+                expected_status: 200,
+            },
+        ];
+
+        let results =
+            execute_success_checks(&checks, &create_test_retry_config(), &Default::default())
+                .await
+                .unwrap();
+
+        assert!(results[0].passed);
+        assert!(!results[1].passed);
+        assert!(results[1].reason.contains("expected 200"));
+    }
+
+    #[tokio::test]
+    async fn test_http_check_does_not_follow_redirects_to_other_hosts() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            use tokio::io::{AsyncReadExt, AsyncWriteExt};
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut buf = [0u8; 1024];
+                let n = socket.read(&mut buf).await.unwrap_or(0);
+                let request = String::from_utf8_lossy(&buf[..n]).to_string();
+                let response = if request.starts_with("GET /ok ") {
+                    "HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string()
+                } else if request.starts_with("GET /same ") {
+                    format!(
+                        "HTTP/1.1 302 Found\r\nLocation: http://127.0.0.1:{}/ok\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                        port
+                    )
+                } else {
+                    format!(
+                        "HTTP/1.1 302 Found\r\nLocation: http://localhost:{}/ok\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                        port
+                    )
+                };
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+        let checks = vec![
+            SuccessCheck::Http {
+                url: format!("http://127.0.0.1:{}/same", port),
+                expected_status: 200,
+            },
+            SuccessCheck::Http {
+                url: format!("http://127.0.0.1:{}/elsewhere", port),
+                expected_status: 200,
+            },
+        ];
+
+        let results =
+            execute_success_checks(&checks, &create_test_retry_config(), &Default::default())
+                .await
+                .unwrap();
+
+        assert!(results[0].passed);
+        assert!(!results[1].passed);
+        assert!(results[1].reason.contains("302"));
+    }
+
+    #[test]
+    fn test_parse_judge_verdict() {
+        let (score, reason) =
+            parse_judge_verdict("Here you go: {\"score\": 0.8, \"reason\": \"Covers the rubric\"}")
+                .unwrap();
+        assert_eq!(score, 0.8);
+        assert_eq!(reason, "Covers the rubric");
+        assert!(parse_judge_verdict("no verdict").is_err());
+        assert!(parse_judge_verdict("{\"reason\": \"missing score\"}").is_err());
+    }
+
+    #[test]
+    fn test_success_check_validation() {
+        let remote = SuccessCheck::Http {
+            url: "https://example.com/health".to_string(),
+            expected_status: 200,
+        };
+        assert!(remote.validate().is_err());
+        let local = SuccessCheck::Http {
+            url: "http://localhost:8080/health".to_string(),
+            expected_status: 200,
+        };
+        assert!(local.validate().is_ok());
+        let judge = SuccessCheck::LlmJudge {
+            rubric: "Mentions the fix".to_string(),
+            threshold: 1.5,
+        };
+        assert!(judge.validate().is_err());
+        let file = SuccessCheck::File {
+            path: "out.txt".to_string(),
+            pattern: Some("(".to_string()),
+        };
+        assert!(file.validate().is_err());
+    }
+
+    #[test]
+    fn test_success_check_deserialization() {
+        let checks: Vec<SuccessCheck> = serde_yaml::from_str(
+            "- type: file\n  path: out.txt\n- type: json_schema\n- type: llm_judge\n  rubric: Explains the change\n",
+        )
+        .unwrap();
+        assert!(matches!(
+            checks[0],
+            SuccessCheck::File { pattern: None, .. }
+        ));
+        assert!(matches!(checks[1], SuccessCheck::JsonSchema {}));
+        assert!(matches!(
+            checks[2],
+            SuccessCheck::LlmJudge { threshold, .. } if threshold == DEFAULT_JUDGE_THRESHOLD
+        ));
     }
 
     #[tokio::test]
@@ -3,6 +3,7 @@
 //! This module contains all the handlers for the schedule management platform tool,
 //! including job creation, execution, monitoring, and session management.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use chrono::Utc;
@@ -10,6 +11,7 @@ use mcp_core::ToolResult;
 use rmcp::model::{Content, ErrorCode, ErrorData};
 
 use crate::recipe::Recipe;
+use crate::scheduler::OverlapPolicy;
 use crate::scheduler_trait::SchedulerTrait;
 
 use super::Agent;
@@ -187,6 +189,12 @@ impl Agent {
             current_session_id: None,
             process_start_time: None,
             execution_mode: Some(execution_mode.to_string()),
+            parameters: HashMap::new(),
+            overlap_policy: OverlapPolicy::Skip,
+            max_runtime_secs: None,
+            retry: None,
+            triggers: Vec::new(),
+            timezone: None,
         };
 
         match scheduler.add_scheduled_job(job).await {
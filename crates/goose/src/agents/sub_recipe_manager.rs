@@ -48,6 +48,14 @@ impl SubRecipeManager {
         }
     }
 
+    pub fn remove_sub_recipe_tools(&mut self, sub_recipe_names: &[String]) {
+        for name in sub_recipe_names {
+            let sub_recipe_key = format!("{}_{}", SUB_RECIPE_TASK_TOOL_NAME_PREFIX, name);
+            self.sub_recipe_tools.remove(&sub_recipe_key);
+            self.sub_recipes.remove(&sub_recipe_key);
+        }
+    }
+
     pub fn is_sub_recipe_tool(&self, tool_name: &str) -> bool {
         self.sub_recipe_tools.contains_key(tool_name)
     }
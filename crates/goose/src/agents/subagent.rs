@@ -1,4 +1,7 @@
 use crate::agents::subagent_task_config::DEFAULT_SUBAGENT_MAX_TURNS;
+use crate::agents::tool_audit::{AuditDecision, ToolAuditLog};
+use crate::agents::tool_execution::ToolAuditor;
+use crate::permission::PermissionDecisionSource;
 use crate::{
     agents::extension::ExtensionConfig,
     agents::{extension_manager::ExtensionManager, Agent, TaskConfig},
@@ -48,6 +51,7 @@ pub struct SubAgent {
     pub turn_count: Arc<Mutex<usize>>,
     pub created_at: DateTime<Utc>,
     pub extension_manager: Arc<RwLock<ExtensionManager>>,
+    auditor: Option<ToolAuditor>,
 }
 
 impl SubAgent {
@@ -56,6 +60,10 @@ impl SubAgent {
     pub async fn new(task_config: TaskConfig) -> Result<Arc<Self>, anyhow::Error> {
         debug!("Creating new subagent with id: {}", task_config.id);
 
+        // Subagents run tools too, so they may only start when the audit log is usable
+        let auditor = ToolAuditLog::from_config()?
+            .map(|log| ToolAuditor::new(log, Some(format!("subagent:{}", task_config.id))));
+
         // Create a new extension manager for this subagent
         let mut extension_manager = ExtensionManager::new();
 
@@ -87,6 +95,7 @@ impl SubAgent {
             turn_count: Arc::new(Mutex::new(0)),
             created_at: Utc::now(),
             extension_manager: Arc::new(RwLock::new(extension_manager)),
+            auditor,
         });
 
         debug!("Subagent {} created successfully", subagent.id);
@@ -154,7 +163,7 @@ impl SubAgent {
         let mut last_error: Option<anyhow::Error> = None;
 
         // Generate response from provider
-        loop {
+        'turns: loop {
             loop_count += 1;
 
             match Agent::generate_response_from_provider(
@@ -196,42 +205,76 @@ impl SubAgent {
 
                     // Process each tool request and create user response messages
                     for request in &tool_requests {
-                        if let Ok(tool_call) = &request.tool_call {
-                            // Handle platform tools or dispatch to extension manager
-                            let tool_result = match self
-                                .extension_manager
-                                .read()
-                                .await
-                                .dispatch_tool_call(tool_call.clone(), CancellationToken::default())
-                                .await
-                            {
-                                Ok(result) => result.result.await,
-                                Err(e) => Err(ErrorData::new(
-                                    ErrorCode::INTERNAL_ERROR,
-                                    e.to_string(),
-                                    None,
-                                )),
-                            };
-
-                            match tool_result {
-                                Ok(result) => {
-                                    // Create a user message with the tool response
-                                    let tool_response_message = Message::user()
-                                        .with_tool_response(request.id.clone(), Ok(result.clone()));
-                                    messages.push(tool_response_message);
-                                }
-                                Err(e) => {
-                                    // Create a user message with the tool error
-                                    let tool_error_message = Message::user().with_tool_response(
-                                        request.id.clone(),
-                                        Err(ErrorData::new(
-                                            ErrorCode::INTERNAL_ERROR,
-                                            e.to_string(),
-                                            None,
-                                        )),
-                                    );
-                                    messages.push(tool_error_message);
-                                }
+                        let Ok(tool_call) = &request.tool_call else {
+                            if let Some(Err(e)) = self.auditor.as_ref().map(|auditor| {
+                                auditor.record_not_run(
+                                    request,
+                                    AuditDecision::Skipped,
+                                    PermissionDecisionSource::Rule,
+                                )
+                            }) {
+                                self.set_status(SubAgentStatus::Completed(format!("Error: {}", e)))
+                                    .await;
+                                last_error = Some(e);
+                                break 'turns;
+                            }
+                            continue;
+                        };
+                        // Subagents run every tool they call without asking
+                        let pending = match self
+                            .auditor
+                            .as_ref()
+                            .map(|auditor| auditor.start(request, PermissionDecisionSource::Rule))
+                            .transpose()
+                        {
+                            Ok(pending) => pending,
+                            Err(e) => {
+                                self.set_status(SubAgentStatus::Completed(format!("Error: {}", e)))
+                                    .await;
+                                last_error = Some(e);
+                                break 'turns;
+                            }
+                        };
+                        // Handle platform tools or dispatch to extension manager
+                        let tool_result = match self
+                            .extension_manager
+                            .read()
+                            .await
+                            .dispatch_tool_call(tool_call.clone(), CancellationToken::default())
+                            .await
+                        {
+                            Ok(result) => result.result.await,
+                            Err(e) => Err(ErrorData::new(
+                                ErrorCode::INTERNAL_ERROR,
+                                e.to_string(),
+                                None,
+                            )),
+                        };
+                        if let Some(Err(e)) = pending.map(|pending| pending.finish(&tool_result)) {
+                            self.set_status(SubAgentStatus::Completed(format!("Error: {}", e)))
+                                .await;
+                            last_error = Some(e);
+                            break 'turns;
+                        }
+
+                        match tool_result {
+                            Ok(result) => {
+                                // Create a user message with the tool response
+                                let tool_response_message = Message::user()
+                                    .with_tool_response(request.id.clone(), Ok(result.clone()));
+                                messages.push(tool_response_message);
+                            }
+                            Err(e) => {
+                                // Create a user message with the tool error
+                                let tool_error_message = Message::user().with_tool_response(
+                                    request.id.clone(),
+                                    Err(ErrorData::new(
+                                        ErrorCode::INTERNAL_ERROR,
+                                        e.to_string(),
+                                        None,
+                                    )),
+                                );
+                                messages.push(tool_error_message);
                             }
                         }
                     }
@@ -1,9 +1,10 @@
 use crate::agents::subagent_execution_tool::lib::{
-    ExecutionResponse, ExecutionStats, SharedState, Task, TaskResult, TaskStatus,
+    ExecutionResponse, ExecutionStats, FailurePolicy, SharedState, Task, TaskResult, TaskStatus,
 };
 use crate::agents::subagent_execution_tool::task_execution_tracker::{
     DisplayMode, TaskExecutionTracker,
 };
+use crate::agents::subagent_execution_tool::task_graph::TaskGraph;
 use crate::agents::subagent_execution_tool::tasks::process_task;
 use crate::agents::subagent_execution_tool::workers::spawn_worker;
 use crate::agents::subagent_task_config::TaskConfig;
@@ -12,6 +13,7 @@ use std::sync::atomic::AtomicUsize;
 use std::sync::Arc;
 use tokio::sync::mpsc;
 use tokio::sync::mpsc::Sender;
+use tokio::task::JoinSet;
 use tokio::time::Instant;
 use tokio_util::sync::CancellationToken;
 
@@ -116,6 +118,189 @@ pub async fn execute_tasks_in_parallel(
     }
 }
 
+/// Runs a graph of dependent tasks, starting each one as soon as its dependencies complete
+/// and keeping at most `DEFAULT_MAX_WORKERS` running at once.
+pub async fn execute_task_graph(
+    mut graph: TaskGraph,
+    notifier: Sender<ServerNotification>,
+    task_config: TaskConfig,
+    failure_policy: FailurePolicy,
+    cancellation_token: Option<CancellationToken>,
+) -> ExecutionResponse {
+    if graph.is_empty() {
+        return create_empty_response();
+    }
+
+    let task_execution_tracker = Arc::new(TaskExecutionTracker::new(
+        graph.tasks().to_vec(),
+        DisplayMode::MultipleTasksOutput,
+        notifier,
+        cancellation_token.clone(),
+    ));
+    let start_time = Instant::now();
+    task_execution_tracker.refresh_display().await;
+
+    // Cancelling the graph on failure must not cancel the caller's token
+    let graph_token = cancellation_token.unwrap_or_default().child_token();
+    let mut results: Vec<Option<TaskResult>> = vec![None; graph.len()];
+    let mut running = JoinSet::new();
+
+    loop {
+        for (index, reason) in graph.take_blocked() {
+            let result = skipped_result(&graph, index, reason);
+            record_result(
+                &mut graph,
+                &task_execution_tracker,
+                &mut results,
+                index,
+                result,
+            )
+            .await;
+        }
+
+        while running.len() < DEFAULT_MAX_WORKERS && !graph_token.is_cancelled() {
+            let Some(index) = graph.next_ready() else {
+                break;
+            };
+            graph.mark_running(index);
+            match graph.prepare_task(index) {
+                Ok(task) => {
+                    task_execution_tracker.start_task(&task.id).await;
+                    let tracker = task_execution_tracker.clone();
+                    let task_config = task_config.clone();
+                    let token = graph_token.clone();
+                    running.spawn(async move {
+                        (
+                            index,
+                            process_task(&task, tracker, task_config, token).await,
+                        )
+                    });
+                }
+                Err(error) => {
+                    let result = skipped_result(&graph, index, error);
+                    record_result(
+                        &mut graph,
+                        &task_execution_tracker,
+                        &mut results,
+                        index,
+                        result,
+                    )
+                    .await;
+                    stop_on_failure(
+                        &mut graph,
+                        &task_execution_tracker,
+                        &mut results,
+                        failure_policy,
+                        &graph_token,
+                    )
+                    .await;
+                }
+            }
+        }
+
+        let Some(joined) = running.join_next().await else {
+            break;
+        };
+        match joined {
+            Ok((index, result)) => {
+                let failed = matches!(result.status, TaskStatus::Failed);
+                record_result(
+                    &mut graph,
+                    &task_execution_tracker,
+                    &mut results,
+                    index,
+                    result,
+                )
+                .await;
+                if failed {
+                    stop_on_failure(
+                        &mut graph,
+                        &task_execution_tracker,
+                        &mut results,
+                        failure_policy,
+                        &graph_token,
+                    )
+                    .await;
+                }
+            }
+            Err(e) => tracing::error!("Task graph worker error: {}", e),
+        }
+    }
+
+    // Anything left could not be scheduled, e.g. after a worker panicked
+    for index in graph.skip_pending() {
+        let result = skipped_result(
+            &graph,
+            index,
+            "Skipped because the task graph stopped".to_string(),
+        );
+        record_result(
+            &mut graph,
+            &task_execution_tracker,
+            &mut results,
+            index,
+            result,
+        )
+        .await;
+    }
+
+    task_execution_tracker.send_tasks_complete().await;
+
+    let results: Vec<TaskResult> = results.into_iter().flatten().collect();
+    let execution_time = start_time.elapsed().as_millis();
+    let stats = calculate_stats(&results, execution_time);
+
+    ExecutionResponse {
+        status: EXECUTION_STATUS_COMPLETED.to_string(),
+        results,
+        stats,
+    }
+}
+
+fn skipped_result(graph: &TaskGraph, index: usize, error: String) -> TaskResult {
+    TaskResult {
+        task_id: graph.tasks()[index].id.clone(),
+        status: TaskStatus::Failed,
+        data: None,
+        error: Some(error),
+    }
+}
+
+async fn record_result(
+    graph: &mut TaskGraph,
+    task_execution_tracker: &TaskExecutionTracker,
+    results: &mut [Option<TaskResult>],
+    index: usize,
+    result: TaskResult,
+) {
+    graph.mark_finished(index, &result);
+    task_execution_tracker
+        .complete_task(&result.task_id, result.clone())
+        .await;
+    results[index] = Some(result);
+}
+
+async fn stop_on_failure(
+    graph: &mut TaskGraph,
+    task_execution_tracker: &TaskExecutionTracker,
+    results: &mut [Option<TaskResult>],
+    failure_policy: FailurePolicy,
+    graph_token: &CancellationToken,
+) {
+    if failure_policy != FailurePolicy::FailFast || graph_token.is_cancelled() {
+        return;
+    }
+    graph_token.cancel();
+    for index in graph.skip_pending() {
+        let result = skipped_result(
+            graph,
+            index,
+            "Skipped because another task failed".to_string(),
+        );
+        record_result(graph, task_execution_tracker, results, index, result).await;
+    }
+}
+
 fn calculate_stats(results: &[TaskResult], execution_time_ms: u128) -> ExecutionStats {
     let completed = results
         .iter()
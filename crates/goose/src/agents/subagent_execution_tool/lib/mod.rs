@@ -1,8 +1,10 @@
 pub use crate::agents::subagent_execution_tool::task_types::{
-    ExecutionMode, ExecutionResponse, ExecutionStats, SharedState, Task, TaskResult, TaskStatus,
+    ExecutionMode, ExecutionResponse, ExecutionStats, FailurePolicy, SharedState, Task, TaskResult,
+    TaskStatus,
 };
 use crate::agents::subagent_execution_tool::{
-    executor::{execute_single_task, execute_tasks_in_parallel},
+    executor::{execute_single_task, execute_task_graph, execute_tasks_in_parallel},
+    task_graph::TaskGraph,
     tasks_manager::TasksManager,
 };
 use crate::agents::subagent_task_config::TaskConfig;
@@ -29,6 +31,31 @@ pub async fn execute_tasks(
 
     let tasks = tasks_manager.get_tasks(&task_ids).await?;
 
+    // Dependencies decide the order, whatever execution mode was requested
+    if TaskGraph::has_dependencies(&tasks) {
+        let failure_policy: FailurePolicy = input
+            .get("failure_policy")
+            .map(|v| serde_json::from_value(v.clone()))
+            .transpose()
+            .map_err(|e| format!("Failed to parse failure_policy: {}", e))?
+            .unwrap_or_default();
+        let graph = TaskGraph::new(tasks)?;
+        let response = execute_task_graph(
+            graph,
+            notifier,
+            task_config,
+            failure_policy,
+            cancellation_token,
+        )
+        .await;
+        return match failure_policy {
+            // Completed results are still useful when the caller chose to continue past failures
+            FailurePolicy::Continue => serde_json::to_value(response)
+                .map_err(|e| format!("Failed to serialize response: {}", e)),
+            FailurePolicy::FailFast => handle_response(response),
+        };
+    }
+
     let task_count = tasks.len();
     match execution_mode {
         ExecutionMode::Sequential => {
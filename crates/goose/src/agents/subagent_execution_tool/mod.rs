@@ -3,6 +3,7 @@ pub mod lib;
 pub mod notification_events;
 pub mod subagent_execute_task_tool;
 pub mod task_execution_tracker;
+pub mod task_graph;
 pub mod task_types;
 pub mod tasks;
 pub mod tasks_manager;
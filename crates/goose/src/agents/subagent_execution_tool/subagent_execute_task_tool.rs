@@ -32,7 +32,10 @@ pub fn create_subagent_execute_task_tool() -> Tool {
         - User: 'get weather and tell me a joke' → Sequential (2 separate tool calls, 1 task each)
         - User: 'get weather and joke in parallel' → Parallel (1 tool call with array of 2 tasks)
         - User: 'run these simultaneously' → Parallel (1 tool call with task array)
-        - User: 'do task A then task B' → Sequential (2 separate tool calls)",
+        - User: 'do task A then task B' → Sequential (2 separate tool calls)
+
+        DEPENDENCIES:
+        - When sub recipes declare depends_on, pass the tasks of all related sub recipes in ONE call. They run in dependency order, with independent tasks in parallel, regardless of execution_mode",
         object!({
             "type": "object",
             "properties": {
@@ -42,6 +45,12 @@ pub fn create_subagent_execute_task_tool() -> Tool {
                     "default": "sequential",
                     "description": "Execution strategy for multiple tasks. Use 'sequential' (default) unless user explicitly requests parallel execution with words like 'parallel', 'simultaneously', 'at the same time', or 'concurrently'."
                 },
+                "failure_policy": {
+                    "type": "string",
+                    "enum": ["fail_fast", "continue"],
+                    "default": "fail_fast",
+                    "description": "Only used for tasks with dependencies. 'fail_fast' stops everything when a task fails; 'continue' keeps running tasks that do not depend on the failed one."
+                },
                 "task_ids": {
                     "type": "array",
                     "items": {
@@ -0,0 +1,456 @@
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+use crate::agents::subagent_execution_tool::task_types::{Task, TaskResult, TaskStatus};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum NodeState {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+}
+
+/// Sub recipe tasks ordered by their `depends_on` edges.
+///
+/// A dependency names a sub recipe, so a task waits for every task of that sub recipe in the
+/// same execution. Outputs of completed tasks are kept to fill the `inputs` of later tasks.
+pub struct TaskGraph {
+    tasks: Vec<Task>,
+    dependencies: Vec<Vec<usize>>,
+    states: Vec<NodeState>,
+    outputs: Vec<Option<Value>>,
+}
+
+impl TaskGraph {
+    pub fn has_dependencies(tasks: &[Task]) -> bool {
+        tasks.iter().any(|task| !task.get_depends_on().is_empty())
+    }
+
+    pub fn new(tasks: Vec<Task>) -> Result<Self, String> {
+        let dependencies = resolve_dependencies(&tasks)?;
+        let graph = Self {
+            states: vec![NodeState::Pending; tasks.len()],
+            outputs: vec![None; tasks.len()],
+            tasks,
+            dependencies,
+        };
+        graph.check_for_cycles()?;
+        Ok(graph)
+    }
+
+    pub fn tasks(&self) -> &[Task] {
+        &self.tasks
+    }
+
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    /// The first pending task whose dependencies have all completed. A repeated sub recipe
+    /// marked `sequential_when_repeated` waits while another of its tasks is running.
+    pub fn next_ready(&self) -> Option<usize> {
+        (0..self.len()).find(|&index| {
+            self.states[index] == NodeState::Pending
+                && self.dependencies[index]
+                    .iter()
+                    .all(|&dep| self.states[dep] == NodeState::Completed)
+                && !self.waits_for_sibling(index)
+        })
+    }
+
+    pub fn mark_running(&mut self, index: usize) {
+        self.states[index] = NodeState::Running;
+    }
+
+    pub fn mark_finished(&mut self, index: usize, result: &TaskResult) {
+        match result.status {
+            TaskStatus::Completed => {
+                self.states[index] = NodeState::Completed;
+                self.outputs[index] = result.data.as_ref().and_then(final_output);
+            }
+            _ => self.states[index] = NodeState::Failed,
+        }
+    }
+
+    /// Fails every pending task that has a failed dependency, returning each with the reason
+    pub fn take_blocked(&mut self) -> Vec<(usize, String)> {
+        let mut blocked = Vec::new();
+        loop {
+            let newly_blocked: Vec<(usize, String)> = (0..self.len())
+                .filter(|&index| self.states[index] == NodeState::Pending)
+                .filter_map(|index| {
+                    self.dependencies[index]
+                        .iter()
+                        .find(|&&dep| self.states[dep] == NodeState::Failed)
+                        .map(|&dep| {
+                            (
+                                index,
+                                format!("Skipped because '{}' failed", self.task_name(dep)),
+                            )
+                        })
+                })
+                .collect();
+            if newly_blocked.is_empty() {
+                return blocked;
+            }
+            for (index, _) in &newly_blocked {
+                self.states[*index] = NodeState::Failed;
+            }
+            blocked.extend(newly_blocked);
+        }
+    }
+
+    /// Fails every task that has not started yet
+    pub fn skip_pending(&mut self) -> Vec<usize> {
+        let pending: Vec<usize> = (0..self.len())
+            .filter(|&index| self.states[index] == NodeState::Pending)
+            .collect();
+        for &index in &pending {
+            self.states[index] = NodeState::Failed;
+        }
+        pending
+    }
+
+    /// The task ready to run, with parameters mapped from its dependencies' outputs
+    pub fn prepare_task(&self, index: usize) -> Result<Task, String> {
+        let mut task = self.tasks[index].clone();
+        let Some(inputs) = task.get_inputs().cloned() else {
+            return Ok(task);
+        };
+
+        let mut resolved = Map::new();
+        for (param, source) in inputs {
+            let source = source.as_str().ok_or_else(|| {
+                format!("Input '{}' must be written as 'sub_recipe.field'", param)
+            })?;
+            let value = self.resolve_input(index, source)?;
+            resolved.insert(param, Value::String(value_to_param(&value)));
+        }
+
+        let command_parameters = task
+            .payload
+            .get_mut("sub_recipe")
+            .and_then(|sub_recipe| sub_recipe.as_object_mut())
+            .map(|sub_recipe| {
+                sub_recipe
+                    .entry("command_parameters")
+                    .or_insert_with(|| Value::Object(Map::new()))
+            })
+            .and_then(|params| params.as_object_mut())
+            .ok_or_else(|| format!("Task {}: Missing sub_recipe", task.id))?;
+        command_parameters.extend(resolved);
+        Ok(task)
+    }
+
+    pub fn task_name(&self, index: usize) -> &str {
+        let task = &self.tasks[index];
+        task.get_sub_recipe_name().unwrap_or(&task.id)
+    }
+
+    fn waits_for_sibling(&self, index: usize) -> bool {
+        let task = &self.tasks[index];
+        task.get_sequential_when_repeated()
+            && (0..self.len()).any(|other| {
+                self.states[other] == NodeState::Running
+                    && self.tasks[other].get_sub_recipe_name() == task.get_sub_recipe_name()
+            })
+    }
+
+    fn resolve_input(&self, index: usize, source: &str) -> Result<Value, String> {
+        let (dependency, field_path) = source.split_once('.').unwrap_or((source, ""));
+        if !self.tasks[index].get_depends_on().contains(&dependency) {
+            return Err(format!(
+                "Input '{}' reads from '{}', which is not listed in depends_on",
+                source, dependency
+            ));
+        }
+
+        let mut values = self.dependencies[index]
+            .iter()
+            .filter(|&&dep| self.tasks[dep].get_sub_recipe_name() == Some(dependency))
+            .map(|&dep| {
+                let output = self.outputs[dep].as_ref().ok_or_else(|| {
+                    format!("'{}' did not produce a JSON final output", dependency)
+                })?;
+                lookup_field(output, field_path).ok_or_else(|| {
+                    format!(
+                        "Final output of '{}' has no field '{}'",
+                        dependency, field_path
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        // Repeated dependencies contribute one value each
+        if values.len() == 1 {
+            Ok(values.remove(0))
+        } else {
+            Ok(Value::Array(values))
+        }
+    }
+
+    fn check_for_cycles(&self) -> Result<(), String> {
+        let mut dependents = vec![Vec::new(); self.len()];
+        let mut remaining: Vec<usize> = self.dependencies.iter().map(Vec::len).collect();
+        for (index, deps) in self.dependencies.iter().enumerate() {
+            for &dep in deps {
+                dependents[dep].push(index);
+            }
+        }
+
+        let mut ready: Vec<usize> = (0..self.len()).filter(|&i| remaining[i] == 0).collect();
+        let mut visited = 0;
+        while let Some(index) = ready.pop() {
+            visited += 1;
+            for &dependent in &dependents[index] {
+                remaining[dependent] -= 1;
+                if remaining[dependent] == 0 {
+                    ready.push(dependent);
+                }
+            }
+        }
+
+        if visited == self.len() {
+            return Ok(());
+        }
+        let mut cycle: Vec<&str> = (0..self.len())
+            .filter(|&i| remaining[i] > 0)
+            .map(|i| self.task_name(i))
+            .collect();
+        cycle.dedup();
+        Err(format!(
+            "Sub recipe dependencies form a cycle between: {}",
+            cycle.join(", ")
+        ))
+    }
+}
+
+fn resolve_dependencies(tasks: &[Task]) -> Result<Vec<Vec<usize>>, String> {
+    let mut tasks_by_name: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (index, task) in tasks.iter().enumerate() {
+        if let Some(name) = task.get_sub_recipe_name() {
+            tasks_by_name.entry(name).or_default().push(index);
+        }
+    }
+
+    tasks
+        .iter()
+        .map(|task| {
+            let mut deps = Vec::new();
+            for dependency in task.get_depends_on() {
+                let indices = tasks_by_name.get(dependency).ok_or_else(|| {
+                    format!(
+                        "Sub recipe '{}' depends on '{}', but no '{}' task is part of this execution. \
+                        Create the '{}' tasks and execute them together with this one.",
+                        task.get_sub_recipe_name().unwrap_or(&task.id),
+                        dependency,
+                        dependency,
+                        dependency
+                    )
+                })?;
+                deps.extend(indices);
+            }
+            Ok(deps)
+        })
+        .collect()
+}
+
+/// The JSON object a sub recipe printed as its final output, if any
+fn final_output(data: &Value) -> Option<Value> {
+    match data {
+        Value::String(text) => serde_json::from_str::<Value>(text)
+            .ok()
+            .filter(Value::is_object),
+        Value::Object(_) => Some(data.clone()),
+        _ => None,
+    }
+}
+
+fn lookup_field(output: &Value, field_path: &str) -> Option<Value> {
+    if field_path.is_empty() {
+        return Some(output.clone());
+    }
+    field_path
+        .split('.')
+        .try_fold(output, |value, segment| match value {
+            Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => value.get(segment),
+        })
+        .cloned()
+}
+
+fn value_to_param(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sub_recipe_task(id: &str, name: &str, depends_on: &[&str], inputs: Value) -> Task {
+        Task {
+            id: id.to_string(),
+            task_type: "sub_recipe".to_string(),
+            payload: json!({
+                "sub_recipe": {
+                    "name": name,
+                    "command_parameters": {},
+                    "recipe_path": format!("/recipes/{}.yaml", name),
+                    "sequential_when_repeated": false,
+                    "depends_on": depends_on,
+                    "inputs": inputs,
+                }
+            }),
+        }
+    }
+
+    fn completed(task_id: &str, output: Value) -> TaskResult {
+        TaskResult {
+            task_id: task_id.to_string(),
+            status: TaskStatus::Completed,
+            data: Some(Value::String(output.to_string())),
+            error: None,
+        }
+    }
+
+    fn failed(task_id: &str) -> TaskResult {
+        TaskResult {
+            task_id: task_id.to_string(),
+            status: TaskStatus::Failed,
+            data: None,
+            error: Some("boom".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_runs_tasks_in_dependency_order() {
+        let mut graph = TaskGraph::new(vec![
+            sub_recipe_task("3", "publish", &["summarize"], json!(null)),
+            sub_recipe_task("2", "summarize", &["research"], json!(null)),
+            sub_recipe_task("1", "research", &[], json!(null)),
+        ])
+        .unwrap();
+
+        for (expected, task_id) in [(2, "1"), (1, "2"), (0, "3")] {
+            let index = graph.next_ready().unwrap();
+            assert_eq!(index, expected);
+            graph.mark_running(index);
+            assert_eq!(graph.next_ready(), None);
+            graph.mark_finished(index, &completed(task_id, json!({})));
+        }
+        assert_eq!(graph.next_ready(), None);
+    }
+
+    #[test]
+    fn test_rejects_missing_dependency_and_cycles() {
+        let missing = TaskGraph::new(vec![sub_recipe_task("1", "b", &["a"], json!(null))]);
+        assert!(missing.err().unwrap().contains("no 'a' task"));
+
+        let cycle = TaskGraph::new(vec![
+            sub_recipe_task("1", "a", &["b"], json!(null)),
+            sub_recipe_task("2", "b", &["a"], json!(null)),
+            sub_recipe_task("3", "c", &[], json!(null)),
+        ]);
+        assert_eq!(
+            cycle.err().unwrap(),
+            "Sub recipe dependencies form a cycle between: a, b"
+        );
+    }
+
+    #[test]
+    fn test_failed_dependency_blocks_only_its_dependents() {
+        let mut graph = TaskGraph::new(vec![
+            sub_recipe_task("1", "fetch", &[], json!(null)),
+            sub_recipe_task("2", "parse", &["fetch"], json!(null)),
+            sub_recipe_task("3", "report", &["parse"], json!(null)),
+            sub_recipe_task("4", "lint", &[], json!(null)),
+        ])
+        .unwrap();
+
+        graph.mark_running(0);
+        graph.mark_finished(0, &failed("1"));
+
+        let blocked = graph.take_blocked();
+        assert_eq!(
+            blocked,
+            vec![
+                (1, "Skipped because 'fetch' failed".to_string()),
+                (2, "Skipped because 'parse' failed".to_string())
+            ]
+        );
+        assert_eq!(graph.next_ready(), Some(3));
+        assert_eq!(graph.skip_pending(), vec![3]);
+        assert_eq!(graph.next_ready(), None);
+    }
+
+    #[test]
+    fn test_maps_dependency_outputs_to_parameters() {
+        let mut graph = TaskGraph::new(vec![
+            sub_recipe_task("1", "research", &[], json!(null)),
+            sub_recipe_task("2", "research", &[], json!(null)),
+            sub_recipe_task("3", "plan", &[], json!(null)),
+            sub_recipe_task(
+                "4",
+                "write",
+                &["research", "plan"],
+                json!({"sources": "research.links.0", "outline": "plan", "title": "plan.meta.title"}),
+            ),
+        ])
+        .unwrap();
+
+        let outputs = [
+            json!({"links": ["a.com"]}),
+            json!({"links": ["b.com"]}),
+            json!({"meta": {"title": "Report"}, "sections": 3}),
+        ];
+        for (index, output) in outputs.into_iter().enumerate() {
+            graph.mark_running(index);
+            graph.mark_finished(index, &completed(&(index + 1).to_string(), output));
+        }
+
+        let task = graph.prepare_task(3).unwrap();
+        let params = task.get_command_parameters().unwrap();
+        assert_eq!(params["sources"], json!("[\"a.com\",\"b.com\"]"));
+        assert_eq!(params["title"], json!("Report"));
+        assert_eq!(
+            params["outline"],
+            json!("{\"meta\":{\"title\":\"Report\"},\"sections\":3}")
+        );
+    }
+
+    #[test]
+    fn test_input_errors_name_the_problem() {
+        let mut graph = TaskGraph::new(vec![
+            sub_recipe_task("1", "research", &[], json!(null)),
+            sub_recipe_task(
+                "2",
+                "write",
+                &["research"],
+                json!({"topic": "research.topic"}),
+            ),
+            sub_recipe_task("3", "review", &[], json!({"draft": "write.text"})),
+        ])
+        .unwrap();
+        graph.mark_running(0);
+        graph.mark_finished(0, &completed("1", json!({"summary": "x"})));
+
+        assert_eq!(
+            graph.prepare_task(1).err().unwrap(),
+            "Final output of 'research' has no field 'topic'"
+        );
+        assert!(graph
+            .prepare_task(2)
+            .err()
+            .unwrap()
+            .contains("not listed in depends_on"));
+    }
+}
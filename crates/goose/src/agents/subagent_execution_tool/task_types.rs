@@ -15,6 +15,17 @@ pub enum ExecutionMode {
     Parallel,
 }
 
+/// What a task graph does when one of its tasks fails
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FailurePolicy {
+    /// Cancel running tasks and skip everything not yet started
+    #[default]
+    FailFast,
+    /// Keep running tasks that do not depend on the failed one
+    Continue,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Task {
     pub id: String,
@@ -53,6 +64,20 @@ impl Task {
             .and_then(|path| path.as_str())
     }
 
+    pub fn get_depends_on(&self) -> Vec<&str> {
+        self.get_sub_recipe()
+            .and_then(|sr| sr.get("depends_on"))
+            .and_then(|deps| deps.as_array())
+            .map(|deps| deps.iter().filter_map(|dep| dep.as_str()).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn get_inputs(&self) -> Option<&Map<String, Value>> {
+        self.get_sub_recipe()
+            .and_then(|sr| sr.get("inputs"))
+            .and_then(|inputs| inputs.as_object())
+    }
+
     pub fn get_text_instruction(&self) -> Option<&str> {
         if self.task_type != "sub_recipe" {
             self.payload
@@ -0,0 +1,532 @@
+//! Append-only, hash-chained audit log of tool calls.
+//!
+//! Every entry is a single JSON line. Each entry stores the hash of the previous entry and its
+//! own blake3 hash over the entry contents, so removing, reordering or editing a line breaks the
+//! chain and is caught by [`verify_audit_log`].
+
+use crate::config::{Config, APP_STRATEGY};
+use crate::permission::permission_store::hash_tool_arguments;
+use crate::permission::PermissionDecisionSource;
+use anyhow::{anyhow, Context, Result};
+use blake3::Hasher;
+use chrono::{DateTime, Utc};
+use etcetera::{choose_app_strategy, AppStrategy};
+use fs2::FileExt;
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Hash used as the `prev_hash` of the first entry in a log
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Whether the tool call was allowed to run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditDecision {
+    Allowed,
+    Denied,
+    Skipped,
+}
+
+/// Outcome of the tool call
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditStatus {
+    /// The call was allowed and is about to run. Its outcome is a later record with the same
+    /// request id, so a started call without one was interrupted.
+    Started,
+    Success,
+    Error,
+    NotRun,
+    /// The call was started but cancelled before it returned a result
+    Cancelled,
+}
+
+/// The facts recorded about a tool call, before they are chained into the log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolAuditRecord {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    pub request_id: String,
+    pub tool_name: String,
+    pub arguments: Value,
+    pub decision: AuditDecision,
+    pub decision_source: PermissionDecisionSource,
+    pub status: AuditStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+}
+
+/// A single line of the audit log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolAuditEntry {
+    pub seq: u64,
+    pub timestamp: DateTime<Utc>,
+    #[serde(flatten)]
+    pub record: ToolAuditRecord,
+    /// blake3 hash of the arguments, as computed by the tool permission store
+    pub arguments_hash: String,
+    pub prev_hash: String,
+    pub hash: String,
+}
+
+impl ToolAuditEntry {
+    fn compute_hash(&self) -> Result<String> {
+        let mut unhashed = serde_json::to_value(self)?;
+        if let Value::Object(map) = &mut unhashed {
+            map.remove("hash");
+        }
+        let mut hasher = Hasher::new();
+        hasher.update(self.prev_hash.as_bytes());
+        hasher.update(serde_json::to_string(&unhashed)?.as_bytes());
+        Ok(hasher.finalize().to_hex().to_string())
+    }
+}
+
+/// The audit log opened from the config, shared by every agent in the process
+static CONFIGURED_LOG: OnceCell<Arc<ToolAuditLog>> = OnceCell::new();
+
+struct ChainHead {
+    next_seq: u64,
+    last_hash: String,
+}
+
+/// Appends tool call records to a hash-chained JSONL file. Appends take an exclusive lock on
+/// the file and continue the chain from its last line, so several handles and processes can
+/// write to the same log.
+pub struct ToolAuditLog {
+    path: PathBuf,
+    write_lock: Mutex<()>,
+}
+
+impl ToolAuditLog {
+    /// Default location of the audit log in the goose data directory
+    pub fn default_path() -> PathBuf {
+        choose_app_strategy(APP_STRATEGY.clone())
+            .map(|strategy| strategy.data_dir())
+            .unwrap_or_else(|_| PathBuf::from(".local/share/goose"))
+            .join("audit")
+            .join("tool_calls.jsonl")
+    }
+
+    /// Path configured through `GOOSE_AUDIT_LOG_PATH`, or the default path
+    pub fn configured_path() -> PathBuf {
+        Config::global()
+            .get_param::<String>("GOOSE_AUDIT_LOG_PATH")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| Self::default_path())
+    }
+
+    /// The configured audit log if `GOOSE_AUDIT_LOG` is enabled. All callers in the process
+    /// share one handle. Fails when the log is enabled but cannot be opened, so that callers
+    /// can refuse to run tools rather than run them unaudited.
+    pub fn from_config() -> Result<Option<Arc<Self>>> {
+        let enabled = Config::global()
+            .get_param::<bool>("GOOSE_AUDIT_LOG")
+            .unwrap_or(false);
+        if !enabled {
+            return Ok(None);
+        }
+        let log = CONFIGURED_LOG.get_or_try_init(|| {
+            let path = Self::configured_path();
+            Self::open(&path)
+                .map(Arc::new)
+                .with_context(|| format!("Failed to open tool audit log {}", path.display()))
+        })?;
+        Ok(Some(log.clone()))
+    }
+
+    /// Open an audit log, checking that its last entry can be continued
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        if path.exists() {
+            let mut file = File::open(&path)?;
+            FileExt::lock_shared(&file)?;
+            let head = read_chain_head(&mut file);
+            FileExt::unlock(&file)?;
+            head?;
+        }
+        Ok(Self {
+            path,
+            write_lock: Mutex::new(()),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Chain a record onto the log and write it as a new line
+    pub fn append(&self, record: ToolAuditRecord) -> Result<ToolAuditEntry> {
+        let _guard = self
+            .write_lock
+            .lock()
+            .map_err(|_| anyhow!("audit log lock poisoned"))?;
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Failed to open audit log {}", self.path.display()))?;
+        FileExt::lock_exclusive(&file)
+            .with_context(|| format!("Failed to lock audit log {}", self.path.display()))?;
+        let result = Self::append_locked(&mut file, record);
+        FileExt::unlock(&file)?;
+        result
+    }
+
+    fn append_locked(file: &mut File, record: ToolAuditRecord) -> Result<ToolAuditEntry> {
+        let head = read_chain_head(file)?;
+        let mut entry = ToolAuditEntry {
+            seq: head.next_seq,
+            timestamp: Utc::now(),
+            arguments_hash: hash_tool_arguments(&record.arguments),
+            record,
+            prev_hash: head.last_hash,
+            hash: String::new(),
+        };
+        entry.hash = entry.compute_hash()?;
+        writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+        file.flush()?;
+        Ok(entry)
+    }
+}
+
+/// Where the chain continues, read from the last line of the file
+fn read_chain_head(file: &mut File) -> Result<ChainHead> {
+    let len = file.metadata()?.len();
+    let mut window = 4096u64;
+    let last_line = loop {
+        let start = len.saturating_sub(window);
+        file.seek(SeekFrom::Start(start))?;
+        let mut tail = Vec::new();
+        file.take(len - start).read_to_end(&mut tail)?;
+        let text = String::from_utf8_lossy(&tail);
+        let text = text.trim_end();
+        match text.rfind('\n') {
+            Some(newline) => break text[newline + 1..].to_string(),
+            None if start == 0 => break text.to_string(),
+            None => window *= 4,
+        }
+    };
+
+    if last_line.trim().is_empty() {
+        return Ok(ChainHead {
+            next_seq: 0,
+            last_hash: GENESIS_HASH.to_string(),
+        });
+    }
+    let entry: ToolAuditEntry =
+        serde_json::from_str(&last_line).context("The last audit entry is unreadable")?;
+    Ok(ChainHead {
+        next_seq: entry.seq + 1,
+        last_hash: entry.hash,
+    })
+}
+
+fn read_entries(path: &Path) -> Result<Vec<ToolAuditEntry>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let reader = BufReader::new(File::open(path)?);
+    let mut entries = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry = serde_json::from_str(&line)
+            .with_context(|| format!("Invalid audit entry on line {}", index + 1))?;
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+/// Result of checking the hash chain of an audit log
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AuditVerification {
+    /// Number of entries that verified before the first problem
+    pub verified: usize,
+    /// Line number (1-based) and description of the first broken link, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub broken: Option<(usize, String)>,
+}
+
+impl AuditVerification {
+    pub fn is_valid(&self) -> bool {
+        self.broken.is_none()
+    }
+}
+
+/// Walk the log and check that every entry is intact and chained to the one before it
+pub fn verify_audit_log(path: &Path) -> Result<AuditVerification> {
+    let reader = BufReader::new(
+        File::open(path).with_context(|| format!("Failed to open {}", path.display()))?,
+    );
+
+    let mut expected_prev = GENESIS_HASH.to_string();
+    let mut expected_seq = 0u64;
+    let mut verified = 0usize;
+
+    for (index, line) in reader.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let broken = |reason: String| AuditVerification {
+            verified,
+            broken: Some((line_number, reason)),
+        };
+
+        let entry: ToolAuditEntry = match serde_json::from_str(&line) {
+            Ok(entry) => entry,
+            Err(e) => return Ok(broken(format!("unparseable entry: {}", e))),
+        };
+        if entry.seq != expected_seq {
+            return Ok(broken(format!(
+                "expected sequence {}, found {}",
+                expected_seq, entry.seq
+            )));
+        }
+        if entry.prev_hash != expected_prev {
+            return Ok(broken("previous hash does not match".to_string()));
+        }
+        if entry.compute_hash()? != entry.hash {
+            return Ok(broken("entry hash does not match its contents".to_string()));
+        }
+        if hash_tool_arguments(&entry.record.arguments) != entry.arguments_hash {
+            return Ok(broken("arguments hash does not match".to_string()));
+        }
+
+        expected_prev = entry.hash;
+        expected_seq += 1;
+        verified += 1;
+    }
+
+    Ok(AuditVerification {
+        verified,
+        broken: None,
+    })
+}
+
+/// Filter for querying the audit log. Unset fields match everything.
+#[derive(Debug, Clone, Default)]
+pub struct AuditFilter {
+    /// Substring matched against the tool name
+    pub tool: Option<String>,
+    pub session_id: Option<String>,
+    pub decision: Option<AuditDecision>,
+    pub source: Option<PermissionDecisionSource>,
+    pub status: Option<AuditStatus>,
+    pub since: Option<DateTime<Utc>>,
+    /// Keep only the most recent `limit` matches
+    pub limit: Option<usize>,
+}
+
+impl AuditFilter {
+    pub fn matches(&self, entry: &ToolAuditEntry) -> bool {
+        let record = &entry.record;
+        self.tool
+            .as_ref()
+            .is_none_or(|tool| record.tool_name.contains(tool.as_str()))
+            && self
+                .session_id
+                .as_ref()
+                .is_none_or(|id| record.session_id.as_ref() == Some(id))
+            && self.decision.is_none_or(|d| record.decision == d)
+            && self.source.is_none_or(|s| record.decision_source == s)
+            && self.status.is_none_or(|s| record.status == s)
+            && self.since.is_none_or(|since| entry.timestamp >= since)
+    }
+}
+
+/// Read the entries of an audit log that match the filter, oldest first
+pub fn query_audit_log(path: &Path, filter: &AuditFilter) -> Result<Vec<ToolAuditEntry>> {
+    let mut entries: Vec<ToolAuditEntry> = read_entries(path)?
+        .into_iter()
+        .filter(|entry| filter.matches(entry))
+        .collect();
+    if let Some(limit) = filter.limit {
+        let skip = entries.len().saturating_sub(limit);
+        entries.drain(..skip);
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn record(tool_name: &str, decision: AuditDecision) -> ToolAuditRecord {
+        ToolAuditRecord {
+            session_id: Some("session-1".to_string()),
+            request_id: "req-1".to_string(),
+            tool_name: tool_name.to_string(),
+            arguments: json!({"command": "ls"}),
+            decision,
+            decision_source: PermissionDecisionSource::Rule,
+            status: if decision == AuditDecision::Allowed {
+                AuditStatus::Success
+            } else {
+                AuditStatus::NotRun
+            },
+            error: None,
+            duration_ms: Some(5),
+        }
+    }
+
+    #[test]
+    fn test_append_and_verify_chain() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("audit.jsonl");
+
+        let log = ToolAuditLog::open(&path).unwrap();
+        let first = log
+            .append(record("developer__shell", AuditDecision::Allowed))
+            .unwrap();
+        let second = log
+            .append(record("developer__text_editor", AuditDecision::Denied))
+            .unwrap();
+
+        assert_eq!(first.prev_hash, GENESIS_HASH);
+        assert_eq!(second.prev_hash, first.hash);
+
+        let verification = verify_audit_log(&path).unwrap();
+        assert!(verification.is_valid());
+        assert_eq!(verification.verified, 2);
+    }
+
+    #[test]
+    fn test_reopen_continues_chain() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("audit.jsonl");
+
+        let first = ToolAuditLog::open(&path)
+            .unwrap()
+            .append(record("developer__shell", AuditDecision::Allowed))
+            .unwrap();
+        let second = ToolAuditLog::open(&path)
+            .unwrap()
+            .append(record("developer__shell", AuditDecision::Allowed))
+            .unwrap();
+
+        assert_eq!(second.seq, 1);
+        assert_eq!(second.prev_hash, first.hash);
+        assert!(verify_audit_log(&path).unwrap().is_valid());
+    }
+
+    #[test]
+    fn test_handles_share_one_chain() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("audit.jsonl");
+
+        let server = Arc::new(ToolAuditLog::open(&path).unwrap());
+        let scheduler = Arc::new(ToolAuditLog::open(&path).unwrap());
+        let threads: Vec<_> = [server, scheduler]
+            .into_iter()
+            .map(|log| {
+                std::thread::spawn(move || {
+                    for _ in 0..20 {
+                        log.append(record("developer__shell", AuditDecision::Allowed))
+                            .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        let verification = verify_audit_log(&path).unwrap();
+        assert!(verification.is_valid(), "{:?}", verification.broken);
+        assert_eq!(verification.verified, 40);
+    }
+
+    #[test]
+    fn test_open_refuses_corrupt_log() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        fs::write(&path, "{\"seq\": 0, \"truncated").unwrap();
+
+        assert!(ToolAuditLog::open(&path).is_err());
+    }
+
+    #[test]
+    fn test_verify_detects_tampering() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("audit.jsonl");
+
+        let log = ToolAuditLog::open(&path).unwrap();
+        log.append(record("developer__shell", AuditDecision::Allowed))
+            .unwrap();
+        log.append(record("developer__shell", AuditDecision::Denied))
+            .unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        fs::write(&path, content.replacen("\"denied\"", "\"allowed\"", 1)).unwrap();
+
+        let verification = verify_audit_log(&path).unwrap();
+        assert_eq!(verification.verified, 1);
+        assert_eq!(verification.broken.unwrap().0, 2);
+    }
+
+    #[test]
+    fn test_verify_detects_removed_entry() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("audit.jsonl");
+
+        let log = ToolAuditLog::open(&path).unwrap();
+        for _ in 0..3 {
+            log.append(record("developer__shell", AuditDecision::Allowed))
+                .unwrap();
+        }
+
+        let content = fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        fs::write(&path, format!("{}\n{}\n", lines[0], lines[2])).unwrap();
+
+        assert!(!verify_audit_log(&path).unwrap().is_valid());
+    }
+
+    #[test]
+    fn test_query_filters() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("audit.jsonl");
+
+        let log = ToolAuditLog::open(&path).unwrap();
+        log.append(record("developer__shell", AuditDecision::Allowed))
+            .unwrap();
+        log.append(record("developer__text_editor", AuditDecision::Denied))
+            .unwrap();
+        log.append(record("developer__shell", AuditDecision::Denied))
+            .unwrap();
+
+        let shell = AuditFilter {
+            tool: Some("shell".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(query_audit_log(&path, &shell).unwrap().len(), 2);
+
+        let denied = AuditFilter {
+            decision: Some(AuditDecision::Denied),
+            limit: Some(1),
+            ..Default::default()
+        };
+        let entries = query_audit_log(&path, &denied).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].seq, 2);
+    }
+}
@@ -1,5 +1,6 @@
 use std::future::Future;
 use std::sync::Arc;
+use std::time::Instant;
 
 use async_stream::try_stream;
 use futures::stream::{self, BoxStream};
@@ -7,9 +8,10 @@ use futures::{Stream, StreamExt};
 use tokio::sync::Mutex;
 use tokio_util::sync::CancellationToken;
 
+use crate::agents::tool_audit::{AuditDecision, AuditStatus, ToolAuditLog, ToolAuditRecord};
 use crate::config::permission::PermissionLevel;
 use crate::config::PermissionManager;
-use crate::permission::Permission;
+use crate::permission::{Permission, PermissionDecisionSource};
 use mcp_core::ToolResult;
 use rmcp::model::{Content, ServerNotification};
 
@@ -29,10 +31,175 @@ impl From<ToolResult<Vec<Content>>> for ToolCallResult {
     }
 }
 
-use super::agent::{tool_stream, ToolStream};
+use super::agent::{tool_stream, ToolStream, ToolStreamItem};
 use crate::agents::Agent;
 use crate::conversation::message::{Message, ToolRequest};
 
+/// Name recorded for tool requests that could not be parsed into a tool call
+const INVALID_TOOL_NAME: &str = "invalid_tool_call";
+
+/// Writes audit records for the tool calls of a single reply. Once a record cannot be
+/// written, no further tools are started and the reply ends with the error.
+#[derive(Clone)]
+pub struct ToolAuditor {
+    log: Arc<ToolAuditLog>,
+    session_id: Option<String>,
+    failure: Arc<std::sync::Mutex<Option<String>>>,
+}
+
+impl ToolAuditor {
+    pub fn new(log: Arc<ToolAuditLog>, session_id: Option<String>) -> Self {
+        Self {
+            log,
+            session_id,
+            failure: Arc::new(std::sync::Mutex::new(None)),
+        }
+    }
+
+    fn record(
+        &self,
+        request: &ToolRequest,
+        decision: AuditDecision,
+        source: PermissionDecisionSource,
+        status: AuditStatus,
+        error: Option<String>,
+        duration_ms: Option<u64>,
+    ) -> anyhow::Result<()> {
+        self.check()?;
+        let (tool_name, arguments, error) = match &request.tool_call {
+            Ok(tool_call) => (tool_call.name.clone(), tool_call.arguments.clone(), error),
+            Err(e) => (
+                INVALID_TOOL_NAME.to_string(),
+                serde_json::Value::Null,
+                Some(e.to_string()),
+            ),
+        };
+        let record = ToolAuditRecord {
+            session_id: self.session_id.clone(),
+            request_id: request.id.clone(),
+            tool_name,
+            arguments,
+            decision,
+            decision_source: source,
+            status,
+            error,
+            duration_ms,
+        };
+        self.log.append(record).map(|_| ()).map_err(|e| {
+            let message = format!("Failed to write tool audit record: {:#}", e);
+            tracing::error!("{}", message);
+            *self.failure.lock().unwrap() = Some(message.clone());
+            anyhow::anyhow!(message)
+        })
+    }
+
+    /// Fail when an earlier record could not be written
+    pub fn check(&self) -> anyhow::Result<()> {
+        match self.failure.lock().unwrap().as_ref() {
+            Some(message) => Err(anyhow::anyhow!(
+                "{}. No more tools will run without a working audit log.",
+                message
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Record a tool call that was not run, because it was denied, skipped or invalid
+    pub fn record_not_run(
+        &self,
+        request: &ToolRequest,
+        decision: AuditDecision,
+        source: PermissionDecisionSource,
+    ) -> anyhow::Result<()> {
+        self.record(request, decision, source, AuditStatus::NotRun, None, None)
+    }
+
+    /// Record that an allowed tool call is about to run. The tool must not be started when
+    /// this fails.
+    pub fn start(
+        &self,
+        request: &ToolRequest,
+        source: PermissionDecisionSource,
+    ) -> anyhow::Result<PendingCall> {
+        self.record(
+            request,
+            AuditDecision::Allowed,
+            source,
+            AuditStatus::Started,
+            None,
+            None,
+        )?;
+        Ok(PendingCall {
+            auditor: self.clone(),
+            request: request.clone(),
+            source,
+            started: Instant::now(),
+            finished: false,
+        })
+    }
+}
+
+/// A started tool call whose outcome has not been recorded yet
+pub struct PendingCall {
+    auditor: ToolAuditor,
+    request: ToolRequest,
+    source: PermissionDecisionSource,
+    started: Instant,
+    finished: bool,
+}
+
+impl PendingCall {
+    /// Record the result and duration of the call
+    pub fn finish(mut self, result: &ToolResult<Vec<Content>>) -> anyhow::Result<()> {
+        self.finished = true;
+        self.record_result(result)
+    }
+
+    fn record_result(&self, result: &ToolResult<Vec<Content>>) -> anyhow::Result<()> {
+        let (status, error) = match result {
+            Ok(_) => (AuditStatus::Success, None),
+            Err(e) => (AuditStatus::Error, Some(e.to_string())),
+        };
+        self.auditor.record(
+            &self.request,
+            AuditDecision::Allowed,
+            self.source,
+            status,
+            error,
+            Some(self.started.elapsed().as_millis() as u64),
+        )
+    }
+
+    /// Wrap the stream of the call so its result and duration are recorded. A stream dropped
+    /// before its result, because the reply was cancelled, is recorded as cancelled. A record
+    /// that cannot be written ends the reply through [`ToolAuditor::check`].
+    pub fn track(mut self, stream: ToolStream) -> ToolStream {
+        Box::pin(stream.map(move |item| {
+            if let ToolStreamItem::Result(result) = &item {
+                // The failure is kept by the auditor, which the reply checks after each batch
+                let _ = self.record_result(result);
+                self.finished = true;
+            }
+            item
+        }))
+    }
+}
+
+impl Drop for PendingCall {
+    fn drop(&mut self) {
+        if !self.finished {
+            let _ = self.auditor.record(
+                &self.request,
+                AuditDecision::Allowed,
+                self.source,
+                AuditStatus::Cancelled,
+                None,
+                Some(self.started.elapsed().as_millis() as u64),
+            );
+        }
+    }
+}
+
 pub const DECLINED_RESPONSE: &str = "The user has declined to run this tool. \
     DO NOT attempt to call this tool again. \
     If there are no alternative methods to proceed, clearly explain the situation and STOP.";
@@ -54,15 +221,23 @@ impl Agent {
         permission_manager: &'a mut PermissionManager,
         message_tool_response: Arc<Mutex<Message>>,
         cancellation_token: Option<CancellationToken>,
+        auditor: Option<ToolAuditor>,
     ) -> BoxStream<'a, anyhow::Result<Message>> {
         try_stream! {
             for request in tool_requests {
                 if let Ok(tool_call) = request.tool_call.clone() {
+                    let prompt = match permission_manager.project() {
+                        Some(_) => format!(
+                            "Goose would like to call the above tool ({}). Allow? (y/n):",
+                            permission_manager.describe_decision_layer(&tool_call.name)
+                        ),
+                        None => "Goose would like to call the above tool. Allow? (y/n):".to_string(),
+                    };
                     let confirmation = Message::user().with_tool_confirmation_request(
                         request.id.clone(),
                         tool_call.name.clone(),
                         tool_call.arguments.clone(),
-                        Some("Goose would like to call the above tool. Allow? (y/n):".to_string()),
+                        Some(prompt),
                     );
                     yield confirmation;
 
@@ -70,10 +245,14 @@ impl Agent {
                     while let Some((req_id, confirmation)) = rx.recv().await {
                         if req_id == request.id {
                             if confirmation.permission == Permission::AllowOnce || confirmation.permission == Permission::AlwaysAllow {
+                                let pending = match &auditor {
+                                    Some(auditor) => Some(auditor.start(request, PermissionDecisionSource::User)?),
+                                    None => None,
+                                };
                                 let (req_id, tool_result) = self.dispatch_tool_call(tool_call.clone(), request.id.clone(), cancellation_token.clone()).await;
                                 let mut futures = tool_futures.lock().await;
 
-                                futures.push((req_id, match tool_result {
+                                let stream = match tool_result {
                                     Ok(result) => tool_stream(
                                         result.notification_stream.unwrap_or_else(|| Box::new(stream::empty())),
                                         result.result,
@@ -82,6 +261,10 @@ impl Agent {
                                         Box::new(stream::empty()),
                                         futures::future::ready(Err(e)),
                                     ),
+                                };
+                                futures.push((req_id, match pending {
+                                    Some(pending) => pending.track(stream),
+                                    None => stream,
                                 }));
 
                                 if confirmation.permission == Permission::AlwaysAllow {
@@ -89,6 +272,9 @@ impl Agent {
                                 }
                             } else {
                                 // User declined - add declined response
+                                if let Some(auditor) = &auditor {
+                                    auditor.record_not_run(request, AuditDecision::Denied, PermissionDecisionSource::User)?;
+                                }
                                 let mut response = message_tool_response.lock().await;
                                 *response = response.clone().with_tool_response(
                                     request.id.clone(),
@@ -129,3 +315,104 @@ impl Agent {
         .boxed()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::tool_audit::{query_audit_log, AuditFilter};
+    use mcp_core::ToolCall;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_track_records_cancelled_calls() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let auditor = ToolAuditor::new(
+            Arc::new(ToolAuditLog::open(&path).unwrap()),
+            Some("session-1".to_string()),
+        );
+        let request = |id: &str| ToolRequest {
+            id: id.to_string(),
+            tool_call: Ok(ToolCall::new(
+                "developer__shell",
+                json!({"command": "sleep 100"}),
+            )),
+        };
+
+        let finished = auditor
+            .start(&request("finished"), PermissionDecisionSource::User)
+            .unwrap()
+            .track(tool_stream(
+                Box::new(stream::empty()),
+                futures::future::ready(Ok(vec![])),
+            ));
+        finished.collect::<Vec<_>>().await;
+
+        let mut cancelled = auditor
+            .start(&request("cancelled"), PermissionDecisionSource::User)
+            .unwrap()
+            .track(tool_stream(
+                Box::new(stream::empty()),
+                futures::future::pending(),
+            ));
+        assert!(futures::poll!(cancelled.next()).is_pending());
+        drop(cancelled);
+
+        let entries = query_audit_log(&path, &AuditFilter::default()).unwrap();
+        let statuses: Vec<_> = entries
+            .iter()
+            .map(|e| (e.record.request_id.as_str(), e.record.status))
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ("finished", AuditStatus::Started),
+                ("finished", AuditStatus::Success),
+                ("cancelled", AuditStatus::Started),
+                ("cancelled", AuditStatus::Cancelled)
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failed_audit_write_stops_tools() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let auditor = ToolAuditor::new(Arc::new(ToolAuditLog::open(&path).unwrap()), None);
+        let invalid = ToolRequest {
+            id: "invalid".to_string(),
+            tool_call: Err(rmcp::model::ErrorData::new(
+                rmcp::model::ErrorCode::INVALID_PARAMS,
+                "nope",
+                None,
+            )),
+        };
+        auditor
+            .record_not_run(
+                &invalid,
+                AuditDecision::Skipped,
+                PermissionDecisionSource::Rule,
+            )
+            .unwrap();
+        let entries = query_audit_log(&path, &AuditFilter::default()).unwrap();
+        assert_eq!(entries[0].record.tool_name, INVALID_TOOL_NAME);
+        assert!(entries[0].record.error.as_ref().unwrap().contains("nope"));
+
+        // A directory in place of the log makes every append fail
+        std::fs::remove_file(&path).unwrap();
+        std::fs::create_dir(&path).unwrap();
+        let request = ToolRequest {
+            id: "shell".to_string(),
+            tool_call: Ok(ToolCall::new("developer__shell", json!({"command": "ls"}))),
+        };
+        assert!(auditor
+            .start(&request, PermissionDecisionSource::User)
+            .is_err());
+        assert!(auditor.check().is_err());
+        // The reply stays stopped even if the log comes back
+        std::fs::remove_dir(&path).unwrap();
+        assert!(auditor
+            .start(&request, PermissionDecisionSource::User)
+            .is_err());
+    }
+}
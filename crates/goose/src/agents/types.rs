@@ -16,6 +16,12 @@ pub const DEFAULT_RETRY_TIMEOUT_SECONDS: u64 = 300;
 /// Default timeout for on_failure operations (10 minutes - longer for on_failure tasks)
 pub const DEFAULT_ON_FAILURE_TIMEOUT_SECONDS: u64 = 600;
 
+/// Default HTTP status expected by an Http success check
+pub const DEFAULT_HTTP_CHECK_STATUS: u16 = 200;
+
+/// Default minimum score (0.0 - 1.0) for an LLM judge success check to pass
+pub const DEFAULT_JUDGE_THRESHOLD: f64 = 0.7;
+
 /// Configuration for retry logic in recipe execution
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct RetryConfig {
@@ -55,6 +61,10 @@ impl RetryConfig {
             }
         }
 
+        for check in &self.checks {
+            check.validate()?;
+        }
+
         Ok(())
     }
 }
@@ -69,6 +79,123 @@ pub enum SuccessCheck {
         /// The shell command to execute
         command: String,
     },
+    /// Check that a file exists and, optionally, that its contents match a regex
+    #[serde(alias = "file")]
+    File {
+        /// Path to the file, relative to the session working directory
+        path: String,
+        /// Regex the file contents must match
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pattern: Option<String>,
+    },
+    /// Validate the final output against the recipe's `response.json_schema`
+    #[serde(alias = "json_schema")]
+    JsonSchema {},
+    /// Send a GET request to a local URL and compare the response status
+    #[serde(alias = "http")]
+    Http {
+        /// URL to request; must point at localhost or a loopback address
+        url: String,
+        /// Expected HTTP status code (default: 200)
+        #[serde(default = "default_http_check_status")]
+        expected_status: u16,
+    },
+    /// Ask the model to grade the final assistant message against a rubric
+    #[serde(alias = "llm_judge")]
+    LlmJudge {
+        /// What a successful answer looks like
+        rubric: String,
+        /// Minimum score between 0.0 and 1.0 needed to pass (default: 0.7)
+        #[serde(default = "default_judge_threshold")]
+        threshold: f64,
+    },
+}
+
+fn default_http_check_status() -> u16 {
+    DEFAULT_HTTP_CHECK_STATUS
+}
+
+fn default_judge_threshold() -> f64 {
+    DEFAULT_JUDGE_THRESHOLD
+}
+
+impl SuccessCheck {
+    /// Validates the check's own settings
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            SuccessCheck::Shell { .. } | SuccessCheck::JsonSchema {} => Ok(()),
+            SuccessCheck::File { path, pattern } => {
+                if path.trim().is_empty() {
+                    return Err("File check path must not be empty".to_string());
+                }
+                if let Some(pattern) = pattern {
+                    regex::Regex::new(pattern)
+                        .map_err(|e| format!("Invalid File check pattern '{}': {}", pattern, e))?;
+                }
+                Ok(())
+            }
+            SuccessCheck::Http { url, .. } => {
+                let parsed = url::Url::parse(url)
+                    .map_err(|e| format!("Invalid Http check url '{}': {}", url, e))?;
+                let is_local = match parsed.host() {
+                    Some(url::Host::Domain(domain)) => domain == "localhost",
+                    Some(url::Host::Ipv4(ip)) => ip.is_loopback(),
+                    Some(url::Host::Ipv6(ip)) => ip.is_loopback(),
+                    None => false,
+                };
+                if !is_local || !matches!(parsed.scheme(), "http" | "https") {
+                    return Err(format!(
+                        "Http check url '{}' must be an http(s) URL on localhost",
+                        url
+                    ));
+                }
+                Ok(())
+            }
+            SuccessCheck::LlmJudge { rubric, threshold } => {
+                if rubric.trim().is_empty() {
+                    return Err("LlmJudge check rubric must not be empty".to_string());
+                }
+                if !(0.0..=1.0).contains(threshold) {
+                    return Err("LlmJudge check threshold must be between 0.0 and 1.0".to_string());
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Short human-readable description used when reporting check results
+    pub fn describe(&self) -> String {
+        match self {
+            SuccessCheck::Shell { command } => format!("shell `{}`", command),
+            SuccessCheck::File {
+                path,
+                pattern: Some(pattern),
+            } => format!("file {} matches /{}/", path, pattern),
+            SuccessCheck::File {
+                path,
+                pattern: None,
+            } => format!("file {} exists", path),
+            SuccessCheck::JsonSchema {} => "final output matches the response schema".to_string(),
+            SuccessCheck::Http {
+                url,
+                expected_status,
+            } => format!("GET {} returns {}", url, expected_status),
+            SuccessCheck::LlmJudge { threshold, .. } => {
+                format!("judge score of at least {:.2}", threshold)
+            }
+        }
+    }
+}
+
+/// Outcome of evaluating a single success check
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SuccessCheckResult {
+    /// Description of the check that ran
+    pub check: String,
+    /// Whether the check passed
+    pub passed: bool,
+    /// Why the check passed or failed
+    pub reason: String,
 }
 
 /// A frontend tool that will be executed by the frontend rather than an extension
@@ -91,6 +218,9 @@ pub struct SessionConfig {
     pub execution_mode: Option<String>,
     /// Maximum number of turns (iterations) allowed without user input
     pub max_turns: Option<u32>,
+    /// Goose mode for this reply, taking precedence over the configured mode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub goose_mode: Option<String>,
     /// Retry configuration for automated validation and recovery
     #[serde(skip_serializing_if = "Option::is_none")]
     pub retry_config: Option<RetryConfig>,
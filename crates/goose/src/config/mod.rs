@@ -3,6 +3,7 @@ pub mod custom_providers;
 mod experiments;
 pub mod extensions;
 pub mod permission;
+pub mod project_permission;
 pub mod signup_openrouter;
 
 pub use crate::agents::ExtensionConfig;
@@ -11,6 +12,7 @@ pub use custom_providers::CustomProviderConfig;
 pub use experiments::ExperimentManager;
 pub use extensions::{ExtensionConfigManager, ExtensionEntry};
 pub use permission::PermissionManager;
+pub use project_permission::{ProjectPermissions, ProjectTrust, ProjectTrustStore};
 pub use signup_openrouter::configure_openrouter;
 
 pub use extensions::DEFAULT_DISPLAY_NAME;
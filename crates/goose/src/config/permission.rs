@@ -1,3 +1,4 @@
+use super::project_permission::ProjectPermissions;
 use super::APP_STRATEGY;
 use etcetera::{choose_app_strategy, AppStrategy};
 use serde::{Deserialize, Serialize};
@@ -15,6 +16,14 @@ pub enum PermissionLevel {
     NeverAllow,  // Tool is never allowed to be used
 }
 
+fn strictness(level: &PermissionLevel) -> usize {
+    match level {
+        PermissionLevel::AlwaysAllow => 0,
+        PermissionLevel::AskBefore => 1,
+        PermissionLevel::NeverAllow => 2,
+    }
+}
+
 /// Struct representing the configuration of permissions, categorized by level.
 #[derive(Debug, Deserialize, Serialize, Default, Clone)]
 pub struct PermissionConfig {
@@ -23,11 +32,20 @@ pub struct PermissionConfig {
     pub never_allow: Vec<String>,  // List of tools that are never allowed
 }
 
+/// The configuration layer a permission rule came from.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionLayer {
+    Global,  // The global permission.yaml
+    Project, // A trusted project .goose/permissions.yaml
+}
+
 /// PermissionManager manages permission configurations for various tools.
 #[derive(Debug)]
 pub struct PermissionManager {
     config_path: PathBuf, // Path to the permission configuration file
     permission_map: HashMap<String, PermissionConfig>, // Mapping of permission names to configurations
+    project: Option<ProjectPermissions>, // Project rules layered over the global user rules
 }
 
 // Constants representing specific permission categories
@@ -59,6 +77,7 @@ impl Default for PermissionManager {
         PermissionManager {
             config_path,
             permission_map,
+            project: None,
         }
     }
 }
@@ -81,9 +100,27 @@ impl PermissionManager {
         PermissionManager {
             config_path,
             permission_map,
+            project: None,
         }
     }
 
+    /// Layers the trusted project permissions for `working_dir`, if any, over the global rules.
+    pub fn with_project_for(mut self, working_dir: &Path) -> Self {
+        self.project = ProjectPermissions::discover_trusted(working_dir);
+        self
+    }
+
+    /// Layers the given project permissions over the global rules.
+    pub fn with_project(mut self, project: Option<ProjectPermissions>) -> Self {
+        self.project = project;
+        self
+    }
+
+    /// Returns the project permissions in effect, if any.
+    pub fn project(&self) -> Option<&ProjectPermissions> {
+        self.project.as_ref()
+    }
+
     /// Returns a list of all the names (keys) in the permission map.
     pub fn get_permission_names(&self) -> Vec<String> {
         self.permission_map.keys().cloned().collect()
@@ -91,7 +128,49 @@ impl PermissionManager {
 
     /// Retrieves the user permission level for a specific tool.
     pub fn get_user_permission(&self, principal_name: &str) -> Option<PermissionLevel> {
-        self.get_permission(USER_PERMISSION, principal_name)
+        self.get_user_permission_with_layer(principal_name)
+            .map(|(level, _)| level)
+    }
+
+    /// Retrieves the user permission level for a specific tool along with the layer that set it.
+    /// A project rule only applies when it is stricter than the global rule for the same tool.
+    pub fn get_user_permission_with_layer(
+        &self,
+        principal_name: &str,
+    ) -> Option<(PermissionLevel, PermissionLayer)> {
+        let global = self.get_permission(USER_PERMISSION, principal_name);
+        let project = self
+            .project
+            .as_ref()
+            .and_then(|project| project.get_permission(principal_name));
+        match (project, global) {
+            (Some(project), Some(global)) if strictness(&project) <= strictness(&global) => {
+                Some((global, PermissionLayer::Global))
+            }
+            (Some(project), _) => Some((project, PermissionLayer::Project)),
+            (None, global) => global.map(|level| (level, PermissionLayer::Global)),
+        }
+    }
+
+    /// Describes which layer decides how a tool is handled: a tool rule, or the goose mode.
+    pub fn describe_decision_layer(&self, principal_name: &str) -> String {
+        match self.get_user_permission_with_layer(principal_name) {
+            Some((_, PermissionLayer::Project)) => match &self.project {
+                Some(project) => format!("project rule in {}", project.path.display()),
+                None => "project rule".to_string(),
+            },
+            Some((_, PermissionLayer::Global)) => {
+                format!("global rule in {}", self.config_path.display())
+            }
+            None => match &self.project {
+                Some(project) if project.config.mode.is_some() => format!(
+                    "project mode '{}' in {}",
+                    project.config.mode.as_deref().unwrap_or_default(),
+                    project.path.display()
+                ),
+                _ => "global goose mode".to_string(),
+            },
+        }
     }
 
     /// Retrieves the smart approve permission level for a specific tool.
@@ -284,6 +363,58 @@ mod tests {
         assert!(config.never_allow.contains(&"tool7".to_string()));
     }
 
+    #[test]
+    fn test_project_rules_override_global_rules() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let project_file = dir.path().join(".goose").join("permissions.yaml");
+        fs::create_dir_all(project_file.parent().unwrap()).unwrap();
+        fs::write(&project_file, "never_allow: [tool8]\n").unwrap();
+        let project = ProjectPermissions::load(&project_file).unwrap();
+
+        let mut manager = create_test_permission_manager().with_project(Some(project));
+        manager.update_user_permission("tool8", PermissionLevel::AlwaysAllow);
+        manager.update_user_permission("tool9", PermissionLevel::AskBefore);
+
+        assert_eq!(
+            manager.get_user_permission_with_layer("tool8"),
+            Some((PermissionLevel::NeverAllow, PermissionLayer::Project))
+        );
+        assert_eq!(
+            manager.get_user_permission_with_layer("tool9"),
+            Some((PermissionLevel::AskBefore, PermissionLayer::Global))
+        );
+    }
+
+    #[test]
+    fn test_project_rules_cannot_loosen_global_rules() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let project_file = dir.path().join(".goose").join("permissions.yaml");
+        fs::create_dir_all(project_file.parent().unwrap()).unwrap();
+        fs::write(
+            &project_file,
+            "always_allow: [tool10, tool12]\nask_before: [tool11]\n",
+        )
+        .unwrap();
+        let project = ProjectPermissions::load(&project_file).unwrap();
+
+        let mut manager = create_test_permission_manager().with_project(Some(project));
+        manager.update_user_permission("tool10", PermissionLevel::NeverAllow);
+        manager.update_user_permission("tool11", PermissionLevel::AskBefore);
+
+        assert_eq!(
+            manager.get_user_permission_with_layer("tool10"),
+            Some((PermissionLevel::NeverAllow, PermissionLayer::Global))
+        );
+        assert_eq!(
+            manager.get_user_permission_with_layer("tool11"),
+            Some((PermissionLevel::AskBefore, PermissionLayer::Global))
+        );
+        assert_eq!(
+            manager.get_user_permission_with_layer("tool12"),
+            Some((PermissionLevel::AlwaysAllow, PermissionLayer::Project))
+        );
+    }
+
     #[test]
     fn test_remove_extension() {
         let mut manager = create_test_permission_manager();
@@ -0,0 +1,276 @@
+use super::permission::PermissionLevel;
+use super::APP_STRATEGY;
+use anyhow::{anyhow, Context, Result};
+use blake3::Hasher;
+use etcetera::{choose_app_strategy, AppStrategy};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Location of the project permission file, relative to the project root
+pub const PROJECT_PERMISSION_FILE: &str = ".goose/permissions.yaml";
+
+const VALID_MODES: [&str; 4] = ["auto", "approve", "smart_approve", "chat"];
+
+/// Contents of a project's `.goose/permissions.yaml`
+#[derive(Debug, Deserialize, Serialize, Default, Clone, PartialEq)]
+#[serde(default)]
+pub struct ProjectPermissionConfig {
+    /// Goose mode to use for sessions inside the project
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    pub always_allow: Vec<String>,
+    pub ask_before: Vec<String>,
+    pub never_allow: Vec<String>,
+}
+
+/// A project permission file found on disk
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectPermissions {
+    /// Directory containing the `.goose` folder
+    pub root: PathBuf,
+    /// Path of the permission file itself
+    pub path: PathBuf,
+    pub config: ProjectPermissionConfig,
+    /// blake3 hash of the file contents, used to re-prompt for trust when it changes
+    pub content_hash: String,
+}
+
+impl ProjectPermissions {
+    /// Find the nearest project permission file at or above `working_dir`
+    pub fn find(working_dir: &Path) -> Option<PathBuf> {
+        working_dir
+            .ancestors()
+            .map(|dir| dir.join(PROJECT_PERMISSION_FILE))
+            .find(|path| path.is_file())
+    }
+
+    /// Load and validate a project permission file
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let config: ProjectPermissionConfig = if content.trim().is_empty() {
+            ProjectPermissionConfig::default()
+        } else {
+            serde_yaml::from_str(&content)
+                .with_context(|| format!("Failed to parse {}", path.display()))?
+        };
+
+        if let Some(mode) = &config.mode {
+            if !VALID_MODES.contains(&mode.as_str()) {
+                return Err(anyhow!(
+                    "Invalid mode '{}' in {}, expected one of: {}",
+                    mode,
+                    path.display(),
+                    VALID_MODES.join(", ")
+                ));
+            }
+        }
+
+        // The file lives at <root>/.goose/permissions.yaml
+        let root = path
+            .parent()
+            .and_then(Path::parent)
+            .map(Path::to_path_buf)
+            .ok_or_else(|| anyhow!("Invalid project permission path {}", path.display()))?;
+
+        let mut hasher = Hasher::new();
+        hasher.update(content.as_bytes());
+
+        Ok(Self {
+            root,
+            path: path.to_path_buf(),
+            config,
+            content_hash: hasher.finalize().to_hex().to_string(),
+        })
+    }
+
+    /// Find and load the project permission file for `working_dir`, if there is one
+    pub fn discover(working_dir: &Path) -> Option<Self> {
+        let path = Self::find(working_dir)?;
+        match Self::load(&path) {
+            Ok(project) => Some(project),
+            Err(e) => {
+                tracing::warn!("Ignoring project permissions: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Find the project permission file for `working_dir`, only if the user has trusted it
+    pub fn discover_trusted(working_dir: &Path) -> Option<Self> {
+        let project = Self::discover(working_dir)?;
+        match ProjectTrustStore::load().trust_of(&project) {
+            ProjectTrust::Trusted => Some(project),
+            ProjectTrust::Untrusted => None,
+            ProjectTrust::Unknown => {
+                tracing::info!(
+                    "Ignoring project permissions at {} until they are trusted",
+                    project.path.display()
+                );
+                None
+            }
+        }
+    }
+
+    /// The project rule for a tool, if any; the strictest level wins when a tool is listed twice
+    pub fn get_permission(&self, principal_name: &str) -> Option<PermissionLevel> {
+        let name = principal_name.to_string();
+        if self.config.never_allow.contains(&name) {
+            Some(PermissionLevel::NeverAllow)
+        } else if self.config.ask_before.contains(&name) {
+            Some(PermissionLevel::AskBefore)
+        } else if self.config.always_allow.contains(&name) {
+            Some(PermissionLevel::AlwaysAllow)
+        } else {
+            None
+        }
+    }
+}
+
+/// Whether the user has agreed to apply a project permission file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectTrust {
+    Trusted,
+    Untrusted,
+    /// Never seen, or changed since the user last decided
+    Unknown,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+struct ProjectTrustRecord {
+    trusted: bool,
+    content_hash: String,
+}
+
+/// Remembers which project permission files the user has trusted, keyed by file path
+#[derive(Debug)]
+pub struct ProjectTrustStore {
+    path: PathBuf,
+    records: HashMap<String, ProjectTrustRecord>,
+}
+
+impl ProjectTrustStore {
+    /// Load the trust store from the goose config directory
+    pub fn load() -> Self {
+        let config_dir = choose_app_strategy(APP_STRATEGY.clone())
+            .map(|strategy| strategy.config_dir())
+            .unwrap_or_else(|_| PathBuf::from(".config/goose"));
+        Self::load_from(config_dir.join("trusted_projects.yaml"))
+    }
+
+    /// Load a trust store from a specific file
+    pub fn load_from(path: PathBuf) -> Self {
+        let records = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_yaml::from_str(&content).ok())
+            .unwrap_or_default();
+        Self { path, records }
+    }
+
+    pub fn trust_of(&self, project: &ProjectPermissions) -> ProjectTrust {
+        match self
+            .records
+            .get(&project.path.to_string_lossy().to_string())
+        {
+            Some(record) if record.content_hash == project.content_hash => {
+                if record.trusted {
+                    ProjectTrust::Trusted
+                } else {
+                    ProjectTrust::Untrusted
+                }
+            }
+            _ => ProjectTrust::Unknown,
+        }
+    }
+
+    /// Record the user's decision for the current contents of a project file
+    pub fn set_trust(&mut self, project: &ProjectPermissions, trusted: bool) -> Result<()> {
+        self.records.insert(
+            project.path.to_string_lossy().to_string(),
+            ProjectTrustRecord {
+                trusted,
+                content_hash: project.content_hash.clone(),
+            },
+        );
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, serde_yaml::to_string(&self.records)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_project_file(root: &Path, content: &str) -> PathBuf {
+        let path = root.join(PROJECT_PERMISSION_FILE);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_find_walks_up_from_working_dir() {
+        let dir = TempDir::new().unwrap();
+        let path = write_project_file(dir.path(), "mode: approve\n");
+        let nested = dir.path().join("src").join("module");
+        fs::create_dir_all(&nested).unwrap();
+
+        assert_eq!(ProjectPermissions::find(&nested), Some(path));
+
+        let project = ProjectPermissions::discover(&nested).unwrap();
+        assert_eq!(project.root, dir.path());
+        assert_eq!(project.config.mode.as_deref(), Some("approve"));
+    }
+
+    #[test]
+    fn test_get_permission() {
+        let dir = TempDir::new().unwrap();
+        let path = write_project_file(
+            dir.path(),
+            "always_allow: [developer__text_editor]\nnever_allow: [developer__shell]\n",
+        );
+        let project = ProjectPermissions::load(&path).unwrap();
+
+        assert_eq!(
+            project.get_permission("developer__text_editor"),
+            Some(PermissionLevel::AlwaysAllow)
+        );
+        assert_eq!(
+            project.get_permission("developer__shell"),
+            Some(PermissionLevel::NeverAllow)
+        );
+        assert_eq!(project.get_permission("other__tool"), None);
+        assert_eq!(project.config.mode, None);
+    }
+
+    #[test]
+    fn test_invalid_mode_is_rejected() {
+        let dir = TempDir::new().unwrap();
+        let path = write_project_file(dir.path(), "mode: yolo\n");
+        assert!(ProjectPermissions::load(&path).is_err());
+    }
+
+    #[test]
+    fn test_trust_is_reset_when_file_changes() {
+        let dir = TempDir::new().unwrap();
+        let path = write_project_file(dir.path(), "mode: approve\n");
+        let mut store = ProjectTrustStore::load_from(dir.path().join("trusted.yaml"));
+
+        let project = ProjectPermissions::load(&path).unwrap();
+        assert_eq!(store.trust_of(&project), ProjectTrust::Unknown);
+
+        store.set_trust(&project, true).unwrap();
+        let store = ProjectTrustStore::load_from(dir.path().join("trusted.yaml"));
+        assert_eq!(store.trust_of(&project), ProjectTrust::Trusted);
+
+        write_project_file(dir.path(), "mode: auto\n");
+        let changed = ProjectPermissions::load(&path).unwrap();
+        assert_eq!(store.trust_of(&changed), ProjectTrust::Unknown);
+    }
+}
@@ -12,6 +12,7 @@ pub mod recipe_deeplink;
 pub mod scheduler;
 pub mod scheduler_factory;
 pub mod scheduler_trait;
+pub mod scheduler_triggers;
 pub mod session;
 pub mod temporal_scheduler;
 pub mod token_counter;
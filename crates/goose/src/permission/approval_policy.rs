@@ -0,0 +1,259 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::Permission;
+
+/// How a tool call that needs confirmation is answered when no pattern of the policy matches it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OnConfirmation {
+    /// Decline the tool call and let the agent carry on
+    Deny,
+    /// Run the tool call
+    Allow,
+    /// Stop the run with an error
+    Fail,
+}
+
+/// Answers tool confirmation requests without a user, for runs where nobody is there to answer
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ApprovalPolicy {
+    /// Tools that are allowed, by name or by a pattern where `*` matches any characters
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow_tools: Vec<String>,
+    /// Tools that are denied, taking precedence over `allow_tools`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deny_tools: Vec<String>,
+    /// What to do with the other tools. Defaults to `deny` when `allow_tools` is set and to
+    /// `allow` otherwise.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_confirmation: Option<OnConfirmation>,
+}
+
+/// The answer of an [`ApprovalPolicy`] to a confirmation request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApprovalDecision {
+    Allow,
+    Deny,
+    Fail,
+}
+
+impl ApprovalDecision {
+    /// The permission to send through `Agent::handle_confirmation`, None when the run should stop
+    pub fn permission(&self) -> Option<Permission> {
+        match self {
+            ApprovalDecision::Allow => Some(Permission::AllowOnce),
+            ApprovalDecision::Deny => Some(Permission::DenyOnce),
+            ApprovalDecision::Fail => None,
+        }
+    }
+}
+
+impl ApprovalPolicy {
+    pub fn is_empty(&self) -> bool {
+        self.allow_tools.is_empty() && self.deny_tools.is_empty() && self.on_confirmation.is_none()
+    }
+
+    /// Whether the policy names tools, so that it needs to see every tool call
+    pub fn has_patterns(&self) -> bool {
+        !self.allow_tools.is_empty() || !self.deny_tools.is_empty()
+    }
+
+    /// Combine with a policy that takes precedence, like command line flags over recipe
+    /// settings. Patterns of both apply and the other policy's `on_confirmation` wins.
+    pub fn merge(mut self, other: ApprovalPolicy) -> Self {
+        self.allow_tools.extend(other.allow_tools);
+        self.deny_tools.extend(other.deny_tools);
+        self.on_confirmation = other.on_confirmation.or(self.on_confirmation);
+        self
+    }
+
+    /// The part of a recipe's policy that can only refuse tool calls: its `deny_tools`, and its
+    /// `on_confirmation` when that is `deny` or `fail`. Allowing tools takes a command line
+    /// flag. Tools the policy does not name are answered the way they would be without it:
+    /// they run in `auto` mode and fail the run in other modes, where nobody could confirm them.
+    pub fn restrictions(&self, goose_mode: &str) -> Self {
+        let on_confirmation = match self.on_confirmation {
+            Some(OnConfirmation::Allow) | None if self.deny_tools.is_empty() => None,
+            Some(OnConfirmation::Allow) | None if goose_mode == "auto" => None,
+            Some(OnConfirmation::Allow) | None => Some(OnConfirmation::Fail),
+            narrowing => narrowing,
+        };
+        Self {
+            allow_tools: Vec::new(),
+            deny_tools: self.deny_tools.clone(),
+            on_confirmation,
+        }
+    }
+
+    pub fn decide(&self, tool_name: &str) -> ApprovalDecision {
+        let matches = |patterns: &[String]| {
+            patterns
+                .iter()
+                .any(|pattern| tool_pattern_matches(pattern, tool_name))
+        };
+        if matches(&self.deny_tools) {
+            return ApprovalDecision::Deny;
+        }
+        if matches(&self.allow_tools) {
+            return ApprovalDecision::Allow;
+        }
+        let fallback = self
+            .on_confirmation
+            .unwrap_or(if self.allow_tools.is_empty() {
+                OnConfirmation::Allow
+            } else {
+                OnConfirmation::Deny
+            });
+        match fallback {
+            OnConfirmation::Allow => ApprovalDecision::Allow,
+            OnConfirmation::Deny => ApprovalDecision::Deny,
+            OnConfirmation::Fail => ApprovalDecision::Fail,
+        }
+    }
+}
+
+/// Match a tool name against a pattern where `*` matches any run of characters
+pub fn tool_pattern_matches(pattern: &str, tool_name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = tool_name.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // No wildcard, the whole name has to match
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tool_pattern_matches() {
+        assert!(tool_pattern_matches("developer__shell", "developer__shell"));
+        assert!(!tool_pattern_matches(
+            "developer__shell",
+            "developer__shells"
+        ));
+        assert!(tool_pattern_matches(
+            "developer__*",
+            "developer__text_editor"
+        ));
+        assert!(!tool_pattern_matches(
+            "developer__*",
+            "github__create_issue"
+        ));
+        assert!(tool_pattern_matches("*__read*", "github__read_file"));
+        assert!(tool_pattern_matches("*", "anything"));
+        assert!(tool_pattern_matches("a*b*c", "aXbYc"));
+        assert!(!tool_pattern_matches("a*b*c", "aXcYb"));
+        assert!(!tool_pattern_matches("ab*ba", "aba"));
+    }
+
+    #[test]
+    fn test_deny_takes_precedence_over_allow() {
+        let policy = ApprovalPolicy {
+            allow_tools: vec!["developer__*".to_string()],
+            deny_tools: vec!["developer__shell".to_string()],
+            on_confirmation: None,
+        };
+
+        assert_eq!(policy.decide("developer__shell"), ApprovalDecision::Deny);
+        assert_eq!(
+            policy.decide("developer__text_editor"),
+            ApprovalDecision::Allow
+        );
+        // An allowlist denies everything else by default
+        assert_eq!(policy.decide("github__merge"), ApprovalDecision::Deny);
+    }
+
+    #[test]
+    fn test_on_confirmation_fallback() {
+        let deny_only = ApprovalPolicy {
+            deny_tools: vec!["developer__shell".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(deny_only.decide("github__merge"), ApprovalDecision::Allow);
+
+        let failing = deny_only.clone().merge(ApprovalPolicy {
+            on_confirmation: Some(OnConfirmation::Fail),
+            ..Default::default()
+        });
+        assert_eq!(failing.decide("github__merge"), ApprovalDecision::Fail);
+        assert_eq!(failing.decide("developer__shell"), ApprovalDecision::Deny);
+        assert_eq!(ApprovalDecision::Fail.permission(), None);
+    }
+
+    #[test]
+    fn test_recipe_policies_only_narrow() {
+        let recipe = ApprovalPolicy {
+            allow_tools: vec!["developer__*".to_string()],
+            deny_tools: vec!["developer__shell".to_string()],
+            on_confirmation: Some(OnConfirmation::Allow),
+        };
+
+        let restrictions = recipe.restrictions("auto");
+        assert!(restrictions.allow_tools.is_empty());
+        assert_eq!(restrictions.deny_tools, vec!["developer__shell"]);
+        assert_eq!(restrictions.on_confirmation, None);
+        assert_eq!(
+            restrictions.decide("developer__shell"),
+            ApprovalDecision::Deny
+        );
+        assert_eq!(
+            restrictions.decide("developer__text_editor"),
+            ApprovalDecision::Allow
+        );
+
+        let restrictions = recipe.restrictions("approve");
+        assert_eq!(
+            restrictions.decide("developer__text_editor"),
+            ApprovalDecision::Fail
+        );
+
+        let allow_all = ApprovalPolicy {
+            on_confirmation: Some(OnConfirmation::Allow),
+            ..Default::default()
+        };
+        assert!(allow_all.restrictions("approve").is_empty());
+
+        let failing = ApprovalPolicy {
+            on_confirmation: Some(OnConfirmation::Fail),
+            ..Default::default()
+        };
+        assert_eq!(failing.restrictions("auto"), failing);
+    }
+
+    #[test]
+    fn test_merge_keeps_patterns_of_both() {
+        let recipe = ApprovalPolicy {
+            allow_tools: vec!["developer__text_editor".to_string()],
+            deny_tools: vec![],
+            on_confirmation: Some(OnConfirmation::Allow),
+        };
+        let flags = ApprovalPolicy {
+            allow_tools: vec!["github__*".to_string()],
+            deny_tools: vec!["github__merge".to_string()],
+            on_confirmation: Some(OnConfirmation::Deny),
+        };
+
+        let policy = recipe.merge(flags);
+
+        assert_eq!(
+            policy.allow_tools,
+            vec!["developer__text_editor", "github__*"]
+        );
+        assert_eq!(policy.deny_tools, vec!["github__merge"]);
+        assert_eq!(policy.on_confirmation, Some(OnConfirmation::Deny));
+    }
+}
@@ -1,7 +1,9 @@
+pub mod approval_policy;
 pub mod permission_confirmation;
 pub mod permission_judge;
 pub mod permission_store;
 
+pub use approval_policy::{ApprovalDecision, ApprovalPolicy, OnConfirmation};
 pub use permission_confirmation::{Permission, PermissionConfirmation};
-pub use permission_judge::detect_read_only_tools;
+pub use permission_judge::{detect_read_only_tools, PermissionDecisionSource};
 pub use permission_store::ToolPermissionStore;
@@ -1,5 +1,5 @@
 use crate::agents::platform_tools::PLATFORM_MANAGE_EXTENSIONS_TOOL_NAME;
-use crate::config::permission::PermissionLevel;
+use crate::config::permission::{PermissionLayer, PermissionLevel};
 use crate::config::PermissionManager;
 use crate::conversation::message::{Message, MessageContent, ToolRequest};
 use crate::conversation::Conversation;
@@ -11,7 +11,7 @@ use rmcp::model::{Tool, ToolAnnotations};
 use rmcp::object;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 #[derive(Serialize)]
@@ -155,12 +155,31 @@ pub async fn detect_read_only_tools(
     }
 }
 
+/// Where a permission decision for a tool request came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionDecisionSource {
+    /// The user answered a confirmation prompt
+    User,
+    /// The smart-approve judge classified the tool during this check
+    SmartApprove,
+    /// A smart-approve decision cached from an earlier check
+    PermissionStore,
+    /// A configured rule: the goose mode, a user permission or a read-only annotation
+    Rule,
+    /// A rule or mode from a trusted project `.goose/permissions.yaml`
+    ProjectRule,
+}
+
 // Define return structure
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PermissionCheckResult {
     pub approved: Vec<ToolRequest>,
     pub needs_approval: Vec<ToolRequest>,
     pub denied: Vec<ToolRequest>,
+    /// Source of the decision for each request, keyed by request id
+    #[serde(default)]
+    pub sources: HashMap<String, PermissionDecisionSource>,
 }
 
 pub async fn check_tool_permissions(
@@ -176,6 +195,12 @@ pub async fn check_tool_permissions(
     let mut denied = vec![];
     let mut llm_detect_candidates = vec![];
     let mut extension_request_ids = vec![];
+    let mut sources = HashMap::new();
+    // Decisions that fall back to the goose mode come from the project when it sets one
+    let mode_source = match permission_manager.project() {
+        Some(project) if project.config.mode.is_some() => PermissionDecisionSource::ProjectRule,
+        _ => PermissionDecisionSource::Rule,
+    };
 
     for request in candidate_requests {
         if let Ok(tool_call) = request.tool_call.clone() {
@@ -183,13 +208,21 @@ pub async fn check_tool_permissions(
                 continue;
             } else if mode == "auto" {
                 approved.push(request.clone());
+                sources.insert(request.id.clone(), mode_source);
             } else {
                 if tool_call.name == PLATFORM_MANAGE_EXTENSIONS_TOOL_NAME {
                     extension_request_ids.push(request.id.clone());
                 }
 
                 // 1. Check user-defined permission
-                if let Some(level) = permission_manager.get_user_permission(&tool_call.name) {
+                if let Some((level, layer)) =
+                    permission_manager.get_user_permission_with_layer(&tool_call.name)
+                {
+                    let source = match layer {
+                        PermissionLayer::Global => PermissionDecisionSource::Rule,
+                        PermissionLayer::Project => PermissionDecisionSource::ProjectRule,
+                    };
+                    sources.insert(request.id.clone(), source);
                     match level {
                         PermissionLevel::AlwaysAllow => approved.push(request.clone()),
                         PermissionLevel::AskBefore => needs_approval.push(request.clone()),
@@ -202,11 +235,16 @@ pub async fn check_tool_permissions(
                 match mode {
                     "approve" => {
                         needs_approval.push(request.clone());
+                        sources.insert(request.id.clone(), mode_source);
                     }
                     "smart_approve" => {
                         if let Some(level) =
                             permission_manager.get_smart_approve_permission(&tool_call.name)
                         {
+                            sources.insert(
+                                request.id.clone(),
+                                PermissionDecisionSource::PermissionStore,
+                            );
                             match level {
                                 PermissionLevel::AlwaysAllow => approved.push(request.clone()),
                                 PermissionLevel::AskBefore => needs_approval.push(request.clone()),
@@ -217,14 +255,17 @@ pub async fn check_tool_permissions(
 
                         if tools_with_readonly_annotation.contains(&tool_call.name) {
                             approved.push(request.clone());
+                            sources.insert(request.id.clone(), PermissionDecisionSource::Rule);
                         } else if tools_without_annotation.contains(&tool_call.name) {
                             llm_detect_candidates.push(request.clone());
                         } else {
                             needs_approval.push(request.clone());
+                            sources.insert(request.id.clone(), mode_source);
                         }
                     }
                     _ => {
                         needs_approval.push(request.clone());
+                        sources.insert(request.id.clone(), mode_source);
                     }
                 }
             }
@@ -237,6 +278,7 @@ pub async fn check_tool_permissions(
             detect_read_only_tools(provider, llm_detect_candidates.iter().collect()).await;
         for request in llm_detect_candidates {
             if let Ok(tool_call) = request.tool_call.clone() {
+                sources.insert(request.id.clone(), PermissionDecisionSource::SmartApprove);
                 if detected_readonly_tools.contains(&tool_call.name) {
                     approved.push(request.clone());
                     permission_manager.update_smart_approve_permission(
@@ -259,6 +301,7 @@ pub async fn check_tool_permissions(
             approved,
             needs_approval,
             denied,
+            sources,
         },
         extension_request_ids,
     )
@@ -468,6 +511,13 @@ mod tests {
         assert!(result.needs_approval.iter().any(|req| req.id == "tool_2"));
         assert!(result.needs_approval.iter().any(|req| req.id == "tool_3"));
         assert!(enable_extension_request_ids.iter().any(|id| id == "tool_3"));
+
+        assert_eq!(result.sources["tool_1"], PermissionDecisionSource::Rule);
+        assert_eq!(
+            result.sources["tool_2"],
+            PermissionDecisionSource::PermissionStore
+        );
+        assert_eq!(result.sources["tool_3"], PermissionDecisionSource::Rule);
     }
 
     #[tokio::test]
@@ -4,10 +4,23 @@ use blake3::Hasher;
 use chrono::Utc;
 use etcetera::{choose_app_strategy, AppStrategy};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::collections::HashMap;
 use std::time::Duration;
 use std::{fs::File, path::PathBuf};
 
+/// Hash a tool's arguments to differentiate similar calls.
+/// This helps identify when the same tool is being used in a different context.
+pub fn hash_tool_arguments(arguments: &Value) -> String {
+    let mut hasher = Hasher::new();
+    hasher.update(
+        serde_json::to_string(arguments)
+            .unwrap_or_default()
+            .as_bytes(),
+    );
+    hasher.finalize().to_hex().to_string()
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ToolPermissionRecord {
     tool_name: String,
@@ -120,15 +133,7 @@ impl ToolPermissionStore {
     }
 
     fn hash_tool_context(&self, tool_request: &ToolRequest) -> String {
-        // Create a hash of the tool's arguments to differentiate similar calls
-        // This helps identify when the same tool is being used in a different context
-        let mut hasher = Hasher::new();
-        hasher.update(
-            serde_json::to_string(&tool_request.tool_call.as_ref().unwrap().arguments)
-                .unwrap_or_default()
-                .as_bytes(),
-        );
-        hasher.finalize().to_hex().to_string()
+        hash_tool_arguments(&tool_request.tool_call.as_ref().unwrap().arguments)
     }
 
     pub fn cleanup_expired(&mut self) -> anyhow::Result<()> {
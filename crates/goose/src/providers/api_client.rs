@@ -1,3 +1,4 @@
+use super::rate_limit;
 use anyhow::Result;
 use async_trait::async_trait;
 use reqwest::{
@@ -173,7 +174,9 @@ impl<'a> ApiRequestBuilder<'a> {
 
     pub async fn response_post(self, payload: &Value) -> Result<Response> {
         let request = self.send_request(|url, client| client.post(url)).await?;
-        Ok(request.json(payload).send().await?)
+        let response = request.json(payload).send().await?;
+        rate_limit::observe_response(response.status(), response.headers());
+        Ok(response)
     }
 
     pub async fn api_get(self) -> Result<ApiResponse> {
@@ -183,7 +186,9 @@ impl<'a> ApiRequestBuilder<'a> {
 
     pub async fn response_get(self) -> Result<Response> {
         let request = self.send_request(|url, client| client.get(url)).await?;
-        Ok(request.send().await?)
+        let response = request.send().await?;
+        rate_limit::observe_response(response.status(), response.headers());
+        Ok(response)
     }
 
     async fn send_request<F>(&self, request_builder: F) -> Result<reqwest::RequestBuilder>
@@ -309,6 +309,19 @@ pub trait LeadWorkerProviderTrait {
     fn get_active_model(&self) -> String;
 }
 
+/// Trait for FallbackProvider-specific functionality
+pub trait FallbackProviderTrait {
+    /// Get the `provider/model` labels of the chain, in order of preference
+    fn get_chain(&self) -> Vec<String>;
+
+    /// Get the label of the active provider and whether it is a fallback
+    fn get_active(&self) -> (String, bool);
+
+    /// Take the model and mode ("primary" or "fallback") the chain switched to since the
+    /// last call, if it switched
+    fn take_model_change(&self) -> Option<(String, String)>;
+}
+
 /// Base trait for AI providers (OpenAI, Anthropic, etc)
 #[async_trait]
 pub trait Provider: Send + Sync {
@@ -372,6 +385,11 @@ pub trait Provider: Send + Sync {
         None
     }
 
+    /// Check if this provider is a FallbackProvider
+    fn as_fallback(&self) -> Option<&dyn FallbackProviderTrait> {
+        None
+    }
+
     async fn stream(
         &self,
         _system: &str,
@@ -8,6 +8,7 @@ use super::{
     claude_code::ClaudeCodeProvider,
     cursor_agent::CursorAgentProvider,
     databricks::DatabricksProvider,
+    fallback::{FallbackProvider, DEFAULT_CIRCUIT_COOLDOWN, DEFAULT_CIRCUIT_FAILURE_THRESHOLD},
     gcpvertexai::GcpVertexAIProvider,
     gemini_cli::GeminiCliProvider,
     google::GoogleProvider,
@@ -18,6 +19,8 @@ use super::{
     openai::OpenAiProvider,
     openrouter::OpenRouterProvider,
     provider_registry::ProviderRegistry,
+    rate_limit::RateLimitedProvider,
+    replay::{self, ReplayProvider, REPLAY_PROVIDER_NAME},
     sagemaker_tgi::SageMakerTgiProvider,
     snowflake::SnowflakeProvider,
     venice::VeniceProvider,
@@ -87,28 +90,179 @@ pub fn refresh_custom_providers() -> Result<()> {
     Ok(())
 }
 
+/// Create a registered provider behind the shared rate limiter for its provider and model
+fn create_from_registry(name: &str, model: ModelConfig) -> Result<Arc<dyn Provider>> {
+    let provider = REGISTRY.read().unwrap().create(name, model)?;
+    Ok(Arc::new(RateLimitedProvider::new(name, provider)))
+}
+
 pub fn create(name: &str, model: ModelConfig) -> Result<Arc<dyn Provider>> {
+    create_with_fallbacks(name, model, None)
+}
+
+/// The configured provider, when it can create embeddings
+pub fn create_embedding_provider() -> Option<Arc<dyn Provider>> {
     let config = crate::config::Config::global();
+    let provider_name = config.get_param::<String>("GOOSE_PROVIDER").ok()?;
+    let model_name = config.get_param::<String>("GOOSE_MODEL").ok()?;
+    let model = ModelConfig::new(&model_name).ok()?;
+    create(&provider_name, model)
+        .ok()
+        .filter(|provider| provider.supports_embeddings())
+}
 
-    if let Ok(lead_model_name) = config.get_param::<String>("GOOSE_LEAD_MODEL") {
+/// Create a provider, wrapped in a fallback chain when `fallback_models` is given or
+/// GOOSE_FALLBACK_MODELS is configured. Fallback models are written as `provider/model`.
+pub fn create_with_fallbacks(
+    name: &str,
+    model: ModelConfig,
+    fallback_models: Option<Vec<String>>,
+) -> Result<Arc<dyn Provider>> {
+    create_provider(name, model, fallback_models, None)
+}
+
+/// Create a provider to switch a running session to. In lead/worker mode the new model
+/// becomes the worker, while the lead stays on `lead_provider_name` instead of following
+/// the switch to a provider that may not serve the lead model.
+pub fn create_for_switch(
+    name: &str,
+    model: ModelConfig,
+    lead_provider_name: &str,
+) -> Result<Arc<dyn Provider>> {
+    create_provider(name, model, None, Some(lead_provider_name))
+}
+
+fn create_provider(
+    name: &str,
+    model: ModelConfig,
+    fallback_models: Option<Vec<String>>,
+    lead_provider_name: Option<&str>,
+) -> Result<Arc<dyn Provider>> {
+    let config = crate::config::Config::global();
+
+    if name == REPLAY_PROVIDER_NAME {
+        return Ok(Arc::new(ReplayProvider::from_env(model)?));
+    }
+
+    let provider = if let Ok(lead_model_name) = config.get_param::<String>("GOOSE_LEAD_MODEL") {
         tracing::info!("Creating lead/worker provider from environment variables");
-        return create_lead_worker_from_env(name, &model, &lead_model_name);
+        let lead_provider_name = lead_provider_name
+            .map(str::to_string)
+            .unwrap_or_else(|| lead_provider_from_env(name));
+        create_lead_worker_from_env(name, &model, &lead_model_name, &lead_provider_name)?
+    } else {
+        create_from_registry(name, model)?
+    };
+
+    let fallback_models = fallback_models.unwrap_or_else(configured_fallback_models);
+    let provider = if fallback_models.is_empty() {
+        provider
+    } else {
+        create_fallback_chain(name, provider, &fallback_models)?
+    };
+
+    // With a fixture configured, any real provider records its responses for later replay
+    match replay::fixture_settings() {
+        Some(settings) => {
+            tracing::info!(
+                "Recording provider responses to {}",
+                settings.path.display()
+            );
+            Ok(Arc::new(ReplayProvider::recording(
+                provider,
+                &settings.path,
+                settings.overwrite,
+            )?))
+        }
+        None => Ok(provider),
+    }
+}
+
+/// Read GOOSE_FALLBACK_MODELS, either a list or a comma separated string
+fn configured_fallback_models() -> Vec<String> {
+    let config = crate::config::Config::global();
+    match config.get_param::<serde_json::Value>("GOOSE_FALLBACK_MODELS") {
+        Ok(serde_json::Value::Array(items)) => items
+            .into_iter()
+            .filter_map(|item| item.as_str().map(str::to_string))
+            .collect(),
+        Ok(serde_json::Value::String(list)) => list
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(str::to_string)
+            .collect(),
+        _ => Vec::new(),
     }
+}
 
-    REGISTRY.read().unwrap().create(name, model)
+/// Split a `provider/model` fallback entry. The model may itself contain slashes.
+pub fn parse_fallback_model(spec: &str) -> Result<(String, String)> {
+    match spec.trim().split_once('/') {
+        Some((provider, model)) if !provider.is_empty() && !model.is_empty() => {
+            Ok((provider.to_string(), model.to_string()))
+        }
+        _ => Err(anyhow::anyhow!(
+            "Invalid fallback model '{}', expected provider/model",
+            spec
+        )),
+    }
+}
+
+fn create_fallback_chain(
+    primary_name: &str,
+    primary: Arc<dyn Provider>,
+    fallback_models: &[String],
+) -> Result<Arc<dyn Provider>> {
+    let config = crate::config::Config::global();
+
+    let failure_threshold = config
+        .get_param::<usize>("GOOSE_FALLBACK_FAILURE_THRESHOLD")
+        .unwrap_or(DEFAULT_CIRCUIT_FAILURE_THRESHOLD);
+    let cooldown = config
+        .get_param::<u64>("GOOSE_FALLBACK_COOLDOWN_SECS")
+        .map(std::time::Duration::from_secs)
+        .unwrap_or(DEFAULT_CIRCUIT_COOLDOWN);
+
+    let mut chain = vec![(primary_name.to_string(), primary)];
+    for spec in fallback_models {
+        let (provider_name, model_name) = parse_fallback_model(spec)?;
+        let model_config = ModelConfig::new(&model_name)?;
+        let provider = create_from_registry(&provider_name, model_config)?;
+        chain.push((provider_name, provider));
+    }
+
+    tracing::info!(
+        "Creating fallback provider chain: {}",
+        chain
+            .iter()
+            .map(|(name, provider)| format!("{}/{}", name, provider.get_model_config().model_name))
+            .collect::<Vec<_>>()
+            .join(" -> ")
+    );
+
+    Ok(Arc::new(FallbackProvider::new_with_settings(
+        chain,
+        failure_threshold,
+        cooldown,
+    )))
+}
+
+/// The provider serving the lead model: GOOSE_LEAD_PROVIDER, or the session's own provider
+pub fn lead_provider_from_env(default_provider_name: &str) -> String {
+    crate::config::Config::global()
+        .get_param::<String>("GOOSE_LEAD_PROVIDER")
+        .unwrap_or_else(|_| default_provider_name.to_string())
 }
 
 fn create_lead_worker_from_env(
     default_provider_name: &str,
     default_model: &ModelConfig,
     lead_model_name: &str,
+    lead_provider_name: &str,
 ) -> Result<Arc<dyn Provider>> {
     let config = crate::config::Config::global();
 
-    let lead_provider_name = config
-        .get_param::<String>("GOOSE_LEAD_PROVIDER")
-        .unwrap_or_else(|_| default_provider_name.to_string());
-
     let lead_turns = config
         .get_param::<usize>("GOOSE_LEAD_TURNS")
         .unwrap_or(DEFAULT_LEAD_TURNS);
@@ -126,14 +280,8 @@ fn create_lead_worker_from_env(
 
     let worker_model_config = create_worker_model_config(default_model)?;
 
-    let lead_provider = REGISTRY
-        .read()
-        .unwrap()
-        .create(&lead_provider_name, lead_model_config)?;
-    let worker_provider = REGISTRY
-        .read()
-        .unwrap()
-        .create(default_provider_name, worker_model_config)?;
+    let lead_provider = create_from_registry(lead_provider_name, lead_model_config)?;
+    let worker_provider = create_from_registry(default_provider_name, worker_model_config)?;
 
     Ok(Arc::new(LeadWorkerProvider::new_with_settings(
         lead_provider,
@@ -174,6 +322,7 @@ mod tests {
     use crate::providers::base::{ProviderMetadata, ProviderUsage, Usage};
     use chrono::Utc;
     use rmcp::model::{AnnotateAble, RawTextContent, Role};
+    use serial_test::serial;
     use std::env;
 
     #[derive(Clone)]
@@ -260,6 +409,7 @@ mod tests {
     }
 
     #[test]
+    #[serial]
     fn test_create_lead_worker_provider() {
         let _guard = EnvVarGuard::new(&[
             "GOOSE_LEAD_MODEL",
@@ -287,6 +437,7 @@ mod tests {
     }
 
     #[test]
+    #[serial]
     fn test_lead_model_env_vars_with_defaults() {
         let _guard = EnvVarGuard::new(&[
             "GOOSE_LEAD_MODEL",
@@ -316,6 +467,7 @@ mod tests {
     }
 
     #[test]
+    #[serial]
     fn test_create_regular_provider_without_lead_config() {
         let _guard = EnvVarGuard::new(&[
             "GOOSE_LEAD_MODEL",
@@ -337,6 +489,7 @@ mod tests {
     }
 
     #[test]
+    #[serial]
     fn test_worker_model_preserves_original_context_limit() {
         let _guard = EnvVarGuard::new(&[
             "GOOSE_LEAD_MODEL",
@@ -349,17 +502,45 @@ mod tests {
         let default_model =
             ModelConfig::new_or_fail("gpt-3.5-turbo").with_context_limit(Some(16_000));
 
-        let result = create_lead_worker_from_env("openai", &default_model, "gpt-4o");
+        let result = create_lead_worker_from_env("openai", &default_model, "gpt-4o", "openai");
 
         _guard.set("GOOSE_WORKER_CONTEXT_LIMIT", "32000");
-        let _result = create_lead_worker_from_env("openai", &default_model, "gpt-4o");
+        let _result = create_lead_worker_from_env("openai", &default_model, "gpt-4o", "openai");
 
         _guard.set("GOOSE_CONTEXT_LIMIT", "64000");
-        let _result = create_lead_worker_from_env("openai", &default_model, "gpt-4o");
+        let _result = create_lead_worker_from_env("openai", &default_model, "gpt-4o", "openai");
 
         match result {
             Ok(_) => {}
             Err(_) => {}
         }
     }
+
+    #[test]
+    #[serial]
+    fn test_lead_provider_from_env() {
+        let _guard = EnvVarGuard::new(&["GOOSE_LEAD_PROVIDER"]);
+
+        assert_eq!(lead_provider_from_env("openai"), "openai");
+
+        _guard.set("GOOSE_LEAD_PROVIDER", "anthropic");
+        assert_eq!(lead_provider_from_env("openai"), "anthropic");
+    }
+
+    #[test]
+    fn test_parse_fallback_model() {
+        assert_eq!(
+            parse_fallback_model("openai/gpt-4o").unwrap(),
+            ("openai".to_string(), "gpt-4o".to_string())
+        );
+        assert_eq!(
+            parse_fallback_model(" openrouter/anthropic/claude-3.5-sonnet ").unwrap(),
+            (
+                "openrouter".to_string(),
+                "anthropic/claude-3.5-sonnet".to_string()
+            )
+        );
+        assert!(parse_fallback_model("gpt-4o").is_err());
+        assert!(parse_fallback_model("openai/").is_err());
+    }
 }
@@ -0,0 +1,534 @@
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use super::base::{
+    FallbackProviderTrait, LeadWorkerProviderTrait, MessageStream, Provider, ProviderMetadata,
+    ProviderUsage,
+};
+use super::errors::ProviderError;
+use crate::conversation::message::Message;
+use crate::model::ModelConfig;
+use rmcp::model::Tool;
+
+pub const DEFAULT_CIRCUIT_FAILURE_THRESHOLD: usize = 2;
+pub const DEFAULT_CIRCUIT_COOLDOWN: Duration = Duration::from_secs(60);
+
+/// State of the circuit breaker guarding one provider in a fallback chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Requests flow normally
+    Closed,
+    /// The provider failed recently and is skipped until the cooldown passes
+    Open,
+    /// The cooldown passed and a single probe request is in flight
+    HalfOpen,
+}
+
+/// How an error from a provider affects its circuit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FailureKind {
+    /// Rate limits and server errors trip the circuit straight away
+    Trip,
+    /// Request failures only trip it once they repeat
+    Transient,
+    /// Anything else says nothing about provider health and is returned as is
+    NotApplicable,
+}
+
+fn classify(error: &ProviderError) -> FailureKind {
+    match error {
+        ProviderError::RateLimitExceeded(_) | ProviderError::ServerError(_) => FailureKind::Trip,
+        ProviderError::RequestFailed(_) => FailureKind::Transient,
+        _ => FailureKind::NotApplicable,
+    }
+}
+
+#[derive(Debug)]
+struct CircuitBreaker {
+    state: CircuitState,
+    consecutive_failures: usize,
+    changed_at: Instant,
+}
+
+impl CircuitBreaker {
+    fn new() -> Self {
+        Self {
+            state: CircuitState::Closed,
+            consecutive_failures: 0,
+            changed_at: Instant::now(),
+        }
+    }
+
+    fn set_state(&mut self, state: CircuitState) {
+        self.state = state;
+        self.changed_at = Instant::now();
+    }
+
+    /// Whether a request may be sent, moving an open circuit to half-open once cooled down.
+    /// A half-open circuit whose probe never reported back is probed again after the cooldown.
+    fn try_acquire(&mut self, cooldown: Duration) -> bool {
+        match self.state {
+            CircuitState::Closed => true,
+            CircuitState::Open | CircuitState::HalfOpen => {
+                if self.changed_at.elapsed() >= cooldown {
+                    self.set_state(CircuitState::HalfOpen);
+                    true
+                } else {
+                    false
+                }
+            }
+        }
+    }
+
+    fn record_success(&mut self) {
+        self.consecutive_failures = 0;
+        if self.state != CircuitState::Closed {
+            self.set_state(CircuitState::Closed);
+        }
+    }
+
+    /// Record a failure, returning true if the circuit is now open
+    fn record_failure(&mut self, kind: FailureKind, threshold: usize) -> bool {
+        match kind {
+            FailureKind::NotApplicable => {
+                // The provider answered, so it is reachable
+                self.record_success();
+                false
+            }
+            FailureKind::Trip | FailureKind::Transient => {
+                self.consecutive_failures += 1;
+                if kind == FailureKind::Trip
+                    || self.state == CircuitState::HalfOpen
+                    || self.consecutive_failures >= threshold
+                {
+                    self.set_state(CircuitState::Open);
+                    true
+                } else {
+                    false
+                }
+            }
+        }
+    }
+}
+
+struct FallbackEntry {
+    provider_name: String,
+    provider: Arc<dyn Provider>,
+    breaker: Mutex<CircuitBreaker>,
+}
+
+impl FallbackEntry {
+    fn label(&self) -> String {
+        format!(
+            "{}/{}",
+            self.provider_name,
+            self.provider.get_model_config().model_name
+        )
+    }
+
+    fn record_result<T>(&self, result: &Result<T, ProviderError>, threshold: usize) -> bool {
+        let mut breaker = self.breaker.lock().unwrap();
+        match result {
+            Ok(_) => {
+                breaker.record_success();
+                false
+            }
+            Err(e) => {
+                let opened = breaker.record_failure(classify(e), threshold);
+                if opened {
+                    tracing::warn!("Circuit opened for {}: {}", self.label(), e);
+                }
+                opened
+            }
+        }
+    }
+}
+
+/// A provider that tries an ordered chain of providers, failing over to the next one when a
+/// provider is rate limited or unavailable.
+///
+/// Each provider sits behind a circuit breaker. Rate limits and server errors open the circuit
+/// immediately, request failures open it after `failure_threshold` in a row. Once the cooldown
+/// passes the next request probes the provider again, and the chain returns to it on success.
+pub struct FallbackProvider {
+    entries: Vec<Arc<FallbackEntry>>,
+    failure_threshold: usize,
+    cooldown: Duration,
+    active: Mutex<usize>,
+    /// Set when the active provider changed and the agent has not been told yet
+    pending_change: Mutex<bool>,
+}
+
+impl FallbackProvider {
+    /// Create a fallback chain from `(provider name, provider)` pairs, in order of preference
+    pub fn new(providers: Vec<(String, Arc<dyn Provider>)>) -> Self {
+        Self::new_with_settings(
+            providers,
+            DEFAULT_CIRCUIT_FAILURE_THRESHOLD,
+            DEFAULT_CIRCUIT_COOLDOWN,
+        )
+    }
+
+    pub fn new_with_settings(
+        providers: Vec<(String, Arc<dyn Provider>)>,
+        failure_threshold: usize,
+        cooldown: Duration,
+    ) -> Self {
+        let entries = providers
+            .into_iter()
+            .map(|(provider_name, provider)| {
+                Arc::new(FallbackEntry {
+                    provider_name,
+                    provider,
+                    breaker: Mutex::new(CircuitBreaker::new()),
+                })
+            })
+            .collect();
+        Self {
+            entries,
+            failure_threshold: failure_threshold.max(1),
+            cooldown,
+            active: Mutex::new(0),
+            pending_change: Mutex::new(false),
+        }
+    }
+
+    /// Circuit state of every provider in the chain, labelled `provider/model`
+    pub fn circuit_states(&self) -> Vec<(String, CircuitState)> {
+        self.entries
+            .iter()
+            .map(|entry| (entry.label(), entry.breaker.lock().unwrap().state))
+            .collect()
+    }
+
+    /// The next provider to try after `after`, acquiring its circuit. If no provider has been
+    /// attempted and every circuit is open, the one that opened first is tried anyway rather
+    /// than failing without sending a request.
+    fn next_candidate(&self, after: Option<usize>) -> Option<usize> {
+        let start = after.map_or(0, |i| i + 1);
+        let available = (start..self.entries.len()).find(|&i| {
+            self.entries[i]
+                .breaker
+                .lock()
+                .unwrap()
+                .try_acquire(self.cooldown)
+        });
+        if available.is_some() || after.is_some() {
+            return available;
+        }
+        (0..self.entries.len()).min_by_key(|&i| self.entries[i].breaker.lock().unwrap().changed_at)
+    }
+
+    fn set_active(&self, index: usize) {
+        let mut active = self.active.lock().unwrap();
+        if *active != index {
+            tracing::info!(
+                "Switching from {} to {}",
+                self.entries[*active].label(),
+                self.entries[index].label()
+            );
+            *active = index;
+            *self.pending_change.lock().unwrap() = true;
+        }
+    }
+
+    fn active_entry(&self) -> &FallbackEntry {
+        &self.entries[*self.active.lock().unwrap()]
+    }
+
+    fn should_fail_over(&self, error: &ProviderError, opened: bool) -> bool {
+        opened || classify(error) == FailureKind::Trip
+    }
+}
+
+impl FallbackProviderTrait for FallbackProvider {
+    fn get_chain(&self) -> Vec<String> {
+        self.entries.iter().map(|entry| entry.label()).collect()
+    }
+
+    fn get_active(&self) -> (String, bool) {
+        let active = *self.active.lock().unwrap();
+        (self.entries[active].label(), active > 0)
+    }
+
+    fn take_model_change(&self) -> Option<(String, String)> {
+        let mut pending = self.pending_change.lock().unwrap();
+        if !*pending {
+            return None;
+        }
+        *pending = false;
+        let active = *self.active.lock().unwrap();
+        let mode = if active == 0 { "primary" } else { "fallback" };
+        Some((
+            self.entries[active].provider.get_model_config().model_name,
+            mode.to_string(),
+        ))
+    }
+}
+
+#[async_trait]
+impl Provider for FallbackProvider {
+    fn metadata() -> ProviderMetadata {
+        // This is a wrapper provider, so we return minimal metadata
+        ProviderMetadata::new(
+            "fallback",
+            "Fallback Provider",
+            "A provider that fails over along a chain of providers when one is unavailable",
+            "",
+            vec![],
+            "",
+            vec![],
+        )
+    }
+
+    fn get_model_config(&self) -> ModelConfig {
+        self.active_entry().provider.get_model_config()
+    }
+
+    async fn complete(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<(Message, ProviderUsage), ProviderError> {
+        let mut last_error = None;
+        let mut next = self.next_candidate(None);
+        while let Some(index) = next {
+            let entry = &self.entries[index];
+            let result = entry.provider.complete(system, messages, tools).await;
+            let opened = entry.record_result(&result, self.failure_threshold);
+            match result {
+                Ok(response) => {
+                    self.set_active(index);
+                    return Ok(response);
+                }
+                Err(e) if self.should_fail_over(&e, opened) => {
+                    tracing::warn!("{} failed, trying the next provider: {}", entry.label(), e);
+                    last_error = Some(e);
+                    next = self.next_candidate(Some(index));
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| {
+            ProviderError::ExecutionError("No providers in the fallback chain".to_string())
+        }))
+    }
+
+    /// Fails over when opening the stream fails. Errors in the middle of a stream are
+    /// returned to the caller but still count against the provider's circuit.
+    async fn stream(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<MessageStream, ProviderError> {
+        let mut last_error = None;
+        let mut next = self.next_candidate(None);
+        while let Some(index) = next {
+            let entry = self.entries[index].clone();
+            let result = entry.provider.stream(system, messages, tools).await;
+            let opened = entry.record_result(&result, self.failure_threshold);
+            match result {
+                Ok(stream) => {
+                    self.set_active(index);
+                    let threshold = self.failure_threshold;
+                    return Ok(Box::pin(stream.inspect(move |item| {
+                        if item.is_err() {
+                            entry.record_result(item, threshold);
+                        }
+                    })));
+                }
+                Err(e) if self.should_fail_over(&e, opened) => {
+                    tracing::warn!("{} failed, trying the next provider: {}", entry.label(), e);
+                    last_error = Some(e);
+                    next = self.next_candidate(Some(index));
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| {
+            ProviderError::ExecutionError("No providers in the fallback chain".to_string())
+        }))
+    }
+
+    fn supports_streaming(&self) -> bool {
+        self.entries
+            .iter()
+            .all(|entry| entry.provider.supports_streaming())
+    }
+
+    async fn fetch_supported_models(&self) -> Result<Option<Vec<String>>, ProviderError> {
+        self.active_entry().provider.fetch_supported_models().await
+    }
+
+    fn as_lead_worker(&self) -> Option<&dyn LeadWorkerProviderTrait> {
+        self.entries
+            .first()
+            .and_then(|entry| entry.provider.as_lead_worker())
+    }
+
+    fn as_fallback(&self) -> Option<&dyn FallbackProviderTrait> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::base::Usage;
+    use std::collections::VecDeque;
+
+    /// A provider that answers with scripted errors before succeeding
+    struct ScriptedProvider {
+        model: String,
+        errors: Mutex<VecDeque<ProviderError>>,
+    }
+
+    impl ScriptedProvider {
+        fn new(model: &str, errors: Vec<ProviderError>) -> Arc<Self> {
+            Arc::new(Self {
+                model: model.to_string(),
+                errors: Mutex::new(errors.into()),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl Provider for ScriptedProvider {
+        fn metadata() -> ProviderMetadata {
+            ProviderMetadata::empty()
+        }
+
+        fn get_model_config(&self) -> ModelConfig {
+            ModelConfig::new_or_fail(&self.model)
+        }
+
+        async fn complete(
+            &self,
+            _system: &str,
+            _messages: &[Message],
+            _tools: &[Tool],
+        ) -> Result<(Message, ProviderUsage), ProviderError> {
+            if let Some(error) = self.errors.lock().unwrap().pop_front() {
+                return Err(error);
+            }
+            Ok((
+                Message::assistant().with_text(self.model.clone()),
+                ProviderUsage::new(self.model.clone(), Usage::default()),
+            ))
+        }
+    }
+
+    async fn answer(provider: &FallbackProvider) -> Result<String, ProviderError> {
+        let (message, _) = provider
+            .complete("system", &[Message::user().with_text("hi")], &[])
+            .await?;
+        Ok(message.as_concat_text())
+    }
+
+    #[tokio::test]
+    async fn test_fails_over_on_rate_limit_and_reports_change() {
+        let primary = ScriptedProvider::new(
+            "primary",
+            vec![ProviderError::RateLimitExceeded("slow down".into())],
+        );
+        let backup = ScriptedProvider::new("backup", vec![]);
+        let provider = FallbackProvider::new(vec![
+            ("a".to_string(), primary as Arc<dyn Provider>),
+            ("b".to_string(), backup as Arc<dyn Provider>),
+        ]);
+
+        assert_eq!(answer(&provider).await.unwrap(), "backup");
+        assert_eq!(
+            provider.take_model_change(),
+            Some(("backup".to_string(), "fallback".to_string()))
+        );
+        assert_eq!(provider.take_model_change(), None);
+        assert_eq!(provider.get_active(), ("b/backup".to_string(), true));
+        assert_eq!(provider.circuit_states()[0].1, CircuitState::Open);
+
+        // The open circuit keeps traffic on the backup until the cooldown passes
+        assert_eq!(answer(&provider).await.unwrap(), "backup");
+    }
+
+    #[tokio::test]
+    async fn test_request_failures_only_fail_over_when_repeated() {
+        let primary = ScriptedProvider::new(
+            "primary",
+            vec![
+                ProviderError::RequestFailed("reset".into()),
+                ProviderError::RequestFailed("reset".into()),
+            ],
+        );
+        let backup = ScriptedProvider::new("backup", vec![]);
+        let provider = FallbackProvider::new(vec![
+            ("a".to_string(), primary as Arc<dyn Provider>),
+            ("b".to_string(), backup as Arc<dyn Provider>),
+        ]);
+
+        assert!(matches!(
+            answer(&provider).await,
+            Err(ProviderError::RequestFailed(_))
+        ));
+        assert_eq!(answer(&provider).await.unwrap(), "backup");
+    }
+
+    #[tokio::test]
+    async fn test_other_errors_are_not_failed_over() {
+        let primary = ScriptedProvider::new(
+            "primary",
+            vec![ProviderError::ContextLengthExceeded("too long".into())],
+        );
+        let backup = ScriptedProvider::new("backup", vec![]);
+        let provider = FallbackProvider::new(vec![
+            ("a".to_string(), primary as Arc<dyn Provider>),
+            ("b".to_string(), backup as Arc<dyn Provider>),
+        ]);
+
+        assert!(matches!(
+            answer(&provider).await,
+            Err(ProviderError::ContextLengthExceeded(_))
+        ));
+        assert_eq!(provider.circuit_states()[0].1, CircuitState::Closed);
+    }
+
+    #[tokio::test]
+    async fn test_half_open_probe_returns_to_primary() {
+        let primary =
+            ScriptedProvider::new("primary", vec![ProviderError::ServerError("down".into())]);
+        let backup = ScriptedProvider::new("backup", vec![]);
+        let provider = FallbackProvider::new_with_settings(
+            vec![
+                ("a".to_string(), primary as Arc<dyn Provider>),
+                ("b".to_string(), backup as Arc<dyn Provider>),
+            ],
+            DEFAULT_CIRCUIT_FAILURE_THRESHOLD,
+            Duration::ZERO,
+        );
+
+        assert_eq!(answer(&provider).await.unwrap(), "backup");
+        provider.take_model_change();
+
+        // With no cooldown the next request probes the primary, which has recovered
+        assert_eq!(answer(&provider).await.unwrap(), "primary");
+        assert_eq!(
+            provider.take_model_change(),
+            Some(("primary".to_string(), "primary".to_string()))
+        );
+        assert_eq!(provider.circuit_states()[0].1, CircuitState::Closed);
+    }
+
+    #[test]
+    fn test_failed_probe_reopens_circuit() {
+        let mut breaker = CircuitBreaker::new();
+        assert!(breaker.record_failure(FailureKind::Trip, 2));
+        assert!(!breaker.try_acquire(Duration::from_secs(60)));
+        assert!(breaker.try_acquire(Duration::ZERO));
+        assert_eq!(breaker.state, CircuitState::HalfOpen);
+        assert!(breaker.record_failure(FailureKind::Transient, 2));
+        assert_eq!(breaker.state, CircuitState::Open);
+    }
+}
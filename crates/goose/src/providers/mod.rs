@@ -10,6 +10,7 @@ pub mod databricks;
 pub mod embedding;
 pub mod errors;
 mod factory;
+pub mod fallback;
 pub mod formats;
 mod gcpauth;
 pub mod gcpvertexai;
@@ -25,6 +26,8 @@ pub mod openai;
 pub mod openrouter;
 pub mod pricing;
 pub mod provider_registry;
+pub mod rate_limit;
+pub mod replay;
 mod retry;
 pub mod sagemaker_tgi;
 pub mod snowflake;
@@ -36,4 +39,7 @@ pub mod utils_universal_openai_stream;
 pub mod venice;
 pub mod xai;
 
-pub use factory::{create, providers, refresh_custom_providers};
+pub use factory::{
+    create, create_embedding_provider, create_for_switch, create_with_fallbacks,
+    lead_provider_from_env, parse_fallback_model, providers, refresh_custom_providers,
+};
@@ -0,0 +1,801 @@
+//! Process-wide rate limiting for provider requests.
+//!
+//! Every provider created through the factory shares one [`RateLimiter`] per provider and model,
+//! so parallel subagents and scheduled jobs draw from the same budget. A limiter enforces
+//! requests per minute and tokens per minute with token buckets and caps the number of requests
+//! in flight. It also learns from the `Retry-After` and rate limit headers returned by OpenAI
+//! and Anthropic compatible APIs, holding back requests until the server says they will succeed.
+//!
+//! Limits are read from `GOOSE_RATE_LIMITS`, a map keyed by `provider/model`, `provider` or
+//! `default`:
+//!
+//! ```yaml
+//! GOOSE_RATE_LIMITS:
+//!   openai/gpt-4o:
+//!     requests_per_minute: 500
+//!     tokens_per_minute: 30000
+//!   anthropic:
+//!     max_in_flight: 4
+//! ```
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use once_cell::sync::Lazy;
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use super::base::{
+    FallbackProviderTrait, LeadWorkerProviderTrait, MessageStream, Provider, ProviderMetadata,
+    ProviderUsage,
+};
+use super::errors::ProviderError;
+use super::retry::RetryConfig;
+use crate::conversation::message::Message;
+use crate::model::ModelConfig;
+use rmcp::model::Tool;
+
+pub const RATE_LIMITS_CONFIG_KEY: &str = "GOOSE_RATE_LIMITS";
+
+/// Limits for one provider or model; unset fields are unlimited
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requests_per_minute: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tokens_per_minute: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_in_flight: Option<usize>,
+}
+
+/// All configured limits, keyed by `provider/model`, `provider` or `default`
+pub fn configured_rate_limits() -> HashMap<String, RateLimitConfig> {
+    crate::config::Config::global()
+        .get_param(RATE_LIMITS_CONFIG_KEY)
+        .unwrap_or_default()
+}
+
+fn limits_for(
+    limits: &HashMap<String, RateLimitConfig>,
+    provider: &str,
+    model: &str,
+) -> RateLimitConfig {
+    limits
+        .get(&format!("{}/{}", provider, model))
+        .or_else(|| limits.get(provider))
+        .or_else(|| limits.get("default"))
+        .cloned()
+        .unwrap_or_default()
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    capacity: f64,
+    available: f64,
+    refill_per_sec: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn per_minute(limit: u32) -> Self {
+        let capacity = limit.max(1) as f64;
+        Self {
+            capacity,
+            available: capacity,
+            refill_per_sec: capacity / 60.0,
+            updated: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.updated).as_secs_f64();
+        self.available = (self.available + elapsed * self.refill_per_sec).min(self.capacity);
+        self.updated = now;
+    }
+
+    /// Time until `amount` is available, without taking it
+    fn wait_for(&mut self, amount: f64) -> Duration {
+        self.refill();
+        if self.available >= amount {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64((amount - self.available) / self.refill_per_sec)
+        }
+    }
+
+    /// Take `amount`, which may leave the bucket in debt until it refills
+    fn take(&mut self, amount: f64) {
+        self.refill();
+        self.available -= amount;
+    }
+}
+
+/// What the server last told us about its limits
+#[derive(Debug, Default, Clone)]
+struct ServerLimits {
+    remaining_requests: Option<u64>,
+    remaining_tokens: Option<u64>,
+}
+
+#[derive(Debug)]
+struct LimiterState {
+    requests: Option<TokenBucket>,
+    tokens: Option<TokenBucket>,
+    blocked_until: Option<Instant>,
+    server: ServerLimits,
+}
+
+/// Shared limiter for one provider and model
+#[derive(Debug)]
+pub struct RateLimiter {
+    key: String,
+    config: RateLimitConfig,
+    state: Mutex<LimiterState>,
+    in_flight: Option<Arc<Semaphore>>,
+    in_flight_count: AtomicUsize,
+}
+
+/// Held while a request is in flight
+pub struct RateLimitPermit {
+    limiter: Arc<RateLimiter>,
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+impl Drop for RateLimitPermit {
+    fn drop(&mut self) {
+        self.limiter.in_flight_count.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Current state of a limiter, as shown by `goose info` and the server status route
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RateLimitSnapshot {
+    pub key: String,
+    #[serde(flatten)]
+    pub config: RateLimitConfig,
+    pub in_flight: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub available_requests: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub available_tokens: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blocked_for_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_remaining_requests: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_remaining_tokens: Option<u64>,
+}
+
+impl RateLimiter {
+    pub fn new(key: impl Into<String>, config: RateLimitConfig) -> Self {
+        Self {
+            key: key.into(),
+            state: Mutex::new(LimiterState {
+                requests: config.requests_per_minute.map(TokenBucket::per_minute),
+                tokens: config.tokens_per_minute.map(TokenBucket::per_minute),
+                blocked_until: None,
+                server: ServerLimits::default(),
+            }),
+            in_flight: config
+                .max_in_flight
+                .map(|max| Arc::new(Semaphore::new(max.max(1)))),
+            in_flight_count: AtomicUsize::new(0),
+            config,
+        }
+    }
+
+    /// How long until a request may be sent, taking a request token if it may go now
+    fn try_reserve(&self) -> Duration {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+
+        let mut wait = match state.blocked_until {
+            Some(until) if until > now => until - now,
+            _ => {
+                state.blocked_until = None;
+                Duration::ZERO
+            }
+        };
+        if let Some(bucket) = state.requests.as_mut() {
+            wait = wait.max(bucket.wait_for(1.0));
+        }
+        if let Some(bucket) = state.tokens.as_mut() {
+            // Token usage is only known afterwards, so wait until the bucket is out of debt
+            wait = wait.max(bucket.wait_for(1.0));
+        }
+
+        if wait.is_zero() {
+            if let Some(bucket) = state.requests.as_mut() {
+                bucket.take(1.0);
+            }
+        }
+        wait
+    }
+
+    /// Wait until a request may be sent
+    pub async fn acquire(self: &Arc<Self>) -> RateLimitPermit {
+        let permit = match &self.in_flight {
+            Some(semaphore) => semaphore.clone().acquire_owned().await.ok(),
+            None => None,
+        };
+        self.reserve_request().await;
+        self.in_flight_count.fetch_add(1, Ordering::SeqCst);
+        RateLimitPermit {
+            limiter: self.clone(),
+            _permit: permit,
+        }
+    }
+
+    /// Wait for and take a request token, without claiming another in-flight slot
+    async fn reserve_request(&self) {
+        loop {
+            let wait = self.try_reserve();
+            if wait.is_zero() {
+                return;
+            }
+            tracing::debug!("Rate limit for {} reached, waiting {:?}", self.key, wait);
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Charge the tokens a request actually used
+    pub fn record_usage(&self, usage: &ProviderUsage) {
+        let tokens = usage.usage.total_tokens.or_else(|| {
+            match (usage.usage.input_tokens, usage.usage.output_tokens) {
+                (None, None) => None,
+                (input, output) => Some(input.unwrap_or(0) + output.unwrap_or(0)),
+            }
+        });
+        if let Some(tokens) = tokens.filter(|t| *t > 0) {
+            if let Some(bucket) = self.state.lock().unwrap().tokens.as_mut() {
+                bucket.take(tokens as f64);
+            }
+        }
+    }
+
+    /// Hold back all requests until `until`, keeping the latest deadline
+    fn block_until(&self, until: Instant) {
+        let mut state = self.state.lock().unwrap();
+        if state.blocked_until.is_none_or(|current| current < until) {
+            state.blocked_until = Some(until);
+        }
+    }
+
+    /// Time left before the server will accept requests again, if it told us to wait
+    pub fn blocked_for(&self) -> Option<Duration> {
+        let state = self.state.lock().unwrap();
+        state
+            .blocked_until
+            .and_then(|until| until.checked_duration_since(Instant::now()))
+            .filter(|d| !d.is_zero())
+    }
+
+    /// Learn from the status and rate limit headers of a provider response
+    pub fn observe_response(&self, status: StatusCode, headers: &HeaderMap) {
+        let observed = parse_rate_limit_headers(headers);
+        let now = Instant::now();
+
+        if let Some(retry_after) = observed.retry_after {
+            if status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+                self.block_until(now + retry_after);
+            }
+        }
+        if observed.remaining_requests == Some(0) {
+            if let Some(reset) = observed.requests_reset {
+                self.block_until(now + reset);
+            }
+        }
+        if observed.remaining_tokens == Some(0) {
+            if let Some(reset) = observed.tokens_reset {
+                self.block_until(now + reset);
+            }
+        }
+
+        let mut state = self.state.lock().unwrap();
+        if observed.remaining_requests.is_some() {
+            state.server.remaining_requests = observed.remaining_requests;
+        }
+        if observed.remaining_tokens.is_some() {
+            state.server.remaining_tokens = observed.remaining_tokens;
+        }
+    }
+
+    pub fn snapshot(&self) -> RateLimitSnapshot {
+        let blocked_for_ms = self.blocked_for().map(|d| d.as_millis() as u64);
+        let mut state = self.state.lock().unwrap();
+        let available = |bucket: &mut Option<TokenBucket>| {
+            bucket.as_mut().map(|b| {
+                b.refill();
+                b.available.floor()
+            })
+        };
+        RateLimitSnapshot {
+            key: self.key.clone(),
+            config: self.config.clone(),
+            in_flight: self.in_flight_count.load(Ordering::SeqCst),
+            available_requests: available(&mut state.requests),
+            available_tokens: available(&mut state.tokens),
+            blocked_for_ms,
+            server_remaining_requests: state.server.remaining_requests,
+            server_remaining_tokens: state.server.remaining_tokens,
+        }
+    }
+}
+
+static LIMITERS: Lazy<Mutex<HashMap<String, Arc<RateLimiter>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// The shared limiter for a provider and model, created from config on first use
+pub fn limiter_for(provider: &str, model: &str) -> Arc<RateLimiter> {
+    let key = format!("{}/{}", provider, model);
+    let mut limiters = LIMITERS.lock().unwrap();
+    limiters
+        .entry(key.clone())
+        .or_insert_with(|| {
+            let config = limits_for(&configured_rate_limits(), provider, model);
+            Arc::new(RateLimiter::new(key, config))
+        })
+        .clone()
+}
+
+/// Snapshots of every limiter used by this process, sorted by key
+pub fn rate_limit_snapshots() -> Vec<RateLimitSnapshot> {
+    let mut snapshots: Vec<_> = LIMITERS
+        .lock()
+        .unwrap()
+        .values()
+        .map(|limiter| limiter.snapshot())
+        .collect();
+    snapshots.sort_by(|a, b| a.key.cmp(&b.key));
+    snapshots
+}
+
+tokio::task_local! {
+    /// The limiter for the request running on this task, so the HTTP layer can report headers
+    static CURRENT_LIMITER: Arc<RateLimiter>;
+}
+
+/// Report a provider response to the limiter of the request in progress, if any
+pub fn observe_response(status: StatusCode, headers: &HeaderMap) {
+    let _ = CURRENT_LIMITER.try_with(|limiter| limiter.observe_response(status, headers));
+}
+
+/// How long the server asked the request in progress to back off, if it did
+pub fn current_backoff() -> Option<Duration> {
+    CURRENT_LIMITER
+        .try_with(|limiter| limiter.blocked_for())
+        .ok()
+        .flatten()
+}
+
+/// Take a request token for a retry of the request in progress, which already holds its
+/// in-flight slot but sends another request to the server
+pub async fn reserve_retry() {
+    if let Ok(limiter) = CURRENT_LIMITER.try_with(|limiter| limiter.clone()) {
+        limiter.reserve_request().await;
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct ObservedLimits {
+    retry_after: Option<Duration>,
+    remaining_requests: Option<u64>,
+    remaining_tokens: Option<u64>,
+    requests_reset: Option<Duration>,
+    tokens_reset: Option<Duration>,
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+}
+
+fn header_u64(headers: &HeaderMap, names: &[&str]) -> Option<u64> {
+    names
+        .iter()
+        .find_map(|name| header_str(headers, name).and_then(|v| v.parse().ok()))
+}
+
+fn header_reset(headers: &HeaderMap, names: &[&str]) -> Option<Duration> {
+    names
+        .iter()
+        .find_map(|name| header_str(headers, name).and_then(parse_reset))
+}
+
+/// Parse the rate limit headers sent by OpenAI (`x-ratelimit-*`) and Anthropic
+/// (`anthropic-ratelimit-*`), plus the standard `Retry-After`
+fn parse_rate_limit_headers(headers: &HeaderMap) -> ObservedLimits {
+    let retry_after = header_str(headers, "retry-after-ms")
+        .and_then(|v| v.parse::<f64>().ok())
+        .map(|ms| Duration::from_secs_f64(ms.max(0.0) / 1000.0))
+        .or_else(|| header_str(headers, "retry-after").and_then(parse_retry_after));
+
+    ObservedLimits {
+        retry_after,
+        remaining_requests: header_u64(
+            headers,
+            &[
+                "x-ratelimit-remaining-requests",
+                "anthropic-ratelimit-requests-remaining",
+            ],
+        ),
+        remaining_tokens: header_u64(
+            headers,
+            &[
+                "x-ratelimit-remaining-tokens",
+                "anthropic-ratelimit-tokens-remaining",
+                "anthropic-ratelimit-input-tokens-remaining",
+            ],
+        ),
+        requests_reset: header_reset(
+            headers,
+            &[
+                "x-ratelimit-reset-requests",
+                "anthropic-ratelimit-requests-reset",
+            ],
+        ),
+        tokens_reset: header_reset(
+            headers,
+            &[
+                "x-ratelimit-reset-tokens",
+                "anthropic-ratelimit-tokens-reset",
+                "anthropic-ratelimit-input-tokens-reset",
+            ],
+        ),
+    }
+}
+
+/// `Retry-After` is either a number of seconds or an HTTP date
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    if let Ok(secs) = value.parse::<f64>() {
+        return Some(Duration::from_secs_f64(secs.max(0.0)));
+    }
+    DateTime::parse_from_rfc2822(value)
+        .ok()
+        .map(|date| until(date.with_timezone(&Utc)))
+}
+
+/// Reset times are durations like `6m0s` or `20ms` from OpenAI, RFC 3339 timestamps from Anthropic
+fn parse_reset(value: &str) -> Option<Duration> {
+    if let Ok(date) = DateTime::parse_from_rfc3339(value) {
+        return Some(until(date.with_timezone(&Utc)));
+    }
+    parse_go_duration(value)
+}
+
+fn until(date: DateTime<Utc>) -> Duration {
+    (date - Utc::now()).to_std().unwrap_or(Duration::ZERO)
+}
+
+fn parse_go_duration(value: &str) -> Option<Duration> {
+    let mut total = 0.0;
+    let mut rest = value;
+    if rest.is_empty() {
+        return None;
+    }
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let number: f64 = rest[..number_len].parse().ok()?;
+        rest = &rest[number_len..];
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(rest.len());
+        let seconds_per_unit = match &rest[..unit_len] {
+            "h" => 3600.0,
+            "m" => 60.0,
+            "s" => 1.0,
+            "ms" => 0.001,
+            "us" | "µs" => 0.000_001,
+            "ns" => 0.000_000_001,
+            _ => return None,
+        };
+        total += number * seconds_per_unit;
+        rest = &rest[unit_len..];
+    }
+    Some(Duration::from_secs_f64(total))
+}
+
+/// Wraps a provider so every request goes through the shared limiter for its provider and model
+pub struct RateLimitedProvider {
+    inner: Arc<dyn Provider>,
+    limiter: Arc<RateLimiter>,
+}
+
+impl RateLimitedProvider {
+    pub fn new(provider_name: &str, inner: Arc<dyn Provider>) -> Self {
+        let limiter = limiter_for(provider_name, &inner.get_model_config().model_name);
+        Self { inner, limiter }
+    }
+}
+
+#[async_trait]
+impl Provider for RateLimitedProvider {
+    fn metadata() -> ProviderMetadata {
+        // This is a wrapper provider, so we return minimal metadata
+        ProviderMetadata::empty()
+    }
+
+    async fn complete(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<(Message, ProviderUsage), ProviderError> {
+        let _permit = self.limiter.acquire().await;
+        let result = CURRENT_LIMITER
+            .scope(
+                self.limiter.clone(),
+                self.inner.complete(system, messages, tools),
+            )
+            .await;
+        if let Ok((_, usage)) = &result {
+            self.limiter.record_usage(usage);
+        }
+        result
+    }
+
+    async fn stream(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<MessageStream, ProviderError> {
+        let permit = self.limiter.acquire().await;
+        let stream = CURRENT_LIMITER
+            .scope(
+                self.limiter.clone(),
+                self.inner.stream(system, messages, tools),
+            )
+            .await?;
+        let limiter = self.limiter.clone();
+        // The request stays in flight until the stream is finished or dropped
+        Ok(Box::pin(stream.inspect(move |item| {
+            let _ = &permit;
+            if let Ok((_, Some(usage))) = item {
+                limiter.record_usage(usage);
+            }
+        })))
+    }
+
+    fn supports_streaming(&self) -> bool {
+        self.inner.supports_streaming()
+    }
+
+    fn get_model_config(&self) -> ModelConfig {
+        self.inner.get_model_config()
+    }
+
+    fn retry_config(&self) -> RetryConfig {
+        self.inner.retry_config()
+    }
+
+    async fn fetch_supported_models(&self) -> Result<Option<Vec<String>>, ProviderError> {
+        self.inner.fetch_supported_models().await
+    }
+
+    fn supports_embeddings(&self) -> bool {
+        self.inner.supports_embeddings()
+    }
+
+    fn supports_cache_control(&self) -> bool {
+        self.inner.supports_cache_control()
+    }
+
+    async fn create_embeddings(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>, ProviderError> {
+        let _permit = self.limiter.acquire().await;
+        CURRENT_LIMITER
+            .scope(self.limiter.clone(), self.inner.create_embeddings(texts))
+            .await
+    }
+
+    fn as_lead_worker(&self) -> Option<&dyn LeadWorkerProviderTrait> {
+        self.inner.as_lead_worker()
+    }
+
+    fn as_fallback(&self) -> Option<&dyn FallbackProviderTrait> {
+        self.inner.as_fallback()
+    }
+
+    async fn configure_oauth(&self) -> Result<(), ProviderError> {
+        self.inner.configure_oauth().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::retry::ProviderRetry;
+    use reqwest::header::HeaderValue;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.insert(*name, HeaderValue::from_str(value).unwrap());
+        }
+        map
+    }
+
+    #[test]
+    fn test_parse_go_duration() {
+        assert_eq!(parse_go_duration("1s"), Some(Duration::from_secs(1)));
+        assert_eq!(parse_go_duration("6m0s"), Some(Duration::from_secs(360)));
+        assert_eq!(parse_go_duration("20ms"), Some(Duration::from_millis(20)));
+        assert_eq!(
+            parse_go_duration("1h2m3.5s"),
+            Some(Duration::from_secs_f64(3723.5))
+        );
+        assert_eq!(parse_go_duration("soon"), None);
+        assert_eq!(parse_go_duration(""), None);
+    }
+
+    #[test]
+    fn test_parse_openai_and_anthropic_headers() {
+        let openai = parse_rate_limit_headers(&headers(&[
+            ("x-ratelimit-remaining-requests", "0"),
+            ("x-ratelimit-reset-requests", "2s"),
+            ("x-ratelimit-remaining-tokens", "1200"),
+        ]));
+        assert_eq!(openai.remaining_requests, Some(0));
+        assert_eq!(openai.requests_reset, Some(Duration::from_secs(2)));
+        assert_eq!(openai.remaining_tokens, Some(1200));
+
+        let reset = (Utc::now() + chrono::Duration::seconds(30)).to_rfc3339();
+        let anthropic = parse_rate_limit_headers(&headers(&[
+            ("anthropic-ratelimit-tokens-remaining", "0"),
+            ("anthropic-ratelimit-tokens-reset", &reset),
+            ("retry-after", "7"),
+        ]));
+        assert_eq!(anthropic.remaining_tokens, Some(0));
+        assert!(anthropic.tokens_reset.unwrap() > Duration::from_secs(25));
+        assert_eq!(anthropic.retry_after, Some(Duration::from_secs(7)));
+    }
+
+    #[test]
+    fn test_retry_after_blocks_on_429_only() {
+        let limiter = RateLimiter::new("test/model", RateLimitConfig::default());
+        let retry = headers(&[("retry-after", "30")]);
+
+        limiter.observe_response(StatusCode::OK, &retry);
+        assert_eq!(limiter.blocked_for(), None);
+
+        limiter.observe_response(StatusCode::TOO_MANY_REQUESTS, &retry);
+        assert!(limiter.blocked_for().unwrap() > Duration::from_secs(25));
+        assert!(limiter.try_reserve() > Duration::from_secs(25));
+    }
+
+    #[test]
+    fn test_request_and_token_buckets() {
+        let limiter = RateLimiter::new(
+            "test/model",
+            RateLimitConfig {
+                requests_per_minute: Some(2),
+                tokens_per_minute: Some(100),
+                max_in_flight: None,
+            },
+        );
+        assert!(limiter.try_reserve().is_zero());
+        assert!(limiter.try_reserve().is_zero());
+        assert!(limiter.try_reserve() > Duration::from_secs(20));
+
+        let limiter = RateLimiter::new(
+            "test/model",
+            RateLimitConfig {
+                requests_per_minute: None,
+                tokens_per_minute: Some(100),
+                max_in_flight: None,
+            },
+        );
+        limiter.record_usage(&ProviderUsage::new(
+            "model".to_string(),
+            crate::providers::base::Usage::new(Some(150), Some(50), Some(200)),
+        ));
+        // 100 tokens in debt at 100 per minute is a minute's wait
+        assert!(limiter.try_reserve() > Duration::from_secs(55));
+        assert_eq!(limiter.snapshot().available_tokens, Some(-100.0));
+    }
+
+    #[tokio::test]
+    async fn test_max_in_flight() {
+        let limiter = Arc::new(RateLimiter::new(
+            "test/model",
+            RateLimitConfig {
+                max_in_flight: Some(1),
+                ..Default::default()
+            },
+        ));
+        let first = limiter.acquire().await;
+        assert_eq!(limiter.snapshot().in_flight, 1);
+
+        let waiting = tokio::time::timeout(Duration::from_millis(50), limiter.acquire()).await;
+        assert!(waiting.is_err());
+
+        drop(first);
+        let _second = limiter.acquire().await;
+        assert_eq!(limiter.snapshot().in_flight, 1);
+    }
+
+    #[test]
+    fn test_limits_for_prefers_most_specific_key() {
+        let limits: HashMap<String, RateLimitConfig> = serde_yaml::from_str(
+            "default: {max_in_flight: 8}\nopenai: {requests_per_minute: 100}\nopenai/gpt-4o: {requests_per_minute: 10}\n",
+        )
+        .unwrap();
+        assert_eq!(
+            limits_for(&limits, "openai", "gpt-4o").requests_per_minute,
+            Some(10)
+        );
+        assert_eq!(
+            limits_for(&limits, "openai", "gpt-4o-mini").requests_per_minute,
+            Some(100)
+        );
+        assert_eq!(limits_for(&limits, "anthropic", "x").max_in_flight, Some(8));
+    }
+
+    /// Fails its first attempt with a rate limit error and retries through `with_retry`
+    struct FlakyProvider {
+        attempts: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Provider for FlakyProvider {
+        fn metadata() -> ProviderMetadata {
+            ProviderMetadata::empty()
+        }
+
+        async fn complete(
+            &self,
+            _system: &str,
+            _messages: &[Message],
+            _tools: &[Tool],
+        ) -> Result<(Message, ProviderUsage), ProviderError> {
+            self.with_retry(|| async {
+                if self.attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+                    Err(ProviderError::RateLimitExceeded("slow down".to_string()))
+                } else {
+                    Ok((
+                        Message::assistant().with_text("done"),
+                        ProviderUsage::new("model".to_string(), Default::default()),
+                    ))
+                }
+            })
+            .await
+        }
+
+        fn get_model_config(&self) -> ModelConfig {
+            ModelConfig::new_or_fail("model")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retries_take_a_request_token() {
+        let provider = RateLimitedProvider {
+            inner: Arc::new(FlakyProvider {
+                attempts: AtomicUsize::new(0),
+            }),
+            limiter: Arc::new(RateLimiter::new(
+                "test/model",
+                RateLimitConfig {
+                    requests_per_minute: Some(10),
+                    ..Default::default()
+                },
+            )),
+        };
+        provider.complete("system", &[], &[]).await.unwrap();
+
+        // The first attempt and its retry each count against requests per minute
+        let available = provider.limiter.snapshot().available_requests.unwrap();
+        assert_eq!(available, 8.0);
+    }
+}
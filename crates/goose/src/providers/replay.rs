@@ -0,0 +1,666 @@
+//! Record and replay provider responses for deterministic runs.
+//!
+//! In recording mode a [`ReplayProvider`] wraps a real provider and writes every `complete`
+//! and `stream` exchange to a JSON fixture. In replay mode it serves those exchanges back
+//! without any network access, which makes CI runs and goose-bench evals reproducible.
+//!
+//! Requests are matched on a hash of the conversation (role and content of each message) and
+//! the names of the available tools. The system prompt is deliberately left out of the hash
+//! because it embeds the current date and other details that change between runs.
+
+use anyhow::{anyhow, Context, Result};
+use async_stream::try_stream;
+use async_trait::async_trait;
+use futures::StreamExt;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+
+use super::base::{
+    ConfigKey, FallbackProviderTrait, LeadWorkerProviderTrait, MessageStream, Provider,
+    ProviderMetadata, ProviderUsage,
+};
+use super::errors::ProviderError;
+use super::retry::RetryConfig;
+use crate::conversation::message::Message;
+use crate::model::ModelConfig;
+use rmcp::model::Tool;
+
+pub const REPLAY_PROVIDER_NAME: &str = "replay";
+
+/// Fixture file written when recording and read when replaying
+pub const FIXTURE_CONFIG_KEY: &str = "GOOSE_PROVIDER_FIXTURE";
+/// Fail on the first request that has no recorded response instead of falling back to order
+pub const STRICT_CONFIG_KEY: &str = "GOOSE_REPLAY_STRICT";
+/// Record over an existing fixture instead of refusing to
+pub const OVERWRITE_CONFIG_KEY: &str = "GOOSE_FIXTURE_OVERWRITE";
+
+const FIXTURE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExchangeKind {
+    Complete,
+    Stream,
+}
+
+/// One item of a provider response; a `complete` call has exactly one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedChunk {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<Message>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<ProviderUsage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedExchange {
+    pub kind: ExchangeKind,
+    pub request_hash: String,
+    /// Last message of the request, kept to make fixtures readable when a replay mismatches
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_message: Option<Message>,
+    pub chunks: Vec<RecordedChunk>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayFixture {
+    pub version: u32,
+    pub exchanges: Vec<RecordedExchange>,
+}
+
+impl Default for ReplayFixture {
+    fn default() -> Self {
+        Self {
+            version: FIXTURE_VERSION,
+            exchanges: Vec::new(),
+        }
+    }
+}
+
+impl ReplayFixture {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read fixture {}", path.display()))?;
+        let fixture: ReplayFixture = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse fixture {}", path.display()))?;
+        if fixture.version != FIXTURE_VERSION {
+            return Err(anyhow!(
+                "Unsupported fixture version {} in {}, expected {}",
+                fixture.version,
+                path.display(),
+                FIXTURE_VERSION
+            ));
+        }
+        Ok(fixture)
+    }
+
+    /// Write the fixture through a temporary file so a crash never leaves it half written
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(self)?)?;
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+}
+
+struct FixtureState {
+    path: PathBuf,
+    fixture: ReplayFixture,
+    used: Vec<bool>,
+}
+
+impl FixtureState {
+    fn record(&mut self, exchange: RecordedExchange) -> Result<()> {
+        self.fixture.exchanges.push(exchange);
+        self.used.push(true);
+        self.fixture.save(&self.path)
+    }
+
+    /// Take the first unused exchange with a matching hash, or in lenient mode the next unused
+    /// exchange of the same kind
+    fn take(
+        &mut self,
+        kind: ExchangeKind,
+        hash: &str,
+        strict: bool,
+    ) -> Result<RecordedExchange, ProviderError> {
+        let exchanges = &self.fixture.exchanges;
+        let candidates =
+            || (0..exchanges.len()).filter(|&i| !self.used[i] && exchanges[i].kind == kind);
+
+        let index = candidates()
+            .find(|&i| exchanges[i].request_hash == hash)
+            .or_else(|| {
+                if strict {
+                    None
+                } else {
+                    let next = candidates().next();
+                    if next.is_some() {
+                        tracing::warn!(
+                            "No recorded response for request {}, replaying the next one in order",
+                            hash
+                        );
+                    }
+                    next
+                }
+            })
+            .ok_or_else(|| {
+                ProviderError::ExecutionError(format!(
+                    "No recorded {:?} response for request hash {} in fixture {}",
+                    kind,
+                    hash,
+                    self.path.display()
+                ))
+            })?;
+
+        self.used[index] = true;
+        Ok(self.fixture.exchanges[index].clone())
+    }
+}
+
+/// Fixture state shared by every provider in the process, so that lead/worker pairs,
+/// subagents and providers created by a model switch all write to the same recording
+static FIXTURES: Lazy<Mutex<HashMap<PathBuf, Arc<Mutex<FixtureState>>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn shared_state(path: &Path, recording: bool, overwrite: bool) -> Result<Arc<Mutex<FixtureState>>> {
+    let mut fixtures = FIXTURES.lock().unwrap();
+    if let Some(state) = fixtures.get(path) {
+        return Ok(state.clone());
+    }
+
+    // Recording starts a fresh fixture, but never silently replaces one recorded earlier
+    let fixture = if recording {
+        if path.exists() && !overwrite {
+            return Err(anyhow!(
+                "Fixture {} already exists; replay it with --provider replay, or pass --overwrite-fixture to record over it",
+                path.display()
+            ));
+        }
+        let fixture = ReplayFixture::default();
+        fixture.save(path)?;
+        fixture
+    } else {
+        ReplayFixture::load(path)?
+    };
+    let state = Arc::new(Mutex::new(FixtureState {
+        path: path.to_path_buf(),
+        used: vec![false; fixture.exchanges.len()],
+        fixture,
+    }));
+    fixtures.insert(path.to_path_buf(), state.clone());
+    Ok(state)
+}
+
+/// Drop the state this process keeps for the fixture at `path`, so the next provider using it
+/// starts from the file again. Until then, recordings append to it and replays continue
+/// from the exchanges not served yet.
+pub fn release_fixture(path: &Path) {
+    FIXTURES.lock().unwrap().remove(path);
+}
+
+/// Where providers record or replay their responses
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FixtureSettings {
+    pub path: PathBuf,
+    /// Fail on requests that have no recorded response when replaying
+    pub strict: bool,
+    /// Record over an existing fixture instead of refusing to
+    pub overwrite: bool,
+}
+
+impl FixtureSettings {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Fixture chosen by the caller for this process, taking precedence over the config keys.
+/// `Some(None)` turns fixtures off even when they are configured.
+static FIXTURE_OVERRIDE: RwLock<Option<Option<FixtureSettings>>> = RwLock::new(None);
+
+/// Use `settings` for every provider created from now on in this process, including
+/// subagents and lead/worker pairs, without touching the process environment
+pub fn set_fixture(settings: Option<FixtureSettings>) {
+    *FIXTURE_OVERRIDE.write().unwrap() = Some(settings);
+}
+
+/// The fixture passed to [`set_fixture`], or else the one configured through
+/// `GOOSE_PROVIDER_FIXTURE`, `GOOSE_REPLAY_STRICT` and `GOOSE_FIXTURE_OVERWRITE`
+pub fn fixture_settings() -> Option<FixtureSettings> {
+    if let Some(settings) = FIXTURE_OVERRIDE.read().unwrap().clone() {
+        return settings;
+    }
+    let config = crate::config::Config::global();
+    let path = config.get_param::<String>(FIXTURE_CONFIG_KEY).ok()?;
+    Some(FixtureSettings {
+        path: path.into(),
+        strict: config.get_param::<bool>(STRICT_CONFIG_KEY).unwrap_or(false),
+        overwrite: config
+            .get_param::<bool>(OVERWRITE_CONFIG_KEY)
+            .unwrap_or(false),
+    })
+}
+
+pub fn hash_request(messages: &[Message], tools: &[Tool]) -> String {
+    let stable_messages: Vec<_> = messages
+        .iter()
+        .map(|msg| (msg.role.clone(), msg.content.clone()))
+        .collect();
+    let tool_names: Vec<_> = tools.iter().map(|tool| tool.name.as_ref()).collect();
+    let serialized = serde_json::to_string(&(stable_messages, tool_names)).unwrap_or_default();
+    let mut hasher = Sha256::new();
+    hasher.update(serialized.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+enum ReplayMode {
+    Record(Arc<dyn Provider>),
+    Replay { strict: bool },
+}
+
+pub struct ReplayProvider {
+    mode: ReplayMode,
+    model: ModelConfig,
+    state: Arc<Mutex<FixtureState>>,
+}
+
+impl ReplayProvider {
+    /// Wrap `inner`, writing its responses to the fixture at `path`. A fixture already
+    /// recording in this process is appended to; an existing file is only replaced when
+    /// `overwrite` is set.
+    pub fn recording(
+        inner: Arc<dyn Provider>,
+        path: impl AsRef<Path>,
+        overwrite: bool,
+    ) -> Result<Self> {
+        Ok(Self {
+            model: inner.get_model_config(),
+            state: shared_state(path.as_ref(), true, overwrite)?,
+            mode: ReplayMode::Record(inner),
+        })
+    }
+
+    /// Serve responses from the fixture at `path`
+    pub fn replaying(path: impl AsRef<Path>, strict: bool, model: ModelConfig) -> Result<Self> {
+        Ok(Self {
+            mode: ReplayMode::Replay { strict },
+            model,
+            state: shared_state(path.as_ref(), false, false)?,
+        })
+    }
+
+    pub fn from_env(model: ModelConfig) -> Result<Self> {
+        let settings = fixture_settings().ok_or_else(|| {
+            anyhow!(
+                "The replay provider needs a fixture, set {} or pass --fixture",
+                FIXTURE_CONFIG_KEY
+            )
+        })?;
+        Self::replaying(settings.path, settings.strict, model)
+    }
+
+    pub fn is_recording(&self) -> bool {
+        matches!(self.mode, ReplayMode::Record(_))
+    }
+
+    fn take(&self, kind: ExchangeKind, hash: &str) -> Result<RecordedExchange, ProviderError> {
+        let strict = matches!(self.mode, ReplayMode::Replay { strict: true });
+        self.state.lock().unwrap().take(kind, hash, strict)
+    }
+}
+
+fn record_exchange(state: &Mutex<FixtureState>, exchange: RecordedExchange) {
+    if let Err(e) = state.lock().unwrap().record(exchange) {
+        tracing::warn!("Failed to write provider fixture: {}", e);
+    }
+}
+
+#[async_trait]
+impl Provider for ReplayProvider {
+    fn metadata() -> ProviderMetadata {
+        ProviderMetadata::new(
+            REPLAY_PROVIDER_NAME,
+            "Replay",
+            "Replays provider responses recorded in a fixture file",
+            "replay",
+            vec![],
+            "",
+            vec![
+                ConfigKey::new(FIXTURE_CONFIG_KEY, true, false, None),
+                ConfigKey::new(STRICT_CONFIG_KEY, false, false, Some("false")),
+                ConfigKey::new(OVERWRITE_CONFIG_KEY, false, false, Some("false")),
+            ],
+        )
+    }
+
+    async fn complete(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<(Message, ProviderUsage), ProviderError> {
+        let hash = hash_request(messages, tools);
+
+        match &self.mode {
+            ReplayMode::Record(inner) => {
+                let (message, usage) = inner.complete(system, messages, tools).await?;
+                record_exchange(
+                    &self.state,
+                    RecordedExchange {
+                        kind: ExchangeKind::Complete,
+                        request_hash: hash,
+                        last_message: messages.last().cloned(),
+                        chunks: vec![RecordedChunk {
+                            message: Some(message.clone()),
+                            usage: Some(usage.clone()),
+                        }],
+                    },
+                );
+                Ok((message, usage))
+            }
+            ReplayMode::Replay { .. } => {
+                let exchange = self.take(ExchangeKind::Complete, &hash)?;
+                let chunk = exchange.chunks.into_iter().next();
+                match chunk {
+                    Some(RecordedChunk {
+                        message: Some(message),
+                        usage: Some(usage),
+                    }) => Ok((message, usage)),
+                    _ => Err(ProviderError::ExecutionError(format!(
+                        "Recorded response for request hash {} is incomplete",
+                        hash
+                    ))),
+                }
+            }
+        }
+    }
+
+    async fn stream(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<MessageStream, ProviderError> {
+        let hash = hash_request(messages, tools);
+
+        match &self.mode {
+            ReplayMode::Record(inner) => {
+                let mut inner_stream = inner.stream(system, messages, tools).await?;
+                let state = self.state.clone();
+                let last_message = messages.last().cloned();
+                Ok(Box::pin(try_stream! {
+                    let mut chunks = Vec::new();
+                    while let Some(item) = inner_stream.next().await {
+                        let (message, usage) = item?;
+                        chunks.push(RecordedChunk {
+                            message: message.clone(),
+                            usage: usage.clone(),
+                        });
+                        yield (message, usage);
+                    }
+                    record_exchange(
+                        &state,
+                        RecordedExchange {
+                            kind: ExchangeKind::Stream,
+                            request_hash: hash,
+                            last_message,
+                            chunks,
+                        },
+                    );
+                }))
+            }
+            ReplayMode::Replay { .. } => {
+                let exchange = self.take(ExchangeKind::Stream, &hash)?;
+                let items = exchange
+                    .chunks
+                    .into_iter()
+                    .map(|chunk| Ok((chunk.message, chunk.usage)));
+                Ok(Box::pin(futures::stream::iter(items)))
+            }
+        }
+    }
+
+    fn supports_streaming(&self) -> bool {
+        match &self.mode {
+            ReplayMode::Record(inner) => inner.supports_streaming(),
+            ReplayMode::Replay { .. } => self
+                .state
+                .lock()
+                .unwrap()
+                .fixture
+                .exchanges
+                .iter()
+                .any(|exchange| exchange.kind == ExchangeKind::Stream),
+        }
+    }
+
+    fn get_model_config(&self) -> ModelConfig {
+        self.model.clone()
+    }
+
+    fn retry_config(&self) -> RetryConfig {
+        match &self.mode {
+            ReplayMode::Record(inner) => inner.retry_config(),
+            ReplayMode::Replay { .. } => RetryConfig::default(),
+        }
+    }
+
+    async fn fetch_supported_models(&self) -> Result<Option<Vec<String>>, ProviderError> {
+        match &self.mode {
+            ReplayMode::Record(inner) => inner.fetch_supported_models().await,
+            ReplayMode::Replay { .. } => Ok(None),
+        }
+    }
+
+    fn as_lead_worker(&self) -> Option<&dyn LeadWorkerProviderTrait> {
+        match &self.mode {
+            ReplayMode::Record(inner) => inner.as_lead_worker(),
+            ReplayMode::Replay { .. } => None,
+        }
+    }
+
+    fn as_fallback(&self) -> Option<&dyn FallbackProviderTrait> {
+        match &self.mode {
+            ReplayMode::Record(inner) => inner.as_fallback(),
+            ReplayMode::Replay { .. } => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::base::Usage;
+    use tempfile::TempDir;
+
+    struct EchoProvider;
+
+    #[async_trait]
+    impl Provider for EchoProvider {
+        fn metadata() -> ProviderMetadata {
+            ProviderMetadata::new("echo", "Echo", "", "echo-model", vec![], "", vec![])
+        }
+
+        async fn complete(
+            &self,
+            _system: &str,
+            messages: &[Message],
+            _tools: &[Tool],
+        ) -> Result<(Message, ProviderUsage), ProviderError> {
+            let text = messages
+                .last()
+                .map(|m| m.as_concat_text())
+                .unwrap_or_default();
+            Ok((
+                Message::assistant().with_text(format!("echo: {}", text)),
+                ProviderUsage::new("echo-model".to_string(), Usage::new(Some(1), Some(2), None)),
+            ))
+        }
+
+        async fn stream(
+            &self,
+            _system: &str,
+            _messages: &[Message],
+            _tools: &[Tool],
+        ) -> Result<MessageStream, ProviderError> {
+            let items = vec![
+                Ok((Some(Message::assistant().with_text("Hello")), None)),
+                Ok((
+                    Some(Message::assistant().with_text(" world")),
+                    Some(ProviderUsage::new(
+                        "echo-model".to_string(),
+                        Usage::default(),
+                    )),
+                )),
+            ];
+            Ok(Box::pin(futures::stream::iter(items)))
+        }
+
+        fn supports_streaming(&self) -> bool {
+            true
+        }
+
+        fn get_model_config(&self) -> ModelConfig {
+            ModelConfig::new_or_fail("echo-model")
+        }
+    }
+
+    async fn collect_text(stream: MessageStream) -> String {
+        let items: Vec<_> = stream.collect().await;
+        items
+            .into_iter()
+            .filter_map(|item| item.unwrap().0)
+            .map(|m| m.as_concat_text())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_record_then_replay_complete_and_stream() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("fixture.json");
+        let first = [Message::user().with_text("one")];
+        let second = [Message::user().with_text("two")];
+
+        let recorder = ReplayProvider::recording(Arc::new(EchoProvider), &path, false).unwrap();
+        recorder.complete("system", &first, &[]).await.unwrap();
+        let streamed = recorder.stream("system", &second, &[]).await.unwrap();
+        assert_eq!(collect_text(streamed).await, "Hello world");
+
+        // Replay from the file alone, as a new process would
+        FIXTURES.lock().unwrap().remove(&path);
+        let fixture = ReplayFixture::load(&path).unwrap();
+        assert_eq!(fixture.exchanges.len(), 2);
+
+        let replayer =
+            ReplayProvider::replaying(&path, true, ModelConfig::new_or_fail("echo-model")).unwrap();
+        assert!(replayer.supports_streaming());
+        let (message, usage) = replayer
+            .complete("a different system prompt", &first, &[])
+            .await
+            .unwrap();
+        assert_eq!(message.as_concat_text(), "echo: one");
+        assert_eq!(usage.usage.output_tokens, Some(2));
+
+        let streamed = replayer.stream("system", &second, &[]).await.unwrap();
+        assert_eq!(collect_text(streamed).await, "Hello world");
+    }
+
+    #[tokio::test]
+    async fn test_strict_mode_rejects_unknown_requests() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("fixture.json");
+
+        let recorder = ReplayProvider::recording(Arc::new(EchoProvider), &path, false).unwrap();
+        recorder
+            .complete("system", &[Message::user().with_text("known")], &[])
+            .await
+            .unwrap();
+        FIXTURES.lock().unwrap().remove(&path);
+
+        let strict =
+            ReplayProvider::replaying(&path, true, ModelConfig::new_or_fail("echo-model")).unwrap();
+        let unknown = [Message::user().with_text("unknown")];
+        assert!(strict.complete("system", &unknown, &[]).await.is_err());
+        FIXTURES.lock().unwrap().remove(&path);
+
+        let lenient =
+            ReplayProvider::replaying(&path, false, ModelConfig::new_or_fail("echo-model"))
+                .unwrap();
+        let (message, _) = lenient.complete("system", &unknown, &[]).await.unwrap();
+        assert_eq!(message.as_concat_text(), "echo: known");
+        // Each recorded exchange is only served once
+        assert!(lenient.complete("system", &unknown, &[]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_cases_sharing_a_fixture_record_then_replay() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("fixture.json");
+        let cases = [
+            [Message::user().with_text("case one")],
+            [Message::user().with_text("case two")],
+        ];
+
+        // Every case of the first run records through its own provider into the same fixture
+        for case in &cases {
+            let recorder = ReplayProvider::recording(Arc::new(EchoProvider), &path, false).unwrap();
+            recorder.complete("system", case, &[]).await.unwrap();
+        }
+        release_fixture(&path);
+        assert_eq!(ReplayFixture::load(&path).unwrap().exchanges.len(), 2);
+
+        for strict in [true, false] {
+            for (case, expected) in cases.iter().zip(["echo: case one", "echo: case two"]) {
+                let replayer = ReplayProvider::replaying(
+                    &path,
+                    strict,
+                    ModelConfig::new_or_fail("echo-model"),
+                )
+                .unwrap();
+                let (message, _) = replayer.complete("system", case, &[]).await.unwrap();
+                assert_eq!(message.as_concat_text(), expected);
+            }
+            release_fixture(&path);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_recording_refuses_to_replace_existing_fixture() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("fixture.json");
+
+        let recorder = ReplayProvider::recording(Arc::new(EchoProvider), &path, false).unwrap();
+        recorder
+            .complete("system", &[Message::user().with_text("one")], &[])
+            .await
+            .unwrap();
+
+        // A model switch re-wraps the new provider and keeps appending to the same recording
+        let switched = ReplayProvider::recording(Arc::new(EchoProvider), &path, false).unwrap();
+        switched
+            .complete("system", &[Message::user().with_text("two")], &[])
+            .await
+            .unwrap();
+        assert_eq!(ReplayFixture::load(&path).unwrap().exchanges.len(), 2);
+
+        // A later run must opt in before the fixture is recorded over
+        FIXTURES.lock().unwrap().remove(&path);
+        assert!(ReplayProvider::recording(Arc::new(EchoProvider), &path, false).is_err());
+        assert_eq!(ReplayFixture::load(&path).unwrap().exchanges.len(), 2);
+
+        ReplayProvider::recording(Arc::new(EchoProvider), &path, true).unwrap();
+        assert!(ReplayFixture::load(&path).unwrap().exchanges.is_empty());
+    }
+}
@@ -101,9 +101,18 @@ pub trait ProviderRetry {
                             error
                         );
 
-                        let delay = config.delay_for_attempt(attempts);
+                        // Prefer the wait the server asked for over blind backoff
+                        let delay = match &error {
+                            ProviderError::RateLimitExceeded(_) => {
+                                super::rate_limit::current_backoff()
+                            }
+                            _ => None,
+                        }
+                        .unwrap_or_else(|| config.delay_for_attempt(attempts));
                         tracing::info!("Backing off for {:?} before retry", delay);
                         sleep(delay).await;
+                        // Each retry is another request against the provider's rate limit
+                        super::rate_limit::reserve_retry().await;
                         continue;
                     }
 
@@ -0,0 +1,243 @@
+use crate::recipe::read_recipe_file_content::{read_recipe_file, RecipeFile};
+use anyhow::{anyhow, Result};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_yaml::{Mapping, Value};
+use std::env;
+use std::path::{Path, PathBuf};
+
+const EXTENDS_KEY: &str = "extends";
+const INCLUDE_KEY: &str = "include";
+const SIGNATURE_KEY: &str = "signature";
+const RECIPE_PATH_ENV_VAR: &str = "GOOSE_RECIPE_PATH";
+const RECIPE_EXTENSIONS: &[&str] = &["yaml", "yml", "json"];
+
+/// An unindented `extends` or `include` key, for content that only parses once templated
+static TOP_LEVEL_COMPOSITION_KEY: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(?m)^"?(extends|include)"?[ \t]*:"#).unwrap());
+
+/// Resolves `extends` and `include` in a raw recipe file so that templating, validation and
+/// previews all see a single, flattened recipe.
+///
+/// Merge rules when a recipe extends another:
+/// * `instructions` - parent instructions followed by the child's
+/// * `extensions` and `sub_recipes` - unioned by `name`, the child's entry replaces the parent's
+/// * `parameters` - merged by `key`, the child's definition overrides the parent's
+/// * `settings` - merged field by field, the child's values win
+/// * everything else - the child's value wins when present
+///
+/// `include` lists instruction fragments (paths relative to the declaring recipe) that are
+/// appended to that recipe's own instructions. Content without either key is returned as is.
+pub fn resolve_recipe_content(content: &str, recipe_dir: &Path) -> Result<String> {
+    let value = match serde_yaml::from_str::<Value>(content) {
+        Ok(Value::Mapping(mapping)) => mapping,
+        _ => {
+            if uses_recipe_composition(content) {
+                return Err(anyhow!(
+                    "Recipes using extends or include must be valid YAML or JSON before templating"
+                ));
+            }
+            return Ok(content.to_string());
+        }
+    };
+
+    if !value.contains_key(EXTENDS_KEY) && !value.contains_key(INCLUDE_KEY) {
+        return Ok(content.to_string());
+    }
+
+    let resolved = resolve_mapping(value, recipe_dir, &mut Vec::new())?;
+    Ok(serde_yaml::to_string(&Value::Mapping(resolved))?)
+}
+
+/// Returns true if the raw recipe content declares `extends` or `include` at the top level.
+pub fn uses_recipe_composition(content: &str) -> bool {
+    match serde_yaml::from_str::<Value>(content) {
+        Ok(Value::Mapping(mapping)) => {
+            mapping.contains_key(EXTENDS_KEY) || mapping.contains_key(INCLUDE_KEY)
+        }
+        Ok(_) => false,
+        Err(_) => TOP_LEVEL_COMPOSITION_KEY.is_match(content),
+    }
+}
+
+fn resolve_mapping(
+    mut recipe: Mapping,
+    recipe_dir: &Path,
+    chain: &mut Vec<PathBuf>,
+) -> Result<Mapping> {
+    let extends = recipe.remove(EXTENDS_KEY);
+    let include = recipe.remove(INCLUDE_KEY);
+
+    if let Some(include) = include {
+        let fragments = read_includes(&include, recipe_dir)?;
+        if !fragments.is_empty() {
+            let mut parts: Vec<String> = recipe
+                .get("instructions")
+                .and_then(Value::as_str)
+                .map(|s| vec![s.to_string()])
+                .unwrap_or_default();
+            parts.extend(fragments);
+            recipe.insert(Value::from("instructions"), Value::from(parts.join("\n\n")));
+        }
+    }
+
+    let Some(extends) = extends else {
+        return Ok(recipe);
+    };
+    let reference = extends
+        .as_str()
+        .ok_or_else(|| anyhow!("'extends' must be a recipe path or name"))?;
+    let parent_file = locate_parent(reference, recipe_dir)?;
+
+    if chain.contains(&parent_file.file_path) {
+        return Err(anyhow!(
+            "Recipe inheritance cycle detected at {}",
+            parent_file.file_path.display()
+        ));
+    }
+    chain.push(parent_file.file_path.clone());
+
+    let parent = match serde_yaml::from_str::<Value>(&parent_file.content) {
+        Ok(Value::Mapping(mapping)) => mapping,
+        _ => {
+            return Err(anyhow!(
+                "Parent recipe {} must be valid YAML or JSON before templating",
+                parent_file.file_path.display()
+            ))
+        }
+    };
+    let mut parent = resolve_mapping(parent, &parent_file.parent_dir, chain)?;
+    // A parent's signature covers the parent alone; only the child's can apply to the result
+    parent.remove(SIGNATURE_KEY);
+    anchor_sub_recipe_paths(&mut parent, &parent_file.parent_dir);
+    chain.pop();
+
+    Ok(merge_recipes(parent, recipe))
+}
+
+fn read_includes(include: &Value, recipe_dir: &Path) -> Result<Vec<String>> {
+    let paths: Vec<&str> = match include {
+        Value::String(path) => vec![path.as_str()],
+        Value::Sequence(items) => items
+            .iter()
+            .map(|item| {
+                item.as_str()
+                    .ok_or_else(|| anyhow!("'include' entries must be file paths"))
+            })
+            .collect::<Result<_>>()?,
+        _ => return Err(anyhow!("'include' must be a file path or a list of paths")),
+    };
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let full_path = recipe_dir.join(path);
+            std::fs::read_to_string(&full_path)
+                .map(|fragment| fragment.trim_end().to_string())
+                .map_err(|e| {
+                    anyhow!(
+                        "Failed to read included fragment {}: {}",
+                        full_path.display(),
+                        e
+                    )
+                })
+        })
+        .collect()
+}
+
+fn locate_parent(reference: &str, recipe_dir: &Path) -> Result<RecipeFile> {
+    let looks_like_path = reference.contains('/')
+        || reference.contains('\\')
+        || Path::new(reference).extension().is_some();
+    if looks_like_path {
+        let path = if reference.starts_with('~') || Path::new(reference).is_absolute() {
+            PathBuf::from(reference)
+        } else {
+            recipe_dir.join(reference)
+        };
+        return read_recipe_file(path);
+    }
+
+    let mut search_dirs = vec![recipe_dir.to_path_buf()];
+    if let Ok(recipe_path_env) = env::var(RECIPE_PATH_ENV_VAR) {
+        search_dirs.extend(env::split_paths(&recipe_path_env));
+    }
+    for dir in &search_dirs {
+        for ext in RECIPE_EXTENSIONS {
+            let candidate = dir.join(format!("{}.{}", reference, ext));
+            if candidate.is_file() {
+                return read_recipe_file(candidate);
+            }
+        }
+    }
+    Err(anyhow!(
+        "Could not find parent recipe '{}' next to the recipe or in {}",
+        reference,
+        RECIPE_PATH_ENV_VAR
+    ))
+}
+
+// Sub-recipe paths are resolved against the final recipe's directory, so inherited ones are
+// made absolute while we still know where they came from.
+fn anchor_sub_recipe_paths(recipe: &mut Mapping, recipe_dir: &Path) {
+    let Some(Value::Sequence(sub_recipes)) = recipe.get_mut("sub_recipes") else {
+        return;
+    };
+    for sub_recipe in sub_recipes {
+        if let Some(Value::String(path)) = sub_recipe.get_mut("path") {
+            if Path::new(path.as_str()).is_relative() && !path.starts_with('~') {
+                *path = recipe_dir
+                    .join(path.as_str())
+                    .to_string_lossy()
+                    .into_owned();
+            }
+        }
+    }
+}
+
+fn merge_recipes(mut parent: Mapping, child: Mapping) -> Mapping {
+    for (key, child_value) in child {
+        let merged = match (key.as_str(), parent.get(&key).cloned()) {
+            (Some("instructions"), Some(Value::String(parent_text))) => match child_value {
+                Value::String(child_text) => {
+                    Value::from(format!("{}\n\n{}", parent_text, child_text))
+                }
+                other => other,
+            },
+            (Some("extensions" | "sub_recipes"), Some(parent_value)) => {
+                merge_keyed_lists(parent_value, child_value, "name")
+            }
+            (Some("parameters"), Some(parent_value)) => {
+                merge_keyed_lists(parent_value, child_value, "key")
+            }
+            (Some("settings"), Some(Value::Mapping(mut parent_settings))) => match child_value {
+                Value::Mapping(child_settings) => {
+                    parent_settings.extend(child_settings);
+                    Value::Mapping(parent_settings)
+                }
+                other => other,
+            },
+            _ => child_value,
+        };
+        parent.insert(key, merged);
+    }
+    parent
+}
+
+fn merge_keyed_lists(parent: Value, child: Value, id_field: &str) -> Value {
+    let (Value::Sequence(mut merged), Value::Sequence(child_items)) = (parent, child.clone())
+    else {
+        return child;
+    };
+    for item in child_items {
+        let id = item.get(id_field).cloned();
+        match merged
+            .iter_mut()
+            .find(|existing| id.is_some() && existing.get(id_field) == id.as_ref())
+        {
+            Some(existing) => *existing = item,
+            None => merged.push(item),
+        }
+    }
+    Value::Sequence(merged)
+}
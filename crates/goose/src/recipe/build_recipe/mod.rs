@@ -7,6 +7,10 @@ use anyhow::Result;
 use std::collections::{HashMap, HashSet};
 use std::path::Path;
 
+mod extends;
+
+pub use extends::{resolve_recipe_content, uses_recipe_composition};
+
 #[derive(Debug, thiserror::Error)]
 pub enum RecipeError {
     #[error("Missing required parameters: {parameters:?}")]
@@ -33,6 +37,7 @@ where
     let recipe_dir_str = recipe_parent_dir
         .to_str()
         .ok_or_else(|| anyhow::anyhow!("Error getting recipe directory"))?;
+    let recipe_file_content = resolve_recipe_content(&recipe_file_content, &recipe_parent_dir)?;
     let recipe_parameters = validate_recipe_parameters(&recipe_file_content, recipe_dir_str)?;
 
     let (params_for_template, missing_params) =
@@ -51,8 +56,10 @@ pub fn validate_recipe_parameters(
     recipe_file_content: &str,
     recipe_dir_str: &str,
 ) -> Result<Option<Vec<RecipeParameter>>> {
+    let recipe_file_content =
+        resolve_recipe_content(recipe_file_content, Path::new(recipe_dir_str))?;
     let (raw_recipe, template_variables) =
-        parse_recipe_content(recipe_file_content, recipe_dir_str.to_string())?;
+        parse_recipe_content(&recipe_file_content, recipe_dir_str.to_string())?;
     let recipe_parameters = raw_recipe.parameters;
     validate_optional_parameters(&recipe_parameters)?;
     validate_parameters_in_template(&recipe_parameters, &template_variables)?;
@@ -163,6 +170,62 @@ fn validate_optional_parameters(parameters: &Option<Vec<RecipeParameter>>) -> Re
     }
 }
 
+/// Checks a set of pre-supplied parameter values against the recipe's declared parameters,
+/// for callers that cannot prompt the user (e.g. scheduled jobs).
+pub fn validate_parameter_values(
+    recipe_parameters: &[RecipeParameter],
+    values: &HashMap<String, String>,
+) -> Result<()> {
+    let mut problems = Vec::new();
+
+    let mut unknown_keys: Vec<&String> = values
+        .keys()
+        .filter(|key| !recipe_parameters.iter().any(|p| &p.key == *key))
+        .collect();
+    unknown_keys.sort();
+    if !unknown_keys.is_empty() {
+        problems.push(format!(
+            "Unknown parameters: {}.",
+            unknown_keys
+                .iter()
+                .map(|s| s.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+
+    let missing_keys: Vec<&str> = recipe_parameters
+        .iter()
+        .filter(|p| p.default.is_none() && !values.contains_key(&p.key))
+        .map(|p| p.key.as_str())
+        .collect();
+    if !missing_keys.is_empty() {
+        problems.push(format!(
+            "Missing values for parameters: {}.",
+            missing_keys.join(", ")
+        ));
+    }
+
+    for param in recipe_parameters {
+        if let (Some(options), Some(value)) = (&param.options, values.get(&param.key)) {
+            if !options.contains(value) {
+                problems.push(format!(
+                    "Invalid value '{}' for parameter '{}'. Expected one of: {}.",
+                    value,
+                    param.key,
+                    options.join(", ")
+                ));
+            }
+        }
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!("{}", problems.join("\n")))
+    }
+}
+
 pub fn apply_values_to_parameters<F>(
     user_params: &[(String, String)],
     recipe_parameters: Option<Vec<RecipeParameter>>,
@@ -1,10 +1,11 @@
 #[cfg(test)]
 mod tests {
     use crate::recipe::build_recipe::{
-        build_recipe_from_template, resolve_sub_recipe_path, RecipeError,
+        build_recipe_from_template, resolve_sub_recipe_path, validate_parameter_values, RecipeError,
     };
     use crate::recipe::read_recipe_file_content::RecipeFile;
-    use crate::recipe::{RecipeParameterInputType, RecipeParameterRequirement};
+    use crate::recipe::{RecipeParameter, RecipeParameterInputType, RecipeParameterRequirement};
+    use std::collections::HashMap;
     use tempfile::TempDir;
 
     const NO_USER_PROMPT: Option<fn(&str, &str) -> Result<String, anyhow::Error>> = None;
@@ -351,6 +352,47 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_validate_parameter_values() {
+        let parameters = vec![
+            RecipeParameter {
+                key: "name".to_string(),
+                input_type: RecipeParameterInputType::String,
+                requirement: RecipeParameterRequirement::Required,
+                description: "name".to_string(),
+                default: None,
+                options: None,
+            },
+            RecipeParameter {
+                key: "tone".to_string(),
+                input_type: RecipeParameterInputType::Select,
+                requirement: RecipeParameterRequirement::Optional,
+                description: "tone".to_string(),
+                default: Some("casual".to_string()),
+                options: Some(vec!["casual".to_string(), "formal".to_string()]),
+            },
+        ];
+
+        let values = HashMap::from([("name".to_string(), "goose".to_string())]);
+        assert!(validate_parameter_values(&parameters, &values).is_ok());
+
+        let err = validate_parameter_values(&parameters, &HashMap::new())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Missing values for parameters: name."));
+
+        let values = HashMap::from([
+            ("name".to_string(), "goose".to_string()),
+            ("tone".to_string(), "angry".to_string()),
+            ("colour".to_string(), "blue".to_string()),
+        ]);
+        let err = validate_parameter_values(&parameters, &values)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Unknown parameters: colour."));
+        assert!(err.contains("Invalid value 'angry' for parameter 'tone'"));
+    }
+
     mod sub_recipe_path_resolution {
         use super::*;
 
@@ -439,4 +481,181 @@ instructions: Child instructions
             );
         }
     }
+
+    mod recipe_extends {
+        use super::*;
+        use crate::recipe::build_recipe::resolve_recipe_content;
+        use crate::recipe::Recipe;
+
+        const BASE_RECIPE: &str = r#"version: 1.0.0
+title: Base
+description: Shared base recipe
+instructions: Follow the team style guide.
+extensions:
+  - type: builtin
+    name: developer
+    timeout: 300
+  - type: builtin
+    name: memory
+settings:
+  goose_provider: openai
+  temperature: 0.2
+parameters:
+  - key: language
+    input_type: string
+    requirement: optional
+    default: rust
+    description: Language to use
+"#;
+
+        fn build_child(temp_dir: &TempDir, child_content: &str) -> Result<Recipe, RecipeError> {
+            let child_path = temp_dir.path().join("child.yaml");
+            std::fs::write(&child_path, child_content).unwrap();
+            let recipe_file = RecipeFile {
+                content: child_content.to_string(),
+                parent_dir: temp_dir.path().to_path_buf(),
+                file_path: child_path,
+            };
+            build_recipe_from_template(recipe_file, Vec::new(), NO_USER_PROMPT)
+        }
+
+        #[test]
+        fn test_extends_merges_parent_recipe() {
+            let temp_dir = tempfile::tempdir().unwrap();
+            std::fs::write(temp_dir.path().join("base.yaml"), BASE_RECIPE).unwrap();
+            let child = r#"extends: base
+title: Child
+description: Child recipe
+instructions: Write {{ language }} code.
+extensions:
+  - type: builtin
+    name: developer
+    timeout: 600
+  - type: builtin
+    name: computercontroller
+settings:
+  temperature: 0.7
+parameters:
+  - key: language
+    input_type: string
+    requirement: optional
+    default: python
+    description: Language to use
+"#;
+
+            let recipe = build_child(&temp_dir, child).unwrap();
+
+            assert_eq!(recipe.title, "Child");
+            assert_eq!(
+                recipe.instructions.as_deref(),
+                Some("Follow the team style guide.\n\nWrite python code.")
+            );
+            let extensions = recipe.extensions.unwrap();
+            let names: Vec<String> = extensions.iter().map(|e| e.name()).collect();
+            assert_eq!(names, vec!["developer", "memory", "computercontroller"]);
+            let settings = recipe.settings.unwrap();
+            assert_eq!(settings.goose_provider.as_deref(), Some("openai"));
+            assert_eq!(settings.temperature, Some(0.7));
+            let parameters = recipe.parameters.unwrap();
+            assert_eq!(parameters.len(), 1);
+            assert_eq!(parameters[0].default.as_deref(), Some("python"));
+        }
+
+        #[test]
+        fn test_include_appends_instruction_fragments() {
+            let temp_dir = tempfile::tempdir().unwrap();
+            std::fs::create_dir(temp_dir.path().join("snippets")).unwrap();
+            std::fs::write(
+                temp_dir.path().join("snippets/testing.md"),
+                "Always add tests.\n",
+            )
+            .unwrap();
+            let child = r#"title: Child
+description: Child recipe
+instructions: Fix the bug.
+include:
+  - snippets/testing.md
+"#;
+
+            let recipe = build_child(&temp_dir, child).unwrap();
+
+            assert_eq!(
+                recipe.instructions.as_deref(),
+                Some("Fix the bug.\n\nAlways add tests.")
+            );
+        }
+
+        #[test]
+        fn test_extends_resolves_inherited_sub_recipe_paths() {
+            let temp_dir = tempfile::tempdir().unwrap();
+            let base_dir = temp_dir.path().join("shared");
+            std::fs::create_dir(&base_dir).unwrap();
+            std::fs::write(
+                base_dir.join("base.yaml"),
+                "title: Base\ndescription: Base\ninstructions: Base\nsub_recipes:\n  - name: lint\n    path: lint.yaml\n",
+            )
+            .unwrap();
+
+            let resolved = resolve_recipe_content(
+                "extends: shared/base.yaml\ntitle: Child\n",
+                temp_dir.path(),
+            )
+            .unwrap();
+            let recipe = Recipe::from_content(&resolved).unwrap();
+
+            let sub_recipes = recipe.sub_recipes.unwrap();
+            assert_eq!(
+                std::path::Path::new(&sub_recipes[0].path),
+                base_dir.canonicalize().unwrap().join("lint.yaml")
+            );
+        }
+
+        #[test]
+        fn test_extends_cycle_is_rejected() {
+            let temp_dir = tempfile::tempdir().unwrap();
+            std::fs::write(
+                temp_dir.path().join("a.yaml"),
+                "extends: b\ntitle: A\ndescription: A\ninstructions: A\n",
+            )
+            .unwrap();
+            std::fs::write(
+                temp_dir.path().join("b.yaml"),
+                "extends: a\ntitle: B\ndescription: B\ninstructions: B\n",
+            )
+            .unwrap();
+
+            let err =
+                resolve_recipe_content("extends: a\ntitle: C\ndescription: C\n", temp_dir.path())
+                    .unwrap_err();
+            assert!(err.to_string().contains("cycle"));
+        }
+
+        #[test]
+        fn test_uses_recipe_composition_only_sees_top_level_keys() {
+            use crate::recipe::build_recipe::uses_recipe_composition;
+
+            assert!(uses_recipe_composition("extends: base\ntitle: Child\n"));
+            assert!(uses_recipe_composition(
+                r#"{"include": ["a.md"], "title": "T"}"#
+            ));
+            // Templated content that isn't YAML yet
+            assert!(uses_recipe_composition(
+                "include:\n  - a.md\ninstructions: {{ not yaml }}\n"
+            ));
+
+            let nested =
+                "title: T\nsettings:\n  extends: nothing\ninstructions: |\n  include: this\n";
+            assert!(!uses_recipe_composition(nested));
+            assert!(!uses_recipe_composition(
+                "title: T\nparameters:\n  - key: extends\n    include: {{ x }}\n"
+            ));
+        }
+
+        #[test]
+        fn test_content_without_extends_is_unchanged() {
+            let content = "title: Plain\ninstructions: {{ not yaml }}\n";
+            let resolved = resolve_recipe_content(content, std::path::Path::new("/tmp")).unwrap();
+            assert_eq!(resolved, content);
+        }
+    }
 }
@@ -5,6 +5,8 @@ use std::fmt;
 
 use crate::agents::extension::ExtensionConfig;
 use crate::agents::types::RetryConfig;
+use crate::permission::ApprovalPolicy;
+use crate::recipe::signature::RecipeSignature;
 use crate::utils::contains_unicode_tags;
 use serde::de::Deserializer;
 use serde::{Deserialize, Serialize};
@@ -12,6 +14,7 @@ use utoipa::ToSchema;
 
 pub mod build_recipe;
 pub mod read_recipe_file_content;
+pub mod signature;
 pub mod template_recipe;
 
 pub const BUILT_IN_RECIPE_DIR_PARAM: &str = "recipe_dir";
@@ -40,6 +43,7 @@ fn default_version() -> String {
 /// * `parameters` - Additional parameters for the Recipe
 /// * `response` - Response configuration including JSON schema validation
 /// * `retry` - Retry configuration for automated validation and recovery
+/// * `signature` - Ed25519 signature of the Recipe's author
 /// # Example
 ///
 ///
@@ -69,6 +73,7 @@ fn default_version() -> String {
 ///     response: None,
 ///     sub_recipes: None,
 ///     retry: None,
+///     signature: None,
 /// };
 ///
 #[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
@@ -115,6 +120,9 @@ pub struct Recipe {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub retry: Option<RetryConfig>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<RecipeSignature>, // signature over the rest of the recipe
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
@@ -136,6 +144,14 @@ pub struct Settings {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub temperature: Option<f32>,
+
+    /// Models to fail over to, in order, written as `provider/model`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fallback_models: Option<Vec<String>>,
+
+    /// How tool calls that need confirmation are answered when the recipe runs headless
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approval: Option<ApprovalPolicy>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
@@ -154,6 +170,12 @@ pub struct SubRecipe {
     pub sequential_when_repeated: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    /// Sub-recipes that must complete before this one runs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub depends_on: Option<Vec<String>>,
+    /// Parameters filled from a dependency's final output, written as `sub_recipe.field`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inputs: Option<HashMap<String, String>>,
 }
 
 fn deserialize_value_map_as_string<'de, D>(
@@ -334,10 +356,80 @@ impl Recipe {
             }
         }
 
+        if let Some(ref sub_recipes) = recipe.sub_recipes {
+            if let Err(validation_error) = validate_sub_recipe_dependencies(sub_recipes) {
+                return Err(anyhow::anyhow!(
+                    "Invalid sub-recipe dependencies: {}",
+                    validation_error
+                ));
+            }
+        }
+
         Ok(recipe)
     }
 }
 
+/// Checks that `depends_on` and `inputs` only name declared sub-recipes and never form a cycle
+fn validate_sub_recipe_dependencies(sub_recipes: &[SubRecipe]) -> Result<(), String> {
+    let by_name: HashMap<&str, &SubRecipe> = sub_recipes
+        .iter()
+        .map(|sub_recipe| (sub_recipe.name.as_str(), sub_recipe))
+        .collect();
+    for sub_recipe in sub_recipes {
+        let depends_on = sub_recipe.depends_on.clone().unwrap_or_default();
+        for dependency in &depends_on {
+            if dependency == &sub_recipe.name {
+                return Err(format!("'{}' depends on itself", sub_recipe.name));
+            }
+            if !by_name.contains_key(dependency.as_str()) {
+                return Err(format!(
+                    "'{}' depends on unknown sub-recipe '{}'",
+                    sub_recipe.name, dependency
+                ));
+            }
+        }
+        for (param, source) in sub_recipe.inputs.iter().flatten() {
+            let (dependency, _) = source.split_once('.').unwrap_or((source, ""));
+            if !depends_on.iter().any(|name| name == dependency) {
+                return Err(format!(
+                    "input '{}' of '{}' reads from '{}', which is not listed in depends_on",
+                    param, sub_recipe.name, dependency
+                ));
+            }
+        }
+    }
+
+    // Depth-first search keeping the current path, so a cycle can be reported in full
+    fn visit<'a>(
+        name: &'a str,
+        by_name: &HashMap<&'a str, &'a SubRecipe>,
+        path: &mut Vec<&'a str>,
+        done: &mut std::collections::HashSet<&'a str>,
+    ) -> Result<(), String> {
+        if done.contains(name) {
+            return Ok(());
+        }
+        if let Some(start) = path.iter().position(|&visited| visited == name) {
+            let mut cycle = path[start..].to_vec();
+            cycle.push(name);
+            return Err(format!("dependency cycle {}", cycle.join(" -> ")));
+        }
+        path.push(name);
+        for dependency in by_name[name].depends_on.iter().flatten() {
+            visit(dependency.as_str(), by_name, path, done)?;
+        }
+        path.pop();
+        done.insert(name);
+        Ok(())
+    }
+
+    let mut done = std::collections::HashSet::new();
+    for sub_recipe in sub_recipes {
+        visit(&sub_recipe.name, &by_name, &mut Vec::new(), &mut done)?;
+    }
+    Ok(())
+}
+
 impl RecipeBuilder {
     /// Sets the version of the Recipe
     pub fn version(mut self, version: impl Into<String>) -> Self {
@@ -445,6 +537,7 @@ impl RecipeBuilder {
             response: self.response,
             sub_recipes: self.sub_recipes,
             retry: self.retry,
+            signature: None,
         })
     }
 }
@@ -635,6 +728,63 @@ sub_recipes:
         );
     }
 
+    #[test]
+    fn test_from_content_with_approval_settings() {
+        let content = r#"version: 1.0.0
+title: CI Recipe
+description: Runs in CI
+prompt: Fix the failing tests
+settings:
+  approval:
+    allow_tools: ["developer__*"]
+    deny_tools: [developer__shell]
+    on_confirmation: fail"#;
+
+        let recipe = Recipe::from_content(content).unwrap();
+        let approval = recipe.settings.unwrap().approval.unwrap();
+
+        assert_eq!(approval.allow_tools, vec!["developer__*"]);
+        assert_eq!(approval.deny_tools, vec!["developer__shell"]);
+        assert_eq!(
+            approval.on_confirmation,
+            Some(crate::permission::OnConfirmation::Fail)
+        );
+    }
+
+    #[test]
+    fn test_from_content_validates_sub_recipe_dependencies() {
+        let recipe_with = |sub_recipes: &str| {
+            format!(
+                "version: 1.0.0\ntitle: Pipeline\ndescription: Runs steps\nprompt: Go\nsub_recipes:\n{}",
+                sub_recipes
+            )
+        };
+
+        let valid = recipe_with(
+            "  - name: build\n    path: build.yaml\n  - name: deploy\n    path: deploy.yaml\n    depends_on: [build]\n    inputs:\n      artifact: build.path\n",
+        );
+        assert!(Recipe::from_content(&valid).is_ok());
+
+        let unknown =
+            recipe_with("  - name: deploy\n    path: deploy.yaml\n    depends_on: [build]\n");
+        let err = Recipe::from_content(&unknown).unwrap_err().to_string();
+        assert!(err.contains("unknown sub-recipe 'build'"), "{}", err);
+
+        let cycle = recipe_with(
+            "  - name: a\n    path: a.yaml\n    depends_on: [b]\n  - name: b\n    path: b.yaml\n    depends_on: [a]\n",
+        );
+        let err = Recipe::from_content(&cycle).unwrap_err().to_string();
+        assert!(err.contains("a -> b -> a"), "{}", err);
+
+        let undeclared_input = recipe_with(
+            "  - name: build\n    path: build.yaml\n  - name: deploy\n    path: deploy.yaml\n    inputs:\n      artifact: build.path\n",
+        );
+        let err = Recipe::from_content(&undeclared_input)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("not listed in depends_on"), "{}", err);
+    }
+
     #[test]
     fn test_from_content_invalid_json() {
         let content = "{ invalid json }";
@@ -784,6 +934,7 @@ isGlobal: true"#;
             response: None,
             sub_recipes: None,
             retry: None,
+            signature: None,
         };
 
         assert!(!recipe.check_for_security_warnings());
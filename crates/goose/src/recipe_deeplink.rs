@@ -2,12 +2,17 @@ use anyhow::Result;
 use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
 use thiserror::Error;
 
+use crate::recipe::signature::{
+    check_recipe_signature_from_config, SignatureAction, SignatureCheck,
+};
 use crate::recipe::Recipe;
 
 #[derive(Error, Debug)]
 pub enum DecodeError {
     #[error("All decoding methods failed")]
     AllMethodsFailed,
+    #[error("{0}")]
+    SignatureRejected(String),
 }
 
 pub fn encode(recipe: &Recipe) -> Result<String, serde_json::Error> {
@@ -17,6 +22,21 @@ pub fn encode(recipe: &Recipe) -> Result<String, serde_json::Error> {
 }
 
 pub fn decode(link: &str) -> Result<Recipe, DecodeError> {
+    decode_verified(link).map(|(recipe, _)| recipe)
+}
+
+/// Decodes a deeplink and checks the recipe's signature, failing if the signature policy
+/// blocks it. Callers that can ask the user should act on the returned check.
+pub fn decode_verified(link: &str) -> Result<(Recipe, SignatureCheck), DecodeError> {
+    let recipe = decode_unverified(link)?;
+    let check = check_recipe_signature_from_config(&recipe);
+    if check.action == SignatureAction::Block {
+        return Err(DecodeError::SignatureRejected(check.message));
+    }
+    Ok((recipe, check))
+}
+
+fn decode_unverified(link: &str) -> Result<Recipe, DecodeError> {
     // Handle the current format: URL-safe Base64 without padding.
     if let Ok(decoded_bytes) = URL_SAFE_NO_PAD.decode(link) {
         if let Ok(recipe_json) = String::from_utf8(decoded_bytes) {
@@ -45,6 +65,7 @@ pub fn decode(link: &str) -> Result<Recipe, DecodeError> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::recipe::signature::{generate_signing_key, sign_recipe};
     use crate::recipe::Recipe;
 
     fn create_test_recipe() -> Recipe {
@@ -105,4 +126,20 @@ mod tests {
         assert!(result.is_err());
         assert!(matches!(result.unwrap_err(), DecodeError::AllMethodsFailed));
     }
+
+    #[test]
+    fn test_decode_rejects_tampered_signed_recipe() {
+        let (pkcs8, _) = generate_signing_key().unwrap();
+        let mut recipe = create_test_recipe();
+        recipe.signature = Some(sign_recipe(&recipe, &pkcs8).unwrap());
+        assert!(decode(&encode(&recipe).unwrap()).is_ok());
+
+        recipe.instructions = Some("Act as a different assistant".to_string());
+        let result = decode(&encode(&recipe).unwrap());
+
+        assert!(matches!(
+            result.unwrap_err(),
+            DecodeError::SignatureRejected(message) if message.contains("modified after it was signed")
+        ));
+    }
 }
@@ -1,7 +1,9 @@
+use std::collections::HashMap;
+
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 
-use crate::scheduler::{ScheduledJob, SchedulerError};
+use crate::scheduler::{JobRunRecord, ScheduledJob, SchedulerError};
 use crate::session::storage::SessionMetadata;
 
 /// Common trait for all scheduler implementations
@@ -25,6 +27,13 @@ pub trait SchedulerTrait: Send + Sync {
     /// Run a job immediately
     async fn run_now(&self, id: &str) -> Result<String, SchedulerError>;
 
+    /// Run a job in response to a trigger event, with `params` passed to its recipe
+    async fn trigger_job(
+        &self,
+        id: &str,
+        params: HashMap<String, String>,
+    ) -> Result<String, SchedulerError>;
+
     /// Get sessions for a scheduled job
     async fn sessions(
         &self,
@@ -32,9 +41,23 @@ pub trait SchedulerTrait: Send + Sync {
         limit: usize,
     ) -> Result<Vec<(String, SessionMetadata)>, SchedulerError>;
 
-    /// Update a schedule's cron expression
-    async fn update_schedule(&self, sched_id: &str, new_cron: String)
-        -> Result<(), SchedulerError>;
+    /// Get run records for a scheduled job, newest first.
+    /// Schedulers that don't track individual runs return an empty list.
+    async fn runs(
+        &self,
+        _sched_id: &str,
+        _limit: usize,
+    ) -> Result<Vec<JobRunRecord>, SchedulerError> {
+        Ok(Vec::new())
+    }
+
+    /// Update a schedule's cron expression, and its timezone when one is given
+    async fn update_schedule(
+        &self,
+        sched_id: &str,
+        new_cron: String,
+        timezone: Option<String>,
+    ) -> Result<(), SchedulerError>;
 
     /// Kill a running job
     async fn kill_running_job(&self, sched_id: &str) -> Result<(), SchedulerError>;
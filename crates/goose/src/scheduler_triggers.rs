@@ -0,0 +1,698 @@
+//! Event-driven triggers for scheduled jobs.
+//!
+//! Besides its cron expression a [`ScheduledJob`] can be started by a file-system change, by a
+//! webhook call on goose-server, or by another job finishing. Whatever caused the run is passed
+//! to the recipe as `trigger_*` parameters (webhook payloads contribute their own keys).
+//!
+//! File changes are debounced: a burst of changes starts one run once the watched files have
+//! been quiet for a poll interval, with every changed path in `trigger_path`, one per line.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinHandle;
+
+use crate::scheduler::{JobRunRecord, JobRunStatus, ScheduledJob};
+use crate::scheduler_trait::SchedulerTrait;
+
+/// Header carrying the shared secret on webhook calls
+pub const WEBHOOK_SECRET_HEADER: &str = "X-Goose-Webhook-Secret";
+
+const FILE_WATCH_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// Longest a run waits for files that keep changing to settle
+const FILE_WATCH_MAX_DELAY: Duration = Duration::from_secs(30);
+const COMPLETION_POLL_INTERVAL: Duration = Duration::from_secs(10);
+const MAX_WATCHED_FILES: usize = 10_000;
+
+pub const TRIGGER_TYPE_PARAM: &str = "trigger_type";
+pub const TRIGGER_PATH_PARAM: &str = "trigger_path";
+pub const TRIGGER_EVENT_PARAM: &str = "trigger_event";
+pub const TRIGGER_JOB_ID_PARAM: &str = "trigger_job_id";
+pub const TRIGGER_STATUS_PARAM: &str = "trigger_status";
+pub const TRIGGER_SESSION_ID_PARAM: &str = "trigger_session_id";
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, utoipa::ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum JobTrigger {
+    /// Run when files matching `glob` (relative to `path`) are created, modified or removed
+    FileWatch {
+        path: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        glob: Option<String>,
+    },
+    /// Run when `POST /schedule/{id}/webhook` is called with the matching secret
+    Webhook { secret: String },
+    /// Run after another job completes successfully
+    AfterJob { job_id: String },
+}
+
+impl JobTrigger {
+    /// Parameter keys this trigger supplies at run time, or `None` when the keys depend on the
+    /// incoming payload and can't be known up front.
+    pub fn provided_parameters(&self) -> Option<&'static [&'static str]> {
+        match self {
+            JobTrigger::FileWatch { .. } => {
+                Some(&[TRIGGER_TYPE_PARAM, TRIGGER_PATH_PARAM, TRIGGER_EVENT_PARAM])
+            }
+            JobTrigger::Webhook { .. } => None,
+            JobTrigger::AfterJob { .. } => Some(&[
+                TRIGGER_TYPE_PARAM,
+                TRIGGER_JOB_ID_PARAM,
+                TRIGGER_STATUS_PARAM,
+                TRIGGER_SESSION_ID_PARAM,
+            ]),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            JobTrigger::FileWatch { path, glob } => {
+                if !Path::new(path).is_dir() {
+                    return Err(format!("File watch path '{}' is not a directory", path));
+                }
+                if let Some(glob) = glob {
+                    glob::Pattern::new(glob)
+                        .map_err(|e| format!("Invalid file watch glob '{}': {}", glob, e))?;
+                }
+                Ok(())
+            }
+            JobTrigger::Webhook { secret } if secret.trim().is_empty() => {
+                Err("Webhook triggers need a non-empty secret".to_string())
+            }
+            JobTrigger::Webhook { .. } => Ok(()),
+            JobTrigger::AfterJob { job_id } if job_id.trim().is_empty() => {
+                Err("After-job triggers need a job id".to_string())
+            }
+            JobTrigger::AfterJob { .. } => Ok(()),
+        }
+    }
+}
+
+/// Checks a webhook call against the job's webhook triggers.
+pub fn verify_webhook_secret(job: &ScheduledJob, provided: &str) -> bool {
+    job.triggers.iter().any(|trigger| match trigger {
+        JobTrigger::Webhook { secret } => constant_time_eq(secret.as_bytes(), provided.as_bytes()),
+        _ => false,
+    })
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Turns a webhook body into recipe parameters. Top-level object fields become parameters
+/// (non-string values as JSON); anything else is passed whole as `trigger_payload`.
+pub fn webhook_parameters(body: &serde_json::Value) -> HashMap<String, String> {
+    let mut params = HashMap::from([(TRIGGER_TYPE_PARAM.to_string(), "webhook".to_string())]);
+    match body {
+        serde_json::Value::Object(fields) => {
+            for (key, value) in fields {
+                let value = match value {
+                    serde_json::Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                params.insert(key.clone(), value);
+            }
+        }
+        serde_json::Value::Null => {}
+        other => {
+            params.insert("trigger_payload".to_string(), other.to_string());
+        }
+    }
+    params
+}
+
+pub fn after_job_parameters(
+    job_id: &str,
+    status: JobRunStatus,
+    session_id: Option<&str>,
+) -> HashMap<String, String> {
+    HashMap::from([
+        (TRIGGER_TYPE_PARAM.to_string(), "after_job".to_string()),
+        (TRIGGER_JOB_ID_PARAM.to_string(), job_id.to_string()),
+        (TRIGGER_STATUS_PARAM.to_string(), status.to_string()),
+        (
+            TRIGGER_SESSION_ID_PARAM.to_string(),
+            session_id.unwrap_or_default().to_string(),
+        ),
+    ])
+}
+
+/// Finds a loop of after-job triggers that `job` would close, as the ids along the loop
+/// starting and ending with `job`. `jobs` are the other scheduled jobs; a stored job with the
+/// same id is replaced by `job`.
+pub fn after_job_cycle(job: &ScheduledJob, jobs: &[ScheduledJob]) -> Option<Vec<String>> {
+    let upstream_of = |id: &str| -> Vec<String> {
+        let triggers = if id == job.id {
+            &job.triggers
+        } else {
+            match jobs.iter().find(|other| other.id == id) {
+                Some(other) => &other.triggers,
+                None => return Vec::new(),
+            }
+        };
+        triggers
+            .iter()
+            .filter_map(|trigger| match trigger {
+                JobTrigger::AfterJob { job_id } => Some(job_id.clone()),
+                _ => None,
+            })
+            .collect()
+    };
+
+    // Depth-first search through the jobs `job` waits on, looking for `job` itself
+    let mut visited = HashSet::new();
+    let mut stack = vec![vec![job.id.clone()]];
+    while let Some(path) = stack.pop() {
+        let current = path.last().unwrap();
+        for upstream in upstream_of(current) {
+            if upstream == job.id {
+                let mut cycle = path.clone();
+                cycle.push(upstream);
+                // Listed in the order the runs would trigger each other
+                cycle.reverse();
+                return Some(cycle);
+            }
+            if visited.insert(upstream.clone()) {
+                let mut next = path.clone();
+                next.push(upstream);
+                stack.push(next);
+            }
+        }
+    }
+    None
+}
+
+/// Ids of the jobs that should run after `completed_job_id` finishes.
+pub fn after_job_dependents<'a>(
+    jobs: impl IntoIterator<Item = &'a ScheduledJob>,
+    completed_job_id: &str,
+) -> Vec<String> {
+    jobs.into_iter()
+        .filter(|job| !job.paused)
+        .filter(|job| {
+            job.triggers.iter().any(|trigger| {
+                matches!(trigger, JobTrigger::AfterJob { job_id } if job_id == completed_job_id)
+            })
+        })
+        .map(|job| job.id.clone())
+        .collect()
+}
+
+/// Starts a job with trigger parameters; provided by each scheduler backend.
+pub type TriggerCallback =
+    Arc<dyn Fn(String, HashMap<String, String>) -> BoxFuture<'static, ()> + Send + Sync>;
+
+/// Background watchers for the file-watch triggers of each job.
+#[derive(Default)]
+pub struct TriggerWatchers {
+    watches: Mutex<HashMap<String, Vec<JoinHandle<()>>>>,
+}
+
+impl TriggerWatchers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// (Re)starts the watchers for a job's file-watch triggers.
+    pub fn watch(&self, job: &ScheduledJob, callback: TriggerCallback) {
+        self.unwatch(&job.id);
+        let handles: Vec<JoinHandle<()>> = job
+            .triggers
+            .iter()
+            .filter_map(|trigger| match trigger {
+                JobTrigger::FileWatch { path, glob } => Some(tokio::spawn(watch_files(
+                    job.id.clone(),
+                    PathBuf::from(path),
+                    glob.clone(),
+                    callback.clone(),
+                ))),
+                _ => None,
+            })
+            .collect();
+        if !handles.is_empty() {
+            self.watches.lock().unwrap().insert(job.id.clone(), handles);
+        }
+    }
+
+    pub fn unwatch(&self, job_id: &str) {
+        if let Some(handles) = self.watches.lock().unwrap().remove(job_id) {
+            for handle in handles {
+                handle.abort();
+            }
+        }
+    }
+}
+
+impl Drop for TriggerWatchers {
+    fn drop(&mut self) {
+        for handles in self.watches.get_mut().unwrap().values() {
+            for handle in handles {
+                handle.abort();
+            }
+        }
+    }
+}
+
+type FileSnapshot = HashMap<PathBuf, SystemTime>;
+
+/// Walks the watched directory on the blocking pool; `None` if the walk could not finish
+async fn snapshot_files_blocking(
+    root: PathBuf,
+    pattern: Option<glob::Pattern>,
+) -> Option<FileSnapshot> {
+    tokio::task::spawn_blocking(move || snapshot_files(&root, pattern.as_ref()))
+        .await
+        .ok()
+}
+
+fn snapshot_files(root: &Path, pattern: Option<&glob::Pattern>) -> FileSnapshot {
+    let mut snapshot = HashMap::new();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                pending.push(path);
+                continue;
+            }
+            let matches = match pattern {
+                Some(pattern) => path
+                    .strip_prefix(root)
+                    .map(|relative| pattern.matches_path(relative))
+                    .unwrap_or(false),
+                None => true,
+            };
+            if matches {
+                let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                snapshot.insert(path, modified);
+                if snapshot.len() >= MAX_WATCHED_FILES {
+                    return snapshot;
+                }
+            }
+        }
+    }
+    snapshot
+}
+
+/// Changes between two snapshots as `(path, event)` pairs, sorted by path.
+fn diff_snapshots(before: &FileSnapshot, after: &FileSnapshot) -> Vec<(PathBuf, &'static str)> {
+    let mut changes: Vec<(PathBuf, &'static str)> = after
+        .iter()
+        .filter_map(|(path, modified)| match before.get(path) {
+            None => Some((path.clone(), "created")),
+            Some(previous) if previous != modified => Some((path.clone(), "modified")),
+            _ => None,
+        })
+        .chain(
+            before
+                .keys()
+                .filter(|path| !after.contains_key(*path))
+                .map(|path| (path.clone(), "removed")),
+        )
+        .collect();
+    changes.sort();
+    changes
+}
+
+/// Folds a newly seen change into the pending changes of a debounce window
+fn merge_change(pending: &mut BTreeMap<PathBuf, &'static str>, path: PathBuf, event: &'static str) {
+    match (pending.get(&path).copied(), event) {
+        // A file that came and went within the window never changed as far as the run is concerned
+        (Some("created"), "removed") => {
+            pending.remove(&path);
+        }
+        (Some("created"), _) => {}
+        (Some("removed"), "created") => {
+            pending.insert(path, "modified");
+        }
+        _ => {
+            pending.insert(path, event);
+        }
+    }
+}
+
+/// Parameters for one run covering every change of a debounce window
+fn file_watch_parameters(changes: &BTreeMap<PathBuf, &'static str>) -> HashMap<String, String> {
+    let paths: Vec<String> = changes
+        .keys()
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect();
+    let mut events = changes.values();
+    let first = events.next().copied().unwrap_or("changed");
+    let event = if events.all(|event| *event == first) {
+        first
+    } else {
+        "changed"
+    };
+    HashMap::from([
+        (TRIGGER_TYPE_PARAM.to_string(), "file_watch".to_string()),
+        (TRIGGER_PATH_PARAM.to_string(), paths.join("\n")),
+        (TRIGGER_EVENT_PARAM.to_string(), event.to_string()),
+    ])
+}
+
+async fn watch_files(
+    job_id: String,
+    root: PathBuf,
+    glob: Option<String>,
+    callback: TriggerCallback,
+) {
+    let pattern = match glob.as_deref().map(glob::Pattern::new).transpose() {
+        Ok(pattern) => pattern,
+        Err(e) => {
+            tracing::error!("Invalid file watch glob for job '{}': {}", job_id, e);
+            return;
+        }
+    };
+    tracing::info!(
+        "Watching {} ({}) for job '{}'",
+        root.display(),
+        glob.as_deref().unwrap_or("all files"),
+        job_id
+    );
+
+    let mut previous = loop {
+        if let Some(snapshot) = snapshot_files_blocking(root.clone(), pattern.clone()).await {
+            break snapshot;
+        }
+        tokio::time::sleep(FILE_WATCH_POLL_INTERVAL).await;
+    };
+    let mut pending: BTreeMap<PathBuf, &'static str> = BTreeMap::new();
+    let mut pending_since: Option<Instant> = None;
+    let mut interval = tokio::time::interval(FILE_WATCH_POLL_INTERVAL);
+    interval.tick().await;
+    loop {
+        interval.tick().await;
+        let Some(current) = snapshot_files_blocking(root.clone(), pattern.clone()).await else {
+            continue;
+        };
+        let changes = diff_snapshots(&previous, &current);
+        previous = current;
+
+        let settled = changes.is_empty();
+        for (path, event) in changes {
+            merge_change(&mut pending, path, event);
+            pending_since.get_or_insert_with(Instant::now);
+        }
+        let waited_too_long =
+            pending_since.is_some_and(|since| since.elapsed() >= FILE_WATCH_MAX_DELAY);
+        if pending.is_empty() {
+            pending_since = None;
+        } else if settled || waited_too_long {
+            let params = file_watch_parameters(&pending);
+            tracing::info!(
+                "{} watched file(s) changed, triggering job '{}'",
+                pending.len(),
+                job_id
+            );
+            tokio::spawn(callback(job_id.clone(), params));
+            pending.clear();
+            pending_since = None;
+        }
+    }
+}
+
+/// Identifies a run record across polls of a job's run history
+type RunKey = (DateTime<Utc>, u32, Option<String>);
+
+/// Runs in `runs` not seen by an earlier poll, oldest first. `seen` is replaced by the keys of
+/// `runs`, which hold the whole retained history, so it stays as small as that history.
+fn new_runs(seen: &mut HashSet<RunKey>, runs: Vec<JobRunRecord>) -> Vec<JobRunRecord> {
+    let keys: HashSet<RunKey> = runs
+        .iter()
+        .map(|run| (run.started_at, run.attempt, run.session_id.clone()))
+        .collect();
+    let mut fresh: Vec<JobRunRecord> = runs
+        .into_iter()
+        .filter(|run| !seen.contains(&(run.started_at, run.attempt, run.session_id.clone())))
+        .collect();
+    fresh.reverse();
+    *seen = keys;
+    fresh
+}
+
+/// Fires after-job triggers for schedulers whose runs happen in another process, as the
+/// Temporal service's do. Those runs write a record when they finish (see
+/// [`crate::temporal_scheduler::record_run`]); the watcher reads the run history, so it sees
+/// every run with its real outcome however short it was. Runs recorded before the watcher
+/// started don't fire triggers.
+pub fn spawn_completion_watcher(scheduler: Arc<dyn SchedulerTrait>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut seen: HashMap<String, HashSet<RunKey>> = HashMap::new();
+        let mut first_poll = true;
+        let mut interval = tokio::time::interval(COMPLETION_POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            let jobs = match scheduler.list_scheduled_jobs().await {
+                Ok(jobs) => jobs,
+                Err(e) => {
+                    tracing::debug!("Completion watcher could not list jobs: {}", e);
+                    continue;
+                }
+            };
+
+            for job in &jobs {
+                let runs = match scheduler.runs(&job.id, usize::MAX).await {
+                    Ok(runs) => runs,
+                    Err(e) => {
+                        tracing::debug!(
+                            "Completion watcher could not read runs of '{}': {}",
+                            job.id,
+                            e
+                        );
+                        continue;
+                    }
+                };
+                let fresh = new_runs(seen.entry(job.id.clone()).or_default(), runs);
+                if first_poll {
+                    continue;
+                }
+                for run in fresh
+                    .iter()
+                    .filter(|run| run.status == JobRunStatus::Succeeded)
+                {
+                    for dependent in after_job_dependents(&jobs, &job.id) {
+                        let scheduler = scheduler.clone();
+                        let params =
+                            after_job_parameters(&job.id, run.status, run.session_id.as_deref());
+                        tokio::spawn(async move {
+                            if let Err(e) = scheduler.trigger_job(&dependent, params).await {
+                                tracing::error!(
+                                    "After-job trigger for '{}' failed: {}",
+                                    dependent,
+                                    e
+                                );
+                            }
+                        });
+                    }
+                }
+            }
+            seen.retain(|id, _| jobs.iter().any(|job| &job.id == id));
+            first_poll = false;
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduler::OverlapPolicy;
+
+    fn job_with_triggers(id: &str, triggers: Vec<JobTrigger>) -> ScheduledJob {
+        ScheduledJob {
+            id: id.to_string(),
+            source: "/tmp/recipe.yaml".to_string(),
+            cron: String::new(),
+            last_run: None,
+            currently_running: false,
+            paused: false,
+            current_session_id: None,
+            process_start_time: None,
+            execution_mode: None,
+            parameters: HashMap::new(),
+            overlap_policy: OverlapPolicy::Skip,
+            max_runtime_secs: None,
+            retry: None,
+            triggers,
+            timezone: None,
+        }
+    }
+
+    #[test]
+    fn test_webhook_secret_and_payload() {
+        let job = job_with_triggers(
+            "hook",
+            vec![JobTrigger::Webhook {
+                secret: "s3cret".to_string(),
+            }],
+        );
+        assert!(verify_webhook_secret(&job, "s3cret"));
+        assert!(!verify_webhook_secret(&job, "s3cre"));
+        assert!(!verify_webhook_secret(
+            &job_with_triggers("other", vec![]),
+            "s3cret"
+        ));
+
+        let params = webhook_parameters(&serde_json::json!({"branch": "main", "count": 3}));
+        assert_eq!(params["branch"], "main");
+        assert_eq!(params["count"], "3");
+        assert_eq!(params[TRIGGER_TYPE_PARAM], "webhook");
+
+        let params = webhook_parameters(&serde_json::json!([1, 2]));
+        assert_eq!(params["trigger_payload"], "[1,2]");
+    }
+
+    #[test]
+    fn test_after_job_dependents() {
+        let upstream = job_with_triggers("build", vec![]);
+        let downstream = job_with_triggers(
+            "deploy",
+            vec![JobTrigger::AfterJob {
+                job_id: "build".to_string(),
+            }],
+        );
+        let mut paused = job_with_triggers(
+            "notify",
+            vec![JobTrigger::AfterJob {
+                job_id: "build".to_string(),
+            }],
+        );
+        paused.paused = true;
+
+        let jobs = [upstream, downstream, paused];
+        assert_eq!(after_job_dependents(&jobs, "build"), vec!["deploy"]);
+        assert!(after_job_dependents(&jobs, "deploy").is_empty());
+    }
+
+    #[test]
+    fn test_after_job_cycle() {
+        let after = |id: &str| {
+            vec![JobTrigger::AfterJob {
+                job_id: id.to_string(),
+            }]
+        };
+        let jobs = vec![
+            job_with_triggers("build", after("deploy")),
+            job_with_triggers("test", after("build")),
+            job_with_triggers("report", vec![]),
+        ];
+
+        // deploy after test closes build -> test -> deploy -> build
+        let deploy = job_with_triggers("deploy", after("test"));
+        assert_eq!(
+            after_job_cycle(&deploy, &jobs),
+            Some(vec![
+                "deploy".to_string(),
+                "build".to_string(),
+                "test".to_string(),
+                "deploy".to_string(),
+            ])
+        );
+
+        let deploy = job_with_triggers("deploy", after("report"));
+        assert_eq!(after_job_cycle(&deploy, &jobs), None);
+
+        // Editing a stored job replaces its old triggers
+        let build = job_with_triggers("build", after("report"));
+        assert_eq!(after_job_cycle(&build, &jobs), None);
+    }
+
+    #[test]
+    fn test_new_runs_reports_each_run_once() {
+        let run = |minute: u32, status: JobRunStatus| JobRunRecord {
+            job_id: "build".to_string(),
+            status,
+            started_at: DateTime::parse_from_rfc3339(&format!("2026-01-01T10:{:02}:00Z", minute))
+                .unwrap()
+                .with_timezone(&Utc),
+            duration_ms: 1,
+            attempt: 1,
+            error: None,
+            session_id: Some(format!("session-{}", minute)),
+        };
+        let mut seen = HashSet::new();
+
+        assert_eq!(
+            new_runs(&mut seen, vec![run(1, JobRunStatus::Failed)]).len(),
+            1
+        );
+        assert!(new_runs(&mut seen, vec![run(1, JobRunStatus::Failed)]).is_empty());
+
+        // Newest first in, oldest first out, with the real status of each run
+        let fresh = new_runs(
+            &mut seen,
+            vec![
+                run(3, JobRunStatus::Succeeded),
+                run(2, JobRunStatus::Failed),
+                run(1, JobRunStatus::Failed),
+            ],
+        );
+        let statuses: Vec<_> = fresh.iter().map(|run| run.status).collect();
+        assert_eq!(
+            statuses,
+            vec![JobRunStatus::Failed, JobRunStatus::Succeeded]
+        );
+        assert_eq!(seen.len(), 3);
+    }
+
+    #[test]
+    fn test_file_changes_are_debounced_into_one_run() {
+        let mut pending = BTreeMap::new();
+        merge_change(&mut pending, PathBuf::from("/w/a.md"), "created");
+        merge_change(&mut pending, PathBuf::from("/w/a.md"), "modified");
+        merge_change(&mut pending, PathBuf::from("/w/b.md"), "modified");
+        merge_change(&mut pending, PathBuf::from("/w/tmp.md"), "created");
+        merge_change(&mut pending, PathBuf::from("/w/tmp.md"), "removed");
+
+        let params = file_watch_parameters(&pending);
+        assert_eq!(params[TRIGGER_PATH_PARAM], "/w/a.md\n/w/b.md");
+        assert_eq!(params[TRIGGER_EVENT_PARAM], "changed");
+
+        pending.remove(Path::new("/w/a.md"));
+        assert_eq!(
+            file_watch_parameters(&pending)[TRIGGER_EVENT_PARAM],
+            "modified"
+        );
+    }
+
+    #[test]
+    fn test_file_snapshot_diff() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join("nested")).unwrap();
+        std::fs::write(root.join("keep.md"), "a").unwrap();
+        std::fs::write(root.join("ignored.txt"), "a").unwrap();
+
+        let pattern = glob::Pattern::new("**/*.md").unwrap();
+        let before = snapshot_files(root, Some(&pattern));
+        assert_eq!(before.len(), 1);
+
+        std::fs::write(root.join("nested/new.md"), "b").unwrap();
+        std::fs::remove_file(root.join("keep.md")).unwrap();
+        let after = snapshot_files(root, Some(&pattern));
+
+        let changes = diff_snapshots(&before, &after);
+        assert_eq!(
+            changes,
+            vec![
+                (root.join("keep.md"), "removed"),
+                (root.join("nested/new.md"), "created"),
+            ]
+        );
+    }
+}
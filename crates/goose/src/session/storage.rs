@@ -64,6 +64,10 @@ pub struct SessionMetadata {
     pub accumulated_input_tokens: Option<i32>,
     /// The number of output tokens used in the session. Accumulated across all messages.
     pub accumulated_output_tokens: Option<i32>,
+    /// Provider chosen with /model during the session, used again when it is resumed
+    pub provider: Option<String>,
+    /// Model chosen with /model during the session, used again when it is resumed
+    pub model: Option<String>,
 }
 
 // Custom deserializer to handle old sessions without working_dir
@@ -84,6 +88,8 @@ impl<'de> Deserialize<'de> for SessionMetadata {
             accumulated_input_tokens: Option<i32>,
             accumulated_output_tokens: Option<i32>,
             working_dir: Option<PathBuf>,
+            provider: Option<String>,
+            model: Option<String>,
         }
 
         let helper = Helper::deserialize(deserializer)?;
@@ -105,6 +111,8 @@ impl<'de> Deserialize<'de> for SessionMetadata {
             accumulated_input_tokens: helper.accumulated_input_tokens,
             accumulated_output_tokens: helper.accumulated_output_tokens,
             working_dir,
+            provider: helper.provider,
+            model: helper.model,
         })
     }
 }
@@ -129,6 +137,8 @@ impl SessionMetadata {
             accumulated_total_tokens: None,
             accumulated_input_tokens: None,
             accumulated_output_tokens: None,
+            provider: None,
+            model: None,
         }
     }
 }
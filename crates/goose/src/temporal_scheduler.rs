@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+use std::path::Path;
 use std::process::Command;
 use std::sync::Arc;
 use std::time::Duration;
@@ -9,10 +11,24 @@ use serde::{Deserialize, Serialize};
 use tokio::time::sleep;
 use tracing::{info, warn};
 
-use crate::scheduler::{normalize_cron_expression, ScheduledJob, SchedulerError};
+use crate::scheduler::{
+    get_default_scheduler_storage_path, normalize_cron_expression, parse_timezone, read_job_runs,
+    record_job_run, validate_job_parameters, validate_job_triggers, JobRunRecord, OverlapPolicy,
+    ScheduledJob, SchedulerError,
+};
 use crate::scheduler_trait::SchedulerTrait;
+use crate::scheduler_triggers::{
+    spawn_completion_watcher, JobTrigger, TriggerCallback, TriggerWatchers,
+};
 use crate::session::storage::SessionMetadata;
 
+/// Records how a run started by the Temporal service ended. The service runs each job as
+/// `goose run --scheduled-job-id`, which calls this when it finishes so that the run history
+/// and after-job triggers see the real outcome of every run.
+pub fn record_run(record: &JobRunRecord) -> Result<(), SchedulerError> {
+    record_job_run(&get_default_scheduler_storage_path()?, record)
+}
+
 const TEMPORAL_SERVICE_STARTUP_TIMEOUT: Duration = Duration::from_secs(15);
 const TEMPORAL_SERVICE_HEALTH_CHECK_INTERVAL: Duration = Duration::from_millis(500);
 
@@ -27,6 +43,12 @@ struct JobRequest {
     cron: Option<String>,
     recipe_path: Option<String>,
     execution_mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parameters: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    triggers: Option<Vec<JobTrigger>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timezone: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -48,6 +70,12 @@ struct TemporalJobStatus {
     paused: bool,
     created_at: String,
     execution_mode: Option<String>,
+    #[serde(default)]
+    parameters: HashMap<String, String>,
+    #[serde(default)]
+    triggers: Vec<JobTrigger>,
+    #[serde(default)]
+    timezone: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -67,6 +95,7 @@ pub struct TemporalScheduler {
     http_client: Client,
     service_url: String,
     port_config: PortConfig,
+    trigger_watchers: Arc<TriggerWatchers>,
 }
 
 impl TemporalScheduler {
@@ -88,6 +117,7 @@ impl TemporalScheduler {
                 temporal_port: 7233, // temporary defaults
                 ui_port: 8233,
             },
+            trigger_watchers: Arc::new(TriggerWatchers::new()),
         });
 
         // Start the Go service if not already running
@@ -109,12 +139,14 @@ impl TemporalScheduler {
             http_client,
             service_url,
             port_config,
+            trigger_watchers: Arc::new(TriggerWatchers::new()),
         });
 
         // Start the status monitor to keep job statuses in sync
         if let Err(e) = final_scheduler.start_status_monitor().await {
             tracing::warn!("Failed to start status monitor: {}", e);
         }
+        final_scheduler.start_trigger_watchers().await;
 
         info!("TemporalScheduler initialized successfully");
         Ok(final_scheduler)
@@ -556,18 +588,34 @@ impl TemporalScheduler {
             );
         }
 
+        validate_job_triggers(&job, &self.list_scheduled_jobs().await?)?;
+        validate_job_parameters(Path::new(&job.source), &job)?;
+        if job.overlap_policy != OverlapPolicy::Skip
+            || job.max_runtime_secs.is_some()
+            || job.retry.is_some()
+        {
+            return Err(SchedulerError::SchedulerInternalError(format!(
+                "Overlap policy, max runtime and retry settings are not supported by the Temporal scheduler (job '{}'). Remove them or use the legacy scheduler.",
+                job.id
+            )));
+        }
+
         let request = JobRequest {
             action: "create".to_string(),
             job_id: Some(job.id.clone()),
             cron: Some(normalized_cron.clone()),
             recipe_path: Some(job.source.clone()),
             execution_mode: job.execution_mode.clone(),
+            parameters: (!job.parameters.is_empty()).then(|| job.parameters.clone()),
+            triggers: (!job.triggers.is_empty()).then(|| job.triggers.clone()),
+            timezone: job.timezone.clone(),
         };
 
         let response = self.make_request(request).await?;
 
         if response.success {
             info!("Successfully created scheduled job: {}", job.id);
+            self.trigger_watchers.watch(&job, self.trigger_callback());
             Ok(())
         } else {
             Err(SchedulerError::SchedulerInternalError(response.message))
@@ -582,6 +630,9 @@ impl TemporalScheduler {
             cron: None,
             recipe_path: None,
             execution_mode: None,
+            parameters: None,
+            triggers: None,
+            timezone: None,
         };
 
         let response = self.make_request(request).await?;
@@ -601,6 +652,12 @@ impl TemporalScheduler {
                         current_session_id: None, // Not provided by Temporal service
                         process_start_time: None, // Not provided by Temporal service
                         execution_mode: tj.execution_mode,
+                        parameters: tj.parameters,
+                        overlap_policy: OverlapPolicy::Skip,
+                        max_runtime_secs: None,
+                        retry: None,
+                        triggers: tj.triggers,
+                        timezone: tj.timezone,
                     }
                 })
                 .collect();
@@ -617,12 +674,16 @@ impl TemporalScheduler {
             cron: None,
             recipe_path: None,
             execution_mode: None,
+            parameters: None,
+            triggers: None,
+            timezone: None,
         };
 
         let response = self.make_request(request).await?;
 
         if response.success {
             info!("Successfully removed scheduled job: {}", id);
+            self.trigger_watchers.unwatch(id);
             Ok(())
         } else {
             Err(SchedulerError::SchedulerInternalError(response.message))
@@ -636,6 +697,9 @@ impl TemporalScheduler {
             cron: None,
             recipe_path: None,
             execution_mode: None,
+            parameters: None,
+            triggers: None,
+            timezone: None,
         };
 
         let response = self.make_request(request).await?;
@@ -655,6 +719,9 @@ impl TemporalScheduler {
             cron: None,
             recipe_path: None,
             execution_mode: None,
+            parameters: None,
+            triggers: None,
+            timezone: None,
         };
 
         let response = self.make_request(request).await?;
@@ -669,12 +736,44 @@ impl TemporalScheduler {
 
     pub async fn run_now(&self, id: &str) -> Result<String, SchedulerError> {
         tracing::info!("TemporalScheduler: run_now() called for job '{}'", id);
+        self.start_run(id, None).await
+    }
+
+    pub async fn trigger_job(
+        &self,
+        id: &str,
+        params: HashMap<String, String>,
+    ) -> Result<String, SchedulerError> {
+        tracing::info!("TemporalScheduler: trigger_job() called for job '{}'", id);
+        let job = self
+            .list_scheduled_jobs()
+            .await?
+            .into_iter()
+            .find(|job| job.id == id)
+            .ok_or_else(|| SchedulerError::JobNotFound(id.to_string()))?;
+        if job.paused {
+            return Err(SchedulerError::AnyhowError(anyhow::anyhow!(
+                "Schedule '{}' is paused",
+                id
+            )));
+        }
+        self.start_run(id, Some(params)).await
+    }
+
+    async fn start_run(
+        &self,
+        id: &str,
+        parameters: Option<HashMap<String, String>>,
+    ) -> Result<String, SchedulerError> {
         let request = JobRequest {
             action: "run_now".to_string(),
             job_id: Some(id.to_string()),
             cron: None,
             recipe_path: None,
             execution_mode: None,
+            parameters,
+            triggers: None,
+            timezone: None,
         };
 
         let response = self.make_request(request).await?;
@@ -752,6 +851,7 @@ impl TemporalScheduler {
         &self,
         sched_id: &str,
         new_cron: String,
+        timezone: Option<String>,
     ) -> Result<(), SchedulerError> {
         tracing::info!(
             "TemporalScheduler: update_schedule() called for job '{}' with cron '{}'",
@@ -769,12 +869,17 @@ impl TemporalScheduler {
             );
         }
 
+        parse_timezone(timezone.as_deref())?;
+
         let request = JobRequest {
             action: "update".to_string(),
             job_id: Some(sched_id.to_string()),
             cron: Some(normalized_cron),
             recipe_path: None,
             execution_mode: None,
+            parameters: None,
+            triggers: None,
+            timezone,
         };
 
         let response = self.make_request(request).await?;
@@ -799,6 +904,9 @@ impl TemporalScheduler {
             cron: None,
             recipe_path: None,
             execution_mode: None,
+            parameters: None,
+            triggers: None,
+            timezone: None,
         };
 
         let response = self.make_request(request).await?;
@@ -825,6 +933,9 @@ impl TemporalScheduler {
                     cron: None,
                     recipe_path: None,
                     execution_mode: None,
+                    parameters: None,
+                    triggers: None,
+                    timezone: None,
                 };
 
                 match self.make_request(request).await {
@@ -907,6 +1018,9 @@ impl TemporalScheduler {
                         cron: None,
                         recipe_path: None,
                         execution_mode: None,
+                        parameters: None,
+                        triggers: None,
+                        timezone: None,
                     };
 
                     if let Err(e) = self.make_request(request).await {
@@ -938,6 +1052,32 @@ impl TemporalScheduler {
         Ok(())
     }
 
+    fn trigger_callback(&self) -> TriggerCallback {
+        let scheduler = self.clone();
+        Arc::new(move |job_id, params| {
+            let scheduler = scheduler.clone();
+            Box::pin(async move {
+                if let Err(e) = scheduler.trigger_job(&job_id, params).await {
+                    tracing::error!("Trigger for job '{}' failed: {}", job_id, e);
+                }
+            })
+        })
+    }
+
+    /// Starts file watchers for the triggers of existing jobs and the watcher that fires
+    /// after-job triggers from the run records written by each run.
+    async fn start_trigger_watchers(self: &Arc<Self>) {
+        match self.list_scheduled_jobs().await {
+            Ok(jobs) => {
+                for job in &jobs {
+                    self.trigger_watchers.watch(job, self.trigger_callback());
+                }
+            }
+            Err(e) => tracing::warn!("Failed to start trigger watchers: {}", e),
+        }
+        spawn_completion_watcher(self.clone());
+    }
+
     pub async fn get_running_job_info(
         &self,
         sched_id: &str,
@@ -954,6 +1094,9 @@ impl TemporalScheduler {
             cron: None,
             recipe_path: None,
             execution_mode: None,
+            parameters: None,
+            triggers: None,
+            timezone: None,
         };
 
         let response = self.make_request(request).await?;
@@ -1208,6 +1351,14 @@ impl SchedulerTrait for TemporalScheduler {
         self.run_now(id).await
     }
 
+    async fn trigger_job(
+        &self,
+        id: &str,
+        params: HashMap<String, String>,
+    ) -> Result<String, SchedulerError> {
+        self.trigger_job(id, params).await
+    }
+
     async fn sessions(
         &self,
         sched_id: &str,
@@ -1216,12 +1367,21 @@ impl SchedulerTrait for TemporalScheduler {
         self.sessions(sched_id, limit).await
     }
 
+    async fn runs(
+        &self,
+        sched_id: &str,
+        limit: usize,
+    ) -> Result<Vec<JobRunRecord>, SchedulerError> {
+        read_job_runs(&get_default_scheduler_storage_path()?, sched_id, limit)
+    }
+
     async fn update_schedule(
         &self,
         sched_id: &str,
         new_cron: String,
+        timezone: Option<String>,
     ) -> Result<(), SchedulerError> {
-        self.update_schedule(sched_id, new_cron).await
+        self.update_schedule(sched_id, new_cron, timezone).await
     }
 
     async fn kill_running_job(&self, sched_id: &str) -> Result<(), SchedulerError> {
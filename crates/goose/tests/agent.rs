@@ -364,6 +364,7 @@ mod schedule_tool_tests {
     use goose::scheduler::{ScheduledJob, SchedulerError};
     use goose::scheduler_trait::SchedulerTrait;
     use goose::session::storage::SessionMetadata;
+    use std::collections::HashMap;
     use std::sync::Arc;
 
     // Mock scheduler for testing
@@ -414,6 +415,14 @@ mod schedule_tool_tests {
             Ok("test_session_123".to_string())
         }
 
+        async fn trigger_job(
+            &self,
+            _id: &str,
+            _params: HashMap<String, String>,
+        ) -> Result<String, SchedulerError> {
+            Ok("test_session_123".to_string())
+        }
+
         async fn sessions(
             &self,
             _sched_id: &str,
@@ -426,6 +435,7 @@ mod schedule_tool_tests {
             &self,
             _sched_id: &str,
             _new_cron: String,
+            _timezone: Option<String>,
         ) -> Result<(), SchedulerError> {
             Ok(())
         }
@@ -864,6 +874,7 @@ mod retry_tests {
             schedule_id: None,
             execution_mode: None,
             max_turns: None,
+            goose_mode: None,
             retry_config: Some(retry_config),
         };
 
@@ -891,7 +902,7 @@ mod retry_tests {
 
     #[tokio::test]
     async fn test_retry_success_check_execution() -> Result<()> {
-        use goose::agents::retry::execute_success_checks;
+        use goose::agents::retry::{execute_success_checks, SuccessCheckContext};
 
         let retry_config = RetryConfig {
             max_retries: 3,
@@ -905,17 +916,18 @@ mod retry_tests {
             command: "echo 'test'".to_string(),
         }];
 
-        let result = execute_success_checks(&success_checks, &retry_config).await;
+        let context = SuccessCheckContext::default();
+        let result = execute_success_checks(&success_checks, &retry_config, &context).await;
         assert!(result.is_ok(), "Success check should pass");
-        assert!(result.unwrap(), "Command should succeed");
+        assert!(result.unwrap()[0].passed, "Command should succeed");
 
         let fail_checks = vec![SuccessCheck::Shell {
             command: "false".to_string(),
         }];
 
-        let result = execute_success_checks(&fail_checks, &retry_config).await;
+        let result = execute_success_checks(&fail_checks, &retry_config, &context).await;
         assert!(result.is_ok(), "Success check execution should not error");
-        assert!(!result.unwrap(), "Command should fail");
+        assert!(!result.unwrap()[0].passed, "Command should fail");
 
         Ok(())
     }
@@ -1033,6 +1045,7 @@ mod max_turns_tests {
             schedule_id: None,
             execution_mode: None,
             max_turns: Some(1),
+            goose_mode: None,
             retry_config: None,
         };
         let conversation = Conversation::new(vec![Message::user().with_text("Hello")]).unwrap();
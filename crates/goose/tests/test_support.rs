@@ -10,7 +10,7 @@ use tempfile::TempDir;
 use tokio::sync::Mutex;
 
 use goose::agents::Agent;
-use goose::scheduler::{ScheduledJob, SchedulerError};
+use goose::scheduler::{OverlapPolicy, ScheduledJob, SchedulerError};
 use goose::scheduler_trait::SchedulerTrait;
 use goose::session::storage::SessionMetadata;
 
@@ -203,6 +203,21 @@ impl SchedulerTrait for ConfigurableMockScheduler {
         }
     }
 
+    async fn trigger_job(
+        &self,
+        id: &str,
+        _params: HashMap<String, String>,
+    ) -> Result<String, SchedulerError> {
+        self.log_call("trigger_job").await;
+
+        let jobs = self.jobs.lock().await;
+        if jobs.contains_key(id) {
+            Ok(format!("{}_session_{}", id, chrono::Utc::now().timestamp()))
+        } else {
+            Err(SchedulerError::JobNotFound(id.to_string()))
+        }
+    }
+
     async fn sessions(
         &self,
         sched_id: &str,
@@ -226,6 +241,7 @@ impl SchedulerTrait for ConfigurableMockScheduler {
         &self,
         sched_id: &str,
         _new_cron: String,
+        _timezone: Option<String>,
     ) -> Result<(), SchedulerError> {
         self.log_call("update_schedule").await;
 
@@ -363,6 +379,12 @@ impl ScheduleToolTestBuilder {
             current_session_id: None,
             process_start_time: None,
             execution_mode: Some("background".to_string()),
+            parameters: HashMap::new(),
+            overlap_policy: OverlapPolicy::Skip,
+            max_runtime_secs: None,
+            retry: None,
+            triggers: Vec::new(),
+            timezone: None,
         };
         {
             let mut jobs = self.scheduler.jobs.lock().await;
@@ -411,5 +433,7 @@ pub fn create_test_session_metadata(message_count: usize, working_dir: &str) ->
         accumulated_total_tokens: Some(100),
         accumulated_input_tokens: Some(50),
         accumulated_output_tokens: Some(50),
+        provider: None,
+        model: None,
     }
 }
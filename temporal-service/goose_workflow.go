@@ -10,6 +10,7 @@ import (
 	"os/exec"
 	"path/filepath"
 	"runtime"
+	"sort"
 	"strings"
 	"time"
 
@@ -27,8 +28,9 @@ type Recipe struct {
 	Prompt       *string `json:"prompt" yaml:"prompt"`
 }
 
-// Workflow definition for executing Goose recipes
-func GooseJobWorkflow(ctx workflow.Context, jobID, recipePath string) (string, error) {
+// Workflow definition for executing Goose recipes. triggerParams carries the parameters
+// passed by the trigger that started this run; scheduled runs leave it empty.
+func GooseJobWorkflow(ctx workflow.Context, jobID, recipePath string, triggerParams map[string]string) (string, error) {
 	logger := workflow.GetLogger(ctx)
 	logger.Info("Starting Goose job workflow", "jobID", jobID, "recipePath", recipePath)
 
@@ -45,7 +47,7 @@ func GooseJobWorkflow(ctx workflow.Context, jobID, recipePath string) (string, e
 	ctx = workflow.WithActivityOptions(ctx, ao)
 
 	var sessionID string
-	err := workflow.ExecuteActivity(ctx, ExecuteGooseRecipe, jobID, recipePath).Get(ctx, &sessionID)
+	err := workflow.ExecuteActivity(ctx, ExecuteGooseRecipe, jobID, recipePath, triggerParams).Get(ctx, &sessionID)
 	if err != nil {
 		logger.Error("Goose job workflow failed", "jobID", jobID, "error", err)
 		return "", err
@@ -56,7 +58,7 @@ func GooseJobWorkflow(ctx workflow.Context, jobID, recipePath string) (string, e
 }
 
 // Activity definition for executing Goose recipes with proper cancellation handling
-func ExecuteGooseRecipe(ctx context.Context, jobID, recipePath string) (string, error) {
+func ExecuteGooseRecipe(ctx context.Context, jobID, recipePath string, triggerParams map[string]string) (string, error) {
 	logger := activity.GetLogger(ctx)
 	logger.Info("Executing Goose recipe", "jobID", jobID, "recipePath", recipePath)
 
@@ -104,12 +106,12 @@ func ExecuteGooseRecipe(ctx context.Context, jobID, recipePath string) (string,
 	// Check if this is a foreground job
 	if isForegroundJob(actualRecipePath) {
 		logger.Info("Executing foreground job with cancellation support", "jobID", jobID)
-		return executeForegroundJobWithCancellation(subCtx, jobID, actualRecipePath)
+		return executeForegroundJobWithCancellation(subCtx, jobID, actualRecipePath, triggerParams)
 	}
 
 	// For background jobs, execute with cancellation support
 	logger.Info("Executing background job with cancellation support", "jobID", jobID)
-	return executeBackgroundJobWithCancellation(subCtx, jobID, actualRecipePath)
+	return executeBackgroundJobWithCancellation(subCtx, jobID, actualRecipePath, triggerParams)
 }
 
 // resolveRecipePath resolves the actual recipe path, handling embedded recipes
@@ -136,8 +138,39 @@ func resolveRecipePath(jobID, recipePath string) (string, error) {
 	return "", fmt.Errorf("recipe not found: %s (and no embedded content available)", recipePath)
 }
 
+// recipeParamArgs builds the --params flags for the recipe parameters stored with a schedule,
+// overridden by any parameters passed by the trigger that started this run
+func recipeParamArgs(jobID string, triggerParams map[string]string) []string {
+	var params map[string]string
+	if globalService != nil {
+		var err error
+		params, err = globalService.getScheduleParameters(jobID)
+		if err != nil {
+			log.Printf("Warning: could not load recipe parameters for job %s: %v", jobID, err)
+		}
+	}
+	if len(triggerParams) > 0 {
+		if params == nil {
+			params = make(map[string]string, len(triggerParams))
+		}
+		for key, value := range triggerParams {
+			params[key] = value
+		}
+	}
+	keys := make([]string, 0, len(params))
+	for key := range params {
+		keys = append(keys, key)
+	}
+	sort.Strings(keys)
+	args := make([]string, 0, len(keys)*2)
+	for _, key := range keys {
+		args = append(args, "--params", fmt.Sprintf("%s=%s", key, params[key]))
+	}
+	return args
+}
+
 // executeBackgroundJobWithCancellation handles background job execution with proper process management
-func executeBackgroundJobWithCancellation(ctx context.Context, jobID, recipePath string) (string, error) {
+func executeBackgroundJobWithCancellation(ctx context.Context, jobID, recipePath string, triggerParams map[string]string) (string, error) {
 	log.Printf("Executing background job %s using recipe file: %s", jobID, recipePath)
 
 	// Find the goose CLI binary
@@ -150,11 +183,13 @@ func executeBackgroundJobWithCancellation(ctx context.Context, jobID, recipePath
 	sessionName := fmt.Sprintf("scheduled-%s", jobID)
 
 	// Create command with context for cancellation
-	cmd := exec.CommandContext(ctx, goosePath, "run",
+	args := []string{"run",
 		"--recipe", recipePath,
 		"--name", sessionName,
 		"--scheduled-job-id", jobID,
-	)
+	}
+	args = append(args, recipeParamArgs(jobID, triggerParams)...)
+	cmd := exec.CommandContext(ctx, goosePath, args...)
 
 	// Set up process group for proper cleanup
 	configureSysProcAttr(cmd)
@@ -204,7 +239,7 @@ func executeBackgroundJobWithCancellation(ctx context.Context, jobID, recipePath
 }
 
 // executeForegroundJobWithCancellation handles foreground job execution with proper process management
-func executeForegroundJobWithCancellation(ctx context.Context, jobID, recipePath string) (string, error) {
+func executeForegroundJobWithCancellation(ctx context.Context, jobID, recipePath string, triggerParams map[string]string) (string, error) {
 	log.Printf("Executing foreground job %s with recipe %s", jobID, recipePath)
 
 	// Parse the recipe file first
@@ -221,7 +256,7 @@ func executeForegroundJobWithCancellation(ctx context.Context, jobID, recipePath
 
 	// Desktop app not running, fall back to CLI
 	log.Printf("Desktop app not running, falling back to CLI mode for job %s", jobID)
-	return executeForegroundJobCLIWithCancellation(ctx, jobID, recipe, recipePath)
+	return executeForegroundJobCLIWithCancellation(ctx, jobID, recipe, recipePath, triggerParams)
 }
 
 // executeForegroundJobGUIWithCancellation handles GUI execution with cancellation
@@ -257,7 +292,7 @@ func executeForegroundJobGUIWithCancellation(ctx context.Context, jobID string,
 }
 
 // executeForegroundJobCLIWithCancellation handles CLI execution with cancellation
-func executeForegroundJobCLIWithCancellation(ctx context.Context, jobID string, recipe *Recipe, recipePath string) (string, error) {
+func executeForegroundJobCLIWithCancellation(ctx context.Context, jobID string, recipe *Recipe, recipePath string, triggerParams map[string]string) (string, error) {
 	log.Printf("Executing job %s via CLI fallback using recipe file: %s", jobID, recipePath)
 	// Find the goose CLI binary
 	goosePath, err := findGooseBinary()
@@ -268,11 +303,13 @@ func executeForegroundJobCLIWithCancellation(ctx context.Context, jobID string,
 	// Generate session name for this scheduled job
 	sessionName := fmt.Sprintf("scheduled-%s", jobID)
 	// Create command with context for cancellation
-	cmd := exec.CommandContext(ctx, goosePath, "run",
+	args := []string{"run",
 		"--recipe", recipePath,
 		"--name", sessionName,
 		"--scheduled-job-id", jobID,
-	)
+	}
+	args = append(args, recipeParamArgs(jobID, triggerParams)...)
+	cmd := exec.CommandContext(ctx, goosePath, args...)
 
 	// Set up process group for proper cleanup
 	configureSysProcAttr(cmd)
@@ -14,25 +14,31 @@ import (
 )
 
 type JobStatus struct {
-	ID               string    `json:"id"`
-	CronExpr         string    `json:"cron"`
-	RecipePath       string    `json:"recipe_path"`
-	LastRun          *string   `json:"last_run,omitempty"`
-	NextRun          *string   `json:"next_run,omitempty"`
-	CurrentlyRunning bool      `json:"currently_running"`
-	Paused           bool      `json:"paused"`
-	CreatedAt        time.Time `json:"created_at"`
-	ExecutionMode    *string   `json:"execution_mode,omitempty"`  // "foreground" or "background"
-	LastManualRun    *string   `json:"last_manual_run,omitempty"` // Track manual runs separately
+	ID               string            `json:"id"`
+	CronExpr         string            `json:"cron"`
+	RecipePath       string            `json:"recipe_path"`
+	LastRun          *string           `json:"last_run,omitempty"`
+	NextRun          *string           `json:"next_run,omitempty"`
+	CurrentlyRunning bool              `json:"currently_running"`
+	Paused           bool              `json:"paused"`
+	CreatedAt        time.Time         `json:"created_at"`
+	ExecutionMode    *string           `json:"execution_mode,omitempty"`  // "foreground" or "background"
+	LastManualRun    *string           `json:"last_manual_run,omitempty"` // Track manual runs separately
+	Parameters       map[string]string `json:"parameters,omitempty"`      // Recipe parameter values
+	Triggers         json.RawMessage   `json:"triggers,omitempty"`        // Event triggers, fired by the goose scheduler client
+	Timezone         string            `json:"timezone,omitempty"`        // IANA timezone the cron expression is evaluated in
 }
 
 // Request/Response types for HTTP API
 type JobRequest struct {
-	Action        string `json:"action"` // create, delete, pause, unpause, list, run_now, kill_job, update
-	JobID         string `json:"job_id"`
-	CronExpr      string `json:"cron"`
-	RecipePath    string `json:"recipe_path"`
-	ExecutionMode string `json:"execution_mode,omitempty"` // "foreground" or "background"
+	Action        string            `json:"action"` // create, delete, pause, unpause, list, run_now, kill_job, update
+	JobID         string            `json:"job_id"`
+	CronExpr      string            `json:"cron"`
+	RecipePath    string            `json:"recipe_path"`
+	ExecutionMode string            `json:"execution_mode,omitempty"` // "foreground" or "background"
+	Parameters    map[string]string `json:"parameters,omitempty"`     // Recipe parameter values; for run_now, trigger parameters for this run
+	Triggers      json.RawMessage   `json:"triggers,omitempty"`       // Event triggers, stored opaquely with the schedule
+	Timezone      string            `json:"timezone,omitempty"`       // IANA timezone the cron expression is evaluated in (default UTC)
 }
 
 type JobResponse struct {
@@ -48,8 +54,12 @@ type RunNowResponse struct {
 
 // createSchedule handles the creation of a new schedule
 func (ts *TemporalService) createSchedule(req JobRequest) JobResponse {
-	if req.JobID == "" || req.CronExpr == "" || req.RecipePath == "" {
-		return JobResponse{Success: false, Message: "Missing required fields: job_id, cron, recipe_path"}
+	if req.JobID == "" || req.RecipePath == "" {
+		return JobResponse{Success: false, Message: "Missing required fields: job_id, recipe_path"}
+	}
+	// Jobs started only by triggers have no cron expression
+	if req.CronExpr == "" && len(req.Triggers) == 0 {
+		return JobResponse{Success: false, Message: "Missing required field: cron (or triggers)"}
 	}
 
 	// Check if job already exists
@@ -79,6 +89,15 @@ func (ts *TemporalService) createSchedule(req JobRequest) JobResponse {
 		"execution_mode": executionMode,
 		"created_at":     time.Now().Format(time.RFC3339),
 	}
+	if len(req.Parameters) > 0 {
+		scheduleMetadata["parameters"] = req.Parameters
+	}
+	if len(req.Triggers) > 0 {
+		scheduleMetadata["triggers"] = req.Triggers
+	}
+	if req.Timezone != "" {
+		scheduleMetadata["timezone"] = req.Timezone
+	}
 
 	// For small recipes, embed content directly in metadata
 	if len(recipeContent) < 8192 { // 8KB limit for embedding
@@ -93,16 +112,22 @@ func (ts *TemporalService) createSchedule(req JobRequest) JobResponse {
 		return JobResponse{Success: false, Message: fmt.Sprintf("Failed to encode metadata: %v", err)}
 	}
 
+	var cronExpressions []string
+	if req.CronExpr != "" {
+		cronExpressions = []string{req.CronExpr}
+	}
+
 	// Create Temporal schedule with metadata in Note field
 	schedule := client.ScheduleOptions{
 		ID: scheduleID,
 		Spec: client.ScheduleSpec{
-			CronExpressions: []string{req.CronExpr},
+			CronExpressions: cronExpressions,
+			TimeZoneName:    req.Timezone,
 		},
 		Action: &client.ScheduleWorkflowAction{
 			ID:        fmt.Sprintf("workflow-%s-{{.ScheduledTime.Unix}}", req.JobID),
 			Workflow:  GooseJobWorkflow,
-			Args:      []interface{}{req.JobID, req.RecipePath},
+			Args:      []interface{}{req.JobID, req.RecipePath, map[string]string{}},
 			TaskQueue: TaskQueueName,
 		},
 		Note: string(metadataJSON), // Store metadata as JSON in the Note field
@@ -125,6 +150,9 @@ func (ts *TemporalService) createSchedule(req JobRequest) JobResponse {
 		Paused:           false,
 		CreatedAt:        time.Now(),
 		ExecutionMode:    &executionMode,
+		Parameters:       req.Parameters,
+		Triggers:         req.Triggers,
+		Timezone:         req.Timezone,
 	}
 	ts.scheduleJobs[req.JobID] = jobStatus
 
@@ -243,14 +271,20 @@ func (ts *TemporalService) updateSchedule(req JobRequest) JobResponse {
 	// Update the schedule with new cron expression while preserving metadata
 	err := handle.Update(ctx, client.ScheduleUpdateOptions{
 		DoUpdate: func(input client.ScheduleUpdateInput) (*client.ScheduleUpdate, error) {
-			// Update the cron expression
+			// Update the cron expression, and the timezone when one is given
 			input.Description.Schedule.Spec.CronExpressions = []string{req.CronExpr}
+			if req.Timezone != "" {
+				input.Description.Schedule.Spec.TimeZoneName = req.Timezone
+			}
 
 			// Update the cron expression in metadata stored in Note field
 			if input.Description.Schedule.State.Note != "" {
 				var metadata map[string]interface{}
 				if err := json.Unmarshal([]byte(input.Description.Schedule.State.Note), &metadata); err == nil {
 					metadata["cron_expr"] = req.CronExpr
+					if req.Timezone != "" {
+						metadata["timezone"] = req.Timezone
+					}
 					if updatedMetadataJSON, err := json.Marshal(metadata); err == nil {
 						input.Description.Schedule.State.Note = string(updatedMetadataJSON)
 					}
@@ -269,6 +303,9 @@ func (ts *TemporalService) updateSchedule(req JobRequest) JobResponse {
 
 	// Update in memory
 	job.CronExpr = req.CronExpr
+	if req.Timezone != "" {
+		job.Timezone = req.Timezone
+	}
 
 	log.Printf("Updated schedule for job: %s with new cron: %s", req.JobID, req.CronExpr)
 	return JobResponse{Success: true, Message: "Schedule updated successfully"}
@@ -335,6 +372,17 @@ func (ts *TemporalService) listSchedules() JobResponse {
 						jobStatus.ExecutionMode = &executionMode
 					}
 
+					// Extract recipe parameters and triggers
+					jobStatus.Parameters = parametersFromMetadata(metadata)
+					if timezone, ok := metadata["timezone"].(string); ok {
+						jobStatus.Timezone = timezone
+					}
+					if triggers, ok := metadata["triggers"]; ok {
+						if raw, err := json.Marshal(triggers); err == nil {
+							jobStatus.Triggers = raw
+						}
+					}
+
 					// Extract creation time
 					if createdAtStr, ok := metadata["created_at"].(string); ok {
 						if createdAt, err := time.Parse(time.RFC3339, createdAtStr); err == nil {
@@ -432,7 +480,8 @@ func (ts *TemporalService) runNow(req JobRequest) JobResponse {
 	job.LastManualRun = &manualRunStr
 	log.Printf("Recording manual run for job %s at %s", req.JobID, manualRunStr)
 
-	// Execute workflow immediately
+	// Execute workflow immediately; parameters on run_now come from a trigger and
+	// are passed as workflow input so they apply to this run only
 	workflowOptions := client.StartWorkflowOptions{
 		ID:        fmt.Sprintf("manual-%s-%d", req.JobID, now.Unix()),
 		TaskQueue: TaskQueueName,
@@ -441,7 +490,7 @@ func (ts *TemporalService) runNow(req JobRequest) JobResponse {
 	ctx, cancel := context.WithTimeout(context.Background(), 10*time.Second)
 	defer cancel()
 
-	we, err := ts.client.ExecuteWorkflow(ctx, workflowOptions, GooseJobWorkflow, req.JobID, job.RecipePath)
+	we, err := ts.client.ExecuteWorkflow(ctx, workflowOptions, GooseJobWorkflow, req.JobID, job.RecipePath, req.Parameters)
 	if err != nil {
 		return JobResponse{Success: false, Message: fmt.Sprintf("Failed to start workflow: %v", err)}
 	}
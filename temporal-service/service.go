@@ -253,6 +253,46 @@ func (ts *TemporalService) getEmbeddedRecipeContent(jobID string) (string, error
 	return "", fmt.Errorf("no embedded recipe content found")
 }
 
+// getScheduleParameters retrieves the recipe parameter values stored with a schedule
+func (ts *TemporalService) getScheduleParameters(jobID string) (map[string]string, error) {
+	ctx, cancel := context.WithTimeout(context.Background(), 10*time.Second)
+	defer cancel()
+
+	scheduleID := fmt.Sprintf("goose-job-%s", jobID)
+	handle := ts.client.ScheduleClient().GetHandle(ctx, scheduleID)
+
+	desc, err := handle.Describe(ctx)
+	if err != nil {
+		return nil, fmt.Errorf("failed to get schedule description: %w", err)
+	}
+
+	if desc.Schedule.State.Note == "" {
+		return nil, nil
+	}
+
+	var metadata map[string]interface{}
+	if err := json.Unmarshal([]byte(desc.Schedule.State.Note), &metadata); err != nil {
+		return nil, fmt.Errorf("failed to parse schedule metadata: %w", err)
+	}
+
+	return parametersFromMetadata(metadata), nil
+}
+
+// parametersFromMetadata extracts the recipe parameter map from schedule metadata
+func parametersFromMetadata(metadata map[string]interface{}) map[string]string {
+	raw, ok := metadata["parameters"].(map[string]interface{})
+	if !ok || len(raw) == 0 {
+		return nil
+	}
+	params := make(map[string]string, len(raw))
+	for key, value := range raw {
+		if str, ok := value.(string); ok {
+			params[key] = str
+		}
+	}
+	return params
+}
+
 // writeErrorResponse writes a standardized error response
 func (ts *TemporalService) writeErrorResponse(w http.ResponseWriter, statusCode int, message string) {
 	w.WriteHeader(statusCode)
@@ -16,6 +16,21 @@ export type Annotations = {
     timestamp?: string;
 };
 
+/**
+ * Answers tool confirmation requests without a user, for runs where nobody is there to answer
+ */
+export type ApprovalPolicy = {
+    /**
+     * Tools that are allowed, by name or by a pattern where `*` matches any characters
+     */
+    allow_tools?: Array<string>;
+    /**
+     * Tools that are denied, taking precedence over `allow_tools`
+     */
+    deny_tools?: Array<string>;
+    on_confirmation?: OnConfirmation | null;
+};
+
 export type Author = {
     contact?: string | null;
     metadata?: string | null;
@@ -121,10 +136,21 @@ export type CreateRecipeResponse = {
 };
 
 export type CreateScheduleRequest = {
-    cron: string;
+    cron?: string;
     execution_mode?: string | null;
     id: string;
+    max_runtime_secs?: number | null;
+    overlap_policy?: OverlapPolicy;
+    parameters?: {
+        [key: string]: string;
+    };
     recipe_source: string;
+    retry?: JobRetryPolicy | null;
+    /**
+     * IANA timezone the cron expression is evaluated in; UTC when omitted
+     */
+    timezone?: string | null;
+    triggers?: Array<JobTrigger>;
 };
 
 export type DecodeRecipeRequest = {
@@ -133,6 +159,7 @@ export type DecodeRecipeRequest = {
 
 export type DecodeRecipeResponse = {
     recipe: Recipe;
+    signature: SignatureCheck;
 };
 
 export type EmbeddedResource = {
@@ -320,12 +347,56 @@ export type InspectJobResponse = {
     sessionId?: string | null;
 };
 
+/**
+ * Retries a failed or timed out run, doubling the delay after each attempt
+ */
+export type JobRetryPolicy = {
+    backoff_secs?: number;
+    max_retries: number;
+};
+
+/**
+ * Outcome of a single attempt to run a scheduled job
+ */
+export type JobRunRecord = {
+    /**
+     * 1-based attempt number; 0 for runs skipped by the overlap policy
+     */
+    attempt: number;
+    duration_ms: number;
+    error?: string | null;
+    job_id: string;
+    session_id?: string | null;
+    started_at: string;
+    status: JobRunStatus;
+};
+
+export type JobRunStatus = 'succeeded' | 'failed' | 'timed_out' | 'cancelled' | 'skipped';
+
+export type JobTrigger = {
+    glob?: string | null;
+    path: string;
+    type: 'file_watch';
+} | {
+    secret: string;
+    type: 'webhook';
+} | {
+    job_id: string;
+    type: 'after_job';
+};
+
 export type KillJobResponse = {
     message: string;
 };
 
 export type ListSchedulesResponse = {
-    jobs: Array<ScheduledJob>;
+    jobs: Array<ScheduledJobResponse>;
+    /**
+     * Upcoming fire times for each job with a cron expression, keyed by job ID
+     */
+    next_runs: {
+        [key: string]: Array<string>;
+    };
 };
 
 /**
@@ -393,6 +464,16 @@ export type ModelInfo = {
     supports_cache_control?: boolean | null;
 };
 
+/**
+ * How a tool call that needs confirmation is answered when no pattern of the policy matches it
+ */
+export type OnConfirmation = 'deny' | 'allow' | 'fail';
+
+/**
+ * What to do when a job is triggered while a previous run is still in progress
+ */
+export type OverlapPolicy = 'skip' | 'queue' | 'cancel_previous';
+
 export type PermissionConfirmationRequest = {
     action: string;
     id: string;
@@ -529,6 +610,7 @@ export type Recipe = {
     response?: Response | null;
     retry?: RetryConfig | null;
     settings?: Settings | null;
+    signature?: RecipeSignature | null;
     sub_recipes?: Array<SubRecipe> | null;
     title: string;
     version?: string;
@@ -547,6 +629,17 @@ export type RecipeParameterInputType = 'string' | 'number' | 'boolean' | 'date'
 
 export type RecipeParameterRequirement = 'required' | 'optional' | 'user_prompt';
 
+export type RecipeSignature = {
+    /**
+     * Base64 encoded ed25519 public key of the signer
+     */
+    public_key: string;
+    /**
+     * Base64 encoded signature over the canonical recipe YAML
+     */
+    value: string;
+};
+
 export type RedactedThinkingContent = {
     data: string;
 };
@@ -603,6 +696,7 @@ export type ScanRecipeRequest = {
 
 export type ScanRecipeResponse = {
     has_security_warnings: boolean;
+    signature: SignatureCheck;
 };
 
 export type ScheduledJob = {
@@ -612,9 +706,33 @@ export type ScheduledJob = {
     execution_mode?: string | null;
     id: string;
     last_run?: string | null;
+    /**
+     * Wall-clock limit for a single run; the run is cancelled when it is exceeded
+     */
+    max_runtime_secs?: number | null;
+    overlap_policy?: OverlapPolicy;
+    parameters?: {
+        [key: string]: string;
+    };
     paused?: boolean;
     process_start_time?: string | null;
+    retry?: JobRetryPolicy | null;
     source: string;
+    /**
+     * IANA timezone the cron expression is evaluated in (e.g. `Europe/Berlin`); UTC when unset
+     */
+    timezone?: string | null;
+    /**
+     * Events that start the job in addition to `cron`, which may be empty when triggers are set
+     */
+    triggers?: Array<JobTrigger>;
+};
+
+/**
+ * A scheduled job as returned by the API, with its triggers redacted
+ */
+export type ScheduledJobResponse = ScheduledJob & {
+    triggers?: Array<TriggerInfo>;
 };
 
 export type SessionConfigRequest = {
@@ -631,6 +749,7 @@ export type SessionDisplayInfo = {
     messageCount: number;
     name: string;
     outputTokens?: number | null;
+    run?: JobRunRecord | null;
     scheduleId?: string | null;
     totalTokens?: number | null;
     workingDir: string;
@@ -690,10 +809,18 @@ export type SessionMetadata = {
      * Number of messages in the session
      */
     message_count: number;
+    /**
+     * Model chosen with /model during the session, used again when it is resumed
+     */
+    model?: string | null;
     /**
      * The number of output tokens used in the session. Retrieved from the provider's last usage.
      */
     output_tokens?: number | null;
+    /**
+     * Provider chosen with /model during the session, used again when it is resumed
+     */
+    provider?: string | null;
     /**
      * ID of the schedule that triggered this session, if any
      */
@@ -713,13 +840,52 @@ export type SessionsQuery = {
 };
 
 export type Settings = {
+    approval?: ApprovalPolicy | null;
+    /**
+     * Models to fail over to, in order, written as `provider/model`
+     */
+    fallback_models?: Array<string> | null;
     goose_model?: string | null;
     goose_provider?: string | null;
     temperature?: number | null;
 };
 
+export type SignatureAction = 'allow' | 'warn' | 'confirm' | 'block';
+
+export type SignatureCheck = {
+    action: SignatureAction;
+    message: string;
+    status: SignatureStatus;
+};
+
+/**
+ * Signed by the trusted key with this name
+ */
+export type SignatureStatus = {
+    signer: string;
+    status: 'verified';
+} | {
+    status: 'unsigned';
+} | {
+    public_key: string;
+    status: 'untrusted';
+} | {
+    reason: string;
+    status: 'invalid';
+};
+
 export type SubRecipe = {
+    /**
+     * Sub-recipes that must complete before this one runs
+     */
+    depends_on?: Array<string> | null;
     description?: string | null;
+    /**
+     * Parameters filled from a dependency's final output, written as `sub_recipe.field`
+     */
+    inputs?: {
+        [key: string]: string;
+    } | null;
     name: string;
     path: string;
     sequential_when_repeated?: boolean;
@@ -737,6 +903,38 @@ export type SuccessCheck = {
      */
     command: string;
     type: 'Shell';
+} | {
+    /**
+     * Path to the file, relative to the session working directory
+     */
+    path: string;
+    /**
+     * Regex the file contents must match
+     */
+    pattern?: string | null;
+    type: 'File';
+} | {
+    type: 'JsonSchema';
+} | {
+    /**
+     * Expected HTTP status code (default: 200)
+     */
+    expected_status?: number;
+    type: 'Http';
+    /**
+     * URL to request; must point at localhost or a loopback address
+     */
+    url: string;
+} | {
+    /**
+     * What a successful answer looks like
+     */
+    rubric: string;
+    /**
+     * Minimum score between 0.0 and 1.0 needed to pass (default: 0.7)
+     */
+    threshold?: number;
+    type: 'LlmJudge';
 };
 
 export type SummarizationRequested = {
@@ -813,6 +1011,21 @@ export type ToolResponse = {
     };
 };
 
+/**
+ * A job trigger as returned by the API; webhook secrets are never sent back to clients
+ */
+export type TriggerInfo = {
+    glob?: string | null;
+    path: string;
+    type: 'file_watch';
+} | {
+    has_secret: boolean;
+    type: 'webhook';
+} | {
+    job_id: string;
+    type: 'after_job';
+};
+
 export type UpdateProviderRequest = {
     model?: string | null;
     provider: string;
@@ -820,6 +1033,10 @@ export type UpdateProviderRequest = {
 
 export type UpdateScheduleRequest = {
     cron: string;
+    /**
+     * IANA timezone the cron expression is evaluated in; the current timezone is kept when omitted
+     */
+    timezone?: string | null;
 };
 
 export type UpsertConfigQuery = {
@@ -1473,6 +1690,10 @@ export type DecodeRecipeErrors = {
      * Bad request
      */
     400: unknown;
+    /**
+     * Recipe rejected by the signature policy
+     */
+    403: unknown;
 };
 
 export type DecodeRecipeResponses = {
@@ -1549,7 +1770,7 @@ export type CreateScheduleResponses = {
     /**
      * Scheduled job created successfully
      */
-    200: ScheduledJob;
+    200: ScheduledJobResponse;
 };
 
 export type CreateScheduleResponse = CreateScheduleResponses[keyof CreateScheduleResponses];
@@ -1589,7 +1810,12 @@ export type DeleteScheduleResponse = DeleteScheduleResponses[keyof DeleteSchedul
 export type ListSchedulesData = {
     body?: never;
     path?: never;
-    query?: never;
+    query?: {
+        /**
+         * Number of upcoming fire times to return for each job
+         */
+        next?: number;
+    };
     url: '/schedule/list';
 };
 
@@ -1640,7 +1866,7 @@ export type UpdateScheduleResponses = {
     /**
      * Scheduled job updated successfully
      */
-    200: ScheduledJob;
+    200: ScheduledJobResponse;
 };
 
 export type UpdateScheduleResponse = UpdateScheduleResponses[keyof UpdateScheduleResponses];
@@ -1746,6 +1972,10 @@ export type RunNowHandlerErrors = {
      * Scheduled job not found
      */
     404: unknown;
+    /**
+     * Scheduled job is already running and does not queue runs
+     */
+    409: unknown;
     /**
      * Internal server error when trying to run the job
      */
@@ -1823,6 +2053,49 @@ export type UnpauseScheduleResponses = {
 
 export type UnpauseScheduleResponse = UnpauseScheduleResponses[keyof UnpauseScheduleResponses];
 
+export type WebhookHandlerData = {
+    /**
+     * Optional JSON payload; object fields are passed as recipe parameters
+     */
+    body: {
+        [key: string]: unknown;
+    };
+    path: {
+        /**
+         * ID of the schedule to trigger
+         */
+        id: string;
+    };
+    query?: never;
+    url: '/schedule/{id}/webhook';
+};
+
+export type WebhookHandlerErrors = {
+    /**
+     * Payload is not valid JSON
+     */
+    400: unknown;
+    /**
+     * Missing or invalid webhook secret
+     */
+    401: unknown;
+    /**
+     * Scheduled job not found or has no webhook trigger
+     */
+    404: unknown;
+    /**
+     * Internal server error
+     */
+    500: unknown;
+};
+
+export type WebhookHandlerResponses = {
+    /**
+     * Scheduled job triggered
+     */
+    202: unknown;
+};
+
 export type ListSessionsData = {
     body?: never;
     path?: never;
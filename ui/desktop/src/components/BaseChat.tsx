@@ -201,6 +201,7 @@ function BaseChatContent({
     handleRecipeAccept,
     handleRecipeCancel,
     hasSecurityWarnings,
+    signatureCheck,
   } = useRecipeManager(messages, location.state);
 
   // Reset recipe usage tracking when recipe changes
@@ -575,6 +576,7 @@ function BaseChatContent({
           instructions: recipeConfig?.instructions || undefined,
         }}
         hasSecurityWarnings={hasSecurityWarnings}
+        signatureCheck={signatureCheck}
       />
 
       {/* Recipe Parameter Modal */}
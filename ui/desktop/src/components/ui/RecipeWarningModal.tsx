@@ -8,6 +8,7 @@ import {
 } from './dialog';
 import { Button } from './button';
 import MarkdownContent from '../MarkdownContent';
+import type { SignatureCheck } from '../../api';
 
 interface RecipeWarningModalProps {
   isOpen: boolean;
@@ -19,6 +20,7 @@ interface RecipeWarningModalProps {
     instructions?: string;
   };
   hasSecurityWarnings?: boolean;
+  signatureCheck?: SignatureCheck | null;
 }
 
 export function RecipeWarningModal({
@@ -27,7 +29,11 @@ export function RecipeWarningModal({
   onCancel,
   recipeDetails,
   hasSecurityWarnings = false,
+  signatureCheck = null,
 }: RecipeWarningModalProps) {
+  const isBlocked = signatureCheck?.action === 'block';
+  const isVerified = signatureCheck?.status.status === 'verified';
+
   return (
     <Dialog open={isOpen} onOpenChange={(open) => !open && onCancel()}>
       <DialogContent className="sm:max-w-[80vw] max-h-[80vh] flex flex-col p-0">
@@ -59,6 +65,24 @@ export function RecipeWarningModal({
           </div>
         )}
 
+        {signatureCheck && (
+          <div className="px-6 pt-4">
+            <div
+              className={
+                isVerified
+                  ? 'bg-green-50 dark:bg-green-900/20 border border-green-200 dark:border-green-800 rounded-lg p-4 text-sm text-green-700 dark:text-green-300'
+                  : 'bg-yellow-50 dark:bg-yellow-900/20 border border-yellow-200 dark:border-yellow-800 rounded-lg p-4 text-sm text-yellow-700 dark:text-yellow-300'
+              }
+            >
+              <p>
+                {isVerified ? '✓ ' : '⚠️ '}
+                {signatureCheck.message}
+                {isBlocked && '. Your recipe signature policy does not allow running it.'}
+              </p>
+            </div>
+          </div>
+        )}
+
         <div className="flex-1 overflow-y-auto p-6 pt-4">
           <div className="bg-background-muted p-4 rounded-lg">
             <h3 className="font-medium mb-3 text-text-standard">Recipe Preview:</h3>
@@ -87,7 +111,7 @@ export function RecipeWarningModal({
           <Button variant="outline" onClick={onCancel}>
             Cancel
           </Button>
-          <Button onClick={onConfirm}>Trust and Execute</Button>
+          {!isBlocked && <Button onClick={onConfirm}>Trust and Execute</Button>}
         </DialogFooter>
       </DialogContent>
     </Dialog>
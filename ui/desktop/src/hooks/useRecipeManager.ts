@@ -1,5 +1,6 @@
 import { useEffect, useMemo, useState, useRef } from 'react';
 import { createRecipe, Recipe, scanRecipe } from '../recipe';
+import type { SignatureCheck } from '../api';
 import { Message, createUserMessage } from '../types/message';
 import { updateSystemPromptWithParameters } from '../utils/providerUtils';
 import { useChatContext } from '../contexts/ChatContext';
@@ -18,6 +19,7 @@ export const useRecipeManager = (messages: Message[], locationState?: LocationSt
   const [isRecipeWarningModalOpen, setIsRecipeWarningModalOpen] = useState(false);
   const [recipeAccepted, setRecipeAccepted] = useState(false);
   const [hasSecurityWarnings, setHasSecurityWarnings] = useState(false);
+  const [signatureCheck, setSignatureCheck] = useState<SignatureCheck | null>(null);
 
   // Get chat context to access persisted recipe and parameters
   const chatContext = useChatContext();
@@ -84,18 +86,22 @@ export const useRecipeManager = (messages: Message[], locationState?: LocationSt
     const checkRecipeAcceptance = async () => {
       if (recipeConfig) {
         try {
-          const hasAccepted = await window.electron.hasAcceptedRecipeBefore(recipeConfig);
+          const securityScanResult = await scanRecipe(recipeConfig);
+          setHasSecurityWarnings(securityScanResult.has_security_warnings);
+          setSignatureCheck(securityScanResult.signature);
 
-          if (!hasAccepted) {
-            const securityScanResult = await scanRecipe(recipeConfig);
-            setHasSecurityWarnings(securityScanResult.has_security_warnings);
+          // The signature policy asks again even for recipes that were accepted before
+          const needsReview = ['confirm', 'block'].includes(securityScanResult.signature.action);
+          const hasAccepted = await window.electron.hasAcceptedRecipeBefore(recipeConfig);
 
+          if (!hasAccepted || needsReview) {
             setIsRecipeWarningModalOpen(true);
           } else {
             setRecipeAccepted(true);
           }
         } catch {
           setHasSecurityWarnings(false);
+          setSignatureCheck(null);
           setIsRecipeWarningModalOpen(true);
         }
       }
@@ -316,5 +322,6 @@ export const useRecipeManager = (messages: Message[], locationState?: LocationSt
     handleRecipeAccept,
     handleRecipeCancel,
     hasSecurityWarnings,
+    signatureCheck,
   };
 };
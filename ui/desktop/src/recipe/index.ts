@@ -8,6 +8,7 @@ import type {
   CreateRecipeRequest as ApiCreateRecipeRequest,
   CreateRecipeResponse as ApiCreateRecipeResponse,
   RecipeParameter,
+  ScanRecipeResponse,
   Message as ApiMessage,
   Role,
   MessageContent,
@@ -134,7 +135,7 @@ export async function decodeRecipe(deeplink: string): Promise<Recipe> {
   }
 }
 
-export async function scanRecipe(recipe: Recipe): Promise<{ has_security_warnings: boolean }> {
+export async function scanRecipe(recipe: Recipe): Promise<ScanRecipeResponse> {
   try {
     const response = await apiScanRecipe({
       body: { recipe },